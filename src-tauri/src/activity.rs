@@ -0,0 +1,65 @@
+use rusqlite::{Connection, Result, params};
+
+/// Oldest entries beyond this many are dropped as new ones arrive.
+const MAX_ENTRIES: i64 = 10_000;
+
+/// Append an event to the activity log. `kind` is one of `save`, `delete`,
+/// `restore`, `purge`, `import` or `export`.
+pub fn record(conn: &Connection, kind: &str, video_id: Option<&str>, detail: Option<&str>) -> Result<()> {
+    conn.execute(
+        "INSERT INTO activity (kind, video_id, detail) VALUES (?1, ?2, ?3)",
+        params![kind, video_id, detail],
+    )?;
+    conn.execute(
+        "DELETE FROM activity WHERE id <= last_insert_rowid() - ?1",
+        params![MAX_ENTRIES],
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry {
+    pub id: i64,
+    pub kind: String,
+    pub video_id: Option<String>,
+    pub detail: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityPage {
+    pub entries: Vec<ActivityEntry>,
+    /// Entries matching the filter across all pages.
+    pub total: i64,
+}
+
+/// One page of the log, newest first, optionally limited to one `kind`.
+pub fn get_activity(conn: &Connection, kind: Option<&str>, limit: i64, offset: i64) -> Result<ActivityPage> {
+    let total = conn.query_row(
+        "SELECT COUNT(*) FROM activity WHERE ?1 IS NULL OR kind = ?1",
+        params![kind],
+        |row| row.get(0),
+    )?;
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, video_id, detail, created_at FROM activity
+         WHERE ?1 IS NULL OR kind = ?1
+         ORDER BY id DESC LIMIT ?2 OFFSET ?3",
+    )?;
+    let rows = stmt.query_map(params![kind, limit, offset], |row| {
+        Ok(ActivityEntry {
+            id: row.get(0)?,
+            kind: row.get(1)?,
+            video_id: row.get(2)?,
+            detail: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    })?;
+    Ok(ActivityPage { entries: rows.collect::<Result<_>>()?, total })
+}
+
+/// Clear the whole log.
+pub fn clear_activity(conn: &Connection) -> Result<usize> {
+    conn.execute("DELETE FROM activity", [])
+}
@@ -0,0 +1,193 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use rusqlite::{backup::Backup, Connection, OpenFlags, Result};
+use crate::types::BackupInfo;
+
+const FILE_PREFIX: &str = "kinesis_data-";
+const FILE_EXTENSION: &str = "db";
+
+/// Backups live in a `backups` folder next to the database file.
+pub fn backup_dir(db_path: &str) -> PathBuf {
+    Path::new(db_path).parent().unwrap_or_else(|| Path::new(".")).join("backups")
+}
+
+/// A fresh, timestamped file name for a backup in `dir`.
+pub fn next_backup_path(conn: &Connection, dir: &Path) -> Result<PathBuf> {
+    let stamp: String = conn.query_row("SELECT strftime('%Y%m%d-%H%M%S', 'now')", [], |row| row.get(0))?;
+    let mut dest = dir.join(format!("{}{}.{}", FILE_PREFIX, stamp, FILE_EXTENSION));
+    let mut n = 1;
+    while dest.exists() {
+        dest = dir.join(format!("{}{}-{}.{}", FILE_PREFIX, stamp, n, FILE_EXTENSION));
+        n += 1;
+    }
+    Ok(dest)
+}
+
+/// Copy the live database into `dest` using SQLite's online backup API, so the
+/// copy is consistent even mid-write. An encrypted database is backed up
+/// encrypted with the same key.
+pub fn snapshot(conn: &Connection, dest: &Path, key: Option<&str>) -> Result<()> {
+    let mut target = Connection::open(dest)?;
+    if let Some(key) = key {
+        target.pragma_update(None, "key", key)?;
+    }
+    {
+        let backup = Backup::new(conn, &mut target)?;
+        backup.run_to_completion(256, Duration::from_millis(5), None)?;
+    }
+    // The copy inherits WAL mode; switch it back so the file stands alone
+    // and opening it never leaves -wal/-shm files beside it.
+    target.pragma_update(None, "journal_mode", "DELETE")
+}
+
+/// Open a backup read-only and check that it can be restored: the key fits,
+/// SQLite's integrity check passes, the core tables exist and its schema isn't
+/// newer than this app understands.
+pub fn open_backup(path: &Path, key: Option<&str>) -> std::result::Result<Connection, String> {
+    if !path.is_file() {
+        return Err("Backup file not found.".to_string());
+    }
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open backup: {}", e))?;
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", key).map_err(|e| e.to_string())?;
+    }
+
+    let check: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|_| "Not a database, or the passphrase is wrong.".to_string())?;
+    if check != "ok" {
+        return Err(format!("Backup is damaged: {}", check));
+    }
+
+    let has = |sql: &str| conn.query_row(sql, [], |_| Ok(())).is_ok();
+    if !has("SELECT 1 FROM pragma_table_info('videos') WHERE name = 'video_id'")
+        || !has("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'settings'")
+    {
+        return Err("Not a Kinesis database.".to_string());
+    }
+    if has("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'") {
+        let version = crate::db::schema_version(&conn).map_err(|e| e.to_string())?;
+        let latest = crate::db::MIGRATIONS.last().map(|m| m.version).unwrap_or(0);
+        if version > latest {
+            return Err(format!("Backup schema v{} is newer than this app supports (v{}).", version, latest));
+        }
+    }
+    Ok(conn)
+}
+
+/// Backups in `dir`, newest first.
+pub fn list_backups(dir: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<BackupInfo> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_name()?.to_str()?;
+            let stamp = name.strip_prefix(FILE_PREFIX)?.strip_suffix(&format!(".{}", FILE_EXTENSION))?;
+            Some(BackupInfo {
+                created_at: format_stamp(stamp)?,
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                path: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect();
+    // Same-second backups get a numeric suffix, so a longer name is the newer one.
+    backups.sort_by(|a, b| (&b.created_at, b.path.len(), &b.path).cmp(&(&a.created_at, a.path.len(), &a.path)));
+    backups
+}
+
+/// Turn a `YYYYMMDD-HHMMSS[-n]` file stamp into SQLite's `YYYY-MM-DD HH:MM:SS` (UTC).
+fn format_stamp(stamp: &str) -> Option<String> {
+    let digits: String = stamp.chars().filter(|c| c.is_ascii_digit()).take(14).collect();
+    if digits.len() != 14 || stamp.as_bytes().get(8) != Some(&b'-') {
+        return None;
+    }
+    Some(format!(
+        "{}-{}-{} {}:{}:{}",
+        &digits[0..4], &digits[4..6], &digits[6..8], &digits[8..10], &digits[10..12], &digits[12..14]
+    ))
+}
+
+/// Delete all but the `keep` newest backups. Returns how many were removed.
+pub fn prune_backups(dir: &Path, keep: usize) -> usize {
+    list_backups(dir)
+        .into_iter()
+        .skip(keep)
+        .filter(|b| std::fs::remove_file(&b.path).is_ok())
+        .count()
+}
+
+/// Whether the newest backup in `dir` is older than `interval` (or there is none).
+pub fn is_due(dir: &Path, interval: Duration) -> bool {
+    let newest = list_backups(dir)
+        .first()
+        .and_then(|b| std::fs::metadata(&b.path).and_then(|m| m.modified()).ok());
+    match newest {
+        Some(modified) => SystemTime::now().duration_since(modified).map(|age| age >= interval).unwrap_or(false),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("kinesis-backup-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn backup_is_a_complete_copy() {
+        let dir = temp_dir("copy");
+        let conn = crate::db::open(dir.join("live.db").to_str().unwrap(), None).unwrap();
+        crate::db::set_setting(&conn, "theme", "light").unwrap();
+
+        std::fs::create_dir_all(dir.join("backups")).unwrap();
+        let path = next_backup_path(&conn, &dir.join("backups")).unwrap();
+        snapshot(&conn, &path, None).unwrap();
+        assert_ne!(next_backup_path(&conn, &dir.join("backups")).unwrap(), path);
+
+        let copy = crate::db::open(path.to_str().unwrap(), None).unwrap();
+        assert_eq!(crate::db::get_setting(&copy, "theme").unwrap().as_deref(), Some("light"));
+        assert_eq!(list_backups(&dir.join("backups")).len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn open_backup_rejects_foreign_files() {
+        let dir = temp_dir("validate");
+        let other = dir.join("other.db");
+        Connection::open(&other).unwrap().execute_batch("CREATE TABLE notes (body TEXT)").unwrap();
+        std::fs::write(dir.join("junk.db"), b"definitely not sqlite, just some bytes that are long enough").unwrap();
+
+        assert_eq!(open_backup(&other, None).unwrap_err(), "Not a Kinesis database.");
+        assert!(open_backup(&dir.join("junk.db"), None).is_err());
+        assert!(open_backup(&dir.join("missing.db"), None).is_err());
+
+        let live = dir.join("live.db");
+        drop(crate::db::open(live.to_str().unwrap(), None).unwrap());
+        assert!(open_backup(&live, None).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prune_keeps_the_newest() {
+        let dir = temp_dir("prune");
+        for stamp in ["20240101-000000", "20240102-000000", "20240103-000000", "20240103-000000-1"] {
+            std::fs::write(dir.join(format!("{}{}.{}", FILE_PREFIX, stamp, FILE_EXTENSION)), b"").unwrap();
+        }
+        std::fs::write(dir.join("unrelated.db"), b"").unwrap();
+
+        assert_eq!(prune_backups(&dir, 2), 2);
+        let left: Vec<String> = list_backups(&dir).into_iter().map(|b| b.created_at).collect();
+        assert_eq!(left, ["2024-01-03 00:00:00", "2024-01-03 00:00:00"]);
+        assert!(list_backups(&dir)[0].path.ends_with("20240103-000000-1.db"));
+        assert!(dir.join("unrelated.db").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
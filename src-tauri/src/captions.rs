@@ -0,0 +1,66 @@
+use crate::types::TranscriptSegment;
+
+/// How long a caption stays up when its source gave no duration and nothing
+/// follows it.
+const DEFAULT_DURATION_MS: i64 = 2000;
+
+/// `HH:MM:SS` followed by `separator` and milliseconds.
+fn timestamp(ms: i64, separator: char) -> String {
+    let ms = ms.max(0);
+    format!("{:02}:{:02}:{:02}{}{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, separator, ms % 1000)
+}
+
+/// Start and end of each segment. Segments without a duration run until the
+/// next one starts.
+fn cue_times(segments: &[TranscriptSegment]) -> impl Iterator<Item = (i64, i64, &TranscriptSegment)> {
+    segments.iter().enumerate().map(|(i, s)| {
+        let end = if s.duration_ms > 0 {
+            s.start_ms + s.duration_ms
+        } else {
+            segments.get(i + 1).map(|next| next.start_ms).filter(|&e| e > s.start_ms).unwrap_or(s.start_ms + DEFAULT_DURATION_MS)
+        };
+        (s.start_ms, end, s)
+    })
+}
+
+/// Timed segments as a SubRip (.srt) file.
+pub fn to_srt(segments: &[TranscriptSegment]) -> String {
+    let mut out = String::new();
+    for (i, (start, end, s)) in cue_times(segments).enumerate() {
+        out.push_str(&format!("{}\n{} --> {}\n{}\n\n", i + 1, timestamp(start, ','), timestamp(end, ','), s.text.trim()));
+    }
+    out
+}
+
+/// Timed segments as a WebVTT (.vtt) file.
+pub fn to_vtt(segments: &[TranscriptSegment]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for (start, end, s) in cue_times(segments) {
+        out.push_str(&format!("{} --> {}\n{}\n\n", timestamp(start, '.'), timestamp(end, '.'), s.text.trim()));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: i64, duration_ms: i64, text: &str) -> TranscriptSegment {
+        TranscriptSegment { start_ms, duration_ms, text: text.to_string() }
+    }
+
+    #[test]
+    fn srt_numbers_cues_and_fills_missing_durations() {
+        let segments = [segment(1200, 2500, "Hello there"), segment(3_723_004, 0, "General Kenobi")];
+        assert_eq!(
+            to_srt(&segments),
+            "1\n00:00:01,200 --> 00:00:03,700\nHello there\n\n2\n01:02:03,004 --> 01:02:05,004\nGeneral Kenobi\n\n",
+        );
+    }
+
+    #[test]
+    fn vtt_has_a_header_and_dotted_milliseconds() {
+        let segments = [segment(0, 1500, "Hi"), segment(1500, 0, "Bye")];
+        assert_eq!(to_vtt(&segments), "WEBVTT\n\n00:00:00.000 --> 00:00:01.500\nHi\n\n00:00:01.500 --> 00:00:03.500\nBye\n\n");
+    }
+}
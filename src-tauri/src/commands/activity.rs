@@ -0,0 +1,22 @@
+use tauri::command;
+use crate::{with_db, activity};
+
+/// Record an event in the activity log. A failure here never fails the
+/// operation being logged.
+pub(crate) fn log_activity(app: &tauri::AppHandle, kind: &str, video_id: Option<&str>, detail: Option<&str>) {
+    if let Err(e) = with_db(app, |c| activity::record(c, kind, video_id, detail)) {
+        log::warn!("Failed to record {} activity: {}", kind, e);
+    }
+}
+
+/// The activity log, newest first. `kind` limits it to one type of event.
+#[command]
+pub fn get_activity_log(app: tauri::AppHandle, kind: Option<String>, limit: Option<i64>, offset: Option<i64>) -> Result<activity::ActivityPage, String> {
+    let kind = kind.filter(|k| !k.trim().is_empty());
+    with_db(&app, |c| activity::get_activity(c, kind.as_deref(), limit.unwrap_or(50).clamp(1, 500), offset.unwrap_or(0).max(0)))
+}
+
+#[command]
+pub fn clear_activity_log(app: tauri::AppHandle) -> Result<usize, String> {
+    with_db(&app, activity::clear_activity)
+}
@@ -1,5 +1,5 @@
 use tauri::command;
-use crate::{get_db_path, db, ollama, venice};
+use crate::{with_db, db, ollama, venice};
 
 #[command]
 pub async fn check_ollama() -> Result<bool, String> {
@@ -28,81 +28,65 @@ pub async fn install_ollama(app: tauri::AppHandle) -> Result<(), String> {
 
 #[command]
 pub fn get_ollama_model(app: tauri::AppHandle) -> Result<String, String> {
-    let db_path = get_db_path(&app);
-    db::get_setting(&db_path, "ollama_model")
-        .map_err(|e| e.to_string())
+    with_db(&app, |c| db::get_setting(c, "ollama_model"))
         .map(|opt| opt.unwrap_or_else(|| "llama3.2".to_string()))
 }
 
 #[command]
 pub fn set_ollama_model(app: tauri::AppHandle, model: String) -> Result<(), String> {
-    let db_path = get_db_path(&app);
-    db::set_setting(&db_path, "ollama_model", &model).map_err(|e| e.to_string())
+    with_db(&app, |c| db::set_setting(c, "ollama_model", &model))
 }
 
 #[command]
 pub fn get_ollama_prompt(app: tauri::AppHandle) -> Result<String, String> {
-    let db_path = get_db_path(&app);
     let default = "Create a synopsis of this video transcript with pretty format.";
-    db::get_setting(&db_path, "ollama_prompt")
-        .map_err(|e| e.to_string())
+    with_db(&app, |c| db::get_setting(c, "ollama_prompt"))
         .map(|opt| opt.unwrap_or_else(|| default.to_string()))
 }
 
 #[command]
 pub fn set_ollama_prompt(app: tauri::AppHandle, prompt: String) -> Result<(), String> {
-    let db_path = get_db_path(&app);
-    db::set_setting(&db_path, "ollama_prompt", &prompt).map_err(|e| e.to_string())
+    with_db(&app, |c| db::set_setting(c, "ollama_prompt", &prompt))
 }
 
 #[command]
 pub fn get_chunk_enabled(app: tauri::AppHandle) -> Result<bool, String> {
-    let db_path = get_db_path(&app);
-    db::get_setting(&db_path, "chunk_enabled")
-        .map_err(|e| e.to_string())
+    with_db(&app, |c| db::get_setting(c, "chunk_enabled"))
         .map(|v| v.unwrap_or_else(|| "true".to_string()) == "true")
 }
 
 #[command]
 pub fn set_chunk_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
-    let db_path = get_db_path(&app);
-    db::set_setting(&db_path, "chunk_enabled", &enabled.to_string()).map_err(|e| e.to_string())
+    with_db(&app, |c| db::set_setting(c, "chunk_enabled", &enabled.to_string()))
 }
 
 #[command]
 pub fn get_chunk_size(app: tauri::AppHandle) -> Result<usize, String> {
-    let db_path = get_db_path(&app);
-    db::get_setting(&db_path, "chunk_size")
-        .map_err(|e| e.to_string())
+    with_db(&app, |c| db::get_setting(c, "chunk_size"))
         .and_then(|v| v.and_then(|v| v.parse().ok()).ok_or_else(|| "Invalid chunk size".to_string()))
 }
 
 #[command]
 pub fn set_chunk_size(app: tauri::AppHandle, size: usize) -> Result<(), String> {
-    let db_path = get_db_path(&app);
-    db::set_setting(&db_path, "chunk_size", &size.to_string()).map_err(|e| e.to_string())
+    with_db(&app, |c| db::set_setting(c, "chunk_size", &size.to_string()))
 }
 
 #[command]
 pub fn get_max_chunks(app: tauri::AppHandle) -> Result<usize, String> {
-    let db_path = get_db_path(&app);
-    db::get_setting(&db_path, "max_chunks")
-        .map_err(|e| e.to_string())
+    with_db(&app, |c| db::get_setting(c, "max_chunks"))
         .and_then(|v| v.and_then(|v| v.parse().ok()).ok_or_else(|| "Invalid max chunks".to_string()))
 }
 
 #[command]
 pub fn set_max_chunks(app: tauri::AppHandle, max: usize) -> Result<(), String> {
-    let db_path = get_db_path(&app);
-    db::set_setting(&db_path, "max_chunks", &max.to_string()).map_err(|e| e.to_string())
+    with_db(&app, |c| db::set_setting(c, "max_chunks", &max.to_string()))
 }
 
 // ─── Summarize commands ───────────────────────────────────────────────────────
 
 #[command]
 pub async fn summarize_transcript(app: tauri::AppHandle, transcript: String) -> Result<String, String> {
-    let db_path = get_db_path(&app);
-    let provider = db::get_setting(&db_path, "summarize_provider")
+    let provider = with_db(&app, |c| db::get_setting(c, "summarize_provider"))
         .unwrap_or(None)
         .unwrap_or_else(|| "local".to_string());
 
@@ -115,50 +99,33 @@ pub async fn summarize_transcript(app: tauri::AppHandle, transcript: String) ->
 
 #[command]
 pub async fn save_summary(app: tauri::AppHandle, video_id: String, summary: String) -> Result<(), String> {
-    let db_path = get_db_path(&app);
-    db::save_summary(&db_path, &video_id, &summary).map_err(|e| e.to_string())
+    with_db(&app, |c| db::save_summary(c, &video_id, &summary))
 }
 
 #[command]
 pub async fn get_summary(app: tauri::AppHandle, video_id: String) -> Result<Option<String>, String> {
-    let db_path = get_db_path(&app);
-    db::get_summary(&db_path, &video_id).map_err(|e| e.to_string())
+    with_db(&app, |c| db::get_summary(c, &video_id))
 }
 
 #[command]
 pub async fn get_summarized_count(app: tauri::AppHandle) -> Result<i64, String> {
-    let db_path = get_db_path(&app);
-    db::get_summarized_count(&db_path).map_err(|e| e.to_string())
+    with_db(&app, db::get_summarized_count)
 }
 
 #[command]
 pub async fn get_videos_with_summaries(app: tauri::AppHandle) -> Result<Vec<String>, String> {
-    let db_path = get_db_path(&app);
-    db::get_videos_with_summaries(&db_path).map_err(|e| e.to_string())
+    with_db(&app, db::get_videos_with_summaries)
 }
 
 #[command]
 pub async fn summarize_all_videos(app: tauri::AppHandle) -> Result<i32, String> {
-    let db_path = get_db_path(&app);
-
-    let videos_without_summary: Vec<(String, String)> = {
-        let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
-        let mut stmt = conn.prepare(
-            "SELECT video_id, transcript FROM videos WHERE (summary IS NULL OR summary = '') AND transcript IS NOT NULL AND transcript != ''"
-        ).map_err(|e| e.to_string())?;
-        let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
-        let mut result = Vec::new();
-        while let Some(row) = rows.next().map_err(|e| e.to_string())? {
-            result.push((row.get(0).map_err(|e| e.to_string())?, row.get(1).map_err(|e| e.to_string())?));
-        }
-        result
-    };
+    let videos_without_summary = with_db(&app, db::get_videos_without_summary)?;
 
     if videos_without_summary.is_empty() {
         return Ok(0);
     }
 
-    let provider = db::get_setting(&db_path, "summarize_provider")
+    let provider = with_db(&app, |c| db::get_setting(c, "summarize_provider"))
         .unwrap_or(None)
         .unwrap_or_else(|| "local".to_string());
 
@@ -175,7 +142,7 @@ pub async fn summarize_all_videos(app: tauri::AppHandle) -> Result<i32, String>
         };
         match result {
             Ok(summary) => {
-                if db::save_summary(&db_path, &video_id, &summary).is_ok() { count += 1; }
+                if with_db(&app, |c| db::save_summary(c, &video_id, &summary)).is_ok() { count += 1; }
             }
             Err(e) => eprintln!("Failed to summarize {}: {}", video_id, e),
         }
@@ -188,33 +155,27 @@ pub async fn summarize_all_videos(app: tauri::AppHandle) -> Result<i32, String>
 
 #[command]
 pub fn get_venice_api_key(app: tauri::AppHandle) -> Result<Option<String>, String> {
-    let db_path = get_db_path(&app);
-    db::get_setting(&db_path, "venice_api_key").map_err(|e| e.to_string())
+    with_db(&app, |c| db::get_setting(c, "venice_api_key"))
 }
 
 #[command]
 pub fn set_venice_api_key(app: tauri::AppHandle, api_key: String) -> Result<(), String> {
-    let db_path = get_db_path(&app);
-    db::set_setting(&db_path, "venice_api_key", &api_key).map_err(|e| e.to_string())
+    with_db(&app, |c| db::set_setting(c, "venice_api_key", &api_key))
 }
 
 #[command]
 pub fn remove_venice_api_key(app: tauri::AppHandle) -> Result<(), String> {
-    let db_path = get_db_path(&app);
-    db::delete_setting(&db_path, "venice_api_key").map_err(|e| e.to_string())
+    with_db(&app, |c| db::delete_setting(c, "venice_api_key"))
 }
 
 #[command]
 pub fn get_venice_prompt(app: tauri::AppHandle) -> Result<String, String> {
-    let db_path = get_db_path(&app);
     let default = "Create a synopsis of this video transcript with pretty format.";
-    db::get_setting(&db_path, "venice_prompt")
-        .map_err(|e| e.to_string())
+    with_db(&app, |c| db::get_setting(c, "venice_prompt"))
         .map(|opt| opt.unwrap_or_else(|| default.to_string()))
 }
 
 #[command]
 pub fn set_venice_prompt(app: tauri::AppHandle, prompt: String) -> Result<(), String> {
-    let db_path = get_db_path(&app);
-    db::set_setting(&db_path, "venice_prompt", &prompt).map_err(|e| e.to_string())
+    with_db(&app, |c| db::set_setting(c, "venice_prompt", &prompt))
 }
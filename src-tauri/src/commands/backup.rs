@@ -0,0 +1,169 @@
+use std::time::Duration;
+use tauri::{command, Manager};
+use crate::{backup, db, get_db_path, install_connection, with_db, with_own_db, DbFileLock, DbKeyState, DbState, UndoState, DB_LOCKED};
+use crate::types::{BackupInfo, BackupSettings};
+
+const DEFAULT_INTERVAL_HOURS: u32 = 24;
+const DEFAULT_KEEP: u32 = 7;
+
+/// How often the scheduler wakes up to see whether a backup is due.
+const SCHEDULER_TICK: Duration = Duration::from_secs(15 * 60);
+
+fn read_settings(app: &tauri::AppHandle) -> BackupSettings {
+    let get = |key: &str, default: u32| -> u32 {
+        with_db(app, |c| db::get_setting(c, key)).unwrap_or(None).and_then(|v| v.parse().ok()).unwrap_or(default)
+    };
+    BackupSettings {
+        interval_hours: get("backup_interval_hours", DEFAULT_INTERVAL_HOURS),
+        keep: get("backup_keep", DEFAULT_KEEP),
+    }
+}
+
+/// Snapshot the database into the backups folder, then drop backups beyond the
+/// configured rotation. Returns the new backup's path.
+async fn run_backup(app: &tauri::AppHandle) -> Result<String, String> {
+    let path = get_db_path(app);
+    let dir = backup::backup_dir(&path);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backups folder: {}", e))?;
+
+    let key = if db::is_encrypted(&path) {
+        app.state::<DbKeyState>().0.lock().unwrap().clone()
+    } else {
+        None
+    };
+    let dest = with_db(app, |c| backup::next_backup_path(c, &dir))?;
+    let partial = dest.with_extension("part");
+    // Written under a temporary name so a half-finished file never shows up as a backup.
+    let target = partial.clone();
+    if let Err(e) = with_own_db(app, move |c| backup::snapshot(c, &target, key.as_deref())).await {
+        let _ = std::fs::remove_file(&partial);
+        return Err(format!("Backup failed: {}", e));
+    }
+    std::fs::rename(&partial, &dest).map_err(|e| format!("Failed to save backup: {}", e))?;
+
+    backup::prune_backups(&dir, read_settings(app).keep.max(1) as usize);
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Back up the database in the background whenever the newest backup is older
+/// than the configured interval.
+pub(crate) fn spawn_backup_scheduler(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let settings = read_settings(&app);
+            let dir = backup::backup_dir(&get_db_path(&app));
+            let interval = Duration::from_secs(settings.interval_hours as u64 * 3600);
+            if settings.interval_hours > 0 && backup::is_due(&dir, interval) {
+                match run_backup(&app).await {
+                    Ok(path) => log::info!("Scheduled backup written to {}", path),
+                    // Nothing to back up until the user unlocks the database
+                    Err(e) if e == DB_LOCKED => {}
+                    Err(e) => log::warn!("Scheduled backup failed: {}", e),
+                }
+            }
+            tokio::time::sleep(SCHEDULER_TICK).await;
+        }
+    });
+}
+
+#[command]
+pub fn get_backup_settings(app: tauri::AppHandle) -> Result<BackupSettings, String> {
+    Ok(read_settings(&app))
+}
+
+#[command]
+pub fn set_backup_settings(app: tauri::AppHandle, settings: BackupSettings) -> Result<(), String> {
+    if settings.keep == 0 {
+        return Err("Keep at least one backup.".to_string());
+    }
+    with_db(&app, |c| db::set_setting(c, "backup_interval_hours", &settings.interval_hours.to_string()))?;
+    with_db(&app, |c| db::set_setting(c, "backup_keep", &settings.keep.to_string()))
+}
+
+/// Available backups, newest first.
+#[command]
+pub fn list_backups(app: tauri::AppHandle) -> Result<Vec<BackupInfo>, String> {
+    Ok(backup::list_backups(&backup::backup_dir(&get_db_path(&app))))
+}
+
+/// Back up the database now, outside the schedule. Returns the backup's path.
+#[command]
+pub async fn create_backup(app: tauri::AppHandle) -> Result<String, String> {
+    run_backup(&app).await
+}
+
+/// Replace the live database with a backup. The backup is validated first, then
+/// copied next to the database and renamed over it, so a failure at any point
+/// leaves the current database in place. The current database is kept as a
+/// backup of its own first, so a restore can be undone by restoring that.
+/// Migrations run when it is reopened. `passphrase` is only needed for an
+/// encrypted backup whose key differs from the current session's. Refused
+/// while a backup or export has the database open.
+#[command]
+pub async fn restore_backup(app: tauri::AppHandle, path: String, passphrase: Option<String>) -> Result<(), String> {
+    let key = if db::is_encrypted(&path) {
+        let key = passphrase.filter(|p| !p.is_empty()).or_else(|| app.state::<DbKeyState>().0.lock().unwrap().clone());
+        Some(key.ok_or_else(|| "Backup is encrypted; enter its passphrase.".to_string())?)
+    } else {
+        None
+    };
+    tauri::async_runtime::spawn_blocking(move || replace_with_backup(&app, &path, key))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// The part of `restore_backup` that checks and copies whole databases, run on
+/// a blocking thread.
+fn replace_with_backup(app: &tauri::AppHandle, path: &str, key: Option<String>) -> Result<(), String> {
+    let db_path = get_db_path(app);
+    let backup_conn = backup::open_backup(std::path::Path::new(path), key.as_deref())?;
+    let staged = std::path::PathBuf::from(format!("{}.restore", db_path));
+    let _ = std::fs::remove_file(&staged);
+    if let Err(e) = backup::snapshot(&backup_conn, &staged, key.as_deref()) {
+        let _ = std::fs::remove_file(&staged);
+        return Err(format!("Failed to copy backup: {}", e));
+    }
+    let dir = backup::backup_dir(&db_path);
+    let kept = std::fs::create_dir_all(&dir).map_err(|e| e.to_string())
+        .and_then(|_| backup::next_backup_path(&backup_conn, &dir).map_err(|e| e.to_string()));
+    drop(backup_conn);
+    let fail = |e: String| {
+        let _ = std::fs::remove_file(&staged);
+        e
+    };
+    let kept = kept.map_err(|e| fail(format!("Failed to keep a copy of the current database: {}", e)))?;
+
+    let files = app.state::<DbFileLock>();
+    let _replacing = files.0.try_write()
+        .map_err(|_| fail("A backup or export is still running; try again once it finishes.".to_string()))?;
+    let state = app.state::<DbState>();
+    let mut guard = state.0.lock().map_err(|e| fail(e.to_string()))?;
+    let current_key = app.state::<DbKeyState>().0.lock().unwrap().clone().filter(|_| db::is_encrypted(&db_path));
+    let kept_copy = match guard.as_ref() {
+        Some((_, conn)) => backup::snapshot(conn, &kept, current_key.as_deref()).map_err(|e| e.to_string()),
+        // Locked, so there's no connection to copy through; the file on its own is whole
+        None => std::fs::copy(&db_path, &kept).map(|_| ()).map_err(|e| e.to_string()),
+    };
+    if let Err(e) = kept_copy {
+        let _ = std::fs::remove_file(&kept);
+        return Err(fail(format!("Failed to keep a copy of the current database: {}", e)));
+    }
+
+    if let Some((_, conn)) = guard.take() {
+        let _ = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE)");
+    }
+    // A leftover WAL from the old database must not be replayed into the restored one
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", db_path, suffix));
+    }
+    if let Err(e) = std::fs::rename(&staged, &db_path) {
+        return Err(fail(format!("Failed to replace database: {}", e)));
+    }
+
+    let conn = db::open(&db_path, key.as_deref()).map_err(|e| e.to_string())?;
+    *app.state::<DbKeyState>().0.lock().unwrap() = key;
+    install_connection(&mut guard, db_path, conn);
+    // Deletions remembered for undo refer to rows of the replaced database
+    *app.state::<UndoState>().0.lock().unwrap() = None;
+    Ok(())
+}
@@ -0,0 +1,8 @@
+use tauri::command;
+use crate::{with_db, db};
+use crate::types::Channel;
+
+#[command]
+pub fn list_channels(app: tauri::AppHandle) -> Result<Vec<Channel>, String> {
+    with_db(&app, db::list_channels)
+}
@@ -0,0 +1,51 @@
+use tauri::command;
+use crate::{with_db, db};
+use crate::types::Collection;
+
+fn normalize_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Collection name cannot be empty.".to_string());
+    }
+    Ok(name.to_string())
+}
+
+#[command]
+pub fn create_collection(app: tauri::AppHandle, name: String) -> Result<i64, String> {
+    let name = normalize_name(&name)?;
+    with_db(&app, |c| db::create_collection(c, &name))
+}
+
+#[command]
+pub fn rename_collection(app: tauri::AppHandle, id: i64, name: String) -> Result<(), String> {
+    let name = normalize_name(&name)?;
+    match with_db(&app, |c| db::rename_collection(c, id, &name))? {
+        0 => Err("Collection not found.".to_string()),
+        _ => Ok(()),
+    }
+}
+
+#[command]
+pub fn delete_collection(app: tauri::AppHandle, id: i64) -> Result<(), String> {
+    with_db(&app, |c| db::delete_collection(c, id))
+}
+
+#[command]
+pub fn list_collections(app: tauri::AppHandle) -> Result<Vec<Collection>, String> {
+    with_db(&app, db::list_collections)
+}
+
+#[command]
+pub fn add_to_collection(app: tauri::AppHandle, collection_id: i64, video_id: String) -> Result<(), String> {
+    with_db(&app, |c| db::add_to_collection(c, collection_id, video_id.trim()))
+}
+
+#[command]
+pub fn remove_from_collection(app: tauri::AppHandle, collection_id: i64, video_id: String) -> Result<(), String> {
+    with_db(&app, |c| db::remove_from_collection(c, collection_id, video_id.trim()))
+}
+
+#[command]
+pub fn get_video_collections(app: tauri::AppHandle, video_id: String) -> Result<Vec<i64>, String> {
+    with_db(&app, |c| db::get_video_collections(c, video_id.trim()))
+}
@@ -0,0 +1,80 @@
+use tauri::{command, Manager};
+use crate::{db, get_db_path, install_connection, open_db, DbFileLock, DbKeyState, DbState, DB_LOCKED};
+use crate::types::EncryptionStatus;
+
+fn current_key(app: &tauri::AppHandle) -> Option<String> {
+    app.state::<DbKeyState>().0.lock().unwrap().clone()
+}
+
+fn set_key(app: &tauri::AppHandle, key: Option<String>) {
+    *app.state::<DbKeyState>().0.lock().unwrap() = key;
+}
+
+/// Re-key the database (see `db::rekey`) on a blocking thread, since it copies
+/// the whole library. The connection lock is held throughout so no command
+/// sees the file mid-swap, and it's refused while a backup or export has the
+/// file open on a connection of its own.
+async fn rekey(app: &tauri::AppHandle, key: Option<String>) -> Result<(), String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = get_db_path(&app);
+        let files = app.state::<DbFileLock>();
+        let _replacing = files.0.try_write()
+            .map_err(|_| "A backup or export is still running; try again once it finishes.".to_string())?;
+        let state = app.state::<DbState>();
+        let mut guard = state.0.lock().map_err(|e| e.to_string())?;
+        let conn = match guard.take() {
+            Some((p, conn)) if p == path => conn,
+            _ => open_db(&app, &path)?,
+        };
+        let conn = db::rekey(conn, &path, key.as_deref())?;
+        set_key(&app, key);
+        install_connection(&mut guard, path, conn);
+        Ok(())
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[command]
+pub fn get_encryption_status(app: tauri::AppHandle) -> Result<EncryptionStatus, String> {
+    let enabled = db::is_encrypted(&get_db_path(&app));
+    let unlocked = !enabled || current_key(&app).is_some();
+    Ok(EncryptionStatus { enabled, unlocked })
+}
+
+/// Provide the passphrase for an encrypted database.
+#[command]
+pub fn unlock_database(app: tauri::AppHandle, passphrase: String) -> Result<(), String> {
+    let path = get_db_path(&app);
+    if !db::is_encrypted(&path) {
+        return Ok(());
+    }
+    let conn = db::open(&path, Some(&passphrase)).map_err(|_| "Incorrect passphrase".to_string())?;
+    set_key(&app, Some(passphrase));
+    install_connection(&mut *app.state::<DbState>().0.lock().map_err(|e| e.to_string())?, path, conn);
+    Ok(())
+}
+
+/// Encrypt the existing database with `passphrase`.
+#[command]
+pub async fn enable_encryption(app: tauri::AppHandle, passphrase: String) -> Result<(), String> {
+    if passphrase.is_empty() {
+        return Err("Passphrase cannot be empty".to_string());
+    }
+    if db::is_encrypted(&get_db_path(&app)) {
+        return Err("Database is already encrypted".to_string());
+    }
+    rekey(&app, Some(passphrase)).await
+}
+
+/// Decrypt the database back to plaintext. The current passphrase is required.
+#[command]
+pub async fn disable_encryption(app: tauri::AppHandle, passphrase: String) -> Result<(), String> {
+    if !db::is_encrypted(&get_db_path(&app)) {
+        return Err("Database is not encrypted".to_string());
+    }
+    match current_key(&app) {
+        None => Err(DB_LOCKED.to_string()),
+        Some(key) if key != passphrase => Err("Incorrect passphrase".to_string()),
+        Some(_) => rekey(&app, None).await,
+    }
+}
@@ -0,0 +1,167 @@
+use tauri::command;
+use crate::{with_db, with_db_blocking, with_own_db, db, export, types::{CaptionFormat, ImportReport}};
+use super::activity::log_activity;
+
+/// Ask the user where to save a file. Resolves to `None` if the dialog is cancelled.
+async fn pick_save_path(app: &tauri::AppHandle, file_name: &str, filter: &str, extension: &str) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let dialog = app.dialog().clone();
+    tauri_plugin_dialog::FileDialogBuilder::new(dialog)
+        .add_filter(filter, &[extension])
+        .set_file_name(file_name)
+        .save_file(move |f| {
+            let _ = tx.send(f.map(|p| p.to_string()));
+        });
+    rx.await.map_err(|e| e.to_string())
+}
+
+/// Ask the user for a file to open. Resolves to `None` if the dialog is cancelled.
+pub(super) async fn pick_open_path(app: &tauri::AppHandle, filter: &str, extension: &str) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let dialog = app.dialog().clone();
+    tauri_plugin_dialog::FileDialogBuilder::new(dialog)
+        .add_filter(filter, &[extension])
+        .pick_file(move |f| {
+            let _ = tx.send(f.map(|p| p.to_string()));
+        });
+    rx.await.map_err(|e| e.to_string())
+}
+
+/// Write the whole library to a JSON file chosen by the user.
+/// Returns the written path, or `None` if the user cancelled.
+#[command]
+pub async fn export_library(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let Some(path) = pick_save_path(&app, "kinesis-library.json", "JSON", "json").await? else {
+        return Ok(None);
+    };
+
+    let library = with_own_db(&app, export::build_export).await?;
+    let json = serde_json::to_string_pretty(&library).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write export: {}", e))?;
+    log_activity(&app, "export", None, Some(&format!("Library ({} videos) to {}", library.videos.len(), path)));
+    Ok(Some(path))
+}
+
+/// Write a saved video's timed transcript as an .srt or .vtt subtitle file
+/// chosen by the user. Returns the written path, or `None` if the user cancelled.
+#[command]
+pub async fn export_captions(app: tauri::AppHandle, video_id: String, format: CaptionFormat) -> Result<Option<String>, String> {
+    let video_id = video_id.trim();
+    let segments = with_db(&app, |c| db::get_transcript_segments(c, video_id))?;
+    if segments.is_empty() {
+        return Err("This video has no timed transcript saved.".to_string());
+    }
+    let title = with_db(&app, |c| db::get_video_details(c, video_id))?.map(|d| d.video.title);
+    let (extension, filter, contents) = match format {
+        CaptionFormat::Srt => ("srt", "SubRip subtitles", crate::captions::to_srt(&segments)),
+        CaptionFormat::Vtt => ("vtt", "WebVTT subtitles", crate::captions::to_vtt(&segments)),
+    };
+    let file_name = export::video_file_name(title.as_deref(), video_id, extension);
+    let Some(path) = pick_save_path(&app, &file_name, filter, extension).await? else {
+        return Ok(None);
+    };
+
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write captions: {}", e))?;
+    log_activity(&app, "export", Some(video_id), Some(&format!("Captions to {}", path)));
+    Ok(Some(path))
+}
+
+/// Write one Markdown note per saved video into a folder chosen by the user.
+/// Returns the number of files written, or `None` if the user cancelled.
+#[command]
+pub async fn export_transcripts_markdown(app: tauri::AppHandle) -> Result<Option<usize>, String> {
+    let Some(folder) = super::select_folder(app.clone()).await? else {
+        return Ok(None);
+    };
+
+    let videos = with_own_db(&app, export::load_videos).await?;
+    let folder = std::path::PathBuf::from(folder);
+    for video in &videos {
+        let path = folder.join(export::markdown_file_name(video));
+        std::fs::write(&path, export::render_markdown(video))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    log_activity(&app, "export", None, Some(&format!("Markdown ({} videos) to {}", videos.len(), folder.display())));
+    Ok(Some(videos.len()))
+}
+
+/// Write video metadata as CSV to a file chosen by the user.
+/// Returns the written path, or `None` if the user cancelled.
+#[command]
+pub async fn export_metadata_csv(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let Some(path) = pick_save_path(&app, "kinesis-videos.csv", "CSV", "csv").await? else {
+        return Ok(None);
+    };
+
+    let videos = with_own_db(&app, export::load_videos).await?;
+    std::fs::write(&path, export::render_metadata_csv(&videos))
+        .map_err(|e| format!("Failed to write export: {}", e))?;
+    log_activity(&app, "export", None, Some(&format!("CSV ({} videos) to {}", videos.len(), path)));
+    Ok(Some(path))
+}
+
+/// Merge a previously exported JSON file, chosen by the user, into the library.
+/// Returns `None` if the user cancelled.
+#[command]
+pub async fn import_library(app: tauri::AppHandle) -> Result<Option<ImportReport>, String> {
+    let Some(path) = pick_open_path(&app, "JSON", "json").await? else {
+        return Ok(None);
+    };
+
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read import: {}", e))?;
+    let library: export::LibraryExport = serde_json::from_str(&json)
+        .map_err(|e| format!("Not a Kinesis library export: {}", e))?;
+    if library.format != export::EXPORT_FORMAT {
+        return Err("Not a Kinesis library export".to_string());
+    }
+    if library.version > export::EXPORT_VERSION {
+        return Err(format!("Export version {} is newer than this app supports", library.version));
+    }
+
+    let report = with_db_blocking(&app, move |c| export::import_library(c, &library)).await?;
+    log_activity(&app, "import", None, Some(&format!(
+        "Library from {}: {} added, {} updated, {} skipped, {} left in the trash",
+        path, report.added, report.updated, report.skipped, report.trashed
+    )));
+    Ok(Some(report))
+}
+
+/// Write the non-secret settings to a JSON file chosen by the user.
+/// Returns the written path, or `None` if the user cancelled.
+#[command]
+pub async fn export_settings(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let Some(path) = pick_save_path(&app, "kinesis-settings.json", "JSON", "json").await? else {
+        return Ok(None);
+    };
+
+    let settings = with_db(&app, export::build_settings_export)?;
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write export: {}", e))?;
+    log_activity(&app, "export", None, Some(&format!("Settings to {}", path)));
+    Ok(Some(path))
+}
+
+/// Apply settings from a file written by `export_settings`. Returns the number
+/// of settings applied, or `None` if the user cancelled.
+#[command]
+pub async fn import_settings(app: tauri::AppHandle) -> Result<Option<usize>, String> {
+    let Some(path) = pick_open_path(&app, "JSON", "json").await? else {
+        return Ok(None);
+    };
+
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read import: {}", e))?;
+    let settings: export::SettingsExport = serde_json::from_str(&json)
+        .map_err(|e| format!("Not a Kinesis settings export: {}", e))?;
+    if settings.format != export::SETTINGS_FORMAT {
+        return Err("Not a Kinesis settings export".to_string());
+    }
+    if settings.version > export::SETTINGS_VERSION {
+        return Err(format!("Settings export version {} is newer than this app supports", settings.version));
+    }
+
+    let applied = with_db(&app, |c| export::import_settings(c, &settings))?;
+    log_activity(&app, "import", None, Some(&format!("{} settings from {}", applied, path)));
+    Ok(Some(applied))
+}
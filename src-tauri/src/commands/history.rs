@@ -1,32 +1,27 @@
 use tauri::command;
-use crate::{get_db_path, history};
+use crate::{with_db, history};
 
 #[command]
 pub fn add_search_history(app: tauri::AppHandle, query: String) -> Result<(), String> {
-    let path = get_db_path(&app);
-    history::add_history(&path, &query).map_err(|e| e.to_string())
+    with_db(&app, |c| history::add_history(c, &query))
 }
 
 #[command]
 pub fn get_search_history(app: tauri::AppHandle, limit: Option<i64>) -> Result<Vec<history::HistoryEntry>, String> {
-    let path = get_db_path(&app);
-    history::get_history(&path, limit.unwrap_or(20)).map_err(|e| e.to_string())
+    with_db(&app, |c| history::get_history(c, limit.unwrap_or(20)))
 }
 
 #[command]
 pub fn clear_history_before_date(app: tauri::AppHandle, date: String) -> Result<usize, String> {
-    let path = get_db_path(&app);
-    history::clear_history_before(&path, &date).map_err(|e| e.to_string())
+    with_db(&app, |c| history::clear_history_before(c, &date))
 }
 
 #[command]
 pub fn delete_history_entry(app: tauri::AppHandle, id: i64) -> Result<(), String> {
-    let path = get_db_path(&app);
-    history::delete_history_entry(&path, id).map_err(|e| e.to_string())
+    with_db(&app, |c| history::delete_history_entry(c, id))
 }
 
 #[command]
 pub fn clear_all_history(app: tauri::AppHandle) -> Result<(), String> {
-    let path = get_db_path(&app);
-    history::clear_all_history(&path).map_err(|e| e.to_string())
+    with_db(&app, history::clear_all_history)
 }
@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+use tauri::{command, Emitter};
+use crate::{with_db, db, media, youtube};
+use crate::media::StreamFormat;
+use crate::types::{MediaFile, VideoFormat};
+use crate::youtube::ClientType;
+use super::youtube::youtube_client;
+
+/// Clients to ask for streams, in turn. The mobile clients give URLs that
+/// work as they are; the web client's often need their signature unscrambled.
+const STREAM_CLIENTS: [ClientType; 3] = [ClientType::Ios, ClientType::Android, ClientType::Web];
+
+/// Where downloaded audio and video are kept: a `media` folder beside the
+/// profile's database.
+fn media_dir(app: &tauri::AppHandle) -> PathBuf {
+    let db_path = PathBuf::from(crate::get_db_path(app));
+    db_path.parent().map(PathBuf::from).unwrap_or_else(|| PathBuf::from(".")).join("media")
+}
+
+/// The stream `pick` chooses from the first client offering one, with its
+/// URL ready to download.
+async fn pick_stream(app: &tauri::AppHandle, video_id: &str, pick: impl Fn(&[StreamFormat]) -> Option<StreamFormat>) -> Result<StreamFormat, String> {
+    let mut refusal = None;
+    for client_type in STREAM_CLIENTS {
+        match youtube_client(app, client_type).player(video_id).await {
+            Ok(player) => {
+                if let Some(mut format) = pick(&media::formats(&player)) {
+                    media::unscramble(&mut format).await?;
+                    return Ok(format);
+                }
+                refusal = youtube::Unplayable::from_player(&player).map(|u| u.to_string()).or(refusal);
+            }
+            Err(e) => log::warn!("{:?} player failed for {}: {}", client_type, video_id, e),
+        }
+    }
+    Err(refusal.unwrap_or_else(|| "No downloadable stream found.".to_string()))
+}
+
+/// Download `format` for a saved video into the media folder and record it,
+/// replacing an earlier download of the same kind. Emits
+/// `media_download_progress` as the bytes arrive.
+async fn download_media(app: &tauri::AppHandle, video_id: &str, kind: &str, format: &StreamFormat) -> Result<MediaFile, String> {
+    let path = media_dir(app).join(format!("{}-{}.{}", video_id, kind, format.extension()));
+    let size = media::download(format, &path, |received, total| {
+        let progress = serde_json::json!({ "videoId": video_id, "kind": kind, "received": received, "total": total });
+        let _ = app.emit("media_download_progress", progress);
+    }).await?;
+
+    let path = path.to_string_lossy().to_string();
+    let earlier = with_db(app, |c| db::get_media_files(c, video_id))?.into_iter().find(|f| f.kind == kind);
+    if let Some(earlier) = earlier.filter(|f| f.path != path) {
+        let _ = std::fs::remove_file(&earlier.path);
+    }
+    with_db(app, |c| db::record_media_file(c, video_id, kind, &path, format.container(), size as i64))?;
+    with_db(app, |c| db::get_media_files(c, video_id))?
+        .into_iter()
+        .find(|f| f.kind == kind)
+        .ok_or_else(|| "Download was not recorded.".to_string())
+}
+
+/// Download a saved video's audio, m4a where YouTube offers it, so the archive
+/// can be listened to as well as read.
+#[command]
+pub async fn download_audio(app: tauri::AppHandle, video_id: String) -> Result<MediaFile, String> {
+    let video_id = video_id.trim();
+    if !with_db(&app, |c| db::check_video_exists(c, video_id))? {
+        return Err("Save the video before downloading its audio.".to_string());
+    }
+    let format = pick_stream(&app, video_id, media::pick_audio_format).await?;
+    download_media(&app, video_id, "audio", &format).await
+}
+
+/// The video streams YouTube offers for a video, for picking a quality to
+/// pass to `download_video`.
+#[command]
+pub async fn list_video_formats(app: tauri::AppHandle, video_id: String) -> Result<Vec<VideoFormat>, String> {
+    let video_id = video_id.trim();
+    for client_type in STREAM_CLIENTS {
+        match youtube_client(&app, client_type).player(video_id).await {
+            Ok(player) => {
+                let formats: Vec<VideoFormat> = media::formats(&player).iter()
+                    .filter(|f| f.is_video())
+                    .map(|f| f.describe())
+                    .collect();
+                if !formats.is_empty() {
+                    return Ok(formats);
+                }
+            }
+            Err(e) => log::warn!("{:?} player failed for {}: {}", client_type, video_id, e),
+        }
+    }
+    Ok(Vec::new())
+}
+
+/// Download a saved video: the stream with `itag` when given, otherwise the
+/// best one no taller than `max_height`. Adaptive streams carry no sound, so
+/// the audio is downloaded beside them unless it already has been.
+#[command]
+pub async fn download_video(app: tauri::AppHandle, video_id: String, itag: Option<i64>, max_height: Option<i64>) -> Result<MediaFile, String> {
+    let video_id = video_id.trim();
+    if !with_db(&app, |c| db::check_video_exists(c, video_id))? {
+        return Err("Save the video before downloading it.".to_string());
+    }
+    let format = pick_stream(&app, video_id, |formats| media::pick_video_format(formats, itag, max_height)).await?;
+    let file = download_media(&app, video_id, "video", &format).await?;
+
+    let has_audio_file = with_db(&app, |c| db::get_media_files(c, video_id))?.iter().any(|f| f.kind == "audio");
+    if !format.has_audio() && !has_audio_file {
+        let audio = match pick_stream(&app, video_id, media::pick_audio_format).await {
+            Ok(audio) => download_media(&app, video_id, "audio", &audio).await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = audio {
+            log::warn!("Downloaded {} without its audio: {}", video_id, e);
+        }
+    }
+    Ok(file)
+}
+
+/// Files downloaded for a video.
+#[command]
+pub fn list_media_files(app: tauri::AppHandle, video_id: String) -> Result<Vec<MediaFile>, String> {
+    with_db(&app, |c| db::get_media_files(c, video_id.trim()))
+}
@@ -0,0 +1,31 @@
+use std::collections::BTreeMap;
+use tauri::command;
+use crate::{with_db, db};
+
+fn normalize_key(key: &str) -> Result<String, String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("Field name cannot be empty.".to_string());
+    }
+    Ok(key.to_string())
+}
+
+#[command]
+pub fn set_video_meta(app: tauri::AppHandle, video_id: String, key: String, value: String) -> Result<(), String> {
+    let key = normalize_key(&key)?;
+    match with_db(&app, |c| db::set_video_meta(c, video_id.trim(), &key, &value))? {
+        0 => Err("Video is not saved in the library.".to_string()),
+        _ => Ok(()),
+    }
+}
+
+#[command]
+pub fn get_video_meta(app: tauri::AppHandle, video_id: String) -> Result<BTreeMap<String, String>, String> {
+    with_db(&app, |c| db::get_video_meta(c, video_id.trim()))
+}
+
+#[command]
+pub fn delete_video_meta(app: tauri::AppHandle, video_id: String, key: String) -> Result<(), String> {
+    let key = normalize_key(&key)?;
+    with_db(&app, |c| db::delete_video_meta(c, video_id.trim(), &key)).map(|_| ())
+}
@@ -2,8 +2,32 @@ pub mod settings;
 pub mod youtube;
 pub mod ai;
 pub mod history;
+pub mod tags;
+pub mod collections;
+pub mod channels;
+pub mod meta;
+pub mod export;
+pub mod encryption;
+pub mod backup;
+pub mod profiles;
+pub mod thumbnails;
+pub mod activity;
+pub mod undo;
+pub mod media;
 
 pub use settings::*;
 pub use youtube::*;
 pub use ai::*;
 pub use history::*;
+pub use tags::*;
+pub use collections::*;
+pub use channels::*;
+pub use meta::*;
+pub use export::*;
+pub use encryption::*;
+pub use backup::*;
+pub use profiles::*;
+pub use thumbnails::*;
+pub use activity::*;
+pub use undo::*;
+pub use media::*;
@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+use tauri::{command, Manager};
+use crate::{active_profile, db, get_data_dir, profile_db_path, ConfManager, DbKeyState, DbPathState, DEFAULT_PROFILE};
+use crate::types::Profile;
+
+/// Profile names become folder names, so keep them to a portable character set.
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > 40 {
+        return Err("Profile names must be 1 to 40 characters.".to_string());
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ' ') {
+        return Err("Profile names may only use letters, digits, spaces, '-' and '_'.".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// Every profile: the default one first, then the rest by name.
+fn profile_names(data_dir: &std::path::Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(data_dir.join("profiles"))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().join("kinesis_data.db").is_file())
+                .filter_map(|e| e.file_name().to_str().map(String::from))
+                .filter(|name| name != DEFAULT_PROFILE)
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort_by_key(|n| n.to_lowercase());
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+/// Find an existing profile by name, ignoring case.
+fn find_profile(data_dir: &std::path::Path, name: &str) -> Option<String> {
+    profile_names(data_dir).into_iter().find(|p| p.eq_ignore_ascii_case(name.trim()))
+}
+
+#[command]
+pub fn list_profiles(app: tauri::AppHandle) -> Result<Vec<Profile>, String> {
+    let data_dir = get_data_dir(&app);
+    let active = active_profile(&app);
+    Ok(profile_names(&data_dir)
+        .into_iter()
+        .map(|name| {
+            let path = profile_db_path(&data_dir, &name);
+            Profile {
+                active: name == active,
+                size_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                path: path.to_string_lossy().to_string(),
+                name,
+            }
+        })
+        .collect())
+}
+
+/// Create an empty, unencrypted profile. It doesn't become active until switched to.
+#[command]
+pub fn create_profile(app: tauri::AppHandle, name: String) -> Result<Profile, String> {
+    let name = validate_name(&name)?;
+    let data_dir = get_data_dir(&app);
+    if find_profile(&data_dir, &name).is_some() {
+        return Err(format!("A profile named \"{}\" already exists.", name));
+    }
+
+    let path = profile_db_path(&data_dir, &name);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create profile: {}", e))?;
+    }
+    db::open(&path.to_string_lossy(), None).map_err(|e| format!("Failed to create profile: {}", e))?;
+    Ok(Profile {
+        name,
+        active: false,
+        size_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+        path: path.to_string_lossy().to_string(),
+    })
+}
+
+/// Make `name` the active profile. Every command works against it from here on,
+/// and it stays active across restarts. An encrypted profile needs unlocking.
+#[command]
+pub fn switch_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let data_dir = get_data_dir(&app);
+    let name = find_profile(&data_dir, &name).ok_or_else(|| "Profile not found.".to_string())?;
+    if name == active_profile(&app) {
+        return Ok(());
+    }
+
+    let path = profile_db_path(&data_dir, &name).to_string_lossy().to_string();
+    let state = app.state::<DbPathState>();
+    let mut guard = state.0.lock().unwrap();
+    crate::close_db(&app);
+    ConfManager::write_attr(&app, "profile", &name)?;
+    *guard = Some(path);
+    // The passphrase belonged to the previous profile's database
+    *app.state::<DbKeyState>().0.lock().unwrap() = None;
+    Ok(())
+}
+
+/// Delete a profile's database and backups for good. The default and the
+/// active profile can't be deleted.
+#[command]
+pub fn delete_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let data_dir = get_data_dir(&app);
+    let name = find_profile(&data_dir, &name).ok_or_else(|| "Profile not found.".to_string())?;
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile can't be deleted.".to_string());
+    }
+    if name == active_profile(&app) {
+        return Err("Switch to another profile before deleting this one.".to_string());
+    }
+    let dir: PathBuf = data_dir.join("profiles").join(&name);
+    std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete profile: {}", e))
+}
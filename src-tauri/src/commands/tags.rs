@@ -0,0 +1,40 @@
+use tauri::command;
+use crate::{with_db, db};
+use crate::types::{Tag, VideoResponse};
+
+fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("Tag name cannot be empty.".to_string());
+    }
+    Ok(tag.to_string())
+}
+
+#[command]
+pub fn add_tag(app: tauri::AppHandle, video_id: String, tag: String) -> Result<(), String> {
+    let tag = normalize_tag(&tag)?;
+    with_db(&app, |c| db::add_tag(c, video_id.trim(), &tag))
+}
+
+#[command]
+pub fn remove_tag(app: tauri::AppHandle, video_id: String, tag: String) -> Result<(), String> {
+    let tag = normalize_tag(&tag)?;
+    with_db(&app, |c| db::remove_tag(c, video_id.trim(), &tag))
+}
+
+#[command]
+pub fn list_tags(app: tauri::AppHandle) -> Result<Vec<Tag>, String> {
+    with_db(&app, db::list_tags)
+}
+
+#[command]
+pub fn get_video_tags(app: tauri::AppHandle, video_id: String) -> Result<Vec<String>, String> {
+    with_db(&app, |c| db::get_video_tags(c, video_id.trim()))
+}
+
+#[command]
+pub fn list_videos_by_tag(app: tauri::AppHandle, tag: String) -> Result<VideoResponse, String> {
+    let tag = normalize_tag(&tag)?;
+    let videos = with_db(&app, |c| db::list_videos_by_tag(c, &tag))?;
+    Ok(VideoResponse { videos, continuation: None, source: "library".to_string() })
+}
@@ -0,0 +1,82 @@
+use base64::Engine;
+use tauri::command;
+use crate::{with_db, db, youtube};
+use crate::types::{ThumbnailCacheStats, ThumbnailQuality};
+
+fn data_url(mime: &str, data: &[u8]) -> String {
+    format!("data:{};base64,{}", mime, base64::engine::general_purpose::STANDARD.encode(data))
+}
+
+/// The size the `thumbnail_quality` setting asks to cache thumbnails at.
+pub(super) fn thumbnail_quality(app: &tauri::AppHandle) -> ThumbnailQuality {
+    with_db(app, |c| db::get_setting(c, "thumbnail_quality"))
+        .unwrap_or(None)
+        .and_then(|q| serde_json::from_value(serde_json::Value::String(q)).ok())
+        .unwrap_or_default()
+}
+
+/// Download and store a saved video's thumbnail unless it's already cached.
+/// Failures are only logged; the UI falls back to the remote image.
+async fn cache_thumbnail(app: &tauri::AppHandle, video_id: &str) -> Option<(String, Vec<u8>)> {
+    if with_db(app, |c| db::has_thumbnail(c, video_id)).unwrap_or(false) {
+        return None;
+    }
+    match youtube::fetch_thumbnail(video_id, thumbnail_quality(app)).await {
+        Ok(Some((mime, data))) => {
+            if let Err(e) = with_db(app, |c| db::save_thumbnail(c, video_id, &mime, &data)) {
+                log::warn!("Failed to cache thumbnail for {}: {}", video_id, e);
+            }
+            Some((mime, data))
+        }
+        Ok(None) => None,
+        Err(e) => {
+            log::warn!("Failed to fetch thumbnail for {}: {}", video_id, e);
+            None
+        }
+    }
+}
+
+/// A saved video's thumbnail as a `data:` URL, served from the local cache.
+/// Videos saved before the cache existed are fetched and cached on first use.
+/// `None` means no local copy could be made (e.g. offline); use the remote URL.
+#[command]
+pub async fn get_thumbnail(app: tauri::AppHandle, video_id: String) -> Result<Option<String>, String> {
+    let video_id = video_id.trim();
+    if let Some((mime, data)) = with_db(&app, |c| db::get_thumbnail(c, video_id))? {
+        return Ok(Some(data_url(&mime, &data)));
+    }
+    if !with_db(&app, |c| db::check_video_exists(c, video_id))? {
+        return Ok(None);
+    }
+    Ok(cache_thumbnail(&app, video_id).await.map(|(mime, data)| data_url(&mime, &data)))
+}
+
+/// Download a video's thumbnail at `quality` (the `thumbnail_quality` setting
+/// when omitted), falling back to smaller sizes, and return it as a `data:`
+/// URL. A saved video's cached thumbnail is replaced with it.
+#[command]
+pub async fn download_thumbnail(app: tauri::AppHandle, video_id: String, quality: Option<ThumbnailQuality>) -> Result<Option<String>, String> {
+    let video_id = video_id.trim();
+    let quality = quality.unwrap_or_else(|| thumbnail_quality(&app));
+    let Some((mime, data)) = youtube::fetch_thumbnail(video_id, quality).await? else {
+        return Ok(None);
+    };
+    with_db(&app, |c| db::save_thumbnail(c, video_id, &mime, &data))?;
+    Ok(Some(data_url(&mime, &data)))
+}
+
+#[command]
+pub fn get_thumbnail_cache_stats(app: tauri::AppHandle) -> Result<ThumbnailCacheStats, String> {
+    let (count, size_bytes) = with_db(&app, db::get_thumbnail_cache_stats)?;
+    Ok(ThumbnailCacheStats { count, size_bytes })
+}
+
+/// Empty the thumbnail cache, or with `trashed_only` just drop thumbnails of
+/// trashed videos. Cleared thumbnails are fetched again when next shown.
+#[command]
+pub fn purge_thumbnail_cache(app: tauri::AppHandle, trashed_only: Option<bool>) -> Result<usize, String> {
+    let removed = with_db(&app, |c| db::purge_thumbnails(c, trashed_only.unwrap_or(false)))?;
+    // Give the freed pages back to the filesystem
+    with_db(&app, |c| c.execute_batch("VACUUM"))?;
+    Ok(removed)
+}
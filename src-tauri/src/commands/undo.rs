@@ -0,0 +1,40 @@
+use tauri::{command, Manager};
+use crate::{get_db_path, with_db, db, UndoState};
+use crate::types::UndoReport;
+use super::activity::log_activity;
+
+/// A deletion that can still be taken back. Only the most recent one is kept,
+/// in memory, so it doesn't survive a restart.
+pub(crate) struct UndoableOperation {
+    /// The database the videos were deleted from; switching profiles or
+    /// locations leaves nothing to undo.
+    db_path: String,
+    kind: &'static str,
+    video_ids: Vec<String>,
+}
+
+/// Remember a deletion for `undo_last_operation`, replacing the previous one.
+pub(crate) fn remember_deletion(app: &tauri::AppHandle, kind: &'static str, video_ids: Vec<String>) {
+    if video_ids.is_empty() {
+        return;
+    }
+    let op = UndoableOperation { db_path: get_db_path(app), kind, video_ids };
+    *app.state::<UndoState>().0.lock().unwrap() = Some(op);
+}
+
+/// Restore the videos removed by the last delete or bulk delete, provided they
+/// are still in the trash. Each deletion can be undone once.
+#[command]
+pub fn undo_last_operation(app: tauri::AppHandle) -> Result<UndoReport, String> {
+    let op = app.state::<UndoState>().0.lock().unwrap().take();
+    let Some(op) = op.filter(|op| op.db_path == get_db_path(&app)) else {
+        return Err("Nothing to undo.".to_string());
+    };
+
+    let restored = with_db(&app, |c| db::restore_videos(c, &op.video_ids))?;
+    if restored.is_empty() {
+        return Err("The deleted videos are no longer in the trash.".to_string());
+    }
+    log_activity(&app, "restore", None, Some(&format!("Undid {} of {} videos", op.kind.replace('_', " "), restored.len())));
+    Ok(UndoReport { operation: op.kind.to_string(), video_ids: restored })
+}
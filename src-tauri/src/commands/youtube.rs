@@ -1,7 +1,12 @@
+use std::collections::HashMap;
 use serde_json::Value;
-use tauri::command;
-use crate::{get_db_path, db, types::*};
-use crate::youtube::{self, YouTubeClient, ClientType};
+use tauri::{command, Emitter};
+use crate::{with_db, db, types::*};
+use crate::youtube::{self, YouTubeClient, ClientType, ChannelTab};
+use crate::youtube::models::{PlayerResponse, SearchResponse, SearchSection};
+use crate::{activity, dearrow, sponsorblock, wayback};
+use super::activity::log_activity;
+use super::undo::remember_deletion;
 use html_escape;
 
 /// Decode HTML entities in a string
@@ -9,66 +14,353 @@ fn decode_html(text: &str) -> String {
     html_escape::decode_html_entities(text).to_string()
 }
 
+/// Minutes InnerTube responses are reused for unless `cache_ttl_minutes` says
+/// otherwise.
+const DEFAULT_CACHE_TTL_MINUTES: u64 = 10;
+
+/// Where cached InnerTube responses are written: a folder per profile, so one
+/// profile's browsing never answers another's.
+pub(crate) fn response_cache_dir(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
+    shared_response_cache_dir(app).map(|d| d.join(crate::active_profile(app)))
+}
+
+/// The folder every profile wrote its cached responses to before each got its own.
+fn shared_response_cache_dir(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
+    use tauri::Manager;
+    app.path().app_cache_dir().ok().map(|d| d.join("innertube"))
+}
+
+/// An InnerTube client carrying the library's YouTube settings: its language
+/// and region, how many times to try each request, how long to cache responses,
+/// the `po_token` setting when one is saved, and imported cookies while
+/// they're turned on.
+pub(super) fn youtube_client(app: &tauri::AppHandle, client_type: ClientType) -> YouTubeClient {
+    let get = |key: &str| with_db(app, |c| db::get_setting(c, key)).unwrap_or(None);
+    let max_attempts = get("max_attempts").and_then(|n| n.trim().parse().ok()).unwrap_or(youtube::DEFAULT_MAX_ATTEMPTS);
+    let ttl_minutes = get("cache_ttl_minutes").and_then(|n| n.trim().parse().ok()).unwrap_or(DEFAULT_CACHE_TTL_MINUTES);
+    let cache = youtube::ResponseCache::new(&crate::active_profile(app), response_cache_dir(app), std::time::Duration::from_secs(ttl_minutes * 60));
+    YouTubeClient::new(client_type, super::settings::saved_locale(app))
+        .with_po_token(get("po_token"))
+        .with_max_attempts(max_attempts)
+        .with_cache(cache)
+        .with_cookies(with_db(app, |c| db::get_credential(c, "youtube_cookies")).unwrap_or(None)
+            .filter(|_| get("use_cookies").as_deref() == Some("true")))
+}
+
+/// Forget cached InnerTube responses so the next requests go to YouTube,
+/// including any left in the folder profiles used to share. Returns how many
+/// were removed from disk.
+#[command]
+pub fn clear_response_cache(app: tauri::AppHandle) -> Result<usize, String> {
+    let shared = youtube::cache::clear(shared_response_cache_dir(&app).as_deref()).map_err(|e| e.to_string())?;
+    let own = youtube::cache::clear(response_cache_dir(&app).as_deref()).map_err(|e| e.to_string())?;
+    Ok(shared + own)
+}
+
+/// Whether the `dearrow` setting asks for crowdsourced titles and thumbnails.
+fn dearrow_enabled(app: &tauri::AppHandle) -> bool {
+    with_db(app, |c| db::get_setting(c, "dearrow")).unwrap_or(None).as_deref() == Some("true")
+}
+
+/// Swap in DeArrow titles and thumbnails when the `dearrow` setting is on.
+/// Videos DeArrow can't be reached for keep their own.
+async fn apply_dearrow<'a>(app: &tauri::AppHandle, videos: impl IntoIterator<Item = &'a mut Video>) {
+    if !dearrow_enabled(app) {
+        return;
+    }
+    let mut videos: Vec<&mut Video> = videos.into_iter().collect();
+    let lookups = videos.iter().map(|v| dearrow::fetch_branding(&v.id));
+    let brandings = futures::future::join_all(lookups).await;
+    for (video, branding) in videos.iter_mut().zip(brandings) {
+        match branding {
+            Ok(branding) => dearrow::apply(video, branding),
+            Err(e) => log::warn!("No DeArrow branding for {}: {}", video.id, e),
+        }
+    }
+}
+
+/// `page` with DeArrow branding applied to its videos.
+async fn with_dearrow(app: &tauri::AppHandle, mut page: VideoResponse) -> VideoResponse {
+    apply_dearrow(app, &mut page.videos).await;
+    page
+}
+
+/// How long a resolved handle is trusted. Handles rarely change hands, but
+/// they can be given up and claimed by another channel.
+const HANDLE_CACHE_DAYS: i64 = 30;
+
+/// The channel ID a channel URL, handle or ID names. Handles and `/c/` or
+/// `/user/` URLs are resolved once and then served from the profile's cache
+/// for `HANDLE_CACHE_DAYS`.
+async fn channel_id_for(app: &tauri::AppHandle, query: &str) -> Result<Option<String>, String> {
+    let channel = youtube::channel_ref(query);
+    // Legacy URLs are cached under the URL, which can't clash with a handle
+    let key = match &channel {
+        youtube::ChannelRef::Id(id) => return Ok(Some(id.clone())),
+        youtube::ChannelRef::Handle(handle) => handle.clone(),
+        youtube::ChannelRef::LegacyUrl(url) => url.clone(),
+    };
+    if let Some(id) = with_db(app, |c| db::get_cached_channel_id(c, &key, HANDLE_CACHE_DAYS)).unwrap_or(None) {
+        return Ok(Some(id));
+    }
+    let id = youtube::resolve_channel_ref(&channel).await?;
+    if let Some(id) = &id {
+        if let Err(e) = with_db(app, |c| db::cache_channel_id(c, &key, id)) {
+            log::warn!("Could not cache the channel ID for {}: {}", key, e);
+        }
+    }
+    Ok(id)
+}
+
 #[command]
-pub async fn resolve_channel(_app: tauri::AppHandle, query: String) -> Result<ChannelInfo, String> {
-    match youtube::extract_channel_id(&query).await? {
+pub async fn resolve_channel(app: tauri::AppHandle, query: String) -> Result<ChannelInfo, String> {
+    match channel_id_for(&app, &query).await? {
         Some(id) => Ok(ChannelInfo { channel_id: id, channel_name: query }),
         None => Err("Could not resolve channel.".to_string()),
     }
 }
 
+/// A channel's name, handle, avatar, banner and counts, from its page.
+#[command]
+pub async fn fetch_channel_info(app: tauri::AppHandle, query: String) -> Result<ChannelDetails, String> {
+    let channel_id = channel_id_for(&app, &query).await?.ok_or("Could not resolve channel.")?;
+    let client = youtube_client(&app, ClientType::Web);
+    let data = client.browse(Some(channel_id.clone()), None).await?;
+    if data["metadata"]["channelMetadataRenderer"].is_null() {
+        return Err("Channel not found".to_string());
+    }
+    Ok(youtube::parse_channel_details(&channel_id, &data))
+}
+
 #[command]
 pub async fn fetch_videos(
-    _app: tauri::AppHandle,
+    app: tauri::AppHandle,
     id: String,
     is_playlist: bool,
     continuation: Option<String>,
 ) -> Result<VideoResponse, String> {
-    let client = YouTubeClient::new(ClientType::Web);
-    let playlist_id = if is_playlist {
-        youtube::extract_playlist_id(&id)
-    } else {
-        let channel_id = youtube::extract_channel_id(&id).await?.ok_or("Channel not found")?;
-        youtube::channel_id_to_uploads_playlist(&channel_id)
-    };
+    let page = fetch_videos_page(&app, id, is_playlist, continuation).await?;
+    Ok(with_dearrow(&app, page).await)
+}
 
-    let browse_id = if playlist_id.starts_with("VL") { playlist_id } else { format!("VL{}", playlist_id) };
-    let data = client.browse(Some(browse_id), continuation).await?;
-    let mut videos = Vec::new();
+/// A page of a playlist or channel's uploads, before any DeArrow branding.
+async fn fetch_videos_page(app: &tauri::AppHandle, id: String, is_playlist: bool, continuation: Option<String>) -> Result<VideoResponse, String> {
+    let client = youtube_client(app, ClientType::Web);
+    if let Some(token) = continuation {
+        // Later pages are addressed by the token alone, for playlists and channels
+        return Ok(videos_from_page(&client.browse(None, Some(token)).await?));
+    }
+    if is_playlist {
+        return Ok(videos_from_page(&client.browse(Some(format!("VL{}", playlist_id(&id))), None).await?));
+    }
 
-    if let Some(tabs) = data["contents"]["twoColumnBrowseResultsRenderer"]["tabs"].as_array() {
-        if let Some(contents) = tabs[0]["tabRenderer"]["content"]["sectionListRenderer"]["contents"].as_array() {
-            if let Some(items) = contents[0]["itemSectionRenderer"]["contents"][0]["playlistVideoListRenderer"]["contents"].as_array() {
-                for item in items {
-                    if let Some(v_renderer) = item.get("playlistVideoRenderer") {
-                        if let Some(v_json) = youtube::extract_playlist_video_info(v_renderer) {
-                            if let Ok(mut v) = serde_json::from_value::<Video>(v_json) {
-                                v.date_added = None;
-                                videos.push(v);
-                            }
-                        }
-                    }
-                }
+    let channel_id = channel_id_for(app, &id).await?.ok_or("Channel not found")?;
+    match client.browse_channel_tab(&channel_id, ChannelTab::Videos).await {
+        Ok(data) => {
+            let page = videos_from_page(&data);
+            if !page.videos.is_empty() {
+                return Ok(page);
             }
+            log::warn!("No videos parsed from the Videos tab of {}; falling back to its feed", channel_id);
         }
+        Err(e) => log::warn!("Videos tab of {} failed: {}; falling back to its feed", channel_id, e),
     }
 
-    if let Some(actions) = data["onResponseReceivedActions"].as_array() {
-        if let Some(items) = actions[0]["appendContinuationItemsAction"]["continuationItems"].as_array() {
-            for item in items {
-                if let Some(v_renderer) = item.get("playlistVideoRenderer") {
-                    if let Some(v_json) = youtube::extract_playlist_video_info(v_renderer) {
-                        if let Ok(mut v) = serde_json::from_value::<Video>(v_json) {
-                            v.date_added = None;
-                            videos.push(v);
-                        }
-                    }
-                }
-            }
+    // The feed only has the latest 15 uploads and can't be paged
+    let videos = youtube::fetch_channel_feed(&channel_id).await?
+        .into_iter()
+        .filter_map(extracted::<Video>)
+        .collect();
+    Ok(VideoResponse { videos, continuation: None, source: "rss".to_string() })
+}
+
+/// Read a video or stream built by one of the `youtube::extract_*` helpers,
+/// logging the ones that don't fit rather than dropping them silently.
+fn extracted<T: serde::de::DeserializeOwned>(item: Value) -> Option<T> {
+    let id = item["id"].as_str().unwrap_or("?").to_string();
+    serde_json::from_value(item)
+        .map_err(|e| log::warn!("Skipping result {}: {}", id, e))
+        .ok()
+}
+
+/// Most pages `fetch_all_playlist_videos` follows, in case a playlist's
+/// continuations never run out. At about 100 videos a page, far beyond
+/// YouTube's 5,000-video playlist limit.
+const MAX_PLAYLIST_PAGES: usize = 100;
+
+/// Every video in a playlist, following its continuations to the end.
+/// Emits `playlist_fetch_progress` with the count so far after each page.
+#[command]
+pub async fn fetch_all_playlist_videos(app: tauri::AppHandle, id: String) -> Result<VideoResponse, String> {
+    let playlist_id = playlist_id(&id);
+    let mut videos = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let mut continuation = None;
+    for _ in 0..MAX_PLAYLIST_PAGES {
+        let page = fetch_videos_page(&app, playlist_id.clone(), true, continuation).await?;
+        let page = with_dearrow(&app, page).await;
+        videos.extend(page.videos);
+        let progress = serde_json::json!({ "playlistId": playlist_id, "fetched": videos.len() });
+        let _ = app.emit("playlist_fetch_progress", progress);
+
+        // A token seen before would only lead round again
+        continuation = page.continuation.filter(|token| seen.insert(token.clone()));
+        if continuation.is_none() {
+            break;
         }
     }
+    Ok(VideoResponse { videos, continuation: None, source: "innertube".to_string() })
+}
+
+/// The playlist ID in a playlist URL or bare ID, without the `VL` browse prefix.
+fn playlist_id(id: &str) -> String {
+    let list = youtube::url::extract_playlist_id(id).unwrap_or_else(|| id.trim().to_string());
+    list.strip_prefix("VL").map(String::from).unwrap_or(list)
+}
+
+/// A playlist's title, owner, description and size, to show before importing it.
+#[command]
+pub async fn fetch_playlist_info(app: tauri::AppHandle, id: String) -> Result<PlaylistDetails, String> {
+    let playlist_id = playlist_id(&id);
+    let client = youtube_client(&app, ClientType::Web);
+    let data = client.browse(Some(format!("VL{}", playlist_id)), None).await?;
+    if data["metadata"]["playlistMetadataRenderer"].is_null() && data["sidebar"].is_null() && data["header"].is_null() {
+        return Err("Playlist not found".to_string());
+    }
+    Ok(youtube::parse_playlist_details(&playlist_id, &data))
+}
+
+/// A page of a channel tab: the first when `continuation` is `None`, otherwise
+/// the one the token points to.
+async fn channel_tab_page(app: &tauri::AppHandle, client: &YouTubeClient, query: &str, tab: ChannelTab, continuation: Option<String>) -> Result<Value, String> {
+    match continuation {
+        Some(token) => client.browse(None, Some(token)).await,
+        None => {
+            let channel_id = channel_id_for(app, query).await?.ok_or("Channel not found")?;
+            client.browse_channel_tab(&channel_id, tab).await
+        }
+    }
+}
+
+/// The videos on a page of a playlist or a channel's Videos tab.
+fn videos_from_page(data: &Value) -> VideoResponse {
+    let items = youtube::playlist_video_items(data).unwrap_or_else(|| youtube::channel_tab_items(data));
+    let author = youtube::channel_title(data);
+
+    let mut videos = Vec::new();
+    for item in &items {
+        let v_json = match item.get("playlistVideoRenderer") {
+            Some(v_renderer) => youtube::extract_playlist_video_info(v_renderer),
+            None => youtube::extract_rich_item_info(item, author.as_deref()),
+        };
+        if let Some(mut v) = v_json.and_then(extracted::<Video>) {
+            v.date_added = None;
+            videos.push(v);
+        }
+    }
+
+    VideoResponse { videos, continuation: youtube::continuation_token(&items), source: "innertube".to_string() }
+}
+
+/// What's trending on YouTube in `category`, everything when it's `None`.
+/// The page isn't paged.
+#[command]
+pub async fn fetch_trending(app: tauri::AppHandle, category: Option<TrendingCategory>) -> Result<VideoResponse, String> {
+    let client = youtube_client(&app, ClientType::Web);
+    let data = client.browse_trending(category.unwrap_or_default()).await?;
+    let videos = youtube::trending_videos(&data)
+        .into_iter()
+        .filter_map(extracted::<Video>)
+        .collect();
+    Ok(with_dearrow(&app, VideoResponse { videos, continuation: None, source: "innertube".to_string() }).await)
+}
+
+/// Videos tagged with a hashtag, a page at a time. A leading `#` is optional.
+#[command]
+pub async fn fetch_hashtag(app: tauri::AppHandle, tag: String, continuation: Option<String>) -> Result<VideoResponse, String> {
+    let client = youtube_client(&app, ClientType::Web);
+    let data = match continuation {
+        Some(token) => client.browse(None, Some(token)).await?,
+        None => client.browse_hashtag(&tag).await?,
+    };
+    let items = youtube::channel_tab_items(&data);
+    let videos = items.iter()
+        .filter_map(|item| youtube::extract_rich_item_info(item, None))
+        .filter_map(extracted::<Video>)
+        .collect();
+    let continuation = youtube::continuation_token(&items);
+    Ok(with_dearrow(&app, VideoResponse { videos, continuation, source: "innertube".to_string() }).await)
+}
+
+/// List a channel's Shorts, a page at a time.
+#[command]
+pub async fn fetch_channel_shorts(app: tauri::AppHandle, query: String, continuation: Option<String>) -> Result<VideoResponse, String> {
+    let client = youtube_client(&app, ClientType::Web);
+    let data = channel_tab_page(&app, &client, &query, ChannelTab::Shorts, continuation).await?;
+    let items = youtube::channel_tab_items(&data);
+    let author = youtube::channel_title(&data);
+    let videos = items.iter()
+        .filter_map(|item| youtube::extract_short_info(item, author.as_deref()))
+        .filter_map(extracted::<Video>)
+        .collect();
+    let page = VideoResponse { videos, continuation: youtube::continuation_token(&items), source: "innertube".to_string() };
+    Ok(with_dearrow(&app, page).await)
+}
+
+/// List the playlists on a channel's Playlists tab, a page at a time, for
+/// importing a whole series at once.
+#[command]
+pub async fn fetch_channel_playlists(app: tauri::AppHandle, query: String, continuation: Option<String>) -> Result<PlaylistPage, String> {
+    let client = youtube_client(&app, ClientType::Web);
+    let data = channel_tab_page(&app, &client, &query, ChannelTab::Playlists, continuation).await?;
+    let (playlists, continuation) = youtube::channel_playlists(&data);
+    Ok(PlaylistPage { playlists, continuation })
+}
+
+/// A page of a channel's Community tab: post text, images and polls. With
+/// `archive`, the posts are also kept in the library, so they outlive
+/// deletion on YouTube.
+#[command]
+pub async fn fetch_community_posts(app: tauri::AppHandle, query: String, continuation: Option<String>, archive: Option<bool>) -> Result<CommunityPage, String> {
+    let client = youtube_client(&app, ClientType::Web);
+    let data = channel_tab_page(&app, &client, &query, ChannelTab::Community, continuation).await?;
+    let (posts, continuation) = youtube::community_posts(&data);
+    if archive.unwrap_or(false) && !posts.is_empty() {
+        with_db(&app, |c| {
+            let tx = c.unchecked_transaction()?;
+            db::save_community_posts(&tx, &posts)?;
+            tx.commit()
+        })?;
+    }
+    Ok(CommunityPage { posts, continuation })
+}
+
+/// A channel's archived Community posts, newest first.
+#[command]
+pub async fn list_archived_posts(app: tauri::AppHandle, channel_id: String) -> Result<Vec<CommunityPost>, String> {
+    with_db(&app, |c| db::get_community_posts(c, channel_id.trim()))
+}
+
+/// List a channel's Live tab: past streams, anything live now and scheduled
+/// streams and premieres.
+#[command]
+pub async fn fetch_channel_streams(app: tauri::AppHandle, query: String, continuation: Option<String>) -> Result<LiveStreamResponse, String> {
+    let client = youtube_client(&app, ClientType::Web);
+    let data = channel_tab_page(&app, &client, &query, ChannelTab::Live, continuation).await?;
+    let items = youtube::channel_tab_items(&data);
+    let author = youtube::channel_title(&data);
+    let streams = items.iter()
+        .filter_map(|item| youtube::extract_stream_info(item, author.as_deref()))
+        .filter_map(extracted::<LiveStream>)
+        .collect();
+    Ok(LiveStreamResponse { streams, continuation: youtube::continuation_token(&items) })
+}
 
-    Ok(VideoResponse { videos, continuation: None })
+/// A GET to the YouTube Data API, waiting its turn with every other request
+/// to YouTube.
+async fn data_api_get(client: &reqwest::Client, url: &str) -> Result<Value, String> {
+    youtube::throttle::wait().await;
+    client.get(url).send().await.map_err(|e| e.to_string())?.json().await.map_err(|e| e.to_string())
 }
 
 #[command]
@@ -77,16 +369,11 @@ pub async fn fetch_channel_videos_v3(
     query: String,
     continuation: Option<String>,
 ) -> Result<VideoResponse, String> {
-    let db_path = get_db_path(&app);
-    let api_key = db::get_setting(&db_path, "api_key").unwrap_or(None).ok_or("API Key not found")?;
-    let channel_id = youtube::extract_channel_id(&query).await?.unwrap_or(query);
-    let client = reqwest::Client::new();
+    let api_key = with_db(&app, |c| db::get_setting(c, "api_key")).unwrap_or(None).ok_or("API Key not found")?;
+    let channel_id = channel_id_for(&app, &query).await?.unwrap_or(query);
+    let client = youtube::http_client();
 
-    let uploads_playlist_id = if channel_id.starts_with("UC") {
-        format!("UU{}", &channel_id[2..])
-    } else {
-        channel_id.clone()
-    };
+    let uploads_playlist_id = youtube::channel_id_to_uploads_playlist(&channel_id);
 
     let mut url = format!(
         "https://youtube.googleapis.com/youtube/v3/playlistItems?part=snippet,contentDetails&maxResults=50&playlistId={}&key={}",
@@ -96,7 +383,7 @@ pub async fn fetch_channel_videos_v3(
         url = format!("{}&pageToken={}", url, token);
     }
 
-    let mut res: Value = client.get(&url).send().await.map_err(|e| e.to_string())?.json().await.map_err(|e| e.to_string())?;
+    let mut res = data_api_get(&client, &url).await?;
 
     if res.get("error").is_some() {
         let mut search_url = format!(
@@ -106,7 +393,7 @@ pub async fn fetch_channel_videos_v3(
         if let Some(token) = continuation {
             search_url = format!("{}&pageToken={}", search_url, token);
         }
-        res = client.get(&search_url).send().await.map_err(|e| e.to_string())?.json().await.map_err(|e| e.to_string())?;
+        res = data_api_get(&client, &search_url).await?;
         if res.get("error").is_some() {
             return Err(format!("API Error: {}", res["error"]["message"].as_str().unwrap_or("Unknown")));
         }
@@ -133,8 +420,7 @@ pub async fn fetch_channel_videos_v3(
                     published_at: snippet["publishedAt"].as_str().unwrap_or("").to_string(),
                     view_count: "0".to_string(),
                     author: snippet["channelTitle"].as_str().map(|s| decode_html(s)),
-                    handle: None, status: None, date_added: None,
-                    length_seconds: None, video_type: None,
+                    ..Default::default()
                 });
             }
         }
@@ -145,14 +431,12 @@ pub async fn fetch_channel_videos_v3(
             "https://youtube.googleapis.com/youtube/v3/videos?part=statistics&id={}&key={}",
             video_ids.join(","), api_key
         );
-        if let Ok(stats_res) = client.get(&stats_url).send().await {
-            if let Ok(stats_data) = stats_res.json::<Value>().await {
-                if let Some(items) = stats_data["items"].as_array() {
-                    for item in items {
-                        if let Some(vid) = item["id"].as_str() {
-                            if let Some(v) = videos.iter_mut().find(|v| v.id == vid) {
-                                v.view_count = item["statistics"]["viewCount"].as_str().unwrap_or("0").to_string();
-                            }
+        if let Ok(stats_data) = data_api_get(&client, &stats_url).await {
+            if let Some(items) = stats_data["items"].as_array() {
+                for item in items {
+                    if let Some(vid) = item["id"].as_str() {
+                        if let Some(v) = videos.iter_mut().find(|v| v.id == vid) {
+                            v.view_count = item["statistics"]["viewCount"].as_str().unwrap_or("0").to_string();
                         }
                     }
                 }
@@ -160,277 +444,1174 @@ pub async fn fetch_channel_videos_v3(
         }
     }
 
-    Ok(VideoResponse { videos, continuation: next_page_token })
+    Ok(with_dearrow(&app, VideoResponse { videos, continuation: next_page_token, source: "data_api".to_string() }).await)
 }
 
+/// A video's player response from `client`, or from the TV embedded client
+/// when `client` is refused because the video is age-restricted. Fails with
+/// the `youtube::Unplayable` message when YouTube won't play it at all.
+async fn fetch_player(app: &tauri::AppHandle, client: &YouTubeClient, video_id: &str) -> Result<Value, String> {
+    fetch_playable(app, client, video_id).await.map_err(|e| e.to_string())
+}
+
+/// `fetch_player`, keeping why it failed.
+async fn fetch_playable(app: &tauri::AppHandle, client: &YouTubeClient, video_id: &str) -> Result<Value, SaveError> {
+    let mut player = client.player(video_id).await.map_err(SaveError::Request)?;
+    if youtube::is_age_restricted(&player) {
+        log::info!("{} is age-restricted; retrying with the TV embedded client", video_id);
+        player = youtube_client(app, ClientType::TvEmbedded).player(video_id).await.map_err(SaveError::Request)?;
+        if player["videoDetails"].is_null() {
+            let reason = player["playabilityStatus"]["reason"].as_str().unwrap_or("no player could fetch it").to_string();
+            return Err(SaveError::Unplayable(youtube::Unplayable::AgeRestricted(reason)));
+        }
+        return Ok(player);
+    }
+    match youtube::Unplayable::from_player(&player) {
+        Some(unplayable) => Err(SaveError::Unplayable(unplayable)),
+        None => Ok(player),
+    }
+}
+
+/// How many videos bulk operations work on at once. Enough to overlap the
+/// round trips without hammering YouTube.
+const FETCH_CONCURRENCY: usize = 4;
+
+/// Run `f` for every ID, at most `FETCH_CONCURRENCY` at a time, and return
+/// the results in the order of `ids`.
+async fn for_each_concurrently<T, Fut>(ids: &[String], f: impl Fn(String) -> Fut) -> Vec<T>
+where
+    Fut: std::future::Future<Output = T>,
+{
+    let permits = tokio::sync::Semaphore::new(FETCH_CONCURRENCY);
+    let permits = &permits;
+    let tasks = ids.iter().map(|id| {
+        let task = f(id.clone());
+        async move {
+            let _permit = permits.acquire().await;
+            task.await
+        }
+    });
+    futures::future::join_all(tasks).await
+}
+
+/// Player responses for many videos, fetched concurrently, in the order of
+/// `ids`.
+async fn fetch_players(app: &tauri::AppHandle, ids: &[String]) -> Vec<Result<Value, String>> {
+    let client = youtube_client(app, ClientType::Web);
+    let client = &client;
+    for_each_concurrently(ids, |id| async move { fetch_player(app, client, &id).await }).await
+}
+
+#[command]
+pub async fn fetch_view_count(app: tauri::AppHandle, video_id: String) -> Result<String, String> {
+    let client = youtube_client(&app, ClientType::Web);
+    let data = fetch_player(&app, &client, &video_id).await?;
+    Ok(view_count_of(&data))
+}
+
+/// Current view counts for many videos, by ID, for refreshing a list at
+/// once. Videos YouTube won't play are left out.
 #[command]
-pub async fn fetch_view_count(_app: tauri::AppHandle, video_id: String) -> Result<String, String> {
-    let client = YouTubeClient::new(ClientType::Web);
-    let data = client.player(&video_id).await?;
-    Ok(data["videoDetails"]["viewCount"].as_str().unwrap_or("0").to_string())
+pub async fn fetch_view_counts(app: tauri::AppHandle, video_ids: Vec<String>) -> Result<HashMap<String, String>, String> {
+    let players = fetch_players(&app, &video_ids).await;
+    let mut counts = HashMap::new();
+    for (id, player) in video_ids.into_iter().zip(players) {
+        match player {
+            Ok(data) => {
+                counts.insert(id, view_count_of(&data));
+            }
+            Err(e) => log::warn!("No view count for {}: {}", id, e),
+        }
+    }
+    Ok(counts)
+}
+
+/// The view count in a player response, "0" when it has none.
+fn view_count_of(player: &Value) -> String {
+    Some(PlayerResponse::parse(player).video_details.view_count).filter(|v| !v.is_empty()).unwrap_or_else(|| "0".to_string())
 }
 
 #[command]
-pub async fn fetch_video_info(_app: tauri::AppHandle, video_id: String) -> Result<Video, String> {
+pub async fn fetch_video_info(app: tauri::AppHandle, video_id: String) -> Result<Video, String> {
     use crate::types::{parse_view_count, extract_handle_from_url};
-    let client = YouTubeClient::new(ClientType::Web);
-    let data = client.player(&video_id).await?;
-    let details = &data["videoDetails"];
-    let published_at = data["microformat"]["playerMicroformatRenderer"]["publishDate"].as_str().unwrap_or("").to_string();
+    let client = youtube_client(&app, ClientType::Web);
+    let data = fetch_player(&app, &client, &video_id).await?;
+    let player = PlayerResponse::parse(&data);
+    let details = &player.video_details;
+    let live = youtube::LiveState::from_player(&data);
+    let flags = youtube::VideoFlags::from_player(&data);
+    let published_at = player.microformat().map(|m| m.publish_date.clone()).unwrap_or_default();
+    let engagement = youtube::parse_engagement(&fetch_next_or_null(&client, &video_id).await);
 
-    let author = if let Some(authors) = details["author"].as_array() {
-        authors.first().and_then(|a| a["name"].as_str()).map(|s| s.to_string())
-    } else {
-        details["author"].as_str().map(|s| s.to_string())
+    let author = details.author.name().map(String::from);
+    let handle = player.microformat().and_then(|m| m.owner_profile_url.as_deref()).and_then(extract_handle_from_url)
+        .or_else(|| details.author.url().and_then(extract_handle_from_url));
+
+    let mut video = Video {
+        id: Some(details.video_id.as_str()).filter(|id| !id.is_empty()).unwrap_or(&video_id).to_string(),
+        title: decode_html(Some(details.title.as_str()).filter(|t| !t.is_empty()).unwrap_or("Unknown")),
+        thumbnail: details.thumbnail.thumbnails.last().map(|t| t.url.clone()).unwrap_or_default(),
+        published_at,
+        view_count: parse_view_count(&details.view_count).to_string(),
+        author,
+        handle,
+        like_count: engagement.like_count,
+        comment_count: engagement.comment_count,
+        is_live: live.is_live,
+        is_upcoming: live.is_upcoming,
+        scheduled_start: live.scheduled_start,
+        is_private: Some(flags.is_private),
+        is_unlisted: Some(flags.is_unlisted),
+        is_live_content: Some(flags.is_live_content),
+        ..Default::default()
     };
+    apply_dearrow(&app, std::slice::from_mut(&mut video)).await;
+    Ok(video)
+}
 
-    let mut handle: Option<String> = None;
-    if let Some(url) = data["microformat"]["playerMicroformatRenderer"]["ownerProfileUrl"].as_str() {
-        handle = extract_handle_from_url(url);
-    }
-    if handle.is_none() {
-        if let Some(authors) = details["author"].as_array() {
-            if let Some(first) = authors.first() {
-                if let Some(url) = first["url"].as_str() {
-                    handle = extract_handle_from_url(url);
+/// The caption language to fetch: `requested`, else the `transcript_language`
+/// setting, else the interface language.
+fn transcript_language(app: &tauri::AppHandle, requested: Option<String>) -> String {
+    requested
+        .or_else(|| with_db(app, |c| db::get_setting(c, "transcript_language")).unwrap_or(None))
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| super::settings::saved_locale(app).hl)
+}
+
+/// A transcript in the requested `format`. Only plain text works without timings.
+fn transcript_output(format: TranscriptFormat, text: String, segments: Vec<TranscriptSegment>) -> Result<TranscriptOutput, String> {
+    if format != TranscriptFormat::Plain && segments.is_empty() {
+        return Err("This transcript has no timestamps.".to_string());
+    }
+    Ok(match format {
+        TranscriptFormat::Plain => TranscriptOutput::Text(text),
+        TranscriptFormat::Segments => TranscriptOutput::Segments(segments),
+        TranscriptFormat::Srt => TranscriptOutput::Text(crate::captions::to_srt(&segments)),
+    })
+}
+
+/// A video's transcript, preferring captions in `language` (see
+/// `transcript_language`), as plain text by default or in another `format`.
+/// A saved transcript is returned as is unless a different language is asked
+/// for, or timings are wanted and it was saved without them.
+#[command]
+pub async fn fetch_transcript(app: tauri::AppHandle, video_id: String, language: Option<String>, format: Option<TranscriptFormat>) -> Result<TranscriptOutput, String> {
+    let video_id = video_id.trim().to_string();
+    let format = format.unwrap_or_default();
+
+    if let Ok(Some(t)) = with_db(&app, |c| db::get_transcript(c, &video_id)) {
+        let saved_language = with_db(&app, |c| db::get_transcript_language(c, &video_id)).unwrap_or(None);
+        let wanted = language.is_none() || saved_language == language;
+        if wanted && !t.trim().is_empty() {
+            let segments = match format {
+                TranscriptFormat::Plain => Vec::new(),
+                _ => with_db(&app, |c| db::get_transcript_segments(c, &video_id)).unwrap_or_default(),
+            };
+            if format == TranscriptFormat::Plain || !segments.is_empty() {
+                return transcript_output(format, t, segments);
+            }
+        }
+    }
+    let language = transcript_language(&app, language);
+
+    let api_key = with_db(&app, |c| db::get_setting(c, "api_key")).unwrap_or(None);
+    if api_key.is_none() || api_key.unwrap().trim().is_empty() {
+        return Err("API_KEY_MISSING".to_string());
+    }
+
+    match youtube::fetch_transcript_from_any_client(&video_id, &language, false, |t| youtube_client(&app, t)).await {
+        Ok(Some(t)) => transcript_output(format, t.text, t.segments),
+        Ok(None) => Err("No transcript available for this video.".to_string()),
+        Err(e) => Err(format!("Player API error: {}", e)),
+    }
+}
+
+/// The sponsor, self-promotion, intro and outro segments SponsorBlock users
+/// have marked in a video.
+#[command]
+pub async fn fetch_sponsor_segments(_app: tauri::AppHandle, video_id: String) -> Result<Vec<SponsorSegment>, String> {
+    sponsorblock::fetch_segments(video_id.trim()).await
+}
+
+/// The caption tracks a video offers, asking each caption client in turn since
+/// some are only shown tracks by one of them.
+#[command]
+pub async fn list_caption_tracks(app: tauri::AppHandle, video_id: String) -> Result<Vec<CaptionTrack>, String> {
+    let mut last_error = None;
+    let mut answered = false;
+    for client_type in ClientType::CAPTION_FALLBACKS {
+        match youtube_client(&app, client_type).player(video_id.trim()).await {
+            Ok(player) => {
+                let tracks = youtube::parse_caption_tracks(&player);
+                if !tracks.is_empty() {
+                    return Ok(tracks);
                 }
+                answered = true;
             }
+            Err(e) => last_error = Some(e),
         }
     }
+    match last_error {
+        Some(e) if !answered => Err(e),
+        _ => Ok(Vec::new()),
+    }
+}
 
-    Ok(Video {
-        id: details["videoId"].as_str().unwrap_or(&video_id).to_string(),
-        title: decode_html(details["title"].as_str().unwrap_or("Unknown").as_ref()),
-        thumbnail: details["thumbnail"]["thumbnails"].as_array()
-            .and_then(|a| a.last())
-            .and_then(|t| t["url"].as_str())
-            .unwrap_or("").to_string(),
-        published_at,
-        view_count: parse_view_count(details["viewCount"].as_str().unwrap_or("0")).to_string(),
-        author, handle, status: None, date_added: None,
-        length_seconds: None, video_type: None,
+/// Save a saved video's captions in `language` as an extra transcript track,
+/// alongside its main transcript.
+#[command]
+pub async fn save_transcript_track(app: tauri::AppHandle, video_id: String, language: String) -> Result<TranscriptTrack, String> {
+    let (video_id, language) = (video_id.trim(), language.trim());
+    if !with_db(&app, |c| db::check_video_exists(c, video_id))? {
+        return Err("Save the video before adding transcript tracks.".to_string());
+    }
+    if with_db(&app, |c| db::list_transcript_tracks(c, video_id))?.first().is_some_and(|t| t.language == language) {
+        return Err(format!("The main transcript is already in {}.", language));
+    }
+    let transcript = youtube::fetch_transcript_from_any_client(video_id, language, false, |t| youtube_client(&app, t)).await?
+        .filter(|t| t.language.as_deref().is_some_and(|l| youtube::same_language(l, language)))
+        .ok_or_else(|| format!("No {} captions for this video.", language))?;
+    // Filed under the track's own code, which may name a region as well
+    let language = transcript.language.as_deref().unwrap_or(language);
+    with_db(&app, |c| db::save_transcript_track(c, video_id, language, &transcript.text, &transcript.segments))?;
+    Ok(TranscriptTrack { language: language.to_string(), main: false })
+}
+
+/// The transcripts saved for a video, the main one first.
+#[command]
+pub async fn list_transcript_tracks(app: tauri::AppHandle, video_id: String) -> Result<Vec<TranscriptTrack>, String> {
+    with_db(&app, |c| db::list_transcript_tracks(c, video_id.trim()))
+}
+
+/// A saved transcript track in `language`, as plain text unless another
+/// `format` is asked for.
+#[command]
+pub async fn get_transcript_track(app: tauri::AppHandle, video_id: String, language: String, format: Option<TranscriptFormat>) -> Result<TranscriptOutput, String> {
+    let (text, segments) = with_db(&app, |c| db::get_transcript_track(c, video_id.trim(), language.trim()))?
+        .ok_or_else(|| format!("No {} transcript is saved for this video.", language.trim()))?;
+    transcript_output(format.unwrap_or_default(), text, segments)
+}
+
+#[command]
+pub async fn delete_transcript_track(app: tauri::AppHandle, video_id: String, language: String) -> Result<(), String> {
+    with_db(&app, |c| db::delete_transcript_track(c, video_id.trim(), language.trim()))?;
+    Ok(())
+}
+
+/// A video's transcript in `lang`, machine-translated by YouTube when the video
+/// has no captions in that language. Nothing is saved.
+#[command]
+pub async fn fetch_transcript_translated(app: tauri::AppHandle, video_id: String, lang: String) -> Result<String, String> {
+    let lang = lang.trim();
+    if lang.is_empty() {
+        return Err("No language given.".to_string());
+    }
+    match youtube::fetch_transcript_from_any_client(video_id.trim(), lang, true, |t| youtube_client(&app, t)).await {
+        Ok(Some(t)) => Ok(t.text),
+        Ok(None) => Err("No transcript available for this video.".to_string()),
+        Err(e) => Err(format!("Player API error: {}", e)),
+    }
+}
+
+/// The watch page data for a video, or `Null` if it couldn't be fetched; the
+/// chapters and counts read from it are extras a save can do without.
+async fn fetch_next_or_null(client: &YouTubeClient, video_id: &str) -> Value {
+    client.next(Some(video_id), None).await.unwrap_or_else(|e| {
+        log::warn!("Failed to fetch watch page data for {}: {}", video_id, e);
+        Value::Null
     })
 }
 
+/// Videos YouTube suggests alongside `video_id`, a batch at a time.
 #[command]
-pub async fn fetch_transcript(app: tauri::AppHandle, video_id: String) -> Result<String, String> {
-    let video_id = video_id.trim().to_string();
-    let db_path = get_db_path(&app);
+pub async fn fetch_related(app: tauri::AppHandle, video_id: String, continuation: Option<String>) -> Result<VideoResponse, String> {
+    let client = youtube_client(&app, ClientType::Web);
+    let data = match continuation {
+        Some(token) => client.next(None, Some(token)).await?,
+        None => client.next(Some(&video_id), None).await?,
+    };
+    let (videos, continuation) = youtube::related_videos(&data);
+    let videos = videos.into_iter()
+        .filter_map(extracted::<Video>)
+        .collect();
+    Ok(with_dearrow(&app, VideoResponse { videos, continuation, source: "innertube".to_string() }).await)
+}
+
+/// A video's chapters: the markers on its player, or failing that the
+/// timestamps in its description.
+fn chapters_or_description(next: &Value, description: &str) -> Vec<Chapter> {
+    let chapters = youtube::parse_chapters(next);
+    if chapters.is_empty() { youtube::chapters_from_description(description) } else { chapters }
+}
 
-    if let Ok(Some(t)) = db::get_transcript(&db_path, &video_id) {
-        if !t.trim().is_empty() { return Ok(t); }
+/// A video's chapters, from the library if they were stored when it was saved.
+/// Saved videos that predate chapter tracking get theirs stored now.
+#[command]
+pub async fn fetch_chapters(app: tauri::AppHandle, video_id: String) -> Result<Vec<Chapter>, String> {
+    let video_id = video_id.trim();
+    let stored = with_db(&app, |c| db::get_chapters(c, video_id))?;
+    if !stored.is_empty() {
+        return Ok(stored);
     }
 
-    let api_key = db::get_setting(&db_path, "api_key").unwrap_or(None);
-    if api_key.is_none() || api_key.unwrap().trim().is_empty() {
-        return Err("API_KEY_MISSING".to_string());
+    let client = youtube_client(&app, ClientType::Web);
+    let player = fetch_player(&app, &client, video_id).await?;
+    let description = PlayerResponse::parse(&player).video_details.short_description;
+    let chapters = chapters_or_description(&fetch_next_or_null(&client, video_id).await, &description);
+    if !chapters.is_empty() && with_db(&app, |c| db::check_video_exists(c, video_id))? {
+        with_db(&app, |c| {
+            let tx = c.unchecked_transaction()?;
+            db::replace_chapters(&tx, video_id, &chapters)?;
+            tx.commit()
+        })?;
+    }
+    Ok(chapters)
+}
+
+/// How much each stretch of a video is replayed, from 0 to 1. Comes from the
+/// library when it was stored with the video; saved videos that predate
+/// heatmaps get theirs stored now. Empty when YouTube doesn't show one.
+#[command]
+pub async fn fetch_heatmap(app: tauri::AppHandle, video_id: String) -> Result<Vec<HeatmapPoint>, String> {
+    let video_id = video_id.trim();
+    let stored = with_db(&app, |c| db::get_heatmap(c, video_id))?;
+    if !stored.is_empty() {
+        return Ok(stored);
+    }
+
+    let client = youtube_client(&app, ClientType::Web);
+    let heatmap = youtube::parse_heatmap(&client.next(Some(video_id), None).await?);
+    if !heatmap.is_empty() && with_db(&app, |c| db::check_video_exists(c, video_id))? {
+        with_db(&app, |c| {
+            let tx = c.unchecked_transaction()?;
+            db::replace_heatmap(&tx, video_id, &heatmap)?;
+            tx.commit()
+        })?;
+    }
+    Ok(heatmap)
+}
+
+/// Scrubbing preview frames for a video, from its player response. `None`
+/// when YouTube has none, as for very short videos.
+#[command]
+pub async fn fetch_storyboard(app: tauri::AppHandle, video_id: String) -> Result<Option<Storyboard>, String> {
+    let player = fetch_player(&app, &youtube_client(&app, ClientType::Web), video_id.trim()).await?;
+    Ok(youtube::parse_storyboard(&player))
+}
+
+/// A song's details and lyrics from YouTube Music. Lyrics stored with a saved
+/// video are served from the library; otherwise they are fetched, and kept
+/// when the video is saved. `lyrics` is `None` when YouTube Music has none.
+#[command]
+pub async fn fetch_song(app: tauri::AppHandle, video_id: String) -> Result<Song, String> {
+    let video_id = video_id.trim();
+    let client = youtube_client(&app, ClientType::Music);
+    let player = client.player(video_id).await?;
+    if player["videoDetails"].is_null() {
+        return Err(youtube::Unplayable::from_player(&player)
+            .map(|u| u.to_string())
+            .unwrap_or_else(|| "YouTube Music doesn't know this video.".to_string()));
+    }
+    let next = fetch_next_or_null(&client, video_id).await;
+    let mut song = youtube::music::parse_song(video_id, &player, &next);
+
+    song.lyrics = with_db(&app, |c| db::get_lyrics(c, video_id))?;
+    if song.lyrics.is_none() {
+        if let Some(browse_id) = youtube::music::lyrics_browse_id(&next) {
+            song.lyrics = youtube::music::parse_lyrics(&client.browse(Some(browse_id), None).await?);
+        }
+        if let Some(lyrics) = &song.lyrics {
+            with_db(&app, |c| db::save_lyrics(c, video_id, lyrics))?;
+        }
     }
+    Ok(song)
+}
+
+/// A page of a video's top comments; the first when `continuation` is `None`.
+#[command]
+pub async fn fetch_comments(app: tauri::AppHandle, video_id: String, continuation: Option<String>) -> Result<CommentPage, String> {
+    let client = youtube_client(&app, ClientType::Web);
+    let token = match continuation {
+        Some(token) => token,
+        None => match youtube::comments_continuation(&client.next(Some(video_id.trim()), None).await?) {
+            Some(token) => token,
+            // Comments are turned off
+            None => return Ok(CommentPage { comments: Vec::new(), continuation: None }),
+        },
+    };
+    let (comments, continuation) = youtube::parse_comments(&client.next(None, Some(token)).await?);
+    Ok(CommentPage { comments, continuation })
+}
 
-    let client = YouTubeClient::new(ClientType::Android);
-    let mut attempts = 0;
-    loop {
-        attempts += 1;
-        match client.player(&video_id).await {
-            Ok(player_json) => match youtube::fetch_transcript(&player_json).await {
-                Ok(Some(t)) if !t.trim().is_empty() => return Ok(t),
-                Ok(_) | Err(_) if attempts < 3 => {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    continue;
+/// Comments archived when the video was saved, in their original order.
+#[command]
+pub async fn get_saved_comments(app: tauri::AppHandle, video_id: String) -> Result<Vec<Comment>, String> {
+    with_db(&app, |c| db::get_comments(c, video_id.trim()))
+}
+
+/// Up to `limit` top comments, following continuations from the watch page.
+/// Stops quietly at whatever it has if a page fails.
+async fn fetch_top_comments(client: &YouTubeClient, video_id: &str, next: &Value, limit: usize) -> Vec<Comment> {
+    let mut comments = Vec::new();
+    let mut token = youtube::comments_continuation(next);
+    while comments.len() < limit {
+        let Some(t) = token.take() else { break };
+        match client.next(None, Some(t)).await {
+            Ok(page) => {
+                let (page_comments, next_token) = youtube::parse_comments(&page);
+                if page_comments.is_empty() {
+                    break;
                 }
-                Ok(_) => return Err("No transcript available for this video.".to_string()),
-                Err(e) => return Err(format!("Transcript error: {}", e)),
-            },
+                comments.extend(page_comments);
+                token = next_token;
+            }
             Err(e) => {
-                if attempts < 3 {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    continue;
-                }
-                return Err(format!("Player API error: {}", e));
+                log::warn!("Failed to fetch comments for {}: {}", video_id, e);
+                break;
             }
         }
     }
+    comments.truncate(limit);
+    comments
 }
 
-#[command]
-pub async fn save_video(app: tauri::AppHandle, video_id: String, summary: Option<String>) -> Result<Video, String> {
+/// Everything a save needs from YouTube, gathered before touching the database
+/// so the writes can be batched.
+struct FetchedVideo {
+    video_id: String,
+    title: String,
+    /// The uploader's title when `title` came from DeArrow.
+    original_title: Option<String>,
+    author: String,
+    handle: Option<String>,
+    length: i32,
+    transcript: String,
+    segments: Vec<TranscriptSegment>,
+    transcript_language: Option<String>,
+    captions_auto_generated: bool,
+    keywords: Vec<String>,
+    category: Option<String>,
+    flags: youtube::VideoFlags,
+    view_count: i64,
+    published_at: String,
+    video_type: &'static str,
+    description: String,
+    channel_id: Option<String>,
+    avatar: Option<String>,
+    thumbnail: Option<(String, Vec<u8>)>,
+    chapters: Vec<Chapter>,
+    heatmap: Vec<HeatmapPoint>,
+    engagement: youtube::Engagement,
+    comments: Vec<Comment>,
+}
+
+/// The library entry for an already-saved video, updating its summary if one
+/// is given. `None` if the video isn't saved.
+fn existing_video(app: &tauri::AppHandle, video_id: &str, summary: Option<&str>) -> Option<Video> {
+    let mut video = with_db(app, |c| db::get_video_details(c, video_id)).ok().flatten()?.video;
+    if let Some(s) = summary {
+        let _ = with_db(app, |c| db::save_summary(c, video_id, s));
+    }
+    video.status = Some("exists".to_string());
+    video.collections = with_db(app, |c| db::get_video_collections(c, video_id)).unwrap_or_default();
+    Some(video)
+}
+
+/// Why a video couldn't be saved, kept apart from its message so bulk saves
+/// can report a stable code for it.
+#[derive(Debug, Clone)]
+enum SaveError {
+    /// YouTube won't play it.
+    Unplayable(youtube::Unplayable),
+    NoTranscript,
+    /// A request to YouTube failed.
+    Request(String),
+    /// Writing it to the library failed.
+    Database(String),
+}
+
+impl SaveError {
+    /// A stable code the UI can group failures on.
+    fn code(&self) -> &'static str {
+        use youtube::Unplayable;
+        match self {
+            Self::Unplayable(Unplayable::Private) => "PRIVATE",
+            Self::Unplayable(Unplayable::Removed(_)) => "REMOVED",
+            Self::Unplayable(Unplayable::RegionLocked) => "REGION_LOCKED",
+            Self::Unplayable(Unplayable::LiveOnly) => "LIVE_ONLY",
+            Self::Unplayable(Unplayable::AgeRestricted(_)) => "AGE_RESTRICTED",
+            Self::Unplayable(Unplayable::MembersOnly) => "MEMBERS_ONLY",
+            Self::Unplayable(Unplayable::Other(_)) => "UNPLAYABLE",
+            Self::NoTranscript => "NO_TRANSCRIPT",
+            Self::Request(_) => "NETWORK",
+            Self::Database(_) => "DATABASE",
+        }
+    }
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unplayable(unplayable) => unplayable.fmt(f),
+            Self::NoTranscript => write!(f, "Cannot save video without transcript."),
+            Self::Request(message) | Self::Database(message) => f.write_str(message),
+        }
+    }
+}
+
+/// What `fetch_for_save` found for a video.
+enum Fetched {
+    Ready(FetchedVideo),
+    /// Live now or yet to premiere, so there's no transcript to save yet. The
+    /// entry has the `live` or `upcoming` status and, for premieres, the time.
+    NotYetAvailable(Video),
+}
+
+/// The entry returned for a video that is live or yet to start instead of
+/// saving it.
+fn not_yet_available(video_id: &str, player: &serde_json::Value, live: youtube::LiveState) -> Video {
+    let details = PlayerResponse::parse(player).video_details;
+    let flags = youtube::VideoFlags::from_player(player);
+    Video {
+        id: video_id.to_string(),
+        title: decode_html(Some(details.title.as_str()).filter(|t| !t.is_empty()).unwrap_or("Unknown")),
+        thumbnail: youtube::thumbnail_url(video_id, ThumbnailQuality::Hq),
+        published_at: String::new(),
+        view_count: "0".to_string(),
+        author: details.author.name().map(decode_html),
+        status: Some(if live.is_live { "live" } else { "upcoming" }.to_string()),
+        is_live: live.is_live,
+        is_upcoming: live.is_upcoming,
+        scheduled_start: live.scheduled_start,
+        is_private: Some(flags.is_private),
+        is_unlisted: Some(flags.is_unlisted),
+        is_live_content: Some(flags.is_live_content),
+        ..Default::default()
+    }
+}
+
+/// Fetch a video's details, transcript, channel avatar and thumbnail, plus up
+/// to `comment_limit` of its top comments.
+async fn fetch_for_save(app: &tauri::AppHandle, video_id: &str, comment_limit: usize) -> Result<Fetched, SaveError> {
     use crate::types::{parse_view_count, extract_handle_from_url};
-    let db_path = get_db_path(&app);
-
-    if let Ok(Some(v_data)) = db::get_video_full(&db_path, &video_id) {
-        // Update summary if provided
-        if let Some(ref s) = summary {
-            let _ = db::save_summary(&db_path, &video_id, s);
-        }
-
-        return Ok(Video {
-            id: v_data.0,
-            title: v_data.1,
-            thumbnail: format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", video_id),
-            published_at: v_data.6,
-            view_count: v_data.5.to_string(),
-            author: Some(v_data.2),
-            handle: Some(v_data.7),
-            status: Some("exists".to_string()),
-            date_added: Some(v_data.9),
-            length_seconds: Some(v_data.3),
-            video_type: Some(v_data.8),
-        });
-    }
-
-    let client_web = YouTubeClient::new(ClientType::Web);
-    let client_android = YouTubeClient::new(ClientType::Android);
-    let player_web = client_web.player(&video_id).await?;
-    let details = &player_web["videoDetails"];
+
+    let client_web = youtube_client(app, ClientType::Web);
+    let player_web = fetch_playable(app, &client_web, video_id).await?;
+    let live = youtube::LiveState::from_player(&player_web);
+    if live.is_live || live.is_upcoming {
+        return Ok(Fetched::NotYetAvailable(not_yet_available(video_id, &player_web, live)));
+    }
+    let player = PlayerResponse::parse(&player_web);
+    let details = player.video_details;
+    let microformat = player.microformat.player_microformat_renderer.unwrap_or_default();
 
     let mut handle: Option<String> = None;
-    if let Some(authors) = details["author"].as_array() {
-        if let Some(first) = authors.first() {
-            if let Some(channel_id) = first["channel_id"].as_str() {
-                handle = youtube::extract_handle_from_channel_id(channel_id).await.ok().flatten();
+    if let Some(channel_id) = details.author.channel_id() {
+        handle = youtube::extract_handle_from_channel_id(channel_id).await.ok().flatten();
+    }
+
+    let language = transcript_language(app, None);
+    let (mut transcript, mut segments, transcript_language, captions_auto_generated) = match youtube::fetch_transcript_from_any_client(video_id, &language, false, |t| youtube_client(app, t)).await.map_err(SaveError::Request)? {
+        Some(t) => (t.text, t.segments, t.language, t.auto_generated),
+        None => return Err(SaveError::NoTranscript),
+    };
+    let strip_sponsors = with_db(app, |c| db::get_setting(c, "strip_sponsor_segments")).unwrap_or(None).as_deref() == Some("true");
+    if strip_sponsors && !segments.is_empty() {
+        match sponsorblock::fetch_segments(video_id).await {
+            Ok(skips) if !skips.is_empty() => {
+                segments = sponsorblock::strip_segments(&segments, &skips);
+                transcript = youtube::join_segments(&segments);
             }
+            Ok(_) => {}
+            Err(e) => log::warn!("Keeping sponsor reads in {}: {}", video_id, e),
         }
     }
 
-    let mut transcript = String::new();
-    let mut attempts = 0;
-    loop {
-        attempts += 1;
-        let p = client_android.player(&video_id).await?;
-        match youtube::fetch_transcript(&p).await {
-            Ok(Some(t)) if !t.trim().is_empty() => { transcript = t; break; }
-            Ok(_) | Err(_) if attempts < 3 => {
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    let mut title = decode_html(Some(details.title.as_str()).filter(|t| !t.is_empty()).unwrap_or("Unknown"));
+    let mut original_title = None;
+    let mut dearrow_thumbnail = None;
+    if dearrow_enabled(app) {
+        match dearrow::fetch_branding(video_id).await {
+            Ok(branding) => {
+                if let Some(replacement) = branding.title {
+                    original_title = Some(std::mem::replace(&mut title, replacement));
+                }
+                dearrow_thumbnail = branding.thumbnail;
             }
-            _ => break,
+            Err(e) => log::warn!("Saving {} under its own title: {}", video_id, e),
         }
     }
+    let author = decode_html(details.author.name().unwrap_or("Unknown"));
 
-    if transcript.is_empty() {
-        return Err("Cannot save video without transcript.".to_string());
+    for try_handle in [
+        microformat.owner_profile_url.as_deref().and_then(extract_handle_from_url),
+        details.author.url().and_then(extract_handle_from_url),
+    ] {
+        if handle.is_none() { handle = try_handle; }
     }
 
-    let title = decode_html(details["title"].as_str().unwrap_or("Unknown"));
-    let author = if let Some(authors) = details["author"].as_array() {
-        decode_html(authors.first().and_then(|a| a["name"].as_str()).unwrap_or("Unknown"))
+    let length = details.length_seconds.parse::<i32>().unwrap_or(0);
+    let channel_id = Some(details.channel_id).filter(|id| !id.is_empty());
+    // The avatar costs a page fetch, so only look it up for new channels
+    let avatar = match channel_id.as_deref() {
+        Some(id) if with_db(app, |c| db::channel_needs_avatar(c, id)).unwrap_or(true) => {
+            youtube::fetch_channel_avatar(id).await.ok().flatten()
+        }
+        _ => None,
+    };
+    let description = details.short_description;
+    let next = fetch_next_or_null(&client_web, video_id).await;
+    let chapters = chapters_or_description(&next, &description);
+    let comments = if comment_limit > 0 {
+        fetch_top_comments(&client_web, video_id, &next, comment_limit).await
     } else {
-        decode_html(details["author"].as_str().unwrap_or("Unknown"))
+        Vec::new()
+    };
+    let thumbnail = if with_db(app, |c| db::has_thumbnail(c, video_id)).unwrap_or(false) {
+        None
+    } else {
+        let dearrow = match dearrow_thumbnail.as_deref() {
+            Some(url) => dearrow::fetch_thumbnail(url).await.unwrap_or_else(|e| {
+                log::warn!("Failed to fetch DeArrow thumbnail for {}: {}", video_id, e);
+                None
+            }),
+            None => None,
+        };
+        match dearrow {
+            Some(thumbnail) => Some(thumbnail),
+            None => youtube::fetch_thumbnail(video_id, super::thumbnails::thumbnail_quality(app)).await.unwrap_or_else(|e| {
+                log::warn!("Failed to fetch thumbnail for {}: {}", video_id, e);
+                None
+            }),
+        }
     };
 
-    for try_handle in [
-        player_web["microformat"]["playerMicroformatRenderer"]["ownerProfileUrl"].as_str().and_then(extract_handle_from_url),
-        details["author"].as_array().and_then(|a| a.first()).and_then(|f| f["url"].as_str()).and_then(extract_handle_from_url),
-    ] {
-        if handle.is_none() { handle = try_handle; }
+    Ok(Fetched::Ready(FetchedVideo {
+        video_id: video_id.to_string(),
+        title,
+        original_title,
+        author,
+        handle,
+        length,
+        transcript,
+        segments,
+        transcript_language,
+        captions_auto_generated,
+        keywords: details.keywords,
+        category: microformat.category,
+        flags: youtube::VideoFlags::from_player(&player_web),
+        view_count: parse_view_count(&details.view_count),
+        published_at: microformat.publish_date,
+        video_type: if length > 0 && length <= 60 { "short" } else { "standard" },
+        description,
+        channel_id,
+        avatar,
+        thumbnail,
+        chapters,
+        heatmap: youtube::parse_heatmap(&next),
+        engagement: youtube::parse_engagement(&next),
+        comments,
+    }))
+}
+
+/// Store a fetched video with its segments, channel and thumbnail. Runs inside
+/// the caller's transaction.
+fn write_fetched_video(conn: &rusqlite::Connection, v: &FetchedVideo, summary: Option<&str>, source: &str) -> rusqlite::Result<()> {
+    db::save_video(conn, &v.video_id, &v.title, &v.author, v.length, &v.transcript, v.view_count, &v.published_at, v.handle.as_deref().unwrap_or(""), v.video_type, summary, &v.description, source)?;
+    if !v.segments.is_empty() {
+        db::replace_transcript_segments(conn, &v.video_id, &v.segments)?;
+    }
+    db::set_transcript_language(conn, &v.video_id, v.transcript_language.as_deref())?;
+    db::set_captions_auto_generated(conn, &v.video_id, Some(v.captions_auto_generated))?;
+    db::set_keywords_and_category(conn, &v.video_id, &v.keywords, v.category.as_deref())?;
+    db::set_video_flags(conn, &v.video_id, &v.flags)?;
+    db::set_original_title(conn, &v.video_id, v.original_title.as_deref())?;
+    if let Some(channel_id) = v.channel_id.as_deref() {
+        db::upsert_channel(conn, channel_id, &v.author, v.handle.as_deref(), v.avatar.as_deref())?;
+        db::set_video_channel(conn, &v.video_id, channel_id)?;
+    }
+    if let Some((mime, data)) = &v.thumbnail {
+        db::save_thumbnail(conn, &v.video_id, mime, data)?;
+    }
+    if !v.chapters.is_empty() {
+        db::replace_chapters(conn, &v.video_id, &v.chapters)?;
+    }
+    if !v.heatmap.is_empty() {
+        db::replace_heatmap(conn, &v.video_id, &v.heatmap)?;
     }
+    db::set_engagement(conn, &v.video_id, v.engagement.like_count, v.engagement.comment_count)?;
+    if !v.comments.is_empty() {
+        db::replace_comments(conn, &v.video_id, &v.comments)?;
+    }
+    activity::record(conn, "save", Some(&v.video_id), Some(&v.title))
+}
 
-    let length = details["lengthSeconds"].as_str().unwrap_or("0").parse::<i32>().unwrap_or(0);
-    let view_count = parse_view_count(details["viewCount"].as_str().unwrap_or("0"));
-    let published_at = player_web["microformat"]["playerMicroformatRenderer"]["publishDate"].as_str().unwrap_or("");
-    let video_type = if length > 0 && length <= 60 { "short" } else { "standard" };
+/// Write a batch of fetched videos in one transaction.
+fn write_fetched_videos(app: &tauri::AppHandle, videos: &[FetchedVideo], summary: Option<&str>, source: &str) -> Result<(), String> {
+    with_db(app, |c| {
+        let tx = c.unchecked_transaction()?;
+        for v in videos {
+            write_fetched_video(&tx, v, summary, source)?;
+        }
+        tx.commit()
+    })
+}
 
-    db::save_video(&db_path, &video_id, &title, &author, length, &transcript, view_count, published_at, handle.as_deref().unwrap_or(""), video_type, summary.as_deref())
-        .map_err(|e| e.to_string())?;
+/// The library entry for a video that was just written.
+fn saved_video(app: &tauri::AppHandle, v: FetchedVideo, source: &str) -> Video {
+    let date_added = with_db(app, |c| db::get_date_added(c, &v.video_id)).ok().flatten();
+    let word_count = db::count_words(&v.transcript);
+    Video {
+        thumbnail: youtube::thumbnail_url(&v.video_id, ThumbnailQuality::Hq),
+        id: v.video_id,
+        title: v.title,
+        published_at: v.published_at,
+        view_count: v.view_count.to_string(),
+        author: Some(v.author),
+        handle: v.handle,
+        status: Some("saved".to_string()),
+        date_added,
+        length_seconds: Some(v.length),
+        video_type: Some(v.video_type.to_string()),
+        source: Some(source.to_string()),
+        word_count: Some(word_count),
+        reading_minutes: Some(db::reading_minutes(word_count)),
+        like_count: v.engagement.like_count,
+        comment_count: v.engagement.comment_count,
+        original_title: v.original_title,
+        captions_auto_generated: Some(v.captions_auto_generated),
+        is_private: Some(v.flags.is_private),
+        is_unlisted: Some(v.flags.is_unlisted),
+        is_live_content: Some(v.flags.is_live_content),
+        ..Default::default()
+    }
+}
 
-    let date_added = {
-        let conn = rusqlite::Connection::open(&db_path).ok();
-        conn.and_then(|c| {
-            c.query_row("SELECT date_added FROM videos WHERE video_id = ?", rusqlite::params![video_id], |row| row.get::<_, Option<String>>(0)).ok().flatten()
-        })
+/// The video ID in a pasted URL, or the input as it is when it isn't one.
+fn video_id_of(input: &str) -> String {
+    youtube::url::extract_video_id(input).unwrap_or_else(|| input.trim().to_string())
+}
+
+/// Save a video to the library, by ID or any form of video URL.
+/// `archive_comments` keeps that many of its top comments alongside it.
+/// A video that is live or yet to premiere isn't saved; it comes back with
+/// the `live` or `upcoming` status and its scheduled start instead.
+#[command]
+pub async fn save_video(app: tauri::AppHandle, video_id: String, summary: Option<String>, source: Option<String>, archive_comments: Option<usize>) -> Result<Video, String> {
+    let video_id = video_id_of(&video_id);
+    let source = source.unwrap_or_else(|| "manual".to_string());
+    if let Some(video) = existing_video(&app, &video_id, summary.as_deref()) {
+        return Ok(video);
+    }
+
+    let fetched = match fetch_for_save(&app, &video_id, archive_comments.unwrap_or(0)).await.map_err(|e| e.to_string())? {
+        Fetched::Ready(fetched) => fetched,
+        Fetched::NotYetAvailable(video) => return Ok(video),
     };
+    write_fetched_videos(&app, std::slice::from_ref(&fetched), summary.as_deref(), &source)?;
+    Ok(saved_video(&app, fetched, &source))
+}
 
-    Ok(Video {
-        thumbnail: format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", video_id),
-        id: video_id,
-        title: title.to_string(),
-        published_at: published_at.to_string(),
-        view_count: view_count.to_string(),
-        author: Some(author.to_string()),
-        handle,
-        status: Some("saved".to_string()),
-        date_added,
-        length_seconds: Some(length),
-        video_type: Some(video_type.to_string()),
-    })
+#[command]
+pub async fn fetch_saved_videos(app: tauri::AppHandle, query: Option<VideoQuery>) -> Result<VideoResponse, String> {
+    let query = query.unwrap_or_default();
+    let videos = with_db(&app, |c| db::list_videos(c, &query))?;
+    Ok(VideoResponse { videos, continuation: None, source: "library".to_string() })
+}
+
+#[command]
+pub async fn list_videos_without_transcript(app: tauri::AppHandle) -> Result<VideoResponse, String> {
+    let videos = with_db(&app, db::list_videos_without_transcript)?;
+    Ok(VideoResponse { videos, continuation: None, source: "library".to_string() })
+}
+
+/// Saved videos whose title, author or handle contains `query`. An empty query
+/// returns the whole library.
+#[command]
+pub async fn search_library(app: tauri::AppHandle, query: String) -> Result<VideoResponse, String> {
+    let videos = if query.trim().is_empty() {
+        with_db(&app, |c| db::list_videos(c, &VideoQuery::default()))?
+    } else {
+        with_db(&app, |c| db::search_library(c, &query))?
+    };
+    Ok(VideoResponse { videos, continuation: None, source: "library".to_string() })
+}
+
+#[command]
+pub async fn search_transcripts(app: tauri::AppHandle, query: String, limit: Option<i64>) -> Result<Vec<TranscriptMatch>, String> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    with_db(&app, |c| db::search_transcripts(c, &query, limit.unwrap_or(50)))
+}
+
+#[command]
+pub async fn set_note(app: tauri::AppHandle, video_id: String, note: String) -> Result<(), String> {
+    match with_db(&app, |c| db::set_note(c, video_id.trim(), &note))? {
+        0 => Err("Video is not saved in the library.".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Record that a video was opened, for `recent_videos`. Returns `false` for
+/// videos that aren't saved, which are not tracked.
+#[command]
+pub async fn mark_opened(app: tauri::AppHandle, video_id: String) -> Result<bool, String> {
+    with_db(&app, |c| db::mark_opened(c, video_id.trim())).map(|n| n > 0)
+}
+
+/// Saved videos by when they were last opened, latest first.
+#[command]
+pub async fn recent_videos(app: tauri::AppHandle, limit: Option<i64>) -> Result<Vec<Video>, String> {
+    with_db(&app, |c| db::recent_videos(c, limit.unwrap_or(20)))
 }
 
 #[command]
-pub async fn fetch_saved_videos(app: tauri::AppHandle, video_type: Option<String>) -> Result<VideoResponse, String> {
-    let db_path = get_db_path(&app);
-    db::init_db(&db_path).map_err(|e| e.to_string())?;
-    let videos = db::list_videos(&db_path, video_type.as_deref()).map_err(|e| e.to_string())?;
-    Ok(VideoResponse { videos, continuation: None })
+pub async fn mark_watched(app: tauri::AppHandle, video_id: String) -> Result<(), String> {
+    match with_db(&app, |c| db::set_watched(c, video_id.trim(), true))? {
+        0 => Err("Video is not saved in the library.".to_string()),
+        _ => Ok(()),
+    }
+}
+
+#[command]
+pub async fn mark_unwatched(app: tauri::AppHandle, video_id: String) -> Result<(), String> {
+    match with_db(&app, |c| db::set_watched(c, video_id.trim(), false))? {
+        0 => Err("Video is not saved in the library.".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Star or unstar a saved video. Returns whether it is now starred.
+#[command]
+pub async fn toggle_starred(app: tauri::AppHandle, video_id: String) -> Result<bool, String> {
+    with_db(&app, |c| db::toggle_starred(c, video_id.trim()))?
+        .ok_or_else(|| "Video is not saved in the library.".to_string())
+}
+
+#[command]
+pub async fn get_video_details(app: tauri::AppHandle, video_id: String) -> Result<VideoDetails, String> {
+    with_db(&app, |c| db::get_video_details(c, video_id.trim()))?
+        .ok_or_else(|| "Video is not saved in the library.".to_string())
+}
+
+/// Timed transcript lines for click-to-seek; empty for videos saved without timing.
+#[command]
+pub async fn get_transcript_segments(app: tauri::AppHandle, video_id: String) -> Result<Vec<TranscriptSegment>, String> {
+    with_db(&app, |c| db::get_transcript_segments(c, video_id.trim()))
+}
+
+#[command]
+pub async fn get_note(app: tauri::AppHandle, video_id: String) -> Result<Option<String>, String> {
+    with_db(&app, |c| db::get_note(c, video_id.trim()))
 }
 
 #[command]
 pub async fn delete_video(app: tauri::AppHandle, video_id: String) -> Result<String, String> {
-    let db_path = get_db_path(&app);
-    db::delete_video(&db_path, &video_id).map_err(|e| e.to_string())?;
+    if with_db(&app, |c| db::delete_video(c, &video_id))? > 0 {
+        log_activity(&app, "delete", Some(&video_id), None);
+        remember_deletion(&app, "delete", vec![video_id]);
+    }
     Ok("Deleted".to_string())
 }
 
+/// Trash many videos at once, either by ID or by filter (not both). Returns how
+/// many were moved to the trash.
+#[command]
+pub async fn bulk_delete_videos(app: tauri::AppHandle, ids: Option<Vec<String>>, filter: Option<BulkDeleteFilter>) -> Result<usize, String> {
+    let trashed = match (ids, filter) {
+        (Some(ids), None) => with_db(&app, |c| db::trash_videos(c, &ids))?,
+        (None, Some(filter)) => {
+            let is_set = |v: &Option<String>| v.as_deref().is_some_and(|s| !s.trim().is_empty());
+            if !is_set(&filter.author) && !is_set(&filter.tag) && !is_set(&filter.added_before) {
+                return Err("Choose an author, tag or date to delete by.".to_string());
+            }
+            with_db(&app, |c| db::trash_matching(c, &filter))?
+        }
+        _ => return Err("Pass either a list of videos or a filter.".to_string()),
+    };
+    if !trashed.is_empty() {
+        log_activity(&app, "delete", None, Some(&format!("Bulk delete of {} videos", trashed.len())));
+    }
+    let count = trashed.len();
+    remember_deletion(&app, "bulk_delete", trashed);
+    Ok(count)
+}
+
+#[command]
+pub async fn list_trash(app: tauri::AppHandle) -> Result<Vec<TrashedVideo>, String> {
+    with_db(&app, db::list_trash)
+}
+
+#[command]
+pub async fn restore_video(app: tauri::AppHandle, video_id: String) -> Result<(), String> {
+    match with_db(&app, |c| db::restore_video(c, &video_id))? {
+        0 => Err("Video is not in the trash".to_string()),
+        _ => {
+            log_activity(&app, "restore", Some(&video_id), None);
+            Ok(())
+        }
+    }
+}
+
+/// Permanently delete trashed videos; `older_than_days` limits it to older deletions.
+#[command]
+pub async fn purge_trash(app: tauri::AppHandle, older_than_days: Option<u32>) -> Result<usize, String> {
+    let purged = with_db(&app, |c| db::purge_trash(c, older_than_days))?;
+    if purged > 0 {
+        log_activity(&app, "purge", None, Some(&format!("Permanently deleted {} videos", purged)));
+    }
+    Ok(purged)
+}
+
 #[command]
 pub async fn check_video_exists(app: tauri::AppHandle, video_id: String) -> Result<bool, String> {
-    let db_path = get_db_path(&app);
-    db::check_video_exists(&db_path, &video_id).map_err(|e| e.to_string())
+    with_db(&app, |c| db::check_video_exists(c, &video_id))
 }
 
 #[command]
-pub async fn bulk_save_videos(app: tauri::AppHandle, video_ids: Vec<String>) -> Result<serde_json::Value, String> {
-    let mut results = Vec::new();
-    for id in video_ids {
-        match save_video(app.clone(), id, None).await {
-            Ok(v) => results.push(serde_json::to_value(v).unwrap()),
-            Err(e) => results.push(serde_json::json!({"error": e})),
+pub async fn check_videos_exist(app: tauri::AppHandle, ids: Vec<String>) -> Result<HashMap<String, bool>, String> {
+    with_db(&app, |c| db::check_videos_exist(c, &ids))
+}
+
+/// Videos fetched by a bulk save are written this many at a time, one
+/// transaction each, so a long run neither fsyncs per video nor loses
+/// everything if it is interrupted.
+const BULK_SAVE_BATCH: usize = 25;
+
+/// Save many videos, fetching each batch of them concurrently.
+#[command]
+pub async fn bulk_save_videos(app: tauri::AppHandle, video_ids: Vec<String>, source: Option<String>) -> Result<BulkSaveReport, String> {
+    let source = source.unwrap_or_else(|| "bulk".to_string());
+    let mut report = BulkSaveReport::default();
+    let fail = |report: &mut BulkSaveReport, id: String, e: SaveError| {
+        let failure = BulkSaveFailure { id, code: e.code().to_string(), message: e.to_string() };
+        log::warn!("Bulk save failed - id: {}, code: {}, message: {}", failure.id, failure.code, failure.message);
+        let _ = app.emit("bulk_save_failed", &failure);
+        report.failed.push(failure);
+    };
+
+    let mut to_fetch: Vec<String> = Vec::new();
+    for id in video_ids.iter().map(|id| video_id_of(id)) {
+        if let Some(video) = existing_video(&app, &id, None) {
+            report.skipped.push(video);
+        } else if !to_fetch.contains(&id) {
+            to_fetch.push(id);
+        }
+    }
+
+    for chunk in to_fetch.chunks(BULK_SAVE_BATCH) {
+        let app = &app;
+        let fetched = for_each_concurrently(chunk, |id| async move { fetch_for_save(app, &id, 0).await }).await;
+        let mut batch = Vec::new();
+        for (id, result) in chunk.iter().zip(fetched) {
+            match result {
+                Ok(Fetched::Ready(video)) => batch.push(video),
+                Ok(Fetched::NotYetAvailable(_)) => fail(&mut report, id.clone(), SaveError::Unplayable(youtube::Unplayable::LiveOnly)),
+                Err(e) => fail(&mut report, id.clone(), e),
+            }
+        }
+        if batch.is_empty() {
+            continue;
+        }
+
+        match write_fetched_videos(app, &batch, None, &source) {
+            Ok(()) => report.saved.extend(batch.into_iter().map(|v| saved_video(app, v, &source))),
+            Err(e) => {
+                for v in batch {
+                    fail(&mut report, v.video_id, SaveError::Database(e.clone()));
+                }
+            }
         }
     }
-    Ok(serde_json::Value::Array(results))
+    Ok(report)
 }
 
+/// How many videos `verify_library` checks between pauses, and the pause.
+const VERIFY_BATCH: usize = 25;
+const VERIFY_PAUSE: tokio::time::Duration = tokio::time::Duration::from_secs(2);
+
+/// Check whether saved videos are still on YouTube and record which are now
+/// private or removed, so the library shows where it holds the only copy.
+/// Videos checked longest ago go first; `limit` caps how many are checked.
+/// Emits `library_check_progress` after each batch.
 #[command]
-pub async fn search_videos(app: tauri::AppHandle, query: String, continuation: Option<String>) -> Result<VideoResponse, String> {
-    let db_path = get_db_path(&app);
-    let api_key = db::get_setting(&db_path, "api_key").unwrap_or(None);
+pub async fn verify_library(app: tauri::AppHandle, limit: Option<usize>) -> Result<LibraryCheckReport, String> {
+    let mut ids = with_db(&app, db::videos_to_verify)?;
+    ids.truncate(limit.unwrap_or(usize::MAX));
+    let client = youtube_client(&app, ClientType::Web);
+    let (handle, client) = (&app, &client);
+    let mut report = LibraryCheckReport::default();
+
+    for (i, chunk) in ids.chunks(VERIFY_BATCH).enumerate() {
+        if i > 0 {
+            tokio::time::sleep(VERIFY_PAUSE).await;
+        }
+        let players = for_each_concurrently(chunk, |id| async move { fetch_playable(handle, client, &id).await }).await;
+        let mut found = Vec::new();
+        for (id, player) in chunk.iter().zip(players) {
+            let availability = match &player {
+                Ok(player) => youtube::availability(player),
+                Err(SaveError::Unplayable(unplayable)) => unplayable.availability(),
+                Err(_) => None,
+            };
+            match availability {
+                Some(availability) => found.push((id, availability)),
+                None => {
+                    log::warn!("Could not check {}: {}", id, player.err().map_or_else(|| "no clear answer".to_string(), |e| e.to_string()));
+                    report.failed += 1;
+                }
+            }
+        }
+        with_db(&app, |c| {
+            let tx = c.unchecked_transaction()?;
+            for (id, availability) in &found {
+                db::set_availability(&tx, id, availability)?;
+            }
+            tx.commit()
+        })?;
+
+        report.checked += found.len();
+        for (id, availability) in found {
+            if availability != "available" {
+                if let Some(details) = with_db(&app, |c| db::get_video_details(c, id))? {
+                    report.unavailable.push(details.video);
+                }
+            }
+        }
+        let progress = serde_json::json!({ "checked": report.checked + report.failed, "total": ids.len() });
+        let _ = app.emit("library_check_progress", progress);
+    }
+    Ok(report)
+}
+
+/// Look for a Wayback Machine capture of a saved video's watch page, most
+/// useful once `verify_library` has found it removed, and keep its URL with
+/// the video. `None` when the page was never captured.
+#[command]
+pub async fn find_archived_copy(app: tauri::AppHandle, video_id: String) -> Result<Option<String>, String> {
+    let video_id = video_id.trim();
+    if !with_db(&app, |c| db::check_video_exists(c, video_id))? {
+        return Err("Video is not saved in the library.".to_string());
+    }
+    let snapshot = wayback::latest_snapshot(video_id).await?;
+    if let Some(url) = &snapshot {
+        with_db(&app, |c| db::set_archive_url(c, video_id, url))?;
+    }
+    Ok(snapshot)
+}
+
+/// Re-run a bulk save for just the IDs that failed last time.
+#[command]
+pub async fn retry_failed(app: tauri::AppHandle, ids: Vec<String>, source: Option<String>) -> Result<BulkSaveReport, String> {
+    bulk_save_videos(app, ids, source).await
+}
+
+/// The Data API's search query parameters for `filters`, each with its
+/// leading `&`.
+fn data_api_search_filters(app: &tauri::AppHandle, filters: &SearchFilters) -> String {
+    let mut query = String::new();
+    if let Some(sort) = filters.sort {
+        let order = match sort {
+            SearchSort::Relevance => "relevance",
+            SearchSort::UploadDate => "date",
+            SearchSort::ViewCount => "viewCount",
+            SearchSort::Rating => "rating",
+        };
+        query.push_str(&format!("&order={}", order));
+    }
+    if let Some(duration) = filters.duration {
+        let duration = match duration {
+            SearchDuration::Short => "short",
+            SearchDuration::Medium => "medium",
+            SearchDuration::Long => "long",
+        };
+        query.push_str(&format!("&videoDuration={}", duration));
+    }
+    if let Some(date) = filters.upload_date {
+        let seconds = match date {
+            UploadDate::LastHour => 3600,
+            UploadDate::Today => 86_400,
+            UploadDate::ThisWeek => 7 * 86_400,
+            UploadDate::ThisMonth => 30 * 86_400,
+            UploadDate::ThisYear => 365 * 86_400,
+        };
+        if let Ok(after) = with_db(app, |c| db::utc_time_ago(c, seconds)) {
+            query.push_str(&format!("&publishedAfter={}", urlencoding::encode(&after)));
+        }
+    }
+    query
+}
+
+/// A page of YouTube search results, through the Data API when a key is saved
+/// and InnerTube otherwise. `filters` narrow the results by upload date and
+/// length, or change their order. With `videos_only`, channels and playlists
+/// are left out.
+async fn search_page(app: &tauri::AppHandle, query: &str, continuation: Option<String>, filters: &SearchFilters, videos_only: bool) -> Result<SearchPage, String> {
+    let api_key = with_db(app, |c| db::get_setting(c, "api_key")).unwrap_or(None);
 
     log::info!("Search called - query: {}, continuation: {:?}, api_key present: {}", query, continuation, api_key.is_some());
 
     // If API key is available, use YouTube Data API with pagination
     if let Some(key) = api_key {
-        let client = reqwest::Client::new();
+        let client = youtube::http_client();
+        // The Data API only filters on length when searching for videos alone
+        let kinds = if videos_only || filters.duration.is_some() { "video" } else { "video,channel,playlist" };
         let mut url = format!(
-            "https://youtube.googleapis.com/youtube/v3/search?part=snippet&maxResults=50&q={}&type=video&key={}",
-            urlencoding::encode(&query), key
+            "https://youtube.googleapis.com/youtube/v3/search?part=snippet&maxResults=50&q={}&type={}&key={}",
+            urlencoding::encode(query), kinds, key
         );
+        url.push_str(&data_api_search_filters(app, filters));
         if let Some(token) = continuation.as_ref() {
             url = format!("{}&pageToken={}", url, token);
         }
 
-        let res: Value = client.get(&url).send().await.map_err(|e| e.to_string())?.json().await.map_err(|e| e.to_string())?;
+        let res = data_api_get(&client, &url).await?;
 
         if res.get("error").is_some() {
             return Err(format!("API Error: {}", res["error"]["message"].as_str().unwrap_or("Unknown")));
         }
 
         let next_page_token = res["nextPageToken"].as_str().map(|s| s.to_string());
-        let mut videos = Vec::new();
+        let mut results = Vec::new();
         let mut video_ids = Vec::new();
 
         if let Some(items) = res["items"].as_array() {
             for item in items {
                 let snippet = &item["snippet"];
+                let thumbnail = snippet["thumbnails"]["high"]["url"].as_str()
+                    .or(snippet["thumbnails"]["default"]["url"].as_str())
+                    .unwrap_or("").to_string();
+                let title = decode_html(snippet["title"].as_str().unwrap_or("Unknown"));
                 if let Some(vid) = item["id"]["videoId"].as_str() {
                     video_ids.push(vid.to_string());
-                    videos.push(Video {
+                    results.push(SearchResult::Video(Video {
                         id: vid.to_string(),
-                        title: decode_html(&snippet["title"].as_str().unwrap_or("Unknown").to_string()),
-                        thumbnail: snippet["thumbnails"]["high"]["url"].as_str()
-                            .or(snippet["thumbnails"]["default"]["url"].as_str())
-                            .unwrap_or("").to_string(),
+                        title,
+                        thumbnail,
                         published_at: snippet["publishedAt"].as_str().unwrap_or("").to_string(),
                         view_count: "0".to_string(),
-                        author: snippet["channelTitle"].as_str().map(|s| decode_html(s)),
-                        handle: None, status: None, date_added: None,
-                        length_seconds: None, video_type: None,
-                    });
+                        author: snippet["channelTitle"].as_str().map(decode_html),
+                        ..Default::default()
+                    }));
+                } else if let Some(channel_id) = item["id"]["channelId"].as_str().filter(|_| item["id"]["kind"] == "youtube#channel") {
+                    results.push(SearchResult::Channel(ChannelResult {
+                        channel_id: channel_id.to_string(),
+                        title,
+                        handle: None,
+                        thumbnail,
+                        subscribers: None,
+                        description: snippet["description"].as_str().filter(|d| !d.is_empty()).map(decode_html),
+                    }));
+                } else if let Some(playlist_id) = item["id"]["playlistId"].as_str() {
+                    results.push(SearchResult::Playlist(PlaylistResult {
+                        playlist_id: playlist_id.to_string(),
+                        title,
+                        author: snippet["channelTitle"].as_str().map(decode_html),
+                        thumbnail,
+                        video_count: None,
+                    }));
                 }
             }
         }
@@ -441,14 +1622,16 @@ pub async fn search_videos(app: tauri::AppHandle, query: String, continuation: O
                 "https://youtube.googleapis.com/youtube/v3/videos?part=statistics&id={}&key={}",
                 video_ids.join(","), key
             );
-            if let Ok(stats_res) = client.get(&stats_url).send().await {
-                if let Ok(stats_data) = stats_res.json::<Value>().await {
-                    if let Some(items) = stats_data["items"].as_array() {
-                        for item in items {
-                            if let Some(vid) = item["id"].as_str() {
-                                if let Some(v) = videos.iter_mut().find(|v| v.id == vid) {
-                                    v.view_count = item["statistics"]["viewCount"].as_str().unwrap_or("0").to_string();
-                                }
+            if let Ok(stats_data) = data_api_get(&client, &stats_url).await {
+                if let Some(items) = stats_data["items"].as_array() {
+                    for item in items {
+                        if let Some(vid) = item["id"].as_str() {
+                            let found = results.iter_mut().find_map(|r| match r {
+                                SearchResult::Video(v) if v.id == vid => Some(v),
+                                _ => None,
+                            });
+                            if let Some(v) = found {
+                                v.view_count = item["statistics"]["viewCount"].as_str().unwrap_or("0").to_string();
                             }
                         }
                     }
@@ -456,30 +1639,62 @@ pub async fn search_videos(app: tauri::AppHandle, query: String, continuation: O
             }
         }
 
-        return Ok(VideoResponse { videos, continuation: next_page_token });
+        return Ok(SearchPage { results, continuation: next_page_token, source: "data_api".to_string() });
     }
 
-    // Fallback to web scraping without pagination
-    let client = YouTubeClient::new(ClientType::Web);
-    let data = client.search(&query).await?;
-    let mut videos = Vec::new();
+    // Without an API key, page through InnerTube's own search results
+    let client = youtube_client(app, ClientType::Web);
+    let data = client.search(query, continuation.clone(), filters).await?;
+    let sections = SearchResponse::parse(&data).sections();
+    if sections.is_none() {
+        log::warn!("Search response for {:?} has no result sections", query);
+    }
+    let sections = sections.unwrap_or_default();
+    let mut results = Vec::new();
 
-    if let Some(results) = data["contents"]["twoColumnSearchResultsRenderer"]["primaryContents"]["sectionListRenderer"]["contents"].as_array() {
-        for section in results {
-            if let Some(items) = section["itemSectionRenderer"]["contents"].as_array() {
-                for item in items {
-                    if let Some(v_renderer) = item.get("videoRenderer") {
-                        if let Some(v_json) = youtube::extract_video_basic_info(v_renderer) {
-                            if let Ok(mut v) = serde_json::from_value::<Video>(v_json) {
-                                v.date_added = None;
-                                videos.push(v);
-                            }
-                        }
-                    }
+    for item in sections.iter().flat_map(SearchSection::items) {
+        if let Some(v_renderer) = item.get("videoRenderer") {
+            if let Some(v_json) = youtube::extract_video_basic_info(v_renderer) {
+                if let Some(mut v) = extracted::<Video>(v_json) {
+                    v.date_added = None;
+                    results.push(SearchResult::Video(v));
                 }
             }
+        } else if videos_only {
+            continue;
+        } else if let Some(channel) = item.get("channelRenderer").and_then(youtube::extract_channel_result) {
+            results.push(SearchResult::Channel(channel));
+        } else if let Some(playlist) = youtube::extract_playlist_result(&item) {
+            results.push(SearchResult::Playlist(playlist));
         }
     }
 
-    Ok(VideoResponse { videos, continuation: None })
+    let next = youtube::continuation_token(&sections);
+    Ok(SearchPage { results, continuation: next, source: "innertube".to_string() })
+}
+
+/// Search YouTube for videos. See `search_page`.
+#[command]
+pub async fn search_videos(app: tauri::AppHandle, query: String, continuation: Option<String>, filters: Option<SearchFilters>) -> Result<VideoResponse, String> {
+    let page = search_page(&app, &query, continuation, &filters.unwrap_or_default(), true).await?;
+    let videos = page.results.into_iter()
+        .filter_map(|r| match r {
+            SearchResult::Video(v) => Some(v),
+            _ => None,
+        })
+        .collect();
+    Ok(with_dearrow(&app, VideoResponse { videos, continuation: page.continuation, source: page.source }).await)
+}
+
+/// Search YouTube for videos, channels and playlists together, so any of them
+/// can be opened or imported from one search box.
+#[command]
+pub async fn search_youtube(app: tauri::AppHandle, query: String, continuation: Option<String>, filters: Option<SearchFilters>) -> Result<SearchPage, String> {
+    let mut page = search_page(&app, &query, continuation, &filters.unwrap_or_default(), false).await?;
+    let videos = page.results.iter_mut().filter_map(|r| match r {
+        SearchResult::Video(v) => Some(v),
+        _ => None,
+    });
+    apply_dearrow(&app, videos).await;
+    Ok(page)
 }
@@ -1,11 +1,270 @@
+use std::collections::{BTreeMap, HashMap};
 use rusqlite::{params, Connection, Result};
+use rusqlite::types::Value as SqlValue;
 use crate::Video;
+use crate::types::{BulkDeleteFilter, Channel, Chapter, Collection, Comment, CommunityPost, ChannelSize, HeatmapPoint, Lyrics, MediaFile, CompressionReport, TableSize, Tag, ThumbnailQuality, TranscriptMatch, TranscriptSegment, TranscriptTrack, TrashedVideo, VideoDetails, VideoQuery, VideoSort};
 
-pub fn init_db(db_path: &str) -> Result<()> {
+/// Open the database at `db_path` and bring its schema up to date.
+/// `key` unlocks a SQLCipher-encrypted file; pass `None` for plaintext databases.
+pub fn open(db_path: &str, key: Option<&str>) -> Result<Connection> {
     let conn = Connection::open(db_path)?;
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", key)?;
+    }
+    init_db(&conn)?;
+    Ok(conn)
+}
+
+/// How long a statement waits on another connection's lock before failing with "database is locked".
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Per-connection settings. WAL lets readers run alongside a writer, and the
+/// busy timeout makes writers queue instead of failing immediately. Also
+/// registers `zstd_decompress()`, which the FTS triggers rely on, `fold_case()`,
+/// a lowercase that unlike SQLite's `lower()` handles non-ASCII, and
+/// `count_words()`, which the word count triggers use.
+pub fn configure_connection(conn: &Connection) -> Result<()> {
+    use rusqlite::functions::FunctionFlags;
+    use rusqlite::types::ValueRef;
+
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    conn.create_scalar_function(
+        "zstd_decompress",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| match ctx.get_raw(0) {
+            ValueRef::Blob(blob) => decompress_transcript(blob).map(Some),
+            _ => Ok(None),
+        },
+    )?;
+    conn.create_scalar_function(
+        "fold_case",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| Ok(ctx.get::<Option<String>>(0)?.map(|s| s.to_lowercase())),
+    )?;
+    conn.create_scalar_function(
+        "count_words",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| Ok(ctx.get::<Option<String>>(0)?.map(|s| count_words(&s))),
+    )?;
+    Ok(())
+}
+
+/// Words in a transcript, split on whitespace.
+pub(crate) fn count_words(text: &str) -> i64 {
+    text.split_whitespace().count() as i64
+}
+
+/// Reading speed behind `Video::reading_minutes`.
+const READING_WORDS_PER_MINUTE: i64 = 230;
+
+/// Minutes to read `word_count` words, rounded up; any text takes at least a minute.
+pub(crate) fn reading_minutes(word_count: i64) -> i64 {
+    (word_count + READING_WORDS_PER_MINUTE - 1) / READING_WORDS_PER_MINUTE
+}
+
+/// zstd level for stored transcripts.
+const TRANSCRIPT_ZSTD_LEVEL: i32 = 3;
+
+/// SQL expression for a video's transcript text, whether it is stored plain
+/// (legacy rows) or compressed in `transcript_zstd`.
+pub(crate) const TRANSCRIPT_TEXT: &str = "COALESCE(transcript, zstd_decompress(transcript_zstd))";
+
+/// Compress a transcript for storage. Empty transcripts aren't worth a blob.
+pub(crate) fn compress_transcript(text: &str) -> Result<Option<Vec<u8>>> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    zstd::encode_all(text.as_bytes(), TRANSCRIPT_ZSTD_LEVEL)
+        .map(Some)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+pub(crate) fn decompress_transcript(blob: &[u8]) -> Result<String> {
+    let conversion_error = |e: Box<dyn std::error::Error + Send + Sync>| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Blob, e)
+    };
+    let bytes = zstd::decode_all(blob).map_err(|e| conversion_error(Box::new(e)))?;
+    String::from_utf8(bytes).map_err(|e| conversion_error(Box::new(e)))
+}
+
+/// Every plaintext SQLite file starts with this header; encrypted files don't.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Whether the file at `db_path` is encrypted. Missing or empty files are not.
+pub fn is_encrypted(db_path: &str) -> bool {
+    use std::io::Read;
+    let mut header = [0u8; 16];
+    match std::fs::File::open(db_path).and_then(|mut f| f.read_exact(&mut header)) {
+        Ok(()) => &header != SQLITE_HEADER,
+        Err(_) => false,
+    }
+}
+
+/// Open the database at `db_path` with the session's passphrase, which only
+/// applies if the file is encrypted. Fails with `DB_LOCKED` when it is and
+/// there is no passphrase yet.
+pub fn open_with_key(db_path: &str, key: Option<&str>) -> std::result::Result<Connection, String> {
+    let key = if is_encrypted(db_path) {
+        Some(key.ok_or_else(|| crate::DB_LOCKED.to_string())?)
+    } else {
+        None
+    };
+    open(db_path, key).map_err(|e| e.to_string())
+}
+
+/// Copy the whole database into a new file at `dest`, encrypted with `key`.
+/// An empty key writes a plaintext copy.
+pub fn export_rekeyed(conn: &Connection, dest: &str, key: &str) -> Result<()> {
+    conn.execute("ATTACH DATABASE ?1 AS rekeyed KEY ?2", params![dest, key])?;
+    let exported = conn.query_row("SELECT sqlcipher_export('rekeyed')", [], |_| Ok(()));
+    conn.execute("DETACH DATABASE rekeyed", [])?;
+    exported
+}
+
+/// Re-key the database at `db_path`, open as `conn`, by exporting it to a
+/// sibling file under `key` and swapping that file in. `None` writes a
+/// plaintext database. The WAL is folded in and removed first so it can't be
+/// replayed into the new file. Returns a connection to the re-keyed database;
+/// on failure the original file is left as it was.
+pub fn rekey(conn: Connection, db_path: &str, key: Option<&str>) -> std::result::Result<Connection, String> {
+    let tmp = format!("{}.rekey", db_path);
+    let _ = std::fs::remove_file(&tmp);
+    if let Err(e) = export_rekeyed(&conn, &tmp, key.unwrap_or("")) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(format!("Failed to re-key database: {}", e));
+    }
+    let _ = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE)");
+    drop(conn);
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", db_path, suffix));
+    }
+    if let Err(e) = std::fs::rename(&tmp, db_path) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(format!("Failed to replace database: {}", e));
+    }
+    open(db_path, key).map_err(|e| e.to_string())
+}
+
+pub fn init_db(conn: &Connection) -> Result<()> {
+    configure_connection(conn)?;
+    run_migrations(conn, MIGRATIONS)?;
+    Ok(())
+}
+
+// ─── Schema migrations ────────────────────────────────────────────────────────
+
+/// One schema change. `up` runs inside a transaction together with recording
+/// `version`, so a failed migration leaves the database untouched.
+pub(crate) struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub up: fn(&Connection) -> Result<()>,
+}
 
-    // Create videos table
+/// Every schema change, oldest first. Append new migrations at the end and never
+/// edit one that has shipped. Versions 1-8 predate `schema_version`, so they must
+/// tolerate running against databases that already have their changes.
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, description: "core tables", up: migrate_core_tables },
+    Migration { version: 2, description: "video source", up: migrate_video_source },
+    Migration { version: 3, description: "transcript search", up: migrate_transcript_fts },
+    Migration { version: 4, description: "tags", up: migrate_tags },
+    Migration { version: 5, description: "collections", up: migrate_collections },
+    Migration { version: 6, description: "notes", up: migrate_notes },
+    Migration { version: 7, description: "trash", up: migrate_trash },
+    Migration { version: 8, description: "transcript compression", up: migrate_transcript_compression },
+    Migration { version: 9, description: "watch status", up: migrate_watch_status },
+    Migration { version: 10, description: "favorites", up: migrate_favorites },
+    Migration { version: 11, description: "custom fields", up: migrate_video_meta },
+    Migration { version: 12, description: "video descriptions", up: migrate_descriptions },
+    Migration { version: 13, description: "channels", up: migrate_channels },
+    Migration { version: 14, description: "transcript segments", up: migrate_transcript_segments },
+    Migration { version: 15, description: "thumbnail cache", up: migrate_thumbnails },
+    Migration { version: 16, description: "activity log", up: migrate_activity },
+    Migration { version: 17, description: "recently opened", up: migrate_last_opened },
+    Migration { version: 18, description: "word counts", up: migrate_word_counts },
+    Migration { version: 19, description: "list indexes", up: migrate_list_indexes },
+    Migration { version: 20, description: "chapters", up: migrate_chapters },
+    Migration { version: 21, description: "engagement", up: migrate_engagement },
+    Migration { version: 22, description: "comments", up: migrate_comments },
+    Migration { version: 23, description: "transcript language", up: migrate_transcript_language },
+    Migration { version: 24, description: "transcript tracks", up: migrate_transcript_tracks },
+    Migration { version: 25, description: "original titles", up: migrate_original_title },
+    Migration { version: 26, description: "heatmaps", up: migrate_heatmaps },
+    Migration { version: 27, description: "media files", up: migrate_media_files },
+    Migration { version: 28, description: "lyrics", up: migrate_lyrics },
+    Migration { version: 29, description: "community posts", up: migrate_community_posts },
+    Migration { version: 30, description: "auto-generated captions", up: migrate_captions_auto_generated },
+    Migration { version: 31, description: "keywords and category", up: migrate_keywords_category },
+    Migration { version: 32, description: "visibility flags", up: migrate_video_flags },
+    Migration { version: 33, description: "availability", up: migrate_availability },
+    Migration { version: 34, description: "archive urls", up: migrate_archive_url },
+    Migration { version: 35, description: "channel handle cache", up: migrate_channel_handles },
+    Migration { version: 36, description: "credentials", up: migrate_credentials },
+];
+
+/// Highest migration version applied to this database, 0 for a fresh one.
+pub(crate) fn schema_version(conn: &Connection) -> Result<u32> {
+    conn.query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| row.get(0))
+}
+
+/// Apply every migration newer than the database, in order, each in its own
+/// transaction. Returns the resulting schema version.
+pub(crate) fn run_migrations(conn: &Connection, migrations: &[Migration]) -> Result<u32> {
     conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version    INTEGER PRIMARY KEY,
+            applied_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    let latest = migrations.last().map(|m| m.version).unwrap_or(0);
+    let current = schema_version(conn)?;
+    if current > latest {
+        return Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_MISMATCH),
+            Some(format!("Database schema v{} is newer than this app supports (v{})", current, latest)),
+        ));
+    }
+
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        // IMMEDIATE takes the write lock up front; re-check the version under it
+        // in case another connection migrated in the meantime.
+        let tx = rusqlite::Transaction::new_unchecked(conn, rusqlite::TransactionBehavior::Immediate)?;
+        if schema_version(&tx)? >= migration.version {
+            continue;
+        }
+        (migration.up)(&tx)?;
+        tx.execute("INSERT INTO schema_version (version) VALUES (?1)", params![migration.version])?;
+        tx.commit()?;
+        log::info!("Applied schema migration {} ({})", migration.version, migration.description);
+    }
+    schema_version(conn)
+}
+
+/// Add `column` to `table` unless it already exists. Returns whether it was added.
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<bool> {
+    let exists = conn.query_row(
+        &format!("SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1", table),
+        params![column],
+        |_| Ok(()),
+    ).is_ok();
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl), [])?;
+    }
+    Ok(!exists)
+}
+
+/// `videos`, `settings` and `search_history`, plus repairs for files written by
+/// the earliest releases.
+fn migrate_core_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS videos (
             video_id     TEXT PRIMARY KEY,
             title        TEXT,
@@ -18,20 +277,18 @@ pub fn init_db(db_path: &str) -> Result<()> {
             video_type   TEXT DEFAULT 'standard',
             published_at DATETIME,
             date_added   DATETIME DEFAULT CURRENT_TIMESTAMP
-        )",
-        [],
-    )?;
-
-    // Create settings table
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS settings (
+        );
+        CREATE TABLE IF NOT EXISTS settings (
             key   TEXT PRIMARY KEY,
             value TEXT
-        )",
-        [],
+        );",
     )?;
 
-    // Create search_history table
+    // search_history originally lacked search_query; the old rows aren't worth keeping
+    let history_ok = conn.query_row("SELECT 1 FROM pragma_table_info('search_history') WHERE name = 'search_query'", [], |_| Ok(())).is_ok();
+    if !history_ok {
+        conn.execute("DROP TABLE IF EXISTS search_history", [])?;
+    }
     conn.execute(
         "CREATE TABLE IF NOT EXISTS search_history (
             id           INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -42,148 +299,684 @@ pub fn init_db(db_path: &str) -> Result<()> {
         [],
     )?;
 
-    // Migration: Ensure settings table exists for old databases that might be missing it
-    let table_count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='settings'",
-        [],
-        |row| row.get(0),
-    )?;
-    
-    if table_count == 0 {
-        conn.execute(
-            "CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT)",
-            [],
+    // Very old files lack most metadata columns
+    add_column_if_missing(conn, "videos", "handle", "TEXT")?;
+    add_column_if_missing(conn, "videos", "length_seconds", "INTEGER")?;
+    add_column_if_missing(conn, "videos", "summary", "TEXT")?;
+    add_column_if_missing(conn, "videos", "view_count", "INTEGER DEFAULT 0")?;
+    add_column_if_missing(conn, "videos", "video_type", "TEXT DEFAULT 'standard'")?;
+    add_column_if_missing(conn, "videos", "published_at", "DATETIME")?;
+    add_column_if_missing(conn, "videos", "date_added", "DATETIME")?;
+
+    let published_at_type: String = conn
+        .query_row("SELECT type FROM pragma_table_info('videos') WHERE name = 'published_at'", [], |row| row.get(0))?;
+    // Early files stored published_at as TEXT; rebuild the table with DATETIME
+    if !published_at_type.eq_ignore_ascii_case("DATETIME") {
+        conn.execute_batch(
+            "CREATE TABLE videos_new (
+                video_id     TEXT PRIMARY KEY,
+                title        TEXT,
+                author       TEXT,
+                handle       TEXT,
+                length_seconds INTEGER,
+                transcript   TEXT,
+                summary      TEXT,
+                view_count   INTEGER DEFAULT 0,
+                video_type   TEXT DEFAULT 'standard',
+                published_at DATETIME,
+                date_added   DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO videos_new (
+                video_id, title, author, handle, length_seconds,
+                transcript, summary, view_count, video_type,
+                published_at, date_added
+            )
+            SELECT
+                video_id, title, author,
+                COALESCE(handle, ''),
+                COALESCE(length_seconds, 0),
+                COALESCE(transcript, ''),
+                COALESCE(summary, ''),
+                COALESCE(view_count, 0),
+                COALESCE(video_type, 'standard'),
+                published_at,
+                COALESCE(date_added, CURRENT_TIMESTAMP)
+            FROM videos;
+            DROP TABLE videos;
+            ALTER TABLE videos_new RENAME TO videos;",
         )?;
     }
+    Ok(())
+}
+
+/// Record provenance; rows saved before this existed count as manual.
+fn migrate_video_source(conn: &Connection) -> Result<()> {
+    if add_column_if_missing(conn, "videos", "source", "TEXT DEFAULT 'manual'")? {
+        conn.execute("UPDATE videos SET source = 'manual' WHERE source IS NULL", [])?;
+    }
+    Ok(())
+}
 
-    // Migration: Ensure search_history has the correct new schema (drop and recreate if it lacks search_query)
-    if conn.query_row("SELECT search_query FROM search_history LIMIT 1", [], |_| Ok(())).is_err() {
-        let _ = conn.execute("DROP TABLE search_history", []);
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS search_history (
-                id           INTEGER PRIMARY KEY AUTOINCREMENT,
-                search_query TEXT NOT NULL,
-                searched_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(search_query)
-            )",
-            [],
+/// Full-text index over title, author and transcript, kept in sync with
+/// `videos` by triggers. Populated from existing rows the first time it is created.
+fn migrate_transcript_fts(conn: &Connection) -> Result<()> {
+    let exists = conn.query_row("SELECT 1 FROM sqlite_master WHERE type='table' AND name='videos_fts'", [], |_| Ok(())).is_ok();
+    if !exists {
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE videos_fts USING fts5(
+                video_id UNINDEXED,
+                title,
+                author,
+                transcript,
+                tokenize = 'unicode61 remove_diacritics 2'
+            );
+            INSERT INTO videos_fts (video_id, title, author, transcript)
+                SELECT video_id, title, author, transcript FROM videos;",
         )?;
     }
 
-    // Migration: Add missing columns or update column types for older database files
-    // Use a robust check to ensure columns are exactly as expected
-    let schema_ok = {
-        let mut ok = true;
-        let mut stmt = conn.prepare("PRAGMA table_info(videos)").unwrap();
-        let mut rows = stmt.query([]).unwrap();
-        let mut found_published_at_dt = false;
-        while let Some(row) = rows.next().unwrap() {
-            let name: String = row.get(1).unwrap();
-            let type_str: String = row.get(2).unwrap();
-            if name == "published_at" && type_str.to_uppercase() == "DATETIME" {
-                found_published_at_dt = true;
+    conn.execute_batch(
+        "CREATE TRIGGER IF NOT EXISTS videos_fts_insert AFTER INSERT ON videos BEGIN
+            INSERT INTO videos_fts (video_id, title, author, transcript)
+            VALUES (new.video_id, new.title, new.author, new.transcript);
+        END;
+        CREATE TRIGGER IF NOT EXISTS videos_fts_delete AFTER DELETE ON videos BEGIN
+            DELETE FROM videos_fts WHERE video_id = old.video_id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS videos_fts_update AFTER UPDATE OF title, author, transcript ON videos BEGIN
+            DELETE FROM videos_fts WHERE video_id = old.video_id;
+            INSERT INTO videos_fts (video_id, title, author, transcript)
+            VALUES (new.video_id, new.title, new.author, new.transcript);
+        END;",
+    )
+}
+
+fn migrate_tags(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tags (
+            id   INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE
+        );
+        CREATE TABLE IF NOT EXISTS video_tags (
+            video_id TEXT NOT NULL,
+            tag_id   INTEGER NOT NULL,
+            PRIMARY KEY (video_id, tag_id)
+        );
+        CREATE TRIGGER IF NOT EXISTS video_tags_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM video_tags WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+fn migrate_collections(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS collections (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            name       TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE TABLE IF NOT EXISTS collection_videos (
+            collection_id INTEGER NOT NULL,
+            video_id      TEXT NOT NULL,
+            added_at      DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (collection_id, video_id)
+        );
+        CREATE TRIGGER IF NOT EXISTS collection_videos_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM collection_videos WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+/// Per-video markdown notes.
+fn migrate_notes(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "videos", "notes", "TEXT").map(|_| ())
+}
+
+/// Soft delete; trashed videos keep their row until purged.
+fn migrate_trash(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "videos", "deleted_at", "DATETIME").map(|_| ())
+}
+
+/// New transcripts are stored zstd-compressed in `transcript_zstd`; `transcript`
+/// stays for legacy rows. The FTS triggers are rebuilt to index either column.
+fn migrate_transcript_compression(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "videos", "transcript_zstd", "BLOB")?;
+    conn.execute_batch(
+        "DROP TRIGGER IF EXISTS videos_fts_insert;
+        DROP TRIGGER IF EXISTS videos_fts_update;
+        CREATE TRIGGER videos_fts_insert AFTER INSERT ON videos BEGIN
+            INSERT INTO videos_fts (video_id, title, author, transcript)
+            VALUES (new.video_id, new.title, new.author, COALESCE(new.transcript, zstd_decompress(new.transcript_zstd)));
+        END;
+        CREATE TRIGGER videos_fts_update AFTER UPDATE OF title, author, transcript, transcript_zstd ON videos BEGIN
+            DELETE FROM videos_fts WHERE video_id = old.video_id;
+            INSERT INTO videos_fts (video_id, title, author, transcript)
+            VALUES (new.video_id, new.title, new.author, COALESCE(new.transcript, zstd_decompress(new.transcript_zstd)));
+        END;",
+    )
+}
+
+/// When a video was marked watched; NULL while it is still unwatched.
+fn migrate_watch_status(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE videos ADD COLUMN watched_at DATETIME", []).map(|_| ())
+}
+
+fn migrate_favorites(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE videos ADD COLUMN starred INTEGER NOT NULL DEFAULT 0", []).map(|_| ())
+}
+
+/// User-defined key/value fields per video.
+fn migrate_video_meta(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE video_meta (
+            video_id TEXT NOT NULL,
+            key      TEXT NOT NULL COLLATE NOCASE,
+            value    TEXT NOT NULL,
+            PRIMARY KEY (video_id, key)
+        );
+        CREATE TRIGGER video_meta_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM video_meta WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+fn migrate_descriptions(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE videos ADD COLUMN description TEXT", []).map(|_| ())
+}
+
+/// Creators of saved videos. Videos saved before this migration have no
+/// `channel_id` until they are saved again.
+fn migrate_channels(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE channels (
+            channel_id TEXT PRIMARY KEY,
+            name       TEXT NOT NULL,
+            handle     TEXT,
+            avatar     TEXT,
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        );
+        ALTER TABLE videos ADD COLUMN channel_id TEXT;
+        CREATE INDEX idx_videos_channel_id ON videos (channel_id);",
+    )
+}
+
+/// Timed caption lines, kept alongside the flat transcript for click-to-seek.
+fn migrate_transcript_segments(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE transcript_segments (
+            video_id    TEXT NOT NULL,
+            seq         INTEGER NOT NULL,
+            start_ms    INTEGER NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            text        TEXT NOT NULL,
+            PRIMARY KEY (video_id, seq)
+        ) WITHOUT ROWID;
+        CREATE TRIGGER transcript_segments_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM transcript_segments WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+/// Thumbnail images kept locally so they show offline and after a video is
+/// taken down. Filled at save time, or on first request for older videos.
+fn migrate_thumbnails(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE thumbnails (
+            video_id   TEXT PRIMARY KEY,
+            mime       TEXT NOT NULL,
+            data       BLOB NOT NULL,
+            fetched_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE TRIGGER thumbnails_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM thumbnails WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+/// An append-only record of saves, deletions, imports and exports. Entries
+/// outlive the videos they mention.
+fn migrate_activity(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE activity (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind       TEXT NOT NULL,
+            video_id   TEXT,
+            detail     TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE INDEX idx_activity_kind ON activity(kind);",
+    )
+}
+
+fn migrate_last_opened(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE videos ADD COLUMN last_opened_at DATETIME;
+        CREATE INDEX idx_videos_last_opened_at ON videos(last_opened_at) WHERE last_opened_at IS NOT NULL;",
+    )
+}
+
+/// Word counts are kept current by triggers whenever a transcript is written.
+/// Existing rows stay NULL until `backfill_word_counts` runs, so opening an
+/// old library doesn't have to decompress every transcript.
+fn migrate_word_counts(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE videos ADD COLUMN word_count INTEGER;
+        CREATE TRIGGER videos_word_count_insert AFTER INSERT ON videos BEGIN
+            UPDATE videos SET word_count = count_words(COALESCE(new.transcript, zstd_decompress(new.transcript_zstd)))
+            WHERE rowid = new.rowid;
+        END;
+        CREATE TRIGGER videos_word_count_update AFTER UPDATE OF transcript, transcript_zstd ON videos BEGIN
+            UPDATE videos SET word_count = count_words(COALESCE(new.transcript, zstd_decompress(new.transcript_zstd)))
+            WHERE rowid = new.rowid;
+        END;",
+    )
+}
+
+/// Indexes for the library's usual sorts and filters. Author and handle match
+/// case-insensitively, so those are indexed with NOCASE.
+fn migrate_list_indexes(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE INDEX idx_videos_date_added ON videos (date_added);
+        CREATE INDEX idx_videos_published_at ON videos (published_at);
+        CREATE INDEX idx_videos_author ON videos (author COLLATE NOCASE);
+        CREATE INDEX idx_videos_handle ON videos (handle COLLATE NOCASE);",
+    )
+}
+
+/// Chapter markers for saved videos, from the player or the description.
+fn migrate_chapters(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE chapters (
+            video_id TEXT NOT NULL,
+            start_ms INTEGER NOT NULL,
+            title    TEXT NOT NULL,
+            PRIMARY KEY (video_id, start_ms)
+        ) WITHOUT ROWID;
+        CREATE TRIGGER chapters_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM chapters WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+/// Like and comment counts captured when a video is saved.
+fn migrate_engagement(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE videos ADD COLUMN like_count INTEGER;
+        ALTER TABLE videos ADD COLUMN comment_count INTEGER;",
+    )
+}
+
+/// Top comments archived with a video when it is saved.
+fn migrate_comments(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE comments (
+            video_id    TEXT NOT NULL,
+            seq         INTEGER NOT NULL,
+            comment_id  TEXT NOT NULL,
+            author      TEXT NOT NULL,
+            text        TEXT NOT NULL,
+            like_count  INTEGER NOT NULL DEFAULT 0,
+            reply_count INTEGER NOT NULL DEFAULT 0,
+            published   TEXT NOT NULL DEFAULT '',
+            PRIMARY KEY (video_id, seq)
+        ) WITHOUT ROWID;
+        CREATE TRIGGER comments_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM comments WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+/// Which caption language a saved transcript is in; unknown for older saves.
+fn migrate_transcript_language(conn: &Connection) -> Result<()> {
+    conn.execute_batch("ALTER TABLE videos ADD COLUMN transcript_language TEXT;")
+}
+
+/// Caption tracks saved in languages besides the video's main transcript, which
+/// stays on `videos` for search, summaries and word counts.
+fn migrate_transcript_tracks(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE transcripts (
+            video_id        TEXT NOT NULL,
+            language        TEXT NOT NULL,
+            transcript_zstd BLOB NOT NULL,
+            segments        TEXT NOT NULL DEFAULT '[]',
+            saved_at        TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (video_id, language)
+        ) WITHOUT ROWID;
+        CREATE TRIGGER transcripts_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM transcripts WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+/// The uploader's title for videos saved under a DeArrow title.
+fn migrate_original_title(conn: &Connection) -> Result<()> {
+    conn.execute_batch("ALTER TABLE videos ADD COLUMN original_title TEXT;")
+}
+
+/// The "most replayed" graph of saved videos, one row per stretch.
+fn migrate_heatmaps(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE heatmaps (
+            video_id    TEXT NOT NULL,
+            start_ms    INTEGER NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            intensity   REAL NOT NULL,
+            PRIMARY KEY (video_id, start_ms)
+        ) WITHOUT ROWID;
+        CREATE TRIGGER heatmaps_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM heatmaps WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+/// Audio and video downloaded for saved videos, at most one of each kind.
+fn migrate_media_files(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE media_files (
+            video_id      TEXT NOT NULL,
+            kind          TEXT NOT NULL,
+            path          TEXT NOT NULL,
+            mime_type     TEXT NOT NULL,
+            size          INTEGER NOT NULL,
+            downloaded_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (video_id, kind)
+        ) WITHOUT ROWID;
+        CREATE TRIGGER media_files_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM media_files WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+/// Song lyrics from YouTube Music, kept like transcripts. `lines` holds the
+/// synced lines as JSON.
+fn migrate_lyrics(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE lyrics (
+            video_id TEXT PRIMARY KEY,
+            text     TEXT NOT NULL,
+            lines    TEXT NOT NULL DEFAULT '[]',
+            source   TEXT,
+            saved_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        ) WITHOUT ROWID;
+        CREATE TRIGGER lyrics_cleanup AFTER DELETE ON videos BEGIN
+            DELETE FROM lyrics WHERE video_id = old.video_id;
+        END;",
+    )
+}
+
+/// Archived Community tab posts. They belong to channels rather than saved
+/// videos, so nothing removes them with a video. `data` is the post as JSON;
+/// `published_at` is worked out from YouTube's "3 days ago" when first saved.
+fn migrate_community_posts(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE community_posts (
+            post_id      TEXT PRIMARY KEY,
+            channel_id   TEXT NOT NULL,
+            data         TEXT NOT NULL,
+            published_at TEXT NOT NULL,
+            saved_at     TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        ) WITHOUT ROWID;
+        CREATE INDEX idx_community_posts_channel ON community_posts(channel_id, published_at);",
+    )
+}
+
+/// Whether a saved transcript came from automatic captions; unknown (NULL) for
+/// older saves.
+fn migrate_captions_auto_generated(conn: &Connection) -> Result<()> {
+    conn.execute_batch("ALTER TABLE videos ADD COLUMN captions_auto_generated INTEGER;")
+}
+
+/// The uploader's keywords, as a JSON array, and YouTube's category, from the
+/// player response. Both are unknown (NULL) for older saves.
+fn migrate_keywords_category(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE videos ADD COLUMN keywords TEXT;
+        ALTER TABLE videos ADD COLUMN category TEXT;
+        CREATE INDEX idx_videos_category ON videos(category);",
+    )
+}
+
+/// Whether a video was private or unlisted when saved, and whether it was a
+/// live stream. Unknown (NULL) for older saves.
+fn migrate_video_flags(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE videos ADD COLUMN is_private INTEGER;
+        ALTER TABLE videos ADD COLUMN is_unlisted INTEGER;
+        ALTER TABLE videos ADD COLUMN is_live_content INTEGER;",
+    )
+}
+
+/// Whether a saved video is still on YouTube, as `verify_library` last found
+/// it, and when that was. Both NULL until it has been checked.
+fn migrate_availability(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE videos ADD COLUMN availability TEXT;
+        ALTER TABLE videos ADD COLUMN availability_checked_at TEXT;",
+    )
+}
+
+/// A Wayback Machine capture of a video's watch page, for videos no longer on
+/// YouTube.
+fn migrate_archive_url(conn: &Connection) -> Result<()> {
+    conn.execute_batch("ALTER TABLE videos ADD COLUMN archive_url TEXT;")
+}
+
+/// Channel IDs that handles resolved to, so looking a channel up again
+/// doesn't go back to YouTube. Not tied to saved videos or `channels`.
+fn migrate_channel_handles(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE channel_handles (
+            handle      TEXT PRIMARY KEY COLLATE NOCASE,
+            channel_id  TEXT NOT NULL,
+            resolved_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        ) WITHOUT ROWID;",
+    )
+}
+
+/// Credentials such as imported YouTube cookies, kept out of `settings` so the
+/// generic settings commands and exports never see them. Moves any cookies
+/// saved by earlier releases.
+fn migrate_credentials(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE credentials (
+            name  TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        ) WITHOUT ROWID;
+        INSERT INTO credentials (name, value)
+            SELECT key, value FROM settings WHERE key = 'youtube_cookies';
+        DELETE FROM settings WHERE key = 'youtube_cookies';",
+    )
+}
+
+/// Columns selected for list views, in the order `row_to_video` expects.
+pub(crate) const VIDEO_COLUMNS: &str = "video_id, title, author, length_seconds, view_count, published_at, date_added, handle, video_type, source, watched_at, starred, word_count, like_count, comment_count, original_title, captions_auto_generated, is_private, is_unlisted, is_live_content, availability";
+
+/// `VIDEO_COLUMNS` qualified with a table alias, for use in joins.
+pub(crate) fn video_columns_as(alias: &str) -> String {
+    VIDEO_COLUMNS.split(", ").map(|c| format!("{}.{}", alias, c)).collect::<Vec<_>>().join(", ")
+}
+
+/// How many columns `VIDEO_COLUMNS` selects, which is the index of the first
+/// column selected after them.
+pub(crate) fn video_column_count() -> usize {
+    VIDEO_COLUMNS.split(", ").count()
+}
+
+/// Map a row selected with `VIDEO_COLUMNS` into a `Video`.
+pub(crate) fn row_to_video(row: &rusqlite::Row) -> Result<Video> {
+    let view_count_str = match row.get::<_, Option<i64>>(4) {
+        Ok(Some(0)) | Ok(None) => "Saved".to_string(),
+        Ok(Some(n)) => n.to_string(),
+        Err(_) => {
+            match row.get::<_, Option<String>>(4) {
+                Ok(Some(ref s)) if s == "0" => "Saved".to_string(),
+                Ok(Some(s)) => s,
+                _ => "Saved".to_string(),
             }
         }
-        found_published_at_dt
     };
+    Ok(Video {
+        id: row.get::<_, String>(0).unwrap_or_default(),
+        title: row.get::<_, Option<String>>(1).unwrap_or(None).unwrap_or_else(|| "Unknown".to_string()),
+        author: row.get::<_, Option<String>>(2).unwrap_or(None),
+        length_seconds: match row.get::<_, Option<i32>>(3) {
+            Ok(v) => v,
+            Err(_) => row.get::<_, Option<String>>(3).unwrap_or(None).and_then(|s| s.parse().ok()),
+        },
+        view_count: view_count_str,
+        thumbnail: crate::youtube::thumbnail_url(&row.get::<_, String>(0).unwrap_or_default(), ThumbnailQuality::Hq),
+        published_at: row.get::<_, Option<String>>(5).unwrap_or(None).unwrap_or_else(|| "".to_string()),
+        status: Some("saved".to_string()),
+        date_added: row.get::<_, Option<String>>(6).unwrap_or(None),
+        handle: row.get::<_, Option<String>>(7).unwrap_or(None),
+        video_type: row.get::<_, Option<String>>(8).unwrap_or(None),
+        source: row.get::<_, Option<String>>(9).unwrap_or(None),
+        watched_at: row.get::<_, Option<String>>(10).unwrap_or(None),
+        starred: row.get::<_, bool>(11).unwrap_or(false),
+        word_count: row.get::<_, Option<i64>>(12).unwrap_or(None),
+        reading_minutes: row.get::<_, Option<i64>>(12).unwrap_or(None).map(reading_minutes),
+        like_count: row.get::<_, Option<i64>>(13).unwrap_or(None),
+        comment_count: row.get::<_, Option<i64>>(14).unwrap_or(None),
+        original_title: row.get::<_, Option<String>>(15).unwrap_or(None),
+        captions_auto_generated: row.get::<_, Option<bool>>(16).unwrap_or(None),
+        is_private: row.get::<_, Option<bool>>(17).unwrap_or(None),
+        is_unlisted: row.get::<_, Option<bool>>(18).unwrap_or(None),
+        is_live_content: row.get::<_, Option<bool>>(19).unwrap_or(None),
+        availability: row.get::<_, Option<String>>(20).unwrap_or(None),
+        ..Default::default()
+    })
+}
 
-    if !schema_ok {
-        // Check if table has published_at at all
-        let has_col = conn.query_row("SELECT name FROM pragma_table_info('videos') WHERE name='published_at'", [], |_| Ok(())).is_ok();
-        
-        if !has_col {
-            // Simple expansion for very old DBs
-            let _ = conn.execute("ALTER TABLE videos ADD COLUMN handle TEXT", []);
-            let _ = conn.execute("ALTER TABLE videos ADD COLUMN length_seconds INTEGER", []);
-            let _ = conn.execute("ALTER TABLE videos ADD COLUMN summary TEXT", []);
-            let _ = conn.execute("ALTER TABLE videos ADD COLUMN view_count INTEGER DEFAULT 0", []);
-            let _ = conn.execute("ALTER TABLE videos ADD COLUMN video_type TEXT DEFAULT 'standard'", []);
-            let _ = conn.execute("ALTER TABLE videos ADD COLUMN published_at DATETIME", []);
-            let _ = conn.execute("ALTER TABLE videos ADD COLUMN date_added DATETIME DEFAULT CURRENT_TIMESTAMP", []);
-        } else {
-            // Full migration needed to change TEXT to DATETIME
-            let _ = conn.execute_batch("
-                BEGIN TRANSACTION;
-                CREATE TABLE videos_new (
-                    video_id     TEXT PRIMARY KEY,
-                    title        TEXT,
-                    author       TEXT,
-                    handle       TEXT,
-                    length_seconds INTEGER,
-                    transcript   TEXT,
-                    summary      TEXT,
-                    view_count   INTEGER DEFAULT 0,
-                    video_type   TEXT DEFAULT 'standard',
-                    published_at DATETIME,
-                    date_added   DATETIME DEFAULT CURRENT_TIMESTAMP
-                );
-                INSERT INTO videos_new (
-                    video_id, title, author, handle, length_seconds, 
-                    transcript, summary, view_count, video_type, 
-                    published_at, date_added
-                )
-                SELECT 
-                    video_id, title, author, 
-                    COALESCE(handle, ''), 
-                    COALESCE(length_seconds, 0), 
-                    COALESCE(transcript, ''), 
-                    COALESCE(summary, ''), 
-                    COALESCE(view_count, 0), 
-                    COALESCE(video_type, 'standard'), 
-                    published_at, 
-                    COALESCE(date_added, CURRENT_TIMESTAMP)
-                FROM videos;
-                DROP TABLE videos;
-                ALTER TABLE videos_new RENAME TO videos;
-                COMMIT;
-            ");
+/// Saved videos matching `query`, in the requested order.
+pub fn list_videos(conn: &Connection, query: &VideoQuery) -> Result<Vec<Video>> {
+    let mut conditions: Vec<&str> = vec!["deleted_at IS NULL"];
+    let mut args: Vec<SqlValue> = Vec::new();
+    let text_arg = |args: &mut Vec<SqlValue>, value: &str| args.push(SqlValue::Text(value.trim().to_string()));
+
+    match query.video_type.as_deref() {
+        Some("short") => conditions.push("video_type = 'short'"),
+        Some("standard") => conditions.push("video_type = 'standard'"),
+        _ => {}
+    }
+    if let Some(source) = query.source.as_deref() {
+        conditions.push("source = ?");
+        text_arg(&mut args, source);
+    }
+    if let Some(channel_id) = query.channel_id.as_deref() {
+        conditions.push("channel_id = ?");
+        text_arg(&mut args, channel_id);
+    }
+    if let Some(id) = query.collection_id {
+        conditions.push("video_id IN (SELECT video_id FROM collection_videos WHERE collection_id = ?)");
+        args.push(SqlValue::Integer(id));
+    }
+    if let Some(author) = query.author.as_deref().filter(|a| !a.trim().is_empty()) {
+        conditions.push("(author = ? COLLATE NOCASE OR handle = ? COLLATE NOCASE)");
+        text_arg(&mut args, author);
+        text_arg(&mut args, author);
+    }
+    if let Some(tag) = query.tag.as_deref().filter(|t| !t.trim().is_empty()) {
+        conditions.push("video_id IN (SELECT vt.video_id FROM video_tags vt JOIN tags t ON t.id = vt.tag_id WHERE t.name = ?)");
+        text_arg(&mut args, tag);
+    }
+    if let Some(category) = query.category.as_deref().filter(|c| !c.trim().is_empty()) {
+        conditions.push("category = ? COLLATE NOCASE");
+        text_arg(&mut args, category);
+    }
+    if let Some(keyword) = query.keyword.as_deref().filter(|k| !k.trim().is_empty()) {
+        conditions.push("EXISTS (SELECT 1 FROM json_each(keywords) WHERE value = ? COLLATE NOCASE)");
+        text_arg(&mut args, keyword);
+    }
+    match query.unavailable {
+        Some(true) => conditions.push("availability IN ('private', 'removed')"),
+        Some(false) => conditions.push("(availability IS NULL OR availability NOT IN ('private', 'removed'))"),
+        None => {}
+    }
+    match query.has_note {
+        Some(true) => conditions.push("notes IS NOT NULL"),
+        Some(false) => conditions.push("notes IS NULL"),
+        None => {}
+    }
+    match query.watched {
+        Some(true) => conditions.push("watched_at IS NOT NULL"),
+        Some(false) => conditions.push("watched_at IS NULL"),
+        None => {}
+    }
+    match query.starred {
+        Some(true) => conditions.push("starred = 1"),
+        Some(false) => conditions.push("starred = 0"),
+        None => {}
+    }
+    let date_bounds = [
+        // Compared as plain strings so the date_added and published_at indexes apply
+        (&query.added_from, "date_added >= date(?)"),
+        (&query.added_to, "date_added < date(?, '+1 day')"),
+        (&query.published_from, "published_at >= date(?)"),
+        (&query.published_to, "published_at < date(?, '+1 day')"),
+    ];
+    for (bound, condition) in date_bounds {
+        if let Some(date) = bound.as_deref().filter(|d| !d.trim().is_empty()) {
+            conditions.push(condition);
+            text_arg(&mut args, date);
         }
     }
 
-    Ok(())
-}
+    // Videos missing the sort value always go last, whichever the direction.
+    let order = match query.sort {
+        VideoSort::DateAdded => "date_added",
+        VideoSort::PublishedAt => "NULLIF(published_at, '')",
+        VideoSort::Title => "NULLIF(title, '') COLLATE NOCASE",
+        VideoSort::ViewCount => "view_count",
+        VideoSort::Length => "length_seconds",
+        VideoSort::WordCount => "word_count",
+    };
+    let descending = query.descending.unwrap_or(query.sort != VideoSort::Title);
+    let direction = if descending { "DESC" } else { "ASC" };
+
+    let query = format!(
+        "SELECT {} FROM videos WHERE {} ORDER BY {} {} NULLS LAST, rowid {}",
+        VIDEO_COLUMNS, conditions.join(" AND "), order, direction, direction
+    );
+    
+    let mut stmt = conn.prepare(&query)?;
+    let video_iter = stmt.query_map(rusqlite::params_from_iter(args), row_to_video)?;
 
+    let mut videos = Vec::new();
+    for video in video_iter {
+        videos.push(video?);
+    }
+    attach_collections(conn, &mut videos)?;
+    Ok(videos)
+}
 
+/// Saved videos whose title, author, handle or keywords contain `query`,
+/// ignoring case, newest first.
+pub fn search_library(conn: &Connection, query: &str) -> Result<Vec<Video>> {
+    let sql = format!(
+        "SELECT {} FROM videos
+         WHERE deleted_at IS NULL
+           AND (instr(fold_case(title), ?1) > 0 OR instr(fold_case(author), ?1) > 0 OR instr(fold_case(handle), ?1) > 0
+                OR instr(fold_case(keywords), ?1) > 0)
+         ORDER BY date_added DESC, rowid DESC",
+        VIDEO_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![query.trim().to_lowercase()], row_to_video)?;
+    let mut videos = rows.collect::<Result<Vec<_>>>()?;
+    attach_collections(conn, &mut videos)?;
+    Ok(videos)
+}
 
-pub fn list_videos(db_path: &str, video_type_filter: Option<&str>) -> Result<Vec<Video>> {
-    let conn = Connection::open(db_path)?;
-    
-    let query = match video_type_filter {
-        Some("short") => "SELECT video_id, title, author, length_seconds, view_count, published_at, date_added, handle, video_type FROM videos WHERE video_type = 'short' ORDER BY date_added DESC, rowid DESC",
-        Some("standard") => "SELECT video_id, title, author, length_seconds, view_count, published_at, date_added, handle, video_type FROM videos WHERE video_type = 'standard' ORDER BY date_added DESC, rowid DESC",
-        _ => "SELECT video_id, title, author, length_seconds, view_count, published_at, date_added, handle, video_type FROM videos ORDER BY date_added DESC, rowid DESC",
-    };
-    
-    let mut stmt = conn.prepare(query)?;
-    let video_iter = stmt.query_map([], |row| {
-        let view_count_str = match row.get::<_, Option<i64>>(4) {
-            Ok(Some(0)) | Ok(None) => "Saved".to_string(),
-            Ok(Some(n)) => n.to_string(),
-            Err(_) => {
-                match row.get::<_, Option<String>>(4) {
-                    Ok(Some(ref s)) if s == "0" => "Saved".to_string(),
-                    Ok(Some(s)) => s,
-                    _ => "Saved".to_string(),
-                }
-            }
-        };
-        Ok(Video {
-            id: row.get::<_, String>(0).unwrap_or_default(),
-            title: row.get::<_, Option<String>>(1).unwrap_or(None).unwrap_or_else(|| "Unknown".to_string()),
-            author: row.get::<_, Option<String>>(2).unwrap_or(None),
-            length_seconds: match row.get::<_, Option<i32>>(3) {
-                Ok(v) => v,
-                Err(_) => row.get::<_, Option<String>>(3).unwrap_or(None).and_then(|s| s.parse().ok()),
-            },
-            view_count: view_count_str,
-            thumbnail: format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", row.get::<_, String>(0).unwrap_or_default()),
-            published_at: row.get::<_, Option<String>>(5).unwrap_or(None).unwrap_or_else(|| "".to_string()),
-            status: Some("saved".to_string()),
-            date_added: row.get::<_, Option<String>>(6).unwrap_or(None),
-            handle: row.get::<_, Option<String>>(7).unwrap_or(None),
-            video_type: row.get::<_, Option<String>>(8).unwrap_or(None),
-        })
-    })?;
+/// Saved videos with an empty or missing transcript, newest first.
+/// The transcript column itself is never selected.
+pub fn list_videos_without_transcript(conn: &Connection) -> Result<Vec<Video>> {
+    let query = format!(
+        "SELECT {} FROM videos WHERE deleted_at IS NULL AND transcript_zstd IS NULL AND (transcript IS NULL OR length(transcript) = 0) ORDER BY date_added DESC, rowid DESC",
+        VIDEO_COLUMNS
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let video_iter = stmt.query_map([], row_to_video)?;
 
     let mut videos = Vec::new();
     for video in video_iter {
@@ -192,104 +985,318 @@ pub fn list_videos(db_path: &str, video_type_filter: Option<&str>) -> Result<Vec
     Ok(videos)
 }
 
-pub fn save_video(db_path: &str, video_id: &str, title: &str, author: &str, length: i32, transcript: &str, view_count: i64, published_at: &str, handle: &str, video_type: &str, summary: Option<&str>) -> Result<()> {
+/// Find saved videos whose transcript (or title/author) contains `phrase`,
+/// best matches first, with a highlighted excerpt of the transcript.
+pub fn search_transcripts(conn: &Connection, phrase: &str, limit: i64) -> Result<Vec<TranscriptMatch>> {
+    // Quote as a single FTS5 phrase so user punctuation can't break the query syntax.
+    let fts_query = format!("\"{}\"", phrase.trim().replace('"', "\"\""));
+    let query = format!(
+        "SELECT {}, snippet(videos_fts, 3, '[', ']', '…', 16)
+         FROM videos_fts JOIN videos v ON v.video_id = videos_fts.video_id
+         WHERE videos_fts MATCH ?1 AND v.deleted_at IS NULL
+         ORDER BY rank LIMIT ?2",
+        video_columns_as("v")
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(params![fts_query, limit], |row| {
+        Ok(TranscriptMatch {
+            video: row_to_video(row)?,
+            snippet: row.get::<_, Option<String>>(video_column_count())?.unwrap_or_default(),
+        })
+    })?;
+
+    let mut matches = Vec::new();
+    for m in rows {
+        matches.push(m?);
+    }
+    Ok(matches)
+}
+
+pub fn save_video(conn: &Connection, video_id: &str, title: &str, author: &str, length: i32, transcript: &str, view_count: i64, published_at: &str, handle: &str, video_type: &str, summary: Option<&str>, description: &str, source: &str) -> Result<()> {
     let video_id = video_id.trim();
-    let conn = Connection::open(db_path)?;
+    let compressed = compress_transcript(transcript)?;
+    let plain = if compressed.is_some() { None } else { Some(transcript) };
     conn.execute(
-        "INSERT INTO videos (video_id, title, author, length_seconds, transcript, view_count, published_at, handle, video_type, summary)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+        "INSERT INTO videos (video_id, title, author, length_seconds, transcript, view_count, published_at, handle, video_type, summary, source, transcript_zstd, description)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, NULLIF(?13, ''))
          ON CONFLICT(video_id) DO UPDATE SET 
             title=excluded.title, 
             author=excluded.author, 
             length_seconds=excluded.length_seconds, 
             transcript=excluded.transcript,
+            transcript_zstd=excluded.transcript_zstd,
             view_count=excluded.view_count,
             published_at=excluded.published_at,
             handle=excluded.handle,
             video_type=excluded.video_type,
-            summary=COALESCE(excluded.summary, videos.summary)",
-        params![video_id, title, author, length, transcript, view_count, published_at, handle, video_type, summary],
+            summary=COALESCE(excluded.summary, videos.summary),
+            description=COALESCE(excluded.description, videos.description),
+            source=COALESCE(videos.source, excluded.source),
+            deleted_at=NULL",
+        params![video_id, title, author, length, plain, view_count, published_at, handle, video_type, summary, source, compressed, description],
     )?;
     Ok(())
 }
 
-pub fn delete_video(db_path: &str, video_id: &str) -> Result<()> {
-    let conn = Connection::open(db_path)?;
-    conn.execute("DELETE FROM videos WHERE video_id = ?", params![video_id])?;
-    Ok(())
+/// Move a video to the trash. It stays restorable until purged. Returns the
+/// number of rows trashed.
+pub fn delete_video(conn: &Connection, video_id: &str) -> Result<usize> {
+    conn.execute(
+        "UPDATE videos SET deleted_at = CURRENT_TIMESTAMP WHERE video_id = ? AND deleted_at IS NULL",
+        params![video_id],
+    )
 }
 
-pub fn check_video_exists(db_path: &str, video_id: &str) -> Result<bool> {
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT 1 FROM videos WHERE video_id = ?")?;
+/// Run `update` (an `UPDATE videos ... WHERE ... IN (?..)` with the placeholder
+/// list left as `{}`, ending in `RETURNING video_id`) over `video_ids` in one
+/// transaction, chunked like `check_videos_exist`. Returns the IDs it changed.
+fn update_videos_returning(conn: &Connection, update: &str, video_ids: &[String]) -> Result<Vec<String>> {
+    const CHUNK: usize = 500;
+    let tx = conn.unchecked_transaction()?;
+    let mut changed = Vec::new();
+    for chunk in video_ids.chunks(CHUNK) {
+        let placeholders = vec!["?"; chunk.len()].join(",");
+        let mut stmt = tx.prepare(&update.replace("{}", &placeholders))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(chunk.iter().map(|id| id.trim())), |row| row.get(0))?;
+        for id in rows {
+            changed.push(id?);
+        }
+    }
+    tx.commit()?;
+    Ok(changed)
+}
+
+/// Move many videos to the trash in one transaction. Returns the IDs trashed.
+pub fn trash_videos(conn: &Connection, video_ids: &[String]) -> Result<Vec<String>> {
+    update_videos_returning(
+        conn,
+        "UPDATE videos SET deleted_at = CURRENT_TIMESTAMP WHERE deleted_at IS NULL AND video_id IN ({}) RETURNING video_id",
+        video_ids,
+    )
+}
+
+/// Take many videos back out of the trash. Returns the IDs restored.
+pub fn restore_videos(conn: &Connection, video_ids: &[String]) -> Result<Vec<String>> {
+    update_videos_returning(
+        conn,
+        "UPDATE videos SET deleted_at = NULL WHERE deleted_at IS NOT NULL AND video_id IN ({}) RETURNING video_id",
+        video_ids,
+    )
+}
+
+/// Move every saved video matching `filter` to the trash. Empty criteria are
+/// ignored; with none left nothing is trashed. Returns the IDs trashed.
+pub fn trash_matching(conn: &Connection, filter: &BulkDeleteFilter) -> Result<Vec<String>> {
+    let mut conditions: Vec<&str> = vec!["deleted_at IS NULL"];
+    let mut args: Vec<String> = Vec::new();
+    if let Some(author) = filter.author.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+        conditions.push("(author = ? COLLATE NOCASE OR handle = ? COLLATE NOCASE)");
+        args.push(author.to_string());
+        args.push(author.to_string());
+    }
+    if let Some(tag) = filter.tag.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        conditions.push("video_id IN (SELECT vt.video_id FROM video_tags vt JOIN tags t ON t.id = vt.tag_id WHERE t.name = ?)");
+        args.push(tag.to_string());
+    }
+    if let Some(date) = filter.added_before.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        conditions.push("date_added < date(?)");
+        args.push(date.to_string());
+    }
+    if args.is_empty() {
+        return Ok(Vec::new());
+    }
+    let query = format!("UPDATE videos SET deleted_at = CURRENT_TIMESTAMP WHERE {} RETURNING video_id", conditions.join(" AND "));
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(args.iter()), |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Take a video back out of the trash. Returns the number of rows restored.
+pub fn restore_video(conn: &Connection, video_id: &str) -> Result<usize> {
+    conn.execute(
+        "UPDATE videos SET deleted_at = NULL WHERE video_id = ? AND deleted_at IS NOT NULL",
+        params![video_id],
+    )
+}
+
+/// Trashed videos, most recently deleted first.
+pub fn list_trash(conn: &Connection) -> Result<Vec<TrashedVideo>> {
+    let query = format!(
+        "SELECT {}, deleted_at FROM videos WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, rowid DESC",
+        VIDEO_COLUMNS
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(TrashedVideo {
+            video: row_to_video(row)?,
+            deleted_at: row.get(video_column_count())?,
+        })
+    })?;
+
+    let mut videos = Vec::new();
+    for video in rows {
+        videos.push(video?);
+    }
+    Ok(videos)
+}
+
+/// Permanently delete trashed videos, or only those trashed more than
+/// `older_than_days` ago. Returns the number of videos removed.
+pub fn purge_trash(conn: &Connection, older_than_days: Option<u32>) -> Result<usize> {
+    match older_than_days {
+        Some(days) => conn.execute(
+            "DELETE FROM videos WHERE deleted_at IS NOT NULL AND deleted_at <= datetime('now', ?1)",
+            params![format!("-{} days", days)],
+        ),
+        None => conn.execute("DELETE FROM videos WHERE deleted_at IS NOT NULL", []),
+    }
+}
+
+pub fn check_video_exists(conn: &Connection, video_id: &str) -> Result<bool> {
+    let mut stmt = conn.prepare("SELECT 1 FROM videos WHERE video_id = ? AND deleted_at IS NULL")?;
     let mut rows = stmt.query(params![video_id])?;
     Ok(rows.next()?.is_some())
 }
 
-pub fn get_transcript(db_path: &str, video_id: &str) -> Result<Option<String>> {
-    let video_id = video_id.trim();
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT transcript FROM videos WHERE video_id = ?")?;
-    let mut rows = stmt.query(params![video_id])?;
-    if let Some(row) = rows.next()? {
-        Ok(Some(row.get(0)?))
-    } else {
-        Ok(None)
+/// Check many IDs in one pass. The `IN` list is chunked to stay under SQLite's
+/// bound-variable limit.
+pub fn check_videos_exist(conn: &Connection, video_ids: &[String]) -> Result<HashMap<String, bool>> {
+    const CHUNK: usize = 500;
+    let mut result: HashMap<String, bool> = video_ids.iter().map(|id| (id.clone(), false)).collect();
+    for chunk in video_ids.chunks(CHUNK) {
+        let placeholders = vec!["?"; chunk.len()].join(",");
+        let query = format!("SELECT video_id FROM videos WHERE deleted_at IS NULL AND video_id IN ({})", placeholders);
+        let mut stmt = conn.prepare(&query)?;
+        let mut rows = stmt.query(rusqlite::params_from_iter(chunk.iter()))?;
+        while let Some(row) = rows.next()? {
+            result.insert(row.get(0)?, true);
+        }
     }
+    Ok(result)
 }
 
-pub fn get_video_full(db_path: &str, video_id: &str) -> Result<Option<(String, String, String, i32, String, i64, String, String, String, String)>> {
-     let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT video_id, title, author, length_seconds, transcript, view_count, published_at, handle, video_type, date_added FROM videos WHERE video_id = ?")?;
+pub fn get_transcript(conn: &Connection, video_id: &str) -> Result<Option<String>> {
+    let video_id = video_id.trim();
+    let mut stmt = conn.prepare("SELECT transcript, transcript_zstd FROM videos WHERE video_id = ?")?;
     let mut rows = stmt.query(params![video_id])?;
     if let Some(row) = rows.next()? {
-        Ok(Some((
-            row.get::<_, String>(0).unwrap_or_default(),
-            row.get::<_, Option<String>>(1).unwrap_or(None).unwrap_or_else(|| "Unknown".to_string()),
-            row.get::<_, Option<String>>(2).unwrap_or(None).unwrap_or_else(|| "Unknown".to_string()),
-            match row.get::<_, Option<i32>>(3) {
-                Ok(Some(v)) => v,
-                Err(_) => row.get::<_, Option<String>>(3).unwrap_or(None).and_then(|s| s.parse().ok()).unwrap_or(0),
-                _ => 0,
-            },
-            row.get::<_, Option<String>>(4).unwrap_or(None).unwrap_or_else(|| "".to_string()),
-            match row.get::<_, Option<i64>>(5) {
-                Ok(Some(n)) => n,
-                Err(_) => match row.get::<_, Option<String>>(5) {
-                    Ok(Some(s)) => s.parse::<i64>().unwrap_or(0),
-                    _ => 0,
-                },
-                _ => 0,
-            },
-            row.get::<_, Option<String>>(6).unwrap_or(None).unwrap_or_else(|| "".to_string()),
-            row.get::<_, Option<String>>(7).unwrap_or(None).unwrap_or_else(|| "".to_string()),
-            row.get::<_, Option<String>>(8).unwrap_or(None).unwrap_or_else(|| "standard".to_string()),
-            row.get::<_, Option<String>>(9).unwrap_or(None).unwrap_or_else(|| "".to_string())
-        )))
+        match row.get::<_, Option<Vec<u8>>>(1)? {
+            Some(blob) => Ok(Some(decompress_transcript(&blob)?)),
+            None => Ok(Some(row.get(0)?)),
+        }
     } else {
         Ok(None)
     }
 }
 
-pub fn vacuum_db(db_path: &str) -> Result<()> {
-    let conn = Connection::open(db_path)?;
-    conn.execute("VACUUM", [])?;
+/// Record the uploader's title for a video saved under a DeArrow title, or
+/// clear it when the video is saved under its own.
+pub fn set_original_title(conn: &Connection, video_id: &str, original_title: Option<&str>) -> Result<()> {
+    conn.execute("UPDATE videos SET original_title = ?2 WHERE video_id = ?1", params![video_id.trim(), original_title])?;
     Ok(())
 }
 
-pub fn get_setting(db_path: &str, key: &str) -> Result<Option<String>> {
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT value FROM settings WHERE key = ?")?;
-    let mut rows = stmt.query(params![key])?;
-    if let Some(row) = rows.next()? {
-        Ok(Some(row.get(0)?))
-    } else {
-        Ok(None)
-    }
+/// Record a video's keywords and category as the player response gave them.
+pub fn set_keywords_and_category(conn: &Connection, video_id: &str, keywords: &[String], category: Option<&str>) -> Result<()> {
+    let keywords = serde_json::to_string(keywords).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute("UPDATE videos SET keywords = ?2, category = ?3 WHERE video_id = ?1", params![video_id.trim(), keywords, category])?;
+    Ok(())
 }
 
-pub fn set_setting(db_path: &str, key: &str, value: &str) -> Result<()> {
-    let conn = Connection::open(db_path)?;
+/// Record the caption language a video's transcript was saved in.
+pub fn set_transcript_language(conn: &Connection, video_id: &str, language: Option<&str>) -> Result<()> {
+    conn.execute("UPDATE videos SET transcript_language = ?2 WHERE video_id = ?1", params![video_id.trim(), language])?;
+    Ok(())
+}
+
+/// Record a video's visibility and live-stream flags as of saving it.
+pub fn set_video_flags(conn: &Connection, video_id: &str, flags: &crate::youtube::VideoFlags) -> Result<()> {
+    conn.execute(
+        "UPDATE videos SET is_private = ?2, is_unlisted = ?3, is_live_content = ?4 WHERE video_id = ?1",
+        params![video_id.trim(), flags.is_private, flags.is_unlisted, flags.is_live_content],
+    )?;
+    Ok(())
+}
+
+/// Record whether a video's transcript came from automatic captions.
+pub fn set_captions_auto_generated(conn: &Connection, video_id: &str, auto_generated: Option<bool>) -> Result<()> {
+    conn.execute("UPDATE videos SET captions_auto_generated = ?2 WHERE video_id = ?1", params![video_id.trim(), auto_generated])?;
+    Ok(())
+}
+
+pub fn get_transcript_language(conn: &Connection, video_id: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare("SELECT transcript_language FROM videos WHERE video_id = ?")?;
+    let mut rows = stmt.query(params![video_id.trim()])?;
+    if let Some(row) = rows.next()? {
+        Ok(row.get(0)?)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Compress every transcript still stored as plain text. Runs inside the
+/// caller's transaction, if any.
+pub(crate) fn compress_plain_transcripts(conn: &Connection) -> Result<CompressionReport> {
+    let mut report = CompressionReport::default();
+    let rows: Vec<(String, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT video_id, transcript FROM videos WHERE transcript_zstd IS NULL AND length(transcript) > 0",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<_>>()?
+    };
+
+    let mut update = conn.prepare("UPDATE videos SET transcript = NULL, transcript_zstd = ?2 WHERE video_id = ?1")?;
+    for (video_id, text) in rows {
+        if let Some(blob) = compress_transcript(&text)? {
+            report.bytes_before += text.len() as u64;
+            report.bytes_after += blob.len() as u64;
+            report.videos += 1;
+            update.execute(params![video_id, blob])?;
+        }
+    }
+    Ok(report)
+}
+
+/// Fill in word counts for videos saved before they were tracked. Returns the
+/// number of videos updated.
+pub fn backfill_word_counts(conn: &Connection) -> Result<usize> {
+    let query = format!(
+        "UPDATE videos SET word_count = COALESCE(count_words({}), 0) WHERE word_count IS NULL",
+        TRANSCRIPT_TEXT
+    );
+    conn.execute(&query, [])
+}
+
+/// One-time migration: compress transcripts saved before compression existed.
+pub fn compress_transcripts(conn: &Connection) -> Result<CompressionReport> {
+    let tx = conn.unchecked_transaction()?;
+    let report = compress_plain_transcripts(&tx)?;
+    tx.commit()?;
+    Ok(report)
+}
+
+pub fn vacuum_db(conn: &Connection) -> Result<()> {
+    conn.execute("VACUUM", [])?;
+    Ok(())
+}
+
+/// The UTC time `seconds` ago in RFC 3339 form, as web APIs expect it.
+pub fn utc_time_ago(conn: &Connection, seconds: u64) -> Result<String> {
+    conn.query_row("SELECT strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?1)", params![format!("-{} seconds", seconds)], |row| row.get(0))
+}
+
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare("SELECT value FROM settings WHERE key = ?")?;
+    let mut rows = stmt.query(params![key])?;
+    if let Some(row) = rows.next()? {
+        Ok(Some(row.get(0)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<()> {
     conn.execute(
         "INSERT OR REPLACE INTO settings (key, value)
          VALUES (?, ?)",
@@ -298,28 +1305,81 @@ pub fn set_setting(db_path: &str, key: &str, value: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn delete_setting(db_path: &str, key: &str) -> Result<()> {
-    let conn = Connection::open(db_path)?;
+pub fn delete_setting(conn: &Connection, key: &str) -> Result<()> {
     conn.execute("DELETE FROM settings WHERE key = ?", params![key])?;
     Ok(())
 }
 
-pub fn get_db_stats(db_path: &str) -> Result<i64> {
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT COUNT(*) FROM videos")?;
+pub fn get_credential(conn: &Connection, name: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare("SELECT value FROM credentials WHERE name = ?")?;
+    let mut rows = stmt.query(params![name])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn set_credential(conn: &Connection, name: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO credentials (name, value) VALUES (?, ?)",
+        params![name, value],
+    )?;
+    Ok(())
+}
+
+pub fn delete_credential(conn: &Connection, name: &str) -> Result<()> {
+    conn.execute("DELETE FROM credentials WHERE name = ?", params![name])?;
+    Ok(())
+}
+
+pub fn get_db_stats(conn: &Connection) -> Result<i64> {
+    let mut stmt = conn.prepare("SELECT COUNT(*) FROM videos WHERE deleted_at IS NULL")?;
     let count: i64 = stmt.query_row([], |row| row.get(0))?;
     Ok(count)
 }
 
-pub fn get_history_stats(db_path: &str) -> Result<i64> {
-    let conn = Connection::open(db_path)?;
+pub fn get_history_stats(conn: &Connection) -> Result<i64> {
     let mut stmt = conn.prepare("SELECT COUNT(*) FROM search_history")?;
     let count: i64 = stmt.query_row([], |row| row.get(0))?;
     Ok(count)
 }
 
-pub fn save_summary(db_path: &str, video_id: &str, summary: &str) -> Result<()> {
-    let conn = Connection::open(db_path)?;
+/// Stored transcript bytes and the number of timed segments, trash included.
+pub fn get_transcript_storage(conn: &Connection) -> Result<(i64, i64)> {
+    conn.query_row(
+        "SELECT (SELECT COALESCE(SUM(COALESCE(length(transcript_zstd), 0) + COALESCE(length(CAST(transcript AS BLOB)), 0)), 0) FROM videos),
+                (SELECT COUNT(*) FROM transcript_segments)",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+/// On-disk size of each table and index, largest first.
+pub fn get_table_sizes(conn: &Connection) -> Result<Vec<TableSize>> {
+    let mut stmt = conn.prepare("SELECT name, SUM(pgsize) AS size FROM dbstat GROUP BY name ORDER BY size DESC, name")?;
+    let rows = stmt.query_map([], |row| Ok(TableSize { name: row.get(0)?, size_bytes: row.get(1)? }))?;
+    rows.collect()
+}
+
+/// The creators whose videos store the most text, largest first. Videos saved
+/// before channels were tracked are grouped by author name.
+pub fn get_top_channels_by_size(conn: &Connection, limit: i64) -> Result<Vec<ChannelSize>> {
+    let mut stmt = conn.prepare(
+        "SELECT COALESCE(c.name, v.author, 'Unknown') AS creator, COUNT(*),
+                SUM(COALESCE(length(v.transcript_zstd), 0) + COALESCE(length(CAST(v.transcript AS BLOB)), 0)
+                    + COALESCE(length(CAST(v.summary AS BLOB)), 0) + COALESCE(length(CAST(v.description AS BLOB)), 0)
+                    + COALESCE(length(CAST(v.notes AS BLOB)), 0)
+                    + COALESCE((SELECT SUM(length(CAST(s.text AS BLOB))) FROM transcript_segments s WHERE s.video_id = v.video_id), 0)) AS size
+         FROM videos v LEFT JOIN channels c ON c.channel_id = v.channel_id
+         GROUP BY creator ORDER BY size DESC LIMIT ?1",
+    )?;
+    let rows = stmt.query_map(params![limit], |row| {
+        Ok(ChannelSize { name: row.get(0)?, video_count: row.get(1)?, size_bytes: row.get(2)? })
+    })?;
+    rows.collect()
+}
+
+pub fn save_summary(conn: &Connection, video_id: &str, summary: &str) -> Result<()> {
     conn.execute(
         "UPDATE videos SET summary = ?1 WHERE video_id = ?2",
         params![summary, video_id],
@@ -327,8 +1387,7 @@ pub fn save_summary(db_path: &str, video_id: &str, summary: &str) -> Result<()>
     Ok(())
 }
 
-pub fn get_summary(db_path: &str, video_id: &str) -> Result<Option<String>> {
-    let conn = Connection::open(db_path)?;
+pub fn get_summary(conn: &Connection, video_id: &str) -> Result<Option<String>> {
     let mut stmt = conn.prepare("SELECT summary FROM videos WHERE video_id = ?")?;
     let mut rows = stmt.query(params![video_id])?;
     if let Some(row) = rows.next()? {
@@ -338,16 +1397,149 @@ pub fn get_summary(db_path: &str, video_id: &str) -> Result<Option<String>> {
     }
 }
 
-pub fn get_summarized_count(db_path: &str) -> Result<i64> {
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT COUNT(*) FROM videos WHERE summary IS NOT NULL AND summary != ''")?;
+pub fn set_note(conn: &Connection, video_id: &str, note: &str) -> Result<usize> {
+    conn.execute(
+        "UPDATE videos SET notes = ?1 WHERE video_id = ?2",
+        params![if note.trim().is_empty() { None } else { Some(note) }, video_id],
+    )
+}
+
+/// Mark a saved video watched or unwatched. Re-marking a watched video keeps
+/// its original watch time.
+pub fn set_watched(conn: &Connection, video_id: &str, watched: bool) -> Result<usize> {
+    conn.execute(
+        "UPDATE videos SET watched_at = CASE WHEN ?1 THEN COALESCE(watched_at, CURRENT_TIMESTAMP) END
+         WHERE video_id = ?2 AND deleted_at IS NULL",
+        params![watched, video_id],
+    )
+}
+
+/// Note that a saved video was just opened. Returns the number of rows updated.
+pub fn mark_opened(conn: &Connection, video_id: &str) -> Result<usize> {
+    conn.execute(
+        "UPDATE videos SET last_opened_at = CURRENT_TIMESTAMP WHERE video_id = ? AND deleted_at IS NULL",
+        params![video_id],
+    )
+}
+
+/// The `limit` most recently opened saved videos, latest first.
+pub fn recent_videos(conn: &Connection, limit: i64) -> Result<Vec<Video>> {
+    let query = format!(
+        "SELECT {} FROM videos
+         WHERE deleted_at IS NULL AND last_opened_at IS NOT NULL
+         ORDER BY last_opened_at DESC, rowid DESC LIMIT ?",
+        VIDEO_COLUMNS
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(params![limit], row_to_video)?;
+    rows.collect()
+}
+
+/// Flip a saved video's starred flag. Returns the new state, or `None` if the
+/// video isn't in the library.
+pub fn toggle_starred(conn: &Connection, video_id: &str) -> Result<Option<bool>> {
+    let mut stmt = conn.prepare(
+        "UPDATE videos SET starred = NOT starred WHERE video_id = ? AND deleted_at IS NULL RETURNING starred",
+    )?;
+    let mut rows = stmt.query(params![video_id])?;
+    if let Some(row) = rows.next()? {
+        Ok(Some(row.get(0)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// A saved video with its description. Trashed videos count as not saved, so
+/// saving one again goes through and takes it out of the trash.
+pub fn get_video_details(conn: &Connection, video_id: &str) -> Result<Option<VideoDetails>> {
+    let query = format!("SELECT {}, description, transcript_language, keywords, category, archive_url FROM videos WHERE video_id = ? AND deleted_at IS NULL", VIDEO_COLUMNS);
+    let mut stmt = conn.prepare(&query)?;
+    let mut rows = stmt.query(params![video_id])?;
+    if let Some(row) = rows.next()? {
+        let extra = video_column_count();
+        Ok(Some(VideoDetails {
+            video: row_to_video(row)?,
+            description: row.get(extra)?,
+            transcript_language: row.get(extra + 1)?,
+            keywords: match row.get::<_, Option<String>>(extra + 2)? {
+                Some(json) => serde_json::from_str(&json).map_err(|e| rusqlite::Error::FromSqlConversionFailure(extra + 2, rusqlite::types::Type::Text, Box::new(e)))?,
+                None => Vec::new(),
+            },
+            category: row.get(extra + 3)?,
+            archive_url: row.get(extra + 4)?,
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn get_note(conn: &Connection, video_id: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare("SELECT notes FROM videos WHERE video_id = ?")?;
+    let mut rows = stmt.query(params![video_id])?;
+    if let Some(row) = rows.next()? {
+        Ok(row.get(0)?)
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn get_summarized_count(conn: &Connection) -> Result<i64> {
+    let mut stmt = conn.prepare("SELECT COUNT(*) FROM videos WHERE deleted_at IS NULL AND summary IS NOT NULL AND summary != ''")?;
     let count: i64 = stmt.query_row([], |row| row.get(0))?;
     Ok(count)
 }
 
-pub fn get_videos_with_summaries(db_path: &str) -> Result<Vec<String>> {
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT video_id FROM videos WHERE summary IS NOT NULL AND summary != ''")?;
+/// `(video_id, transcript)` for every video that has a transcript but no summary yet.
+pub fn get_videos_without_summary(conn: &Connection) -> Result<Vec<(String, String)>> {
+    let query = format!(
+        "SELECT video_id, {} FROM videos
+         WHERE deleted_at IS NULL AND (summary IS NULL OR summary = '')
+           AND (transcript_zstd IS NOT NULL OR (transcript IS NOT NULL AND transcript != ''))",
+        TRANSCRIPT_TEXT
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let mut rows = stmt.query([])?;
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        result.push((row.get(0)?, row.get(1)?));
+    }
+    Ok(result)
+}
+
+pub fn get_date_added(conn: &Connection, video_id: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare("SELECT date_added FROM videos WHERE video_id = ?")?;
+    let mut rows = stmt.query(params![video_id])?;
+    if let Some(row) = rows.next()? {
+        Ok(row.get(0)?)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Saved videos to check for availability, those checked longest ago (or
+/// never) first.
+pub fn videos_to_verify(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT video_id FROM videos WHERE deleted_at IS NULL ORDER BY availability_checked_at NULLS FIRST, rowid")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Record the Wayback Machine capture found for a video.
+pub fn set_archive_url(conn: &Connection, video_id: &str, url: &str) -> Result<usize> {
+    conn.execute("UPDATE videos SET archive_url = ?2 WHERE video_id = ?1", params![video_id, url])
+}
+
+/// Record what `verify_library` found for a video.
+pub fn set_availability(conn: &Connection, video_id: &str, availability: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE videos SET availability = ?2, availability_checked_at = CURRENT_TIMESTAMP WHERE video_id = ?1",
+        params![video_id, availability],
+    )?;
+    Ok(())
+}
+
+pub fn get_videos_with_summaries(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT video_id FROM videos WHERE deleted_at IS NULL AND summary IS NOT NULL AND summary != ''")?;
     let mut rows = stmt.query([])?;
     let mut ids = Vec::new();
     while let Some(row) = rows.next()? {
@@ -355,3 +1547,1174 @@ pub fn get_videos_with_summaries(db_path: &str) -> Result<Vec<String>> {
     }
     Ok(ids)
 }
+
+// ─── Tags ─────────────────────────────────────────────────────────────────────
+
+/// Attach a tag to a video, creating the tag if it doesn't exist yet.
+/// Tag names are matched case-insensitively.
+pub fn add_tag(conn: &Connection, video_id: &str, name: &str) -> Result<()> {
+    conn.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", params![name])?;
+    conn.execute(
+        "INSERT OR IGNORE INTO video_tags (video_id, tag_id)
+         SELECT ?1, id FROM tags WHERE name = ?2",
+        params![video_id, name],
+    )?;
+    Ok(())
+}
+
+/// Detach a tag from a video. Tags left without any videos are removed.
+pub fn remove_tag(conn: &Connection, video_id: &str, name: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM video_tags
+         WHERE video_id = ?1 AND tag_id = (SELECT id FROM tags WHERE name = ?2)",
+        params![video_id, name],
+    )?;
+    conn.execute(
+        "DELETE FROM tags WHERE id NOT IN (SELECT DISTINCT tag_id FROM video_tags)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// All tags with the number of videos carrying each, alphabetically.
+pub fn list_tags(conn: &Connection) -> Result<Vec<Tag>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.name, COUNT(v.video_id) FROM tags t
+         LEFT JOIN video_tags vt ON vt.tag_id = t.id
+         LEFT JOIN videos v ON v.video_id = vt.video_id AND v.deleted_at IS NULL
+         GROUP BY t.id ORDER BY t.name COLLATE NOCASE",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Tag {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            video_count: row.get(2)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Tags attached to a single video.
+pub fn get_video_tags(conn: &Connection, video_id: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.name FROM tags t JOIN video_tags vt ON vt.tag_id = t.id
+         WHERE vt.video_id = ?1 ORDER BY t.name COLLATE NOCASE",
+    )?;
+    let rows = stmt.query_map(params![video_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Saved videos carrying the given tag, newest first.
+pub fn list_videos_by_tag(conn: &Connection, name: &str) -> Result<Vec<Video>> {
+    let query = format!(
+        "SELECT {} FROM videos v
+         JOIN video_tags vt ON vt.video_id = v.video_id
+         JOIN tags t ON t.id = vt.tag_id
+         WHERE t.name = ?1 AND v.deleted_at IS NULL
+         ORDER BY v.date_added DESC, v.rowid DESC",
+        video_columns_as("v")
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(params![name], row_to_video)?;
+    let mut videos = Vec::new();
+    for video in rows {
+        videos.push(video?);
+    }
+    attach_collections(conn, &mut videos)?;
+    Ok(videos)
+}
+
+// ─── Collections ──────────────────────────────────────────────────────────────
+
+pub fn create_collection(conn: &Connection, name: &str) -> Result<i64> {
+    conn.execute("INSERT INTO collections (name) VALUES (?1)", params![name])?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn rename_collection(conn: &Connection, id: i64, name: &str) -> Result<usize> {
+    conn.execute("UPDATE collections SET name = ?1 WHERE id = ?2", params![name, id])
+}
+
+/// Delete a collection. The videos in it stay in the library.
+pub fn delete_collection(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM collection_videos WHERE collection_id = ?1", params![id])?;
+    conn.execute("DELETE FROM collections WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+pub fn list_collections(conn: &Connection) -> Result<Vec<Collection>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.name, c.created_at, COUNT(v.video_id) FROM collections c
+         LEFT JOIN collection_videos cv ON cv.collection_id = c.id
+         LEFT JOIN videos v ON v.video_id = cv.video_id AND v.deleted_at IS NULL
+         GROUP BY c.id ORDER BY c.name COLLATE NOCASE",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Collection {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            created_at: row.get(2)?,
+            video_count: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn add_to_collection(conn: &Connection, collection_id: i64, video_id: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO collection_videos (collection_id, video_id) VALUES (?1, ?2)",
+        params![collection_id, video_id],
+    )?;
+    Ok(())
+}
+
+pub fn remove_from_collection(conn: &Connection, collection_id: i64, video_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM collection_videos WHERE collection_id = ?1 AND video_id = ?2",
+        params![collection_id, video_id],
+    )?;
+    Ok(())
+}
+
+/// IDs of the collections a video belongs to.
+pub fn get_video_collections(conn: &Connection, video_id: &str) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare("SELECT collection_id FROM collection_videos WHERE video_id = ?1 ORDER BY collection_id")?;
+    let rows = stmt.query_map(params![video_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Fill in the collections each of `videos` belongs to.
+fn attach_collections(conn: &Connection, videos: &mut [Video]) -> Result<()> {
+    let mut stmt = conn.prepare("SELECT video_id, collection_id FROM collection_videos ORDER BY collection_id")?;
+    let mut memberships: HashMap<String, Vec<i64>> = HashMap::new();
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        memberships.entry(row.get(0)?).or_default().push(row.get(1)?);
+    }
+    for video in videos {
+        video.collections = memberships.remove(&video.id).unwrap_or_default();
+    }
+    Ok(())
+}
+
+// ─── Transcript segments ──────────────────────────────────────────────────────
+
+/// Replace a video's timed transcript segments. Runs inside the caller's
+/// transaction, which keeps the delete and inserts together.
+pub(crate) fn replace_transcript_segments(conn: &Connection, video_id: &str, segments: &[TranscriptSegment]) -> Result<()> {
+    conn.execute("DELETE FROM transcript_segments WHERE video_id = ?1", params![video_id])?;
+    let mut stmt = conn.prepare(
+        "INSERT INTO transcript_segments (video_id, seq, start_ms, duration_ms, text) VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for (seq, segment) in segments.iter().enumerate() {
+        stmt.execute(params![video_id, seq as i64, segment.start_ms, segment.duration_ms, segment.text])?;
+    }
+    Ok(())
+}
+
+/// A video's timed transcript segments in playback order; empty if none were stored.
+pub fn get_transcript_segments(conn: &Connection, video_id: &str) -> Result<Vec<TranscriptSegment>> {
+    let mut stmt = conn.prepare(
+        "SELECT start_ms, duration_ms, text FROM transcript_segments WHERE video_id = ?1 ORDER BY seq",
+    )?;
+    let rows = stmt.query_map(params![video_id], |row| {
+        Ok(TranscriptSegment { start_ms: row.get(0)?, duration_ms: row.get(1)?, text: row.get(2)? })
+    })?;
+    rows.collect()
+}
+
+// ─── Transcript tracks ────────────────────────────────────────────────────────
+
+/// Language recorded for main transcripts saved before languages were tracked.
+pub const UNKNOWN_LANGUAGE: &str = "und";
+
+/// The language of a video's main transcript, `UNKNOWN_LANGUAGE` if it wasn't
+/// recorded. `None` if the video isn't saved.
+fn main_transcript_language(conn: &Connection, video_id: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare("SELECT COALESCE(transcript_language, ?2) FROM videos WHERE video_id = ?1")?;
+    let mut rows = stmt.query(params![video_id, UNKNOWN_LANGUAGE])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+/// Store a caption track in `language` next to a video's main transcript,
+/// replacing any earlier copy in that language.
+pub fn save_transcript_track(conn: &Connection, video_id: &str, language: &str, text: &str, segments: &[TranscriptSegment]) -> Result<()> {
+    let compressed = compress_transcript(text)?.unwrap_or_default();
+    let segments = serde_json::to_string(segments).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO transcripts (video_id, language, transcript_zstd, segments) VALUES (?1, ?2, ?3, ?4)",
+        params![video_id, language, compressed, segments],
+    )?;
+    Ok(())
+}
+
+/// Every transcript saved for a video, the main one first.
+pub fn list_transcript_tracks(conn: &Connection, video_id: &str) -> Result<Vec<TranscriptTrack>> {
+    let Some(main_language) = main_transcript_language(conn, video_id)? else { return Ok(Vec::new()) };
+    let mut tracks = vec![TranscriptTrack { language: main_language.clone(), main: true }];
+    let mut stmt = conn.prepare("SELECT language FROM transcripts WHERE video_id = ?1 AND language != ?2 ORDER BY language")?;
+    let rows = stmt.query_map(params![video_id, main_language], |row| Ok(TranscriptTrack { language: row.get(0)?, main: false }))?;
+    for track in rows {
+        tracks.push(track?);
+    }
+    Ok(tracks)
+}
+
+/// A saved transcript's text and timed segments in `language`, whether it's the
+/// main transcript or an extra track. `None` if none is saved in it.
+pub fn get_transcript_track(conn: &Connection, video_id: &str, language: &str) -> Result<Option<(String, Vec<TranscriptSegment>)>> {
+    if main_transcript_language(conn, video_id)?.as_deref() == Some(language) {
+        let Some(text) = get_transcript(conn, video_id)? else { return Ok(None) };
+        return Ok(Some((text, get_transcript_segments(conn, video_id)?)));
+    }
+    let mut stmt = conn.prepare("SELECT transcript_zstd, segments FROM transcripts WHERE video_id = ?1 AND language = ?2")?;
+    let mut rows = stmt.query(params![video_id, language])?;
+    let Some(row) = rows.next()? else { return Ok(None) };
+    let text = decompress_transcript(&row.get::<_, Vec<u8>>(0)?)?;
+    let segments = serde_json::from_str(&row.get::<_, String>(1)?)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e)))?;
+    Ok(Some((text, segments)))
+}
+
+/// Remove an extra caption track. The main transcript can't be removed this way.
+pub fn delete_transcript_track(conn: &Connection, video_id: &str, language: &str) -> Result<usize> {
+    conn.execute("DELETE FROM transcripts WHERE video_id = ?1 AND language = ?2", params![video_id, language])
+}
+
+// ─── Chapters ─────────────────────────────────────────────────────────────────
+
+/// Replace a video's chapters. Runs inside the caller's transaction.
+pub(crate) fn replace_chapters(conn: &Connection, video_id: &str, chapters: &[Chapter]) -> Result<()> {
+    conn.execute("DELETE FROM chapters WHERE video_id = ?1", params![video_id])?;
+    let mut stmt = conn.prepare("INSERT OR REPLACE INTO chapters (video_id, start_ms, title) VALUES (?1, ?2, ?3)")?;
+    for chapter in chapters {
+        stmt.execute(params![video_id, chapter.start_ms, chapter.title])?;
+    }
+    Ok(())
+}
+
+/// A video's chapters in order; empty if it has none or none were stored.
+pub fn get_chapters(conn: &Connection, video_id: &str) -> Result<Vec<Chapter>> {
+    let mut stmt = conn.prepare("SELECT title, start_ms FROM chapters WHERE video_id = ?1 ORDER BY start_ms")?;
+    let rows = stmt.query_map(params![video_id], |row| Ok(Chapter { title: row.get(0)?, start_ms: row.get(1)? }))?;
+    rows.collect()
+}
+
+// ─── Heatmaps ─────────────────────────────────────────────────────────────────
+
+/// Replace a video's "most replayed" graph. Runs inside the caller's transaction.
+pub(crate) fn replace_heatmap(conn: &Connection, video_id: &str, points: &[HeatmapPoint]) -> Result<()> {
+    conn.execute("DELETE FROM heatmaps WHERE video_id = ?1", params![video_id])?;
+    let mut stmt = conn.prepare("INSERT OR REPLACE INTO heatmaps (video_id, start_ms, duration_ms, intensity) VALUES (?1, ?2, ?3, ?4)")?;
+    for point in points {
+        stmt.execute(params![video_id, point.start_ms, point.duration_ms, point.intensity])?;
+    }
+    Ok(())
+}
+
+/// A video's "most replayed" graph in time order; empty if none was stored.
+pub fn get_heatmap(conn: &Connection, video_id: &str) -> Result<Vec<HeatmapPoint>> {
+    let mut stmt = conn.prepare("SELECT start_ms, duration_ms, intensity FROM heatmaps WHERE video_id = ?1 ORDER BY start_ms")?;
+    let rows = stmt.query_map(params![video_id], |row| {
+        Ok(HeatmapPoint { start_ms: row.get(0)?, duration_ms: row.get(1)?, intensity: row.get(2)? })
+    })?;
+    rows.collect()
+}
+
+// ─── Media files ──────────────────────────────────────────────────────────────
+
+/// Record a downloaded file, replacing any earlier download of the same kind.
+pub fn record_media_file(conn: &Connection, video_id: &str, kind: &str, path: &str, mime_type: &str, size: i64) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO media_files (video_id, kind, path, mime_type, size) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![video_id, kind, path, mime_type, size],
+    )?;
+    Ok(())
+}
+
+/// Files downloaded for a video, audio before video.
+pub fn get_media_files(conn: &Connection, video_id: &str) -> Result<Vec<MediaFile>> {
+    let mut stmt = conn.prepare(
+        "SELECT video_id, kind, path, mime_type, size, downloaded_at FROM media_files WHERE video_id = ?1 ORDER BY kind",
+    )?;
+    let rows = stmt.query_map(params![video_id], |row| {
+        Ok(MediaFile {
+            video_id: row.get(0)?,
+            kind: row.get(1)?,
+            path: row.get(2)?,
+            mime_type: row.get(3)?,
+            size: row.get(4)?,
+            downloaded_at: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+// ─── Lyrics ───────────────────────────────────────────────────────────────────
+
+/// Store a song's lyrics, replacing earlier ones. Ignored for videos that
+/// aren't saved.
+pub fn save_lyrics(conn: &Connection, video_id: &str, lyrics: &Lyrics) -> Result<usize> {
+    let lines = serde_json::to_string(&lyrics.lines).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO lyrics (video_id, text, lines, source)
+         SELECT ?1, ?2, ?3, ?4 WHERE EXISTS (SELECT 1 FROM videos WHERE video_id = ?1)",
+        params![video_id, lyrics.text, lines, lyrics.source],
+    )
+}
+
+pub fn get_lyrics(conn: &Connection, video_id: &str) -> Result<Option<Lyrics>> {
+    let mut stmt = conn.prepare("SELECT text, lines, source FROM lyrics WHERE video_id = ?1")?;
+    let mut rows = stmt.query(params![video_id])?;
+    let Some(row) = rows.next()? else { return Ok(None) };
+    let lines = serde_json::from_str(&row.get::<_, String>(1)?)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e)))?;
+    Ok(Some(Lyrics { text: row.get(0)?, lines, source: row.get(2)? }))
+}
+
+// ─── Community posts ──────────────────────────────────────────────────────────
+
+/// Archive posts, updating earlier copies so edits and poll results are kept
+/// up to date. A post's publish time is estimated the first time it is saved,
+/// while YouTube's wording of it is most precise. Runs inside the caller's
+/// transaction.
+pub fn save_community_posts(conn: &Connection, posts: &[CommunityPost]) -> Result<()> {
+    let mut stmt = conn.prepare(
+        "INSERT INTO community_posts (post_id, channel_id, data, published_at)
+         VALUES (?1, ?2, ?3, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?4))
+         ON CONFLICT(post_id) DO UPDATE SET data = excluded.data",
+    )?;
+    for post in posts {
+        let data = serde_json::to_string(post).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let age = crate::youtube::parse_time_ago(&post.published).unwrap_or(0);
+        stmt.execute(params![post.id, post.channel_id, data, format!("-{} seconds", age)])?;
+    }
+    Ok(())
+}
+
+/// A channel's archived posts, newest first.
+pub fn get_community_posts(conn: &Connection, channel_id: &str) -> Result<Vec<CommunityPost>> {
+    let mut stmt = conn.prepare("SELECT data FROM community_posts WHERE channel_id = ?1 ORDER BY published_at DESC")?;
+    let rows = stmt.query_map(params![channel_id], |row| row.get::<_, String>(0))?;
+    rows.map(|data| {
+        serde_json::from_str(&data?).map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))
+    }).collect()
+}
+
+// ─── Comments ─────────────────────────────────────────────────────────────────
+
+/// Replace a video's archived comments, keeping their order. Runs inside the
+/// caller's transaction.
+pub(crate) fn replace_comments(conn: &Connection, video_id: &str, comments: &[Comment]) -> Result<()> {
+    conn.execute("DELETE FROM comments WHERE video_id = ?1", params![video_id])?;
+    let mut stmt = conn.prepare(
+        "INSERT INTO comments (video_id, seq, comment_id, author, text, like_count, reply_count, published)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?;
+    for (seq, c) in comments.iter().enumerate() {
+        stmt.execute(params![video_id, seq as i64, c.id, c.author, c.text, c.like_count, c.reply_count, c.published])?;
+    }
+    Ok(())
+}
+
+/// A video's archived comments in the order they were fetched.
+pub fn get_comments(conn: &Connection, video_id: &str) -> Result<Vec<Comment>> {
+    let mut stmt = conn.prepare(
+        "SELECT comment_id, author, text, like_count, reply_count, published FROM comments WHERE video_id = ?1 ORDER BY seq",
+    )?;
+    let rows = stmt.query_map(params![video_id], |row| {
+        Ok(Comment {
+            id: row.get(0)?,
+            author: row.get(1)?,
+            text: row.get(2)?,
+            like_count: row.get(3)?,
+            reply_count: row.get(4)?,
+            published: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+// ─── Channels ─────────────────────────────────────────────────────────────────
+
+/// Record a channel, refreshing its name. A missing handle or avatar keeps the stored one.
+pub fn upsert_channel(conn: &Connection, channel_id: &str, name: &str, handle: Option<&str>, avatar: Option<&str>) -> Result<()> {
+    conn.execute(
+        "INSERT INTO channels (channel_id, name, handle, avatar) VALUES (?1, ?2, NULLIF(?3, ''), NULLIF(?4, ''))
+         ON CONFLICT(channel_id) DO UPDATE SET
+            name = excluded.name,
+            handle = COALESCE(excluded.handle, channels.handle),
+            avatar = COALESCE(excluded.avatar, channels.avatar),
+            updated_at = CURRENT_TIMESTAMP",
+        params![channel_id, name, handle, avatar],
+    )?;
+    Ok(())
+}
+
+/// The channel ID a handle resolved to within the last `max_age_days`.
+pub fn get_cached_channel_id(conn: &Connection, handle: &str, max_age_days: i64) -> Result<Option<String>> {
+    let mut stmt = conn.prepare("SELECT channel_id FROM channel_handles WHERE handle = ?1 AND resolved_at > datetime('now', ?2)")?;
+    let mut rows = stmt.query(params![handle, format!("-{} days", max_age_days)])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+/// Remember the channel ID a handle resolved to.
+pub fn cache_channel_id(conn: &Connection, handle: &str, channel_id: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO channel_handles (handle, channel_id, resolved_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+        params![handle, channel_id],
+    )?;
+    Ok(())
+}
+
+/// Whether a channel is unknown or still lacks an avatar.
+pub fn channel_needs_avatar(conn: &Connection, channel_id: &str) -> Result<bool> {
+    let mut stmt = conn.prepare("SELECT avatar IS NULL FROM channels WHERE channel_id = ?")?;
+    let mut rows = stmt.query(params![channel_id])?;
+    if let Some(row) = rows.next()? {
+        Ok(row.get(0)?)
+    } else {
+        Ok(true)
+    }
+}
+
+/// Record a video's like and comment counts. Unknown counts keep the stored ones.
+pub fn set_engagement(conn: &Connection, video_id: &str, like_count: Option<i64>, comment_count: Option<i64>) -> Result<usize> {
+    conn.execute(
+        "UPDATE videos SET like_count = COALESCE(?1, like_count), comment_count = COALESCE(?2, comment_count) WHERE video_id = ?3",
+        params![like_count, comment_count, video_id],
+    )
+}
+
+pub fn set_video_channel(conn: &Connection, video_id: &str, channel_id: &str) -> Result<usize> {
+    conn.execute("UPDATE videos SET channel_id = ?1 WHERE video_id = ?2", params![channel_id, video_id])
+}
+
+/// Channels with at least one saved video, with their video counts, by name.
+pub fn list_channels(conn: &Connection) -> Result<Vec<Channel>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.channel_id, c.name, c.handle, c.avatar, COUNT(v.video_id) FROM channels c
+         JOIN videos v ON v.channel_id = c.channel_id AND v.deleted_at IS NULL
+         GROUP BY c.channel_id ORDER BY c.name COLLATE NOCASE",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Channel {
+            channel_id: row.get(0)?,
+            name: row.get(1)?,
+            handle: row.get(2)?,
+            avatar: row.get(3)?,
+            video_count: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+// ─── Custom fields ────────────────────────────────────────────────────────────
+
+/// Set a custom field on a saved video, replacing any existing value for the
+/// key. Keys are matched case-insensitively. Returns 0 if the video isn't saved.
+pub fn set_video_meta(conn: &Connection, video_id: &str, key: &str, value: &str) -> Result<usize> {
+    conn.execute(
+        "INSERT INTO video_meta (video_id, key, value)
+         SELECT ?1, ?2, ?3 WHERE EXISTS (SELECT 1 FROM videos WHERE video_id = ?1 AND deleted_at IS NULL)
+         ON CONFLICT (video_id, key) DO UPDATE SET value = excluded.value",
+        params![video_id, key, value],
+    )
+}
+
+/// All custom fields of a video, ordered by key.
+pub fn get_video_meta(conn: &Connection, video_id: &str) -> Result<BTreeMap<String, String>> {
+    let mut stmt = conn.prepare("SELECT key, value FROM video_meta WHERE video_id = ?1 ORDER BY key")?;
+    let rows = stmt.query_map(params![video_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+pub fn delete_video_meta(conn: &Connection, video_id: &str, key: &str) -> Result<usize> {
+    conn.execute("DELETE FROM video_meta WHERE video_id = ?1 AND key = ?2", params![video_id, key])
+}
+
+/// Cache a video's thumbnail. Ignored for videos that aren't saved.
+pub fn save_thumbnail(conn: &Connection, video_id: &str, mime: &str, data: &[u8]) -> Result<usize> {
+    conn.execute(
+        "INSERT OR REPLACE INTO thumbnails (video_id, mime, data)
+         SELECT ?1, ?2, ?3 WHERE EXISTS (SELECT 1 FROM videos WHERE video_id = ?1)",
+        params![video_id, mime, data],
+    )
+}
+
+/// The cached thumbnail's MIME type and bytes.
+pub fn get_thumbnail(conn: &Connection, video_id: &str) -> Result<Option<(String, Vec<u8>)>> {
+    let mut stmt = conn.prepare("SELECT mime, data FROM thumbnails WHERE video_id = ?")?;
+    let mut rows = stmt.query(params![video_id])?;
+    match rows.next()? {
+        Some(row) => Ok(Some((row.get(0)?, row.get(1)?))),
+        None => Ok(None),
+    }
+}
+
+pub fn has_thumbnail(conn: &Connection, video_id: &str) -> Result<bool> {
+    let mut stmt = conn.prepare("SELECT 1 FROM thumbnails WHERE video_id = ?")?;
+    let mut rows = stmt.query(params![video_id])?;
+    Ok(rows.next()?.is_some())
+}
+
+/// Number of cached thumbnails and their total size in bytes.
+pub fn get_thumbnail_cache_stats(conn: &Connection) -> Result<(i64, i64)> {
+    conn.query_row("SELECT COUNT(*), COALESCE(SUM(length(data)), 0) FROM thumbnails", [], |row| Ok((row.get(0)?, row.get(1)?)))
+}
+
+/// Drop cached thumbnails: all of them, or only those of trashed videos.
+/// Returns the number removed.
+pub fn purge_thumbnails(conn: &Connection, trashed_only: bool) -> Result<usize> {
+    if trashed_only {
+        conn.execute("DELETE FROM thumbnails WHERE video_id IN (SELECT video_id FROM videos WHERE deleted_at IS NOT NULL)", [])
+    } else {
+        conn.execute("DELETE FROM thumbnails", [])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Barrier, Mutex};
+    use std::thread;
+
+    /// A fresh database file in the temp dir, removed (with its WAL files) on drop.
+    struct TempDb(String);
+
+    impl TempDb {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("kinesis-{}-{}.db", name, std::process::id()));
+            let db = TempDb(path.to_string_lossy().to_string());
+            db.cleanup();
+            db
+        }
+
+        fn cleanup(&self) {
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{}", self.0, suffix));
+            }
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            self.cleanup();
+        }
+    }
+
+    fn save(conn: &Connection, id: &str) -> Result<()> {
+        save_video(conn, id, "title", "author", 60, "some transcript words", 1, "2024-01-01", "@author", "standard", None, "", "manual")
+    }
+
+    #[test]
+    fn open_enables_wal_and_busy_timeout() {
+        let db = TempDb::new("pragmas");
+        let conn = open(&db.0, None).unwrap();
+        let mode: String = conn.query_row("PRAGMA journal_mode", [], |r| r.get(0)).unwrap();
+        let timeout: i64 = conn.query_row("PRAGMA busy_timeout", [], |r| r.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+        assert_eq!(timeout, BUSY_TIMEOUT.as_millis() as i64);
+    }
+
+    #[test]
+    fn parallel_connections_never_hit_locked() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 40;
+
+        let db = TempDb::new("parallel");
+        drop(open(&db.0, None).unwrap());
+
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let handles: Vec<_> = (0..THREADS).map(|t| {
+            let path = db.0.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || -> Result<()> {
+                let conn = open(&path, None)?;
+                barrier.wait();
+                for i in 0..PER_THREAD {
+                    save(&conn, &format!("t{:02}v{:04}", t, i))?;
+                    set_note(&conn, &format!("t{:02}v{:04}", t, i), "note")?;
+                    list_videos(&conn, &VideoQuery::default())?;
+                    search_transcripts(&conn, "transcript", 5)?;
+                }
+                Ok(())
+            })
+        }).collect();
+
+        for handle in handles {
+            handle.join().unwrap().expect("concurrent access failed");
+        }
+
+        let conn = open(&db.0, None).unwrap();
+        assert_eq!(get_db_stats(&conn).unwrap(), (THREADS * PER_THREAD) as i64);
+    }
+
+    #[test]
+    fn reads_proceed_during_open_write_transaction() {
+        let db = TempDb::new("reader");
+        let writer = open(&db.0, None).unwrap();
+        save(&writer, "existing001").unwrap();
+
+        // Without WAL an exclusive writer locks readers out until it commits.
+        writer.execute_batch("BEGIN EXCLUSIVE").unwrap();
+        save(&writer, "pending0001").unwrap();
+
+        let reader = open(&db.0, None).unwrap();
+        assert_eq!(list_videos(&reader, &VideoQuery::default()).unwrap().len(), 1);
+
+        writer.execute_batch("COMMIT").unwrap();
+        assert_eq!(list_videos(&reader, &VideoQuery::default()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn shared_connection_serves_parallel_callers() {
+        // Mirrors DbState: one connection behind a mutex, used by concurrent commands.
+        let db = TempDb::new("shared");
+        let shared = Arc::new(Mutex::new(open(&db.0, None).unwrap()));
+
+        let handles: Vec<_> = (0..8).map(|t| {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                for i in 0..25 {
+                    let conn = shared.lock().unwrap();
+                    save(&conn, &format!("s{:02}v{:04}", t, i)).unwrap();
+                    check_videos_exist(&conn, &[format!("s{:02}v{:04}", t, i)]).unwrap();
+                }
+            })
+        }).collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(get_db_stats(&shared.lock().unwrap()).unwrap(), 200);
+    }
+
+    #[test]
+    fn fresh_database_applies_every_migration() {
+        let db = TempDb::new("migrate-fresh");
+        let conn = open(&db.0, None).unwrap();
+        let latest = MIGRATIONS.last().unwrap().version;
+        assert_eq!(schema_version(&conn).unwrap(), latest);
+        let applied: Vec<u32> = conn.prepare("SELECT version FROM schema_version ORDER BY version").unwrap()
+            .query_map([], |r| r.get(0)).unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(applied, MIGRATIONS.iter().map(|m| m.version).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn migration_versions_are_strictly_increasing() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+        assert_eq!(MIGRATIONS[0].version, 1);
+    }
+
+    #[test]
+    fn reopening_is_a_no_op() {
+        let db = TempDb::new("migrate-reopen");
+        drop(open(&db.0, None).unwrap());
+        let conn = open(&db.0, None).unwrap();
+        let rows: u32 = conn.query_row("SELECT COUNT(*) FROM schema_version", [], |r| r.get(0)).unwrap();
+        assert_eq!(rows as usize, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn upgrades_database_from_before_schema_version() {
+        let db = TempDb::new("migrate-legacy");
+        {
+            let conn = Connection::open(&db.0).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE videos (
+                    video_id TEXT PRIMARY KEY, title TEXT, author TEXT, transcript TEXT,
+                    published_at TEXT
+                );
+                CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT);
+                CREATE TABLE search_history (id INTEGER PRIMARY KEY, query TEXT);
+                INSERT INTO videos (video_id, title, author, transcript, published_at)
+                    VALUES ('legacy00001', 'Old title', 'Someone', 'ancient transcript', '2020-01-01');",
+            ).unwrap();
+        }
+
+        let conn = open(&db.0, None).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), MIGRATIONS.last().unwrap().version);
+        let (source, ty): (String, String) = conn.query_row(
+            "SELECT source, (SELECT type FROM pragma_table_info('videos') WHERE name = 'published_at') FROM videos",
+            [], |r| Ok((r.get(0)?, r.get(1)?)),
+        ).unwrap();
+        assert_eq!(source, "manual");
+        assert_eq!(ty, "DATETIME");
+        assert_eq!(get_transcript(&conn, "legacy00001").unwrap().as_deref(), Some("ancient transcript"));
+        let hits: i64 = conn.query_row("SELECT COUNT(*) FROM videos_fts WHERE videos_fts MATCH 'ancient'", [], |r| r.get(0)).unwrap();
+        assert_eq!(hits, 1);
+        conn.execute("INSERT INTO search_history (search_query) VALUES ('q')", []).unwrap();
+    }
+
+    #[test]
+    fn failed_migration_rolls_back_and_stops() {
+        fn create(conn: &Connection) -> Result<()> { conn.execute_batch("CREATE TABLE t (a INTEGER)") }
+        fn add_b(conn: &Connection) -> Result<()> { conn.execute_batch("ALTER TABLE t ADD COLUMN b INTEGER") }
+        fn broken(conn: &Connection) -> Result<()> { conn.execute_batch("ALTER TABLE t ADD COLUMN c INTEGER; SELECT * FROM missing") }
+        let steps = [
+            Migration { version: 1, description: "create", up: create },
+            Migration { version: 2, description: "add b", up: add_b },
+            Migration { version: 3, description: "broken", up: broken },
+        ];
+
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(run_migrations(&conn, &steps).is_err());
+        assert_eq!(schema_version(&conn).unwrap(), 2);
+        let has_c = conn.query_row("SELECT 1 FROM pragma_table_info('t') WHERE name = 'c'", [], |_| Ok(())).is_ok();
+        assert!(!has_c);
+
+        // Running again with the fix picks up where it stopped
+        let fixed = [
+            Migration { version: 1, description: "create", up: create },
+            Migration { version: 2, description: "add b", up: add_b },
+            Migration { version: 3, description: "add c", up: |c| c.execute_batch("ALTER TABLE t ADD COLUMN c INTEGER") },
+        ];
+        assert_eq!(run_migrations(&conn, &fixed).unwrap(), 3);
+    }
+
+    #[test]
+    fn refuses_schema_newer_than_app() {
+        let db = TempDb::new("migrate-newer");
+        {
+            let conn = open(&db.0, None).unwrap();
+            conn.execute("INSERT INTO schema_version (version) VALUES (9999)", []).unwrap();
+        }
+        let err = open(&db.0, None).unwrap_err().to_string();
+        assert!(err.contains("newer"), "{}", err);
+    }
+
+    #[test]
+    fn bulk_delete_by_ids_and_filter() {
+        let db = TempDb::new("bulk-delete");
+        let conn = open(&db.0, None).unwrap();
+        for id in ["a", "b", "c", "d"] {
+            save(&conn, id).unwrap();
+        }
+        conn.execute("UPDATE videos SET author = 'Other', date_added = '2020-01-01 00:00:00' WHERE video_id = 'd'", []).unwrap();
+        add_tag(&conn, "c", "old").unwrap();
+
+        let ids = vec!["a".to_string(), "missing".to_string()];
+        assert_eq!(trash_videos(&conn, &ids).unwrap(), ["a"]);
+        assert!(trash_videos(&conn, &ids).unwrap().is_empty());
+
+        assert!(trash_matching(&conn, &BulkDeleteFilter::default()).unwrap().is_empty());
+        let by_tag = BulkDeleteFilter { author: Some("AUTHOR".into()), tag: Some("old".into()), ..Default::default() };
+        assert_eq!(trash_matching(&conn, &by_tag).unwrap(), ["c"]);
+        let by_date = BulkDeleteFilter { added_before: Some("2021-01-01".into()), ..Default::default() };
+        assert_eq!(trash_matching(&conn, &by_date).unwrap(), ["d"]);
+
+        assert!(check_video_exists(&conn, "b").unwrap());
+        assert_eq!(list_trash(&conn).unwrap().len(), 3);
+
+        let trashed = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        assert_eq!(restore_videos(&conn, &trashed).unwrap(), ["a", "c"]);
+        assert_eq!(list_trash(&conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn word_counts_follow_the_transcript() {
+        let db = TempDb::new("word-count");
+        let conn = open(&db.0, None).unwrap();
+        save(&conn, "a").unwrap();
+        let count = |conn: &Connection| -> Option<i64> {
+            conn.query_row("SELECT word_count FROM videos WHERE video_id = 'a'", [], |r| r.get(0)).unwrap()
+        };
+        assert_eq!(count(&conn), Some(3));
+
+        save_video(&conn, "a", "title", "author", 60, "now four words here", 1, "2024-01-01", "@author", "standard", None, "", "manual").unwrap();
+        assert_eq!(count(&conn), Some(4));
+
+        conn.execute("UPDATE videos SET word_count = NULL", []).unwrap();
+        assert_eq!(backfill_word_counts(&conn).unwrap(), 1);
+        assert_eq!(count(&conn), Some(4));
+        assert_eq!((reading_minutes(0), reading_minutes(1), reading_minutes(231)), (0, 1, 2));
+    }
+
+    #[test]
+    fn date_filters_are_inclusive_and_indexed() {
+        let db = TempDb::new("list-indexes");
+        let conn = open(&db.0, None).unwrap();
+        for (id, added) in [("a", "2024-01-01 09:00:00"), ("b", "2024-01-02 23:59:59"), ("c", "2024-01-03 00:00:00")] {
+            save(&conn, id).unwrap();
+            conn.execute("UPDATE videos SET date_added = ?1 WHERE video_id = ?2", [added, id]).unwrap();
+        }
+        let query = VideoQuery { added_from: Some("2024-01-01".into()), added_to: Some("2024-01-02".into()), ..Default::default() };
+        let ids: Vec<String> = list_videos(&conn, &query).unwrap().into_iter().map(|v| v.id).collect();
+        assert_eq!(ids, ["b", "a"]);
+
+        let plan: String = conn
+            .query_row("EXPLAIN QUERY PLAN SELECT video_id FROM videos ORDER BY date_added DESC NULLS LAST, rowid DESC", [], |r| r.get(3))
+            .unwrap();
+        assert!(plan.contains("idx_videos_date_added"), "{}", plan);
+    }
+
+    #[test]
+    fn extra_transcript_tracks_sit_beside_the_main_one() {
+        let db = TempDb::new("transcript-tracks");
+        let conn = open(&db.0, None).unwrap();
+        save(&conn, "a").unwrap();
+        let segments = [TranscriptSegment { start_ms: 0, duration_ms: 900, text: "hola".into() }];
+        save_transcript_track(&conn, "a", "es", "hola", &segments).unwrap();
+
+        let languages: Vec<(String, bool)> = list_transcript_tracks(&conn, "a").unwrap().into_iter().map(|t| (t.language, t.main)).collect();
+        assert_eq!(languages, [(UNKNOWN_LANGUAGE.to_string(), true), ("es".to_string(), false)]);
+        assert_eq!(get_transcript_track(&conn, "a", "es").unwrap(), Some(("hola".to_string(), segments.to_vec())));
+        assert_eq!(get_transcript_track(&conn, "a", UNKNOWN_LANGUAGE).unwrap().unwrap().0, "some transcript words");
+        assert_eq!(get_transcript_track(&conn, "a", "fr").unwrap(), None);
+
+        set_transcript_language(&conn, "a", Some("en")).unwrap();
+        assert_eq!(list_transcript_tracks(&conn, "a").unwrap()[0].language, "en");
+        assert_eq!(get_video_details(&conn, "a").unwrap().unwrap().video.captions_auto_generated, None);
+        set_captions_auto_generated(&conn, "a", Some(true)).unwrap();
+        assert_eq!(get_video_details(&conn, "a").unwrap().unwrap().video.captions_auto_generated, Some(true));
+        set_video_flags(&conn, "a", &crate::youtube::VideoFlags { is_unlisted: true, ..Default::default() }).unwrap();
+        let video = list_videos(&conn, &VideoQuery::default()).unwrap().remove(0);
+        assert_eq!((video.is_private, video.is_unlisted, video.is_live_content), (Some(false), Some(true), Some(false)));
+        conn.execute("DELETE FROM videos WHERE video_id = 'a'", []).unwrap();
+        assert_eq!(get_transcript_track(&conn, "a", "es").unwrap(), None);
+    }
+
+    #[test]
+    fn media_files_keep_one_download_per_kind() {
+        let db = TempDb::new("media-files");
+        let conn = open(&db.0, None).unwrap();
+        save(&conn, "a").unwrap();
+        record_media_file(&conn, "a", "audio", "/media/a-audio.webm", "audio/webm", 10).unwrap();
+        record_media_file(&conn, "a", "audio", "/media/a-audio.m4a", "audio/mp4", 12).unwrap();
+
+        let files = get_media_files(&conn, "a").unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!((files[0].path.as_str(), files[0].size), ("/media/a-audio.m4a", 12));
+        conn.execute("DELETE FROM videos WHERE video_id = 'a'", []).unwrap();
+        assert!(get_media_files(&conn, "a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn lyrics_round_trip_for_saved_videos() {
+        use crate::types::LyricLine;
+        let db = TempDb::new("lyrics");
+        let conn = open(&db.0, None).unwrap();
+        save(&conn, "a").unwrap();
+        let lyrics = Lyrics {
+            text: "One\nTwo".to_string(),
+            lines: vec![LyricLine { start_ms: 0, end_ms: 900, text: "One".to_string() }, LyricLine { start_ms: 900, end_ms: 2000, text: "Two".to_string() }],
+            source: Some("Source: LyricFind".to_string()),
+        };
+        assert_eq!(save_lyrics(&conn, "a", &lyrics).unwrap(), 1);
+        assert_eq!(save_lyrics(&conn, "unsaved", &lyrics).unwrap(), 0);
+        assert_eq!(get_lyrics(&conn, "a").unwrap(), Some(lyrics));
+        conn.execute("DELETE FROM videos WHERE video_id = 'a'", []).unwrap();
+        assert_eq!(get_lyrics(&conn, "a").unwrap(), None);
+    }
+
+    #[test]
+    fn community_posts_are_archived_newest_first() {
+        let db = TempDb::new("community");
+        let conn = open(&db.0, None).unwrap();
+        let post = |id: &str, published: &str, text: &str| CommunityPost {
+            id: id.to_string(),
+            channel_id: "UCx".to_string(),
+            author: "Someone".to_string(),
+            text: text.to_string(),
+            published: published.to_string(),
+            like_count: 0,
+            images: Vec::new(),
+            poll: None,
+            video_id: None,
+        };
+        save_community_posts(&conn, &[post("old", "3 weeks ago", "Old"), post("new", "2 hours ago", "New")]).unwrap();
+        // A later copy updates the post but keeps its first publish estimate
+        save_community_posts(&conn, &[post("old", "1 year ago", "Old, edited")]).unwrap();
+
+        let posts = get_community_posts(&conn, "UCx").unwrap();
+        assert_eq!(posts.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), ["new", "old"]);
+        assert_eq!(posts[1].text, "Old, edited");
+        assert!(get_community_posts(&conn, "UCy").unwrap().is_empty());
+    }
+
+    #[test]
+    fn keywords_and_category_are_filterable() {
+        let db = TempDb::new("keywords");
+        let conn = open(&db.0, None).unwrap();
+        for id in ["a", "b"] {
+            save(&conn, id).unwrap();
+        }
+        set_keywords_and_category(&conn, "a", &["Rust".to_string(), "borrow checker".to_string()], Some("Education")).unwrap();
+        set_keywords_and_category(&conn, "b", &["cooking".to_string()], Some("Howto & Style")).unwrap();
+
+        let ids = |query: VideoQuery| -> Vec<String> { list_videos(&conn, &query).unwrap().into_iter().map(|v| v.id).collect() };
+        assert_eq!(ids(VideoQuery { category: Some("education".into()), ..Default::default() }), ["a"]);
+        assert_eq!(ids(VideoQuery { keyword: Some("rust".into()), ..Default::default() }), ["a"]);
+        // Keywords match whole, not as substrings
+        assert!(ids(VideoQuery { keyword: Some("borrow".into()), ..Default::default() }).is_empty());
+        assert_eq!(search_library(&conn, "Borrow").unwrap().into_iter().map(|v| v.id).collect::<Vec<_>>(), ["a"]);
+
+        let details = get_video_details(&conn, "b").unwrap().unwrap();
+        assert_eq!((details.keywords, details.category.as_deref()), (vec!["cooking".to_string()], Some("Howto & Style")));
+    }
+
+    #[test]
+    fn availability_is_recorded_and_filterable() {
+        let db = TempDb::new("availability");
+        let conn = open(&db.0, None).unwrap();
+        for id in ["a", "b", "c"] {
+            save(&conn, id).unwrap();
+        }
+        set_availability(&conn, "a", "available").unwrap();
+        set_availability(&conn, "b", "removed").unwrap();
+        // Never-checked videos come first
+        assert_eq!(videos_to_verify(&conn).unwrap()[0], "c");
+
+        let ids = |unavailable: bool| -> Vec<String> {
+            let query = VideoQuery { unavailable: Some(unavailable), sort: VideoSort::Title, ..Default::default() };
+            list_videos(&conn, &query).unwrap().into_iter().map(|v| v.id).collect()
+        };
+        assert_eq!(ids(true), ["b"]);
+        assert_eq!(ids(false), ["a", "c"]);
+        assert_eq!(list_videos(&conn, &VideoQuery::default()).unwrap().iter().find(|v| v.id == "b").unwrap().availability.as_deref(), Some("removed"));
+
+        assert_eq!(set_archive_url(&conn, "b", "https://web.archive.org/web/2023/https://www.youtube.com/watch?v=b").unwrap(), 1);
+        assert!(get_video_details(&conn, "b").unwrap().unwrap().archive_url.unwrap().starts_with("https://web.archive.org/"));
+    }
+
+    #[test]
+    fn channel_handles_are_cached_until_stale() {
+        let db = TempDb::new("channel-handles");
+        let conn = open(&db.0, None).unwrap();
+        cache_channel_id(&conn, "SomeCreator", "UCx").unwrap();
+        assert_eq!(get_cached_channel_id(&conn, "somecreator", 30).unwrap().as_deref(), Some("UCx"));
+        assert_eq!(get_cached_channel_id(&conn, "other", 30).unwrap(), None);
+
+        conn.execute("UPDATE channel_handles SET resolved_at = datetime('now', '-31 days')", []).unwrap();
+        assert_eq!(get_cached_channel_id(&conn, "SomeCreator", 30).unwrap(), None);
+    }
+
+    #[test]
+    fn saving_a_trashed_video_restores_it() {
+        let db = TempDb::new("resave-trashed");
+        let conn = open(&db.0, None).unwrap();
+        save(&conn, "trashed0001").unwrap();
+        assert!(get_video_details(&conn, "trashed0001").unwrap().is_some());
+
+        delete_video(&conn, "trashed0001").unwrap();
+        assert!(get_video_details(&conn, "trashed0001").unwrap().is_none());
+        assert!(!check_videos_exist(&conn, &["trashed0001".to_string()]).unwrap()["trashed0001"]);
+
+        save(&conn, "trashed0001").unwrap();
+        assert!(get_video_details(&conn, "trashed0001").unwrap().is_some());
+        assert!(list_trash(&conn).unwrap().is_empty());
+        assert_eq!(list_videos(&conn, &VideoQuery::default()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn transcript_search_returns_snippets_and_skips_trash() {
+        let db = TempDb::new("transcript-search");
+        let conn = open(&db.0, None).unwrap();
+        save_video(&conn, "searchable1", "Boats", "author", 60, "we talk about sailing boats across the sea", 1, "2024-01-01", "@author", "standard", None, "", "manual").unwrap();
+        save_video(&conn, "searchable2", "Trains", "author", 60, "steam trains and sailing boats compared", 1, "2024-01-01", "@author", "standard", None, "", "manual").unwrap();
+        save_video(&conn, "searchable3", "Cars", "author", 60, "nothing about the water here", 1, "2024-01-01", "@author", "standard", None, "", "manual").unwrap();
+
+        let matches = search_transcripts(&conn, "sailing boats", 10).unwrap();
+        let mut ids: Vec<&str> = matches.iter().map(|m| m.video.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, ["searchable1", "searchable2"]);
+        let boats = matches.iter().find(|m| m.video.id == "searchable1").unwrap();
+        assert_eq!(boats.video.title, "Boats");
+        assert!(boats.snippet.contains("[sailing boats]"), "snippet: {}", boats.snippet);
+
+        delete_video(&conn, "searchable1").unwrap();
+        let matches = search_transcripts(&conn, "sailing boats", 10).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].video.id, "searchable2");
+        // Quotes in the phrase don't break the query
+        assert!(search_transcripts(&conn, "say \"hi", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn listed_videos_carry_their_collections() {
+        let db = TempDb::new("collections");
+        let conn = open(&db.0, None).unwrap();
+        save(&conn, "collected01").unwrap();
+        save(&conn, "loose000001").unwrap();
+        let talks = create_collection(&conn, "Talks").unwrap();
+        let later = create_collection(&conn, "Watch later").unwrap();
+        add_to_collection(&conn, later, "collected01").unwrap();
+        add_to_collection(&conn, talks, "collected01").unwrap();
+        add_to_collection(&conn, talks, "collected01").unwrap();
+
+        let videos = list_videos(&conn, &VideoQuery::default()).unwrap();
+        let collected = videos.iter().find(|v| v.id == "collected01").unwrap();
+        assert_eq!(collected.collections, vec![talks, later]);
+        assert!(videos.iter().find(|v| v.id == "loose000001").unwrap().collections.is_empty());
+
+        let in_later = list_videos(&conn, &VideoQuery { collection_id: Some(later), ..Default::default() }).unwrap();
+        assert_eq!(in_later.len(), 1);
+        let counts: Vec<(String, i64)> = list_collections(&conn).unwrap().into_iter().map(|c| (c.name, c.video_count)).collect();
+        assert_eq!(counts, [("Talks".to_string(), 1), ("Watch later".to_string(), 1)]);
+
+        remove_from_collection(&conn, talks, "collected01").unwrap();
+        delete_collection(&conn, later).unwrap();
+        assert!(get_video_collections(&conn, "collected01").unwrap().is_empty());
+        assert_eq!(list_videos(&conn, &VideoQuery::default()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn tags_attach_count_and_clean_up() {
+        let db = TempDb::new("tags");
+        let conn = open(&db.0, None).unwrap();
+        save(&conn, "tagged00001").unwrap();
+        save(&conn, "tagged00002").unwrap();
+        add_tag(&conn, "tagged00001", "rust").unwrap();
+        add_tag(&conn, "tagged00001", "Talks").unwrap();
+        add_tag(&conn, "tagged00001", "rust").unwrap();
+        add_tag(&conn, "tagged00002", "rust").unwrap();
+
+        assert_eq!(get_video_tags(&conn, "tagged00001").unwrap(), ["rust", "Talks"]);
+        let counts: Vec<(String, i64)> = list_tags(&conn).unwrap().into_iter().map(|t| (t.name, t.video_count)).collect();
+        assert_eq!(counts, [("rust".to_string(), 2), ("Talks".to_string(), 1)]);
+        assert_eq!(list_videos_by_tag(&conn, "rust").unwrap().len(), 2);
+        assert_eq!(list_videos(&conn, &VideoQuery { tag: Some("Talks".into()), ..Default::default() }).unwrap().len(), 1);
+
+        // Trashed videos don't count, and a tag left on no video goes away
+        delete_video(&conn, "tagged00002").unwrap();
+        assert_eq!(list_videos_by_tag(&conn, "rust").unwrap().len(), 1);
+        remove_tag(&conn, "tagged00001", "Talks").unwrap();
+        assert_eq!(list_tags(&conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn export_round_trips_into_a_fresh_library() {
+        let source_db = TempDb::new("export-source");
+        let conn = open(&source_db.0, None).unwrap();
+        save(&conn, "exported001").unwrap();
+        save(&conn, "exported002").unwrap();
+        set_note(&conn, "exported001", "worth a rewatch").unwrap();
+        add_tag(&conn, "exported001", "rust").unwrap();
+        let talks = create_collection(&conn, "Talks").unwrap();
+        add_to_collection(&conn, talks, "exported002").unwrap();
+        set_video_meta(&conn, "exported001", "speaker", "Ferris").unwrap();
+        let library = crate::export::build_export(&conn).unwrap();
+        assert_eq!(library.videos.len(), 2);
+
+        let target_db = TempDb::new("export-target");
+        let target = open(&target_db.0, None).unwrap();
+        let report = crate::export::import_library(&target, &library).unwrap();
+        assert_eq!((report.added, report.updated, report.skipped), (2, 0, 0));
+        assert_eq!(get_transcript(&target, "exported001").unwrap().as_deref(), Some("some transcript words"));
+        assert_eq!(get_note(&target, "exported001").unwrap().as_deref(), Some("worth a rewatch"));
+        assert_eq!(get_video_tags(&target, "exported001").unwrap(), ["rust"]);
+        assert_eq!(list_collections(&target).unwrap()[0].video_count, 1);
+        assert_eq!(get_video_meta(&target, "exported001").unwrap().get("speaker").map(String::as_str), Some("Ferris"));
+
+        // Importing the same export again adds nothing
+        let again = crate::export::import_library(&target, &library).unwrap();
+        assert_eq!((again.added, again.updated, again.skipped), (0, 0, 2));
+    }
+
+    #[test]
+    fn compressing_old_transcripts_keeps_them_searchable() {
+        let db = TempDb::new("compression");
+        let conn = open(&db.0, None).unwrap();
+        let text = "the quick brown fox jumps over the lazy dog ".repeat(50);
+        save_video(&conn, "plain000001", "Fox", "author", 60, &text, 1, "2024-01-01", "@author", "standard", None, "", "manual").unwrap();
+        // As saved before compression existed
+        conn.execute("UPDATE videos SET transcript = ?1, transcript_zstd = NULL WHERE video_id = 'plain000001'", params![text]).unwrap();
+
+        let report = compress_transcripts(&conn).unwrap();
+        assert_eq!(report.videos, 1);
+        assert_eq!(report.bytes_before, text.len() as u64);
+        assert!(report.bytes_after < report.bytes_before / 4, "{:?}", report);
+        let (plain, blob): (Option<String>, Option<Vec<u8>>) = conn.query_row(
+            "SELECT transcript, transcript_zstd FROM videos WHERE video_id = 'plain000001'", [], |r| Ok((r.get(0)?, r.get(1)?)),
+        ).unwrap();
+        assert_eq!(plain, None);
+        assert!(blob.is_some());
+
+        assert_eq!(get_transcript(&conn, "plain000001").unwrap(), Some(text));
+        assert_eq!(search_transcripts(&conn, "lazy dog", 10).unwrap().len(), 1);
+        // Nothing is left to compress the second time
+        assert_eq!(compress_transcripts(&conn).unwrap().videos, 0);
+    }
+
+    #[test]
+    fn undoing_a_bulk_delete_restores_only_what_is_still_trashed() {
+        let db = TempDb::new("undo");
+        let conn = open(&db.0, None).unwrap();
+        for id in ["undo0000001", "undo0000002", "undo0000003"] {
+            save(&conn, id).unwrap();
+        }
+        let ids: Vec<String> = ["undo0000001", "undo0000002", "missing0001"].iter().map(|s| s.to_string()).collect();
+        let mut trashed = trash_videos(&conn, &ids).unwrap();
+        trashed.sort();
+        assert_eq!(trashed, ["undo0000001", "undo0000002"]);
+        assert_eq!(list_videos(&conn, &VideoQuery::default()).unwrap().len(), 1);
+
+        // Saved again in the meantime, so no longer in the trash
+        save(&conn, "undo0000002").unwrap();
+        assert_eq!(restore_videos(&conn, &trashed).unwrap(), ["undo0000001"]);
+        assert_eq!(list_videos(&conn, &VideoQuery::default()).unwrap().len(), 3);
+        assert!(restore_videos(&conn, &trashed).unwrap().is_empty());
+    }
+
+    #[test]
+    fn encrypts_and_decrypts_in_place() {
+        let db = TempDb::new("rekey");
+        let conn = open(&db.0, None).unwrap();
+        save(&conn, "a").unwrap();
+
+        let conn = rekey(conn, &db.0, Some("secret")).unwrap();
+        assert!(is_encrypted(&db.0));
+        assert!(check_video_exists(&conn, "a").unwrap());
+        drop(conn);
+        assert!(!std::path::Path::new(&format!("{}.rekey", db.0)).exists());
+
+        assert_eq!(open_with_key(&db.0, None).err().as_deref(), Some(crate::DB_LOCKED));
+        assert!(open(&db.0, Some("wrong")).is_err());
+        let conn = open_with_key(&db.0, Some("secret")).unwrap();
+        assert!(check_video_exists(&conn, "a").unwrap());
+
+        let conn = rekey(conn, &db.0, None).unwrap();
+        assert!(!is_encrypted(&db.0));
+        save(&conn, "b").unwrap();
+        drop(conn);
+        // A plaintext file ignores any passphrase left over from the session
+        let conn = open_with_key(&db.0, Some("secret")).unwrap();
+        assert!(check_video_exists(&conn, "a").unwrap() && check_video_exists(&conn, "b").unwrap());
+    }
+
+    #[test]
+    fn video_details_carry_stored_engagement_and_word_count() {
+        let db = TempDb::new("details-engagement");
+        let conn = open(&db.0, None).unwrap();
+        save(&conn, "engaged0001").unwrap();
+        set_engagement(&conn, "engaged0001", Some(120), Some(7)).unwrap();
+
+        let video = get_video_details(&conn, "engaged0001").unwrap().unwrap().video;
+        assert_eq!((video.like_count, video.comment_count), (Some(120), Some(7)));
+        assert_eq!(video.word_count, Some(3));
+    }
+
+    #[test]
+    fn cookies_move_out_of_settings() {
+        let conn = Connection::open_in_memory().unwrap();
+        let before = MIGRATIONS.iter().position(|m| m.version == 36).unwrap();
+        run_migrations(&conn, &MIGRATIONS[..before]).unwrap();
+        set_setting(&conn, "youtube_cookies", "SID=abc").unwrap();
+        set_setting(&conn, "use_cookies", "true").unwrap();
+
+        run_migrations(&conn, MIGRATIONS).unwrap();
+        assert_eq!(get_setting(&conn, "youtube_cookies").unwrap(), None);
+        assert_eq!(get_setting(&conn, "use_cookies").unwrap().as_deref(), Some("true"));
+        assert_eq!(get_credential(&conn, "youtube_cookies").unwrap().as_deref(), Some("SID=abc"));
+
+        delete_credential(&conn, "youtube_cookies").unwrap();
+        assert_eq!(get_credential(&conn, "youtube_cookies").unwrap(), None);
+    }
+}
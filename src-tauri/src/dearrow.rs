@@ -0,0 +1,95 @@
+use serde_json::Value;
+use reqwest::header::CONTENT_TYPE;
+use crate::types::Video;
+use crate::youtube;
+
+/// A video's crowdsourced DeArrow title and thumbnail, where it has them.
+#[derive(Debug, Default, PartialEq)]
+pub struct Branding {
+    pub title: Option<String>,
+    /// URL of a frame chosen in place of the uploader's thumbnail.
+    pub thumbnail: Option<String>,
+}
+
+pub async fn fetch_branding(video_id: &str) -> Result<Branding, String> {
+    let url = format!("https://sponsor.ajay.app/api/branding?videoID={}", urlencoding::encode(video_id));
+    let response = youtube::http_client().get(url).send().await
+        .map_err(|e| format!("DeArrow request failed: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Branding::default());
+    }
+    if !response.status().is_success() {
+        return Err(format!("DeArrow error: {}", response.status()));
+    }
+    let data: Value = response.json().await.map_err(|e| format!("Failed to parse DeArrow response: {}", e))?;
+    Ok(parse_branding(video_id, &data))
+}
+
+/// Submissions come best first. One that is the original, or that voters have
+/// turned down, means there is nothing better to show.
+fn first_trusted(items: &Value) -> Option<&Value> {
+    items.as_array()?.first().filter(|item| {
+        item["original"].as_bool() != Some(true)
+            && (item["locked"].as_bool() == Some(true) || item["votes"].as_i64().unwrap_or(0) >= 0)
+    })
+}
+
+fn parse_branding(video_id: &str, data: &Value) -> Branding {
+    // A leading `>` asks clients not to reformat the title
+    let title = first_trusted(&data["titles"])
+        .and_then(|t| t["title"].as_str())
+        .map(|t| t.trim_start_matches('>').trim().to_string())
+        .filter(|t| !t.is_empty());
+    let thumbnail = first_trusted(&data["thumbnails"])
+        .and_then(|t| t["timestamp"].as_f64())
+        .map(|time| format!("https://dearrow-thumb.ajay.app/api/v1/getThumbnail?videoID={}&time={}", urlencoding::encode(video_id), time));
+    Branding { title, thumbnail }
+}
+
+/// Show `branding` on `video`, keeping the uploader's title in `original_title`.
+pub fn apply(video: &mut Video, branding: Branding) {
+    if let Some(title) = branding.title {
+        video.original_title = Some(std::mem::replace(&mut video.title, title));
+    }
+    if let Some(thumbnail) = branding.thumbnail {
+        video.thumbnail = thumbnail;
+    }
+}
+
+/// Download a DeArrow thumbnail. Returns its MIME type and bytes.
+pub async fn fetch_thumbnail(url: &str) -> Result<Option<(String, Vec<u8>)>, String> {
+    let response = youtube::http_client().get(url).send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+    let mime = response.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| v.starts_with("image/"))
+        .unwrap_or("image/webp")
+        .to_string();
+    let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+    Ok((!bytes.is_empty()).then(|| (mime, bytes.to_vec())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_trusted_replacements_are_used() {
+        let data = serde_json::json!({
+            "titles": [{ "title": ">Why the bridge fell down", "original": false, "votes": 3, "locked": false }],
+            "thumbnails": [{ "timestamp": 42.5, "original": false, "votes": -1, "locked": false }]
+        });
+        assert_eq!(parse_branding("abc", &data), Branding { title: Some("Why the bridge fell down".into()), thumbnail: None });
+
+        let data = serde_json::json!({
+            "titles": [{ "title": "Same as YouTube's", "original": true, "votes": 9 }],
+            "thumbnails": [{ "timestamp": 12, "original": false, "votes": 0, "locked": true }]
+        });
+        let branding = parse_branding("abc", &data);
+        assert_eq!(branding.title, None);
+        assert_eq!(branding.thumbnail.as_deref(), Some("https://dearrow-thumb.ajay.app/api/v1/getThumbnail?videoID=abc&time=12"));
+    }
+}
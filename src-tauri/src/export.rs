@@ -0,0 +1,474 @@
+use std::collections::BTreeMap;
+use rusqlite::{Connection, Result, params};
+use serde::{Deserialize, Serialize};
+use crate::{db, types::{ImportReport, TranscriptSegment}};
+
+pub const EXPORT_FORMAT: &str = "kinesis-library";
+pub const EXPORT_VERSION: u32 = 1;
+
+pub const SETTINGS_FORMAT: &str = "kinesis-settings";
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Settings holding credentials; these never leave the machine in an export.
+pub(crate) const SECRET_SETTINGS: [&str; 5] = ["api_key", "venice_api_key", "po_token", "proxy_url", "youtube_cookies"];
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryExport {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub videos: Vec<ExportedVideo>,
+    #[serde(default)]
+    pub collections: Vec<ExportedCollection>,
+    #[serde(default)]
+    pub settings: BTreeMap<String, String>,
+}
+
+/// Just the settings, for replicating a setup without copying the library.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsExport {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub settings: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedVideo {
+    pub video_id: String,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub handle: Option<String>,
+    pub length_seconds: Option<i64>,
+    pub transcript: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub notes: Option<String>,
+    pub view_count: Option<i64>,
+    pub video_type: Option<String>,
+    pub published_at: Option<String>,
+    pub date_added: Option<String>,
+    pub source: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Names of the collections this video belongs to.
+    #[serde(default)]
+    pub collections: Vec<String>,
+    /// User-defined custom fields.
+    #[serde(default)]
+    pub meta: BTreeMap<String, String>,
+    /// Timed transcript lines, when the transcript was saved with timing.
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedCollection {
+    pub name: String,
+    pub created_at: Option<String>,
+}
+
+/// Snapshot the whole library: every video with its transcript, summary, notes,
+/// tags, collections, custom fields and timed segments, plus collections and
+/// non-secret settings.
+pub fn build_export(conn: &Connection) -> Result<LibraryExport> {
+    let exported_at: String = conn.query_row("SELECT datetime('now')", [], |row| row.get(0))?;
+
+    let mut videos = load_videos(conn)?;
+
+    let tags = group_by_video(conn, "SELECT vt.video_id, t.name FROM video_tags vt JOIN tags t ON t.id = vt.tag_id ORDER BY t.name")?;
+    let memberships = group_by_video(conn, "SELECT cv.video_id, c.name FROM collection_videos cv JOIN collections c ON c.id = cv.collection_id ORDER BY c.name")?;
+    let mut meta: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    {
+        let mut stmt = conn.prepare("SELECT video_id, key, value FROM video_meta")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            meta.entry(row.get(0)?).or_default().insert(row.get(1)?, row.get(2)?);
+        }
+    }
+    let mut segments: BTreeMap<String, Vec<TranscriptSegment>> = BTreeMap::new();
+    {
+        let mut stmt = conn.prepare("SELECT video_id, start_ms, duration_ms, text FROM transcript_segments ORDER BY video_id, seq")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            segments.entry(row.get(0)?).or_default().push(TranscriptSegment {
+                start_ms: row.get(1)?,
+                duration_ms: row.get(2)?,
+                text: row.get(3)?,
+            });
+        }
+    }
+    for video in &mut videos {
+        video.tags = tags.get(&video.video_id).cloned().unwrap_or_default();
+        video.collections = memberships.get(&video.video_id).cloned().unwrap_or_default();
+        video.meta = meta.remove(&video.video_id).unwrap_or_default();
+        video.segments = segments.remove(&video.video_id).unwrap_or_default();
+    }
+
+    let mut collections = Vec::new();
+    {
+        let mut stmt = conn.prepare("SELECT name, created_at FROM collections ORDER BY name")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            collections.push(ExportedCollection { name: row.get(0)?, created_at: row.get(1)? });
+        }
+    }
+
+    Ok(LibraryExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        exported_at,
+        videos,
+        collections,
+        settings: load_settings(conn)?,
+    })
+}
+
+/// Every setting except credentials.
+fn load_settings(conn: &Connection) -> Result<BTreeMap<String, String>> {
+    let mut settings = BTreeMap::new();
+    let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let key: String = row.get(0)?;
+        if SECRET_SETTINGS.contains(&key.as_str()) { continue; }
+        if let Some(value) = row.get::<_, Option<String>>(1)? {
+            settings.insert(key, value);
+        }
+    }
+    Ok(settings)
+}
+
+/// Snapshot the non-secret settings on their own.
+pub fn build_settings_export(conn: &Connection) -> Result<SettingsExport> {
+    let exported_at: String = conn.query_row("SELECT datetime('now')", [], |row| row.get(0))?;
+    Ok(SettingsExport {
+        format: SETTINGS_FORMAT.to_string(),
+        version: SETTINGS_VERSION,
+        exported_at,
+        settings: load_settings(conn)?,
+    })
+}
+
+/// Apply exported settings, replacing local values. Unlike a library import the
+/// file wins, since the point is to copy a setup over. Credentials in the file
+/// are ignored. Returns the number of settings applied.
+pub fn import_settings(conn: &Connection, export: &SettingsExport) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut applied = 0;
+    for (key, value) in &export.settings {
+        if SECRET_SETTINGS.contains(&key.as_str()) { continue; }
+        applied += tx.execute("INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)", params![key, value])?;
+    }
+    tx.commit()?;
+    Ok(applied)
+}
+
+/// Columns merged from an import into an existing row. Local values always win;
+/// the import only fills columns that are still empty.
+const MERGE_COLUMNS: [&str; 12] = [
+    "title", "author", "handle", "length_seconds", "transcript", "summary",
+    "notes", "view_count", "video_type", "published_at", "source", "description",
+];
+
+/// SQL for a column's current value, NULL when empty. A compressed transcript
+/// lives in `transcript_zstd` while the plain column stays NULL.
+fn current_value(column: &str) -> String {
+    match column {
+        "transcript" => "COALESCE(NULLIF(transcript, ''), transcript_zstd)".to_string(),
+        _ => format!("NULLIF({column}, '')"),
+    }
+}
+
+/// Merge an export into the library by `video_id`. New videos are inserted,
+/// existing ones only get their empty fields, tags and collections filled in,
+/// and videos the import adds nothing to are counted as skipped. Videos in the
+/// local trash are merged the same way but stay there, counted apart.
+pub fn import_library(conn: &Connection, library: &LibraryExport) -> Result<ImportReport> {
+    let tx = conn.unchecked_transaction()?;
+    let mut report = ImportReport::default();
+
+    for collection in &library.collections {
+        tx.execute(
+            "INSERT OR IGNORE INTO collections (name, created_at) VALUES (?1, COALESCE(?2, CURRENT_TIMESTAMP))",
+            params![collection.name, collection.created_at],
+        )?;
+    }
+
+    let set_clause = MERGE_COLUMNS.iter().enumerate()
+        .map(|(i, col)| format!("{col} = CASE WHEN {} IS NULL THEN ?{} ELSE {col} END", current_value(col), i + 2))
+        .collect::<Vec<_>>().join(", ");
+    let changed_clause = MERGE_COLUMNS.iter().enumerate()
+        .map(|(i, col)| format!("({} IS NULL AND NULLIF(?{}, '') IS NOT NULL)", current_value(col), i + 2))
+        .collect::<Vec<_>>().join(" OR ");
+    let merge_sql = format!("UPDATE videos SET {set_clause} WHERE video_id = ?1 AND ({changed_clause})");
+
+    for video in &library.videos {
+        let (exists, trashed): (bool, bool) = tx.query_row(
+            "SELECT COUNT(*) > 0, COUNT(deleted_at) > 0 FROM videos WHERE video_id = ?1",
+            params![video.video_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let mut changed = if exists {
+            tx.execute(&merge_sql, params![
+                video.video_id, video.title, video.author, video.handle, video.length_seconds,
+                video.transcript, video.summary, video.notes, video.view_count, video.video_type,
+                video.published_at, video.source, video.description,
+            ])? > 0
+        } else {
+            tx.execute(
+                "INSERT INTO videos (video_id, title, author, handle, length_seconds, transcript, summary, notes,
+                                     view_count, video_type, published_at, date_added, source, description)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, COALESCE(?9, 0), COALESCE(?10, 'standard'), ?11,
+                         COALESCE(?12, CURRENT_TIMESTAMP), COALESCE(?13, 'import'), ?14)",
+                params![
+                    video.video_id, video.title, video.author, video.handle, video.length_seconds,
+                    video.transcript, video.summary, video.notes, video.view_count, video.video_type,
+                    video.published_at, video.date_added, video.source, video.description,
+                ],
+            )?;
+            true
+        };
+
+        for tag in &video.tags {
+            db::add_tag(&tx, &video.video_id, tag)?;
+            changed |= tx.changes() > 0;
+        }
+        for name in &video.collections {
+            tx.execute("INSERT OR IGNORE INTO collections (name) VALUES (?1)", params![name])?;
+            changed |= tx.execute(
+                "INSERT OR IGNORE INTO collection_videos (collection_id, video_id)
+                 SELECT id, ?2 FROM collections WHERE name = ?1",
+                params![name, video.video_id],
+            )? > 0;
+        }
+        for (key, value) in &video.meta {
+            changed |= tx.execute(
+                "INSERT OR IGNORE INTO video_meta (video_id, key, value) VALUES (?1, ?2, ?3)",
+                params![video.video_id, key, value],
+            )? > 0;
+        }
+        if !video.segments.is_empty() && db::get_transcript_segments(&tx, &video.video_id)?.is_empty() {
+            db::replace_transcript_segments(&tx, &video.video_id, &video.segments)?;
+            changed = true;
+        }
+
+        if !exists {
+            report.added += 1;
+        } else if trashed {
+            report.trashed += 1;
+        } else if changed {
+            report.updated += 1;
+        } else {
+            report.skipped += 1;
+        }
+    }
+
+    // Settings are restored only where the user hasn't set them locally.
+    for (key, value) in &library.settings {
+        if SECRET_SETTINGS.contains(&key.as_str()) { continue; }
+        tx.execute("INSERT OR IGNORE INTO settings (key, value) VALUES (?1, ?2)", params![key, value])?;
+    }
+
+    // Imported transcripts arrive as plain text; store them like any other save.
+    db::compress_plain_transcripts(&tx)?;
+
+    tx.commit()?;
+    Ok(report)
+}
+
+/// Every saved video with its full text fields; tags, collections, custom
+/// fields and segments are left empty.
+pub fn load_videos(conn: &Connection) -> Result<Vec<ExportedVideo>> {
+    let mut videos = Vec::new();
+    let query = format!(
+        "SELECT video_id, title, author, handle, length_seconds, {}, summary, notes,
+                view_count, video_type, published_at, date_added, source, description
+         FROM videos WHERE deleted_at IS NULL ORDER BY date_added, rowid",
+        db::TRANSCRIPT_TEXT
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        videos.push(ExportedVideo {
+            video_id: row.get(0)?,
+            title: row.get(1)?,
+            author: row.get(2)?,
+            handle: row.get(3)?,
+            length_seconds: row.get::<_, Option<i64>>(4).unwrap_or(None),
+            transcript: row.get(5)?,
+            summary: row.get(6)?,
+            notes: row.get(7)?,
+            view_count: row.get::<_, Option<i64>>(8).unwrap_or(None),
+            video_type: row.get(9)?,
+            published_at: row.get::<_, Option<String>>(10).unwrap_or(None),
+            date_added: row.get::<_, Option<String>>(11).unwrap_or(None),
+            source: row.get(12)?,
+            description: row.get(13)?,
+            tags: Vec::new(),
+            collections: Vec::new(),
+            meta: BTreeMap::new(),
+            segments: Vec::new(),
+        });
+    }
+    Ok(videos)
+}
+
+/// Run a `(video_id, name)` query and group the names per video.
+fn group_by_video(conn: &Connection, query: &str) -> Result<BTreeMap<String, Vec<String>>> {
+    let mut map: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query(params![])?;
+    while let Some(row) = rows.next()? {
+        map.entry(row.get(0)?).or_default().push(row.get(1)?);
+    }
+    Ok(map)
+}
+
+/// Render a video as a standalone Markdown note.
+pub fn render_markdown(video: &ExportedVideo) -> String {
+    let title = video.title.as_deref().filter(|t| !t.is_empty()).unwrap_or(&video.video_id);
+    let mut md = format!("# {}\n\n", title);
+    if let Some(author) = video.author.as_deref().filter(|a| !a.is_empty()) {
+        md.push_str(&format!("- **Author:** {}\n", author));
+    }
+    md.push_str(&format!("- **URL:** https://www.youtube.com/watch?v={}\n", video.video_id));
+    if let Some(date) = video.published_at.as_deref().or(video.date_added.as_deref()).filter(|d| !d.is_empty()) {
+        md.push_str(&format!("- **Date:** {}\n", date));
+    }
+    md.push_str("\n## Transcript\n\n");
+    match video.transcript.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        Some(transcript) => md.push_str(transcript),
+        None => md.push_str("_No transcript available._"),
+    }
+    md.push('\n');
+    md
+}
+
+/// File name for a video's Markdown note: the title made filesystem-safe,
+/// suffixed with the video ID so identical titles don't collide.
+pub fn markdown_file_name(video: &ExportedVideo) -> String {
+    video_file_name(video.title.as_deref(), &video.video_id, "md")
+}
+
+/// `title [video_id].extension` with the title made filesystem-safe, or just
+/// the ID when there's no usable title.
+pub fn video_file_name(title: Option<&str>, video_id: &str, extension: &str) -> String {
+    let title: String = title.unwrap_or("")
+        .chars()
+        .filter(|c| !c.is_control() && !matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*'))
+        .take(80)
+        .collect();
+    let title = title.trim().trim_end_matches('.');
+    if title.is_empty() {
+        format!("{}.{}", video_id, extension)
+    } else {
+        format!("{} [{}].{}", title, video_id, extension)
+    }
+}
+
+const CSV_HEADER: [&str; 7] = ["video_id", "title", "author", "published_at", "view_count", "length_seconds", "date_added"];
+
+/// Render video metadata as RFC 4180 CSV. Starts with a UTF-8 BOM so
+/// spreadsheet apps pick the right encoding for non-ASCII titles.
+pub fn render_metadata_csv(videos: &[ExportedVideo]) -> String {
+    let mut csv = String::from("\u{feff}");
+    csv.push_str(&CSV_HEADER.join(","));
+    csv.push_str("\r\n");
+    for video in videos {
+        let fields = [
+            video.video_id.clone(),
+            video.title.clone().unwrap_or_default(),
+            video.author.clone().unwrap_or_default(),
+            video.published_at.clone().unwrap_or_default(),
+            video.view_count.map(|v| v.to_string()).unwrap_or_default(),
+            video.length_seconds.map(|v| v.to_string()).unwrap_or_default(),
+            video.date_added.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library(videos: serde_json::Value) -> LibraryExport {
+        serde_json::from_value(serde_json::json!({
+            "format": EXPORT_FORMAT,
+            "version": EXPORT_VERSION,
+            "exportedAt": "2024-01-01T00:00:00Z",
+            "videos": videos,
+        })).unwrap()
+    }
+
+    fn title_and_notes(conn: &Connection, id: &str) -> (Option<String>, Option<String>) {
+        conn.query_row("SELECT title, notes FROM videos WHERE video_id = ?1", params![id], |r| Ok((r.get(0)?, r.get(1)?))).unwrap()
+    }
+
+    fn counts(report: &ImportReport) -> (usize, usize, usize, usize) {
+        (report.added, report.updated, report.skipped, report.trashed)
+    }
+
+    #[test]
+    fn adds_new_videos() {
+        let conn = db::open(":memory:", None).unwrap();
+        let report = import_library(&conn, &library(serde_json::json!([
+            { "videoId": "aaaaaaaaaaa", "title": "First", "tags": ["music"] },
+        ]))).unwrap();
+        assert_eq!(counts(&report), (1, 0, 0, 0));
+        assert_eq!(title_and_notes(&conn, "aaaaaaaaaaa"), (Some("First".to_string()), None));
+        assert_eq!(db::get_video_tags(&conn, "aaaaaaaaaaa").unwrap(), vec!["music".to_string()]);
+    }
+
+    #[test]
+    fn merges_only_empty_fields_and_keeps_local_values() {
+        let conn = db::open(":memory:", None).unwrap();
+        import_library(&conn, &library(serde_json::json!([{ "videoId": "aaaaaaaaaaa", "title": "Local" }]))).unwrap();
+
+        // A different title conflicts and loses; the missing notes are filled in
+        let report = import_library(&conn, &library(serde_json::json!([
+            { "videoId": "aaaaaaaaaaa", "title": "Theirs", "notes": "from the export" },
+        ]))).unwrap();
+        assert_eq!(counts(&report), (0, 1, 0, 0));
+        assert_eq!(title_and_notes(&conn, "aaaaaaaaaaa"), (Some("Local".to_string()), Some("from the export".to_string())));
+
+        // Nothing left to fill in
+        let report = import_library(&conn, &library(serde_json::json!([
+            { "videoId": "aaaaaaaaaaa", "title": "Theirs", "notes": "other notes" },
+        ]))).unwrap();
+        assert_eq!(counts(&report), (0, 0, 1, 0));
+        assert_eq!(title_and_notes(&conn, "aaaaaaaaaaa"), (Some("Local".to_string()), Some("from the export".to_string())));
+    }
+
+    #[test]
+    fn trashed_videos_stay_in_the_trash() {
+        let conn = db::open(":memory:", None).unwrap();
+        import_library(&conn, &library(serde_json::json!([{ "videoId": "aaaaaaaaaaa", "title": "Local" }]))).unwrap();
+        db::delete_video(&conn, "aaaaaaaaaaa").unwrap();
+
+        let report = import_library(&conn, &library(serde_json::json!([
+            { "videoId": "aaaaaaaaaaa", "notes": "from the export" },
+            { "videoId": "bbbbbbbbbbb", "title": "New" },
+        ]))).unwrap();
+        assert_eq!(counts(&report), (1, 0, 0, 1));
+        assert!(!db::check_video_exists(&conn, "aaaaaaaaaaa").unwrap());
+        assert_eq!(title_and_notes(&conn, "aaaaaaaaaaa").1.as_deref(), Some("from the export"));
+    }
+}
@@ -2,8 +2,7 @@ use rusqlite::{Connection, Result, params};
 
 /// Add (or update the timestamp of) a query in history.
 /// Deduplication: same query just updates `searched_at`.
-pub fn add_history(path: &str, query: &str) -> Result<()> {
-    let conn = Connection::open(path)?;
+pub fn add_history(conn: &Connection, query: &str) -> Result<()> {
     conn.execute(
         "INSERT INTO search_history (search_query)
          VALUES (?1)
@@ -22,8 +21,7 @@ pub struct HistoryEntry {
 }
 
 /// Return the N most recent history entries.
-pub fn get_history(path: &str, limit: i64) -> Result<Vec<HistoryEntry>> {
-    let conn = Connection::open(path)?;
+pub fn get_history(conn: &Connection, limit: i64) -> Result<Vec<HistoryEntry>> {
     let mut stmt = conn.prepare(
         "SELECT id, search_query, searched_at FROM search_history
          ORDER BY searched_at DESC LIMIT ?1",
@@ -39,8 +37,7 @@ pub fn get_history(path: &str, limit: i64) -> Result<Vec<HistoryEntry>> {
 }
 
 /// Delete all entries on or before the given date (YYYY-MM-DD).
-pub fn clear_history_before(path: &str, date: &str) -> Result<usize> {
-    let conn = Connection::open(path)?;
+pub fn clear_history_before(conn: &Connection, date: &str) -> Result<usize> {
     let n = conn.execute(
         "DELETE FROM search_history WHERE date(searched_at) <= date(?1)",
         params![date],
@@ -49,15 +46,13 @@ pub fn clear_history_before(path: &str, date: &str) -> Result<usize> {
 }
 
 /// Delete a single entry by id.
-pub fn delete_history_entry(path: &str, id: i64) -> Result<()> {
-    let conn = Connection::open(path)?;
+pub fn delete_history_entry(conn: &Connection, id: i64) -> Result<()> {
     conn.execute("DELETE FROM search_history WHERE id = ?1", params![id])?;
     Ok(())
 }
 
 /// Clear the entire history table.
-pub fn clear_all_history(path: &str) -> Result<()> {
-    let conn = Connection::open(path)?;
+pub fn clear_all_history(conn: &Connection) -> Result<()> {
     conn.execute_batch("DELETE FROM search_history;")?;
     Ok(())
 }
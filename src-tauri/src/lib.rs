@@ -15,13 +15,21 @@ fn get_window_title() -> String {
 
 mod db;
 mod youtube;
+mod captions;
+mod sponsorblock;
+mod dearrow;
+mod wayback;
+mod media;
 mod history;
+mod activity;
+mod export;
+mod backup;
 mod types;
 mod ollama;
 mod venice;
 mod commands;
 
-pub use types::{Video, ChannelInfo, VideoResponse, DisplaySettings, DbDetails};
+pub use types::{Video, ChannelInfo, VideoResponse, DisplaySettings, DbDetails, BulkSaveReport, BulkSaveFailure, ImportReport};
 pub use types::{parse_view_count, extract_handle_from_url};
 
 
@@ -29,6 +37,30 @@ pub use types::{parse_view_count, extract_handle_from_url};
 
 pub(crate) struct DbPathState(pub Mutex<Option<String>>);
 
+/// The open database connection, shared by all commands. Tagged with the path
+/// it was opened from so a changed DB location reopens it.
+pub(crate) struct DbState(pub Mutex<Option<(String, rusqlite::Connection)>>);
+
+/// Held for reading by each connection `with_own_db` opens, and for writing
+/// while the database file is replaced, so it never changes under one.
+pub(crate) struct DbFileLock(pub std::sync::RwLock<()>);
+
+/// Passphrase for an encrypted database. Held in memory for the session only.
+pub(crate) struct DbKeyState(pub Mutex<Option<String>>);
+
+/// Why the data directory from `KINESIS_DATA_DIR` was passed over, kept until
+/// the UI asks for it since the choice is made before any window listens.
+pub(crate) struct DataDirWarningState(pub Mutex<Option<String>>);
+
+/// The most recent deletion, kept so it can be undone.
+pub(crate) struct UndoState(pub Mutex<Option<commands::undo::UndoableOperation>>);
+
+/// Trashed videos older than this are purged for good when the app exits.
+const TRASH_RETENTION_DAYS: u32 = 30;
+
+/// Error returned while an encrypted database is waiting for its passphrase.
+pub(crate) const DB_LOCKED: &str = "DB_LOCKED";
+
 // ─── Config file manager ──────────────────────────────────────────────────────
 
 pub(crate) struct ConfManager;
@@ -81,6 +113,18 @@ pub(crate) fn ensure_no_ghost_db(path: &str) {
     }
 }
 
+/// Environment variable that overrides where the database lives (portable installs, testing).
+pub(crate) const DATA_DIR_ENV: &str = "KINESIS_DATA_DIR";
+
+/// Create `dir` if needed and confirm a file can be written inside it.
+fn is_writable_dir(dir: &std::path::Path) -> bool {
+    if std::fs::create_dir_all(dir).is_err() { return false; }
+    let probe = dir.join(".kinesis_write_test");
+    let ok = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    ok
+}
+
 pub(crate) fn get_db_path(app: &tauri::AppHandle) -> String {
     let state = app.state::<DbPathState>();
     let mut guard = state.0.lock().unwrap();
@@ -89,7 +133,21 @@ pub(crate) fn get_db_path(app: &tauri::AppHandle) -> String {
         return path.clone();
     }
 
-    let db_file_path = if let Some(saved_path) = ConfManager::read_attr(app, "db_path") {
+    let env_dir = std::env::var(DATA_DIR_ENV).ok().filter(|v| !v.trim().is_empty()).and_then(|dir| {
+        let path = PathBuf::from(dir.trim());
+        if is_writable_dir(&path) {
+            Some(path)
+        } else {
+            let msg = format!("{} is not writable ({}); using the default data directory.", DATA_DIR_ENV, path.display());
+            log::warn!("{}", msg);
+            *app.state::<DataDirWarningState>().0.lock().unwrap() = Some(msg);
+            None
+        }
+    });
+
+    let db_file_path = if let Some(dir) = env_dir {
+        dir.join("kinesis_data.db")
+    } else if let Some(saved_path) = ConfManager::read_attr(app, "db_path").filter(|p| !p.is_empty()) {
         let path = PathBuf::from(&saved_path);
         if !path.exists() { let _ = std::fs::create_dir_all(&path); }
         path.join("kinesis_data.db")
@@ -111,12 +169,110 @@ pub(crate) fn get_db_path(app: &tauri::AppHandle) -> String {
         }
     };
 
+    let data_dir = db_file_path.parent().map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
+    let db_file_path = profile_db_path(&data_dir, &active_profile(app));
+    if let Some(dir) = db_file_path.parent() {
+        if !dir.exists() { let _ = std::fs::create_dir_all(dir); }
+    }
+
     let path_str = db_file_path.to_string_lossy().to_string();
     *guard = Some(path_str.clone());
-    let _ = db::init_db(&path_str);
     path_str
 }
 
+/// The profile every install starts with. Its database sits directly in the data folder.
+pub(crate) const DEFAULT_PROFILE: &str = "default";
+
+/// The profile chosen in `init.conf`, or the default one.
+pub(crate) fn active_profile(app: &tauri::AppHandle) -> String {
+    ConfManager::read_attr(app, "profile")
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// Where `profile`'s database lives inside the data folder. Other profiles get
+/// their own folder under `profiles`, which also keeps their backups apart.
+pub(crate) fn profile_db_path(data_dir: &std::path::Path, profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        data_dir.join("kinesis_data.db")
+    } else {
+        data_dir.join("profiles").join(profile).join("kinesis_data.db")
+    }
+}
+
+/// The folder holding the default database and the `profiles` folder.
+pub(crate) fn get_data_dir(app: &tauri::AppHandle) -> PathBuf {
+    let db_path = PathBuf::from(get_db_path(app));
+    let levels = if active_profile(app) == DEFAULT_PROFILE { 1 } else { 3 };
+    db_path.ancestors().nth(levels).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Open the database at `path`, applying the session passphrase if the file is encrypted.
+pub(crate) fn open_db(app: &tauri::AppHandle, path: &str) -> Result<rusqlite::Connection, String> {
+    let key = app.state::<DbKeyState>().0.lock().unwrap().clone();
+    db::open_with_key(path, key.as_deref())
+}
+
+/// Run `f` against the shared connection, opening (and initializing) it on first use.
+pub(crate) fn with_db<T>(app: &tauri::AppHandle, f: impl FnOnce(&rusqlite::Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let path = get_db_path(app);
+    let state = app.state::<DbState>();
+    let mut guard = state.0.lock().map_err(|e| e.to_string())?;
+
+    if guard.as_ref().map(|(p, _)| p != &path).unwrap_or(true) {
+        let conn = open_db(app, &path)?;
+        install_connection(&mut guard, path, conn);
+    }
+
+    let (_, conn) = guard.as_ref().unwrap();
+    f(conn).map_err(|e| e.to_string())
+}
+
+/// Make `conn` the shared connection for `path`, applying the settings each
+/// database keeps for itself: the proxy and the client versions. Every path
+/// that opens or swaps in a database goes through here.
+pub(crate) fn install_connection(slot: &mut Option<(String, rusqlite::Connection)>, path: String, conn: rusqlite::Connection) {
+    let proxy = db::get_setting(&conn, "proxy_url").unwrap_or(None);
+    if let Err(e) = youtube::set_proxy(proxy.as_deref()) {
+        log::warn!("Ignoring saved proxy: {}", e);
+    }
+    commands::load_client_versions(&conn);
+    *slot = Some((path, conn));
+}
+
+/// `with_db` on a blocking thread, for writes long enough to stall an async
+/// worker if run on one.
+pub(crate) async fn with_db_blocking<T: Send + 'static>(
+    app: &tauri::AppHandle,
+    f: impl FnOnce(&rusqlite::Connection) -> rusqlite::Result<T> + Send + 'static,
+) -> Result<T, String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || with_db(&app, f)).await.map_err(|e| e.to_string())?
+}
+
+/// Run `f` on a blocking thread against a connection of its own, for long
+/// reads such as exports and backups. WAL lets other commands keep using the
+/// shared connection meanwhile.
+pub(crate) async fn with_own_db<T: Send + 'static>(
+    app: &tauri::AppHandle,
+    f: impl FnOnce(&rusqlite::Connection) -> rusqlite::Result<T> + Send + 'static,
+) -> Result<T, String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let files = app.state::<DbFileLock>();
+        let _open = files.0.read().unwrap_or_else(|e| e.into_inner());
+        let conn = open_db(&app, &get_db_path(&app))?;
+        f(&conn).map_err(|e| e.to_string())
+    }).await.map_err(|e| e.to_string())?
+}
+
+/// Close the shared connection so the file can be moved or replaced.
+pub(crate) fn close_db(app: &tauri::AppHandle) {
+    let state = app.state::<DbState>();
+    let mut guard = state.0.lock().unwrap();
+    *guard = None;
+}
+
 #[tauri::command]
 fn get_app_info() -> serde_json::Value {
     serde_json::json!({ "name": APP_NAME, "version": VERSION })
@@ -135,27 +291,97 @@ pub fn run() {
             commands::get_api_key,
             commands::set_api_key,
             commands::remove_api_key,
+            commands::get_proxy,
+            commands::set_proxy,
+            commands::get_locale,
+            commands::set_locale,
+            commands::get_client_versions,
+            commands::set_client_version,
+            commands::import_cookies,
+            commands::get_cookie_status,
+            commands::set_cookies_enabled,
+            commands::remove_cookies,
             commands::open_db_location,
+            commands::take_data_dir_warning,
             commands::select_folder,
             commands::set_db_path_override,
+            commands::set_db_location,
             commands::get_db_details,
+            commands::compress_transcripts,
+            commands::backfill_word_counts,
             commands::get_display_settings,
             commands::set_display_settings,
             commands::get_setting,
             commands::set_setting,
+            // Encryption
+            commands::get_encryption_status,
+            commands::unlock_database,
+            commands::enable_encryption,
+            commands::disable_encryption,
             // YouTube
             commands::resolve_channel,
+            commands::fetch_channel_info,
             commands::fetch_videos,
+            commands::fetch_all_playlist_videos,
+            commands::fetch_playlist_info,
+            commands::fetch_channel_shorts,
+            commands::fetch_channel_streams,
+            commands::fetch_channel_playlists,
+            commands::fetch_community_posts,
+            commands::list_archived_posts,
             commands::fetch_channel_videos_v3,
             commands::fetch_view_count,
+            commands::fetch_view_counts,
             commands::fetch_video_info,
             commands::fetch_transcript,
+            commands::fetch_transcript_translated,
+            commands::list_caption_tracks,
+            commands::fetch_sponsor_segments,
+            commands::save_transcript_track,
+            commands::list_transcript_tracks,
+            commands::get_transcript_track,
+            commands::delete_transcript_track,
+            commands::get_transcript_segments,
+            commands::fetch_chapters,
+            commands::fetch_comments,
+            commands::get_saved_comments,
             commands::save_video,
             commands::fetch_saved_videos,
+            commands::get_video_details,
+            commands::list_videos_without_transcript,
+            commands::search_library,
+            commands::search_transcripts,
             commands::delete_video,
+            commands::bulk_delete_videos,
+            commands::list_trash,
+            commands::restore_video,
+            commands::purge_trash,
+            commands::undo_last_operation,
+            commands::set_note,
+            commands::get_note,
+            commands::mark_opened,
+            commands::recent_videos,
+            commands::mark_watched,
+            commands::mark_unwatched,
+            commands::toggle_starred,
             commands::check_video_exists,
+            commands::check_videos_exist,
             commands::bulk_save_videos,
+            commands::retry_failed,
+            commands::verify_library,
+            commands::find_archived_copy,
             commands::search_videos,
+            commands::search_youtube,
+            commands::fetch_trending,
+            commands::fetch_hashtag,
+            commands::fetch_related,
+            commands::fetch_heatmap,
+            commands::fetch_storyboard,
+            commands::fetch_song,
+            commands::download_audio,
+            commands::list_media_files,
+            commands::list_video_formats,
+            commands::download_video,
             // AI / Summarize / Ollama / Venice
             commands::check_ollama,
             commands::check_model_pulled,
@@ -189,16 +415,71 @@ pub fn run() {
             commands::clear_history_before_date,
             commands::delete_history_entry,
             commands::clear_all_history,
+            // Tags
+            commands::add_tag,
+            commands::remove_tag,
+            commands::list_tags,
+            commands::get_video_tags,
+            commands::list_videos_by_tag,
+            // Collections
+            commands::create_collection,
+            commands::rename_collection,
+            commands::delete_collection,
+            commands::list_collections,
+            commands::add_to_collection,
+            commands::remove_from_collection,
+            commands::get_video_collections,
+            // Channels
+            commands::list_channels,
+            // Custom fields
+            commands::set_video_meta,
+            commands::get_video_meta,
+            commands::delete_video_meta,
+            // Export / Import
+            commands::export_library,
+            commands::export_captions,
+            commands::import_library,
+            commands::export_transcripts_markdown,
+            commands::export_metadata_csv,
+            commands::export_settings,
+            commands::import_settings,
+            // Activity log
+            commands::get_activity_log,
+            commands::clear_activity_log,
+            // Thumbnails
+            commands::get_thumbnail,
+            commands::download_thumbnail,
+            commands::get_thumbnail_cache_stats,
+            commands::purge_thumbnail_cache,
+            commands::clear_response_cache,
+            // Profiles
+            commands::list_profiles,
+            commands::create_profile,
+            commands::switch_profile,
+            commands::delete_profile,
+            // Backups
+            commands::get_backup_settings,
+            commands::set_backup_settings,
+            commands::list_backups,
+            commands::create_backup,
+            commands::restore_backup,
             // Misc
             get_app_info,
         ])
         .manage(DbPathState(Mutex::new(None)))
+        .manage(DbState(Mutex::new(None)))
+        .manage(DbFileLock(std::sync::RwLock::new(())))
+        .manage(DbKeyState(Mutex::new(None)))
+        .manage(UndoState(Mutex::new(None)))
+        .manage(DataDirWarningState(Mutex::new(None)))
         .setup(|app| {
             let app_handle = app.handle();
-            let db_path = get_db_path(app_handle);
+            commands::spawn_backup_scheduler(app_handle.clone());
+            commands::spawn_client_version_refresh(app_handle.clone());
+            commands::forward_cool_downs(app_handle.clone());
 
-            let resolution = db::get_setting(&db_path, "resolution").unwrap_or(None).unwrap_or_else(|| "1440x900".to_string());
-            let fullscreen = db::get_setting(&db_path, "fullscreen").unwrap_or(None).map(|s| s == "true").unwrap_or(false);
+            let resolution = with_db(app_handle, |c| db::get_setting(c, "resolution")).unwrap_or(None).unwrap_or_else(|| "1440x900".to_string());
+            let fullscreen = with_db(app_handle, |c| db::get_setting(c, "fullscreen")).unwrap_or(None).map(|s| s == "true").unwrap_or(false);
 
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.set_title(&get_window_title());
@@ -216,8 +497,11 @@ pub fn run() {
         .expect("error while building tauri application")
         .run(|app_handle, event| {
             if let tauri::RunEvent::ExitRequested { .. } = event {
-                let db_path = get_db_path(app_handle);
-                let _ = db::vacuum_db(&db_path);
+                let _ = with_db(app_handle, |c| match db::purge_trash(c, Some(TRASH_RETENTION_DAYS))? {
+                    0 => Ok(()),
+                    n => activity::record(c, "purge", None, Some(&format!("Removed {} videos trashed over {} days ago", n, TRASH_RETENTION_DAYS))),
+                });
+                let _ = with_db(app_handle, db::vacuum_db);
             }
         });
 }
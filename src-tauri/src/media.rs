@@ -0,0 +1,263 @@
+use serde_json::Value;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use crate::types::VideoFormat;
+use crate::youtube;
+
+pub mod cipher;
+
+/// Bytes asked for per request. googlevideo throttles requests for a whole
+/// stream, but serves ranges of this size at full speed.
+const CHUNK_SIZE: u64 = 10 * 1024 * 1024;
+
+/// A signature the player scrambled, to be unscrambled and added to the
+/// stream URL as `param`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scrambled {
+    pub param: String,
+    pub signature: String,
+}
+
+/// One stream from a player response's `streamingData`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamFormat {
+    pub itag: i64,
+    pub url: String,
+    /// Set for formats behind a `signatureCipher`; see `unscramble`.
+    pub scrambled: Option<Scrambled>,
+    /// The full MIME type, codecs included: `audio/mp4; codecs="mp4a.40.2"`.
+    pub mime_type: String,
+    pub bitrate: i64,
+    pub content_length: Option<u64>,
+    pub quality_label: Option<String>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub fps: Option<i64>,
+    /// Listed under `formats`, with audio and video in one file, rather than
+    /// under `adaptiveFormats`.
+    pub progressive: bool,
+}
+
+impl StreamFormat {
+    fn from_json(format: &Value, progressive: bool) -> Option<Self> {
+        let (url, scrambled) = match format["url"].as_str() {
+            Some(url) => (url.to_string(), None),
+            None => {
+                let cipher = reqwest::Url::parse(&format!("https://cipher/?{}", format["signatureCipher"].as_str()?)).ok()?;
+                let get = |key: &str| cipher.query_pairs().find(|(k, _)| k == key).map(|(_, v)| v.to_string());
+                let scrambled = Scrambled {
+                    param: get("sp").unwrap_or_else(|| "signature".to_string()),
+                    signature: get("s")?,
+                };
+                (get("url")?, Some(scrambled))
+            }
+        };
+        Some(Self {
+            itag: format["itag"].as_i64()?,
+            url,
+            scrambled,
+            mime_type: format["mimeType"].as_str()?.to_string(),
+            bitrate: format["bitrate"].as_i64().unwrap_or(0),
+            content_length: format["contentLength"].as_str().and_then(|l| l.parse().ok()),
+            quality_label: format["qualityLabel"].as_str().map(String::from),
+            width: format["width"].as_i64(),
+            height: format["height"].as_i64(),
+            fps: format["fps"].as_i64(),
+            progressive,
+        })
+    }
+
+    pub fn is_video(&self) -> bool {
+        self.container().starts_with("video/")
+    }
+
+    /// Whether the file carries sound: every audio stream and every
+    /// progressive one.
+    pub fn has_audio(&self) -> bool {
+        self.progressive || self.container().starts_with("audio/")
+    }
+
+    /// What the quality picker shows for this format.
+    pub fn describe(&self) -> VideoFormat {
+        VideoFormat {
+            itag: self.itag,
+            mime_type: self.mime_type.clone(),
+            quality_label: self.quality_label.clone(),
+            width: self.width,
+            height: self.height,
+            fps: self.fps,
+            bitrate: self.bitrate,
+            content_length: self.content_length,
+            has_audio: self.has_audio(),
+        }
+    }
+
+    /// The MIME type without its codecs.
+    pub fn container(&self) -> &str {
+        self.mime_type.split(';').next().unwrap_or("").trim()
+    }
+
+    /// The file extension for this format's container.
+    pub fn extension(&self) -> &'static str {
+        match self.container() {
+            "audio/mp4" => "m4a",
+            "audio/webm" | "video/webm" => "webm",
+            _ => "mp4",
+        }
+    }
+}
+
+/// Every stream in `player`: the progressive `formats`, then the adaptive ones.
+pub fn formats(player: &Value) -> Vec<StreamFormat> {
+    let data = &player["streamingData"];
+    let list = |key: &str, progressive: bool| {
+        data[key].as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(move |f| StreamFormat::from_json(f, progressive))
+            .collect::<Vec<_>>()
+    };
+    let mut formats = list("formats", true);
+    formats.extend(list("adaptiveFormats", false));
+    formats
+}
+
+/// The best audio-only stream: m4a when there is one, since more players
+/// handle it than webm, otherwise webm. Highest bitrate within each.
+pub fn pick_audio_format(formats: &[StreamFormat]) -> Option<StreamFormat> {
+    ["audio/mp4", "audio/webm"].iter()
+        .find_map(|container| formats.iter().filter(|f| f.container() == *container).max_by_key(|f| f.bitrate))
+        .cloned()
+}
+
+/// The video stream to download: `itag` when given, otherwise the tallest no
+/// taller than `max_height`. At the same height, one with sound wins, then
+/// mp4 over webm, then the higher bitrate.
+pub fn pick_video_format(formats: &[StreamFormat], itag: Option<i64>, max_height: Option<i64>) -> Option<StreamFormat> {
+    if let Some(itag) = itag {
+        return formats.iter().find(|f| f.itag == itag).cloned();
+    }
+    formats.iter()
+        .filter(|f| f.is_video() && max_height.map_or(true, |max| f.height.unwrap_or(0) <= max))
+        .max_by_key(|f| (f.height.unwrap_or(0), f.has_audio(), f.container() == "video/mp4", f.bitrate))
+        .cloned()
+}
+
+/// Make a scrambled format's URL usable by unscrambling its signature with
+/// the current player's steps. Formats with plain URLs are left alone.
+pub async fn unscramble(format: &mut StreamFormat) -> Result<(), String> {
+    let Some(scrambled) = format.scrambled.take() else { return Ok(()) };
+    let operations = cipher::operations().await?;
+    let signature = cipher::apply(&operations, &scrambled.signature);
+    format.url = format!("{}&{}={}", format.url, scrambled.param, urlencoding::encode(&signature));
+    Ok(())
+}
+
+/// Download `format` to `path` a range at a time, calling `progress` with the
+/// bytes received so far and the total when known. A partial file is removed
+/// on failure. Returns the size written.
+pub async fn download(format: &StreamFormat, path: &Path, progress: impl Fn(u64, Option<u64>)) -> Result<u64, String> {
+    let partial = path.with_extension("part");
+    let result = download_to(format, &partial, &progress).await;
+    match result {
+        Ok(size) => {
+            tokio::fs::rename(&partial, path).await.map_err(|e| e.to_string())?;
+            Ok(size)
+        }
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&partial).await;
+            Err(e)
+        }
+    }
+}
+
+async fn download_to(format: &StreamFormat, path: &Path, progress: &impl Fn(u64, Option<u64>)) -> Result<u64, String> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await.map_err(|e| e.to_string())?;
+    }
+    let mut file = tokio::fs::File::create(path).await.map_err(|e| e.to_string())?;
+    let client = youtube::http_client();
+    let total = format.content_length;
+    let mut received = 0u64;
+
+    loop {
+        let end = received + CHUNK_SIZE - 1;
+        youtube::throttle::wait().await;
+        let mut response = client.get(&format.url)
+            .header(reqwest::header::RANGE, format!("bytes={}-{}", received, end))
+            .send()
+            .await
+            .map_err(|e| format!("Download failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Download failed: {}", response.status()));
+        }
+
+        let before = received;
+        while let Some(chunk) = response.chunk().await.map_err(|e| format!("Download failed: {}", e))? {
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+            received += chunk.len() as u64;
+        }
+        progress(received, total);
+
+        let done = match total {
+            Some(total) => received >= total,
+            // Without a length, a short range means the stream has ended
+            None => received - before < CHUNK_SIZE,
+        };
+        if done || received == before {
+            break;
+        }
+    }
+    file.flush().await.map_err(|e| e.to_string())?;
+
+    if let Some(total) = total.filter(|total| received < *total) {
+        return Err(format!("Download ended early: got {} of {} bytes", received, total));
+    }
+    Ok(received)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_prefers_m4a_then_bitrate() {
+        let player = serde_json::json!({ "streamingData": { "adaptiveFormats": [
+            { "itag": 137, "url": "https://v/137", "mimeType": "video/mp4; codecs=\"avc1.640028\"", "bitrate": 4000000 },
+            { "itag": 251, "url": "https://v/251", "mimeType": "audio/webm; codecs=\"opus\"", "bitrate": 160000 },
+            { "itag": 139, "url": "https://v/139", "mimeType": "audio/mp4; codecs=\"mp4a.40.5\"", "bitrate": 48000 },
+            { "itag": 140, "url": "https://v/140", "mimeType": "audio/mp4; codecs=\"mp4a.40.2\"", "bitrate": 130000, "contentLength": "3456789" }
+        ] } });
+        let format = pick_audio_format(&formats(&player)).unwrap();
+        assert_eq!((format.itag, format.extension(), format.content_length), (140, "m4a", Some(3456789)));
+
+        let webm_only = serde_json::json!({ "streamingData": { "adaptiveFormats": [
+            { "itag": 251, "url": "https://v/251", "mimeType": "audio/webm; codecs=\"opus\"", "bitrate": 160000 }
+        ] } });
+        assert_eq!(pick_audio_format(&formats(&webm_only)).map(|f| f.extension()), Some("webm"));
+        assert_eq!(pick_audio_format(&formats(&Value::Null)), None);
+    }
+
+    #[test]
+    fn video_is_picked_by_itag_or_height() {
+        let player = serde_json::json!({ "streamingData": {
+            "formats": [
+                { "itag": 18, "url": "https://v/18", "mimeType": "video/mp4; codecs=\"avc1.42001E, mp4a.40.2\"", "height": 360, "bitrate": 500000 }
+            ],
+            "adaptiveFormats": [
+                { "itag": 134, "url": "https://v/134", "mimeType": "video/mp4; codecs=\"avc1.4d401e\"", "height": 360, "bitrate": 600000 },
+                { "itag": 248, "url": "https://v/248", "mimeType": "video/webm; codecs=\"vp9\"", "height": 1080, "bitrate": 2600000 },
+                { "itag": 137, "signatureCipher": "s=SIG%3D&sp=sig&url=https%3A%2F%2Fv%2F137%3Fid%3D1", "mimeType": "video/mp4; codecs=\"avc1.640028\"", "height": 1080, "bitrate": 4000000 }
+            ]
+        } });
+        let formats = formats(&player);
+        let best = pick_video_format(&formats, None, None).unwrap();
+        assert_eq!(best.itag, 137);
+        assert_eq!(best.url, "https://v/137?id=1");
+        assert_eq!(best.scrambled, Some(Scrambled { param: "sig".into(), signature: "SIG=".into() }));
+        // At 360p the progressive stream wins for carrying sound
+        assert_eq!(pick_video_format(&formats, None, Some(720)).map(|f| f.itag), Some(18));
+        assert_eq!(pick_video_format(&formats, Some(248), None).map(|f| f.extension()), Some("webm"));
+        assert_eq!(pick_video_format(&formats, None, Some(144)), None);
+    }
+}
@@ -0,0 +1,134 @@
+use regex::Regex;
+use std::sync::Mutex;
+use crate::youtube;
+
+/// One step of the player's signature scrambling, undone in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Reverse,
+    /// Drop this many characters from the front.
+    Splice(usize),
+    /// Swap the first character with the one at this index (modulo length).
+    Swap(usize),
+}
+
+/// The operations of the last player script read, keyed by its player ID.
+/// A new player build only appears every few days.
+static OPERATIONS: Mutex<Option<(String, Vec<Operation>)>> = Mutex::new(None);
+
+/// The ID of the player build YouTube currently serves.
+async fn player_id() -> Result<String, String> {
+    youtube::throttle::wait().await;
+    let text = youtube::http_client().get("https://www.youtube.com/iframe_api")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    let re = Regex::new(r"player\\?/([0-9a-fA-F]{8})\\?/").unwrap();
+    re.captures(&text)
+        .map(|caps| caps[1].to_string())
+        .ok_or_else(|| "Could not find the current player version.".to_string())
+}
+
+/// The current player's signature operations, read from its script unless
+/// they are already known.
+pub async fn operations() -> Result<Vec<Operation>, String> {
+    let id = player_id().await?;
+    if let Some((cached_id, ops)) = OPERATIONS.lock().unwrap().as_ref() {
+        if *cached_id == id {
+            return Ok(ops.clone());
+        }
+    }
+
+    let url = format!("https://www.youtube.com/s/player/{}/player_ias.vflset/en_US/base.js", id);
+    youtube::throttle::wait().await;
+    let js = youtube::http_client().get(url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    let ops = parse_operations(&js).ok_or("Could not read the signature steps from the player script.")?;
+    *OPERATIONS.lock().unwrap() = Some((id, ops.clone()));
+    Ok(ops)
+}
+
+/// The signature function's steps. It splits the signature into characters,
+/// calls methods of one helper object on them, and joins them again; each
+/// method reverses, splices or swaps, which its body gives away.
+pub fn parse_operations(js: &str) -> Option<Vec<Operation>> {
+    const NAME: &str = r"[A-Za-z_$][\w$]*";
+    let function = Regex::new(&format!(
+        r#"=function\({n}\)\{{{n}={n}\.split\(""\);(.*?);return {n}\.join\(""\)\}}"#,
+        n = NAME
+    )).unwrap();
+    let body = function.captures(js)?.get(1)?.as_str();
+
+    let call = Regex::new(&format!(r#"({n})(?:\.({n})|\["([^"]+)"\])\({n},(\d+)\)"#, n = NAME)).unwrap();
+    let calls: Vec<(String, String, usize)> = call.captures_iter(body)
+        .filter_map(|caps| {
+            let method = caps.get(2).or(caps.get(3))?.as_str().to_string();
+            Some((caps[1].to_string(), method, caps[4].parse().ok()?))
+        })
+        .collect();
+    let helper = &calls.first()?.0;
+
+    let object = Regex::new(&format!(r"(?s)var {}=\{{(.*?)\}};", regex::escape(helper))).unwrap();
+    let methods_src = object.captures(js)?.get(1)?.as_str();
+    let method = Regex::new(&format!(r#"({n}|"[^"]+"):function\([^)]*\)\{{([^}}]*)\}}"#, n = NAME)).unwrap();
+    let methods: Vec<(String, &str)> = method.captures_iter(methods_src)
+        .map(|caps| (caps[1].trim_matches('"').to_string(), caps.get(2).map_or("", |b| b.as_str())))
+        .collect();
+
+    calls.iter()
+        .map(|(_, name, arg)| {
+            let (_, body) = methods.iter().find(|(m, _)| m == name)?;
+            Some(if body.contains("reverse") {
+                Operation::Reverse
+            } else if body.contains("splice") {
+                Operation::Splice(*arg)
+            } else {
+                Operation::Swap(*arg)
+            })
+        })
+        .collect()
+}
+
+/// Unscramble `signature` with `operations`.
+pub fn apply(operations: &[Operation], signature: &str) -> String {
+    let mut chars: Vec<char> = signature.chars().collect();
+    for op in operations {
+        match *op {
+            Operation::Reverse => chars.reverse(),
+            Operation::Splice(n) => {
+                chars.drain(..n.min(chars.len()));
+            }
+            Operation::Swap(n) if !chars.is_empty() => {
+                let len = chars.len();
+                chars.swap(0, n % len);
+            }
+            Operation::Swap(_) => {}
+        }
+    }
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_steps_are_read_and_applied() {
+        let js = r#"var x=1;var Qk={Xd:function(a){a.reverse()},
+            "u$":function(a,b){a.splice(0,b)},
+            pE:function(a,b){var c=a[0];a[0]=a[b%a.length];a[b%a.length]=c}};
+            Dta=function(a){a=a.split("");Qk.pE(a,3);Qk["u$"](a,2);Qk.Xd(a,41);return a.join("")};"#;
+        let ops = parse_operations(js).unwrap();
+        assert_eq!(ops, [Operation::Swap(3), Operation::Splice(2), Operation::Reverse]);
+        assert_eq!(apply(&ops, "abcdefg"), "gfeac");
+        assert_eq!(parse_operations("var nothing=here;"), None);
+    }
+}
@@ -4,7 +4,7 @@ use tauri::{AppHandle, Emitter, Manager};
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-use crate::{db, get_db_path};
+use crate::{db, with_db};
 
 // Default chunk settings
 const DEFAULT_CHUNK_SIZE: usize = 1000; // words per chunk
@@ -202,9 +202,7 @@ pub async fn check_ollama() -> Result<bool, String> {
 
 /// Check if the specific model is pulled
 pub async fn check_model_pulled(app: AppHandle) -> Result<bool, String> {
-    let db_path = get_db_path(&app);
-    let model_setting = db::get_setting(&db_path, "ollama_model")
-        .map_err(|e| e.to_string())?
+    let model_setting = with_db(&app, |c| db::get_setting(c, "ollama_model"))?
         .unwrap_or_else(|| "llama3.2".to_string());
     
     let client = reqwest::Client::new();
@@ -228,9 +226,7 @@ pub async fn check_model_pulled(app: AppHandle) -> Result<bool, String> {
 /// Pull a model from Ollama
 pub async fn pull_model(app: AppHandle) -> Result<(), String> {
     // Get the selected model from settings
-    let db_path = get_db_path(&app);
-    let model_setting = db::get_setting(&db_path, "ollama_model")
-        .map_err(|e| e.to_string())?
+    let model_setting = with_db(&app, |c| db::get_setting(c, "ollama_model"))?
         .unwrap_or_else(|| "llama3.2".to_string());
     
     println!("Starting model pull: {}", model_setting);
@@ -290,9 +286,7 @@ pub async fn pull_model(app: AppHandle) -> Result<(), String> {
 /// Delete model from Ollama
 pub async fn delete_model(app: AppHandle) -> Result<(), String> {
     // Get the selected model from settings
-    let db_path = get_db_path(&app);
-    let model_setting = db::get_setting(&db_path, "ollama_model")
-        .map_err(|e| e.to_string())?
+    let model_setting = with_db(&app, |c| db::get_setting(c, "ollama_model"))?
         .unwrap_or_else(|| "llama3.2".to_string());
     
     println!("Starting model delete: {}", model_setting);
@@ -670,29 +664,22 @@ pub async fn summarize_transcript(app: AppHandle, transcript: String) -> Result<
     ensure_ollama_running().await?;
     
     // Get settings from database
-    let db_path = get_db_path(&app);
-    let model_setting = db::get_setting(&db_path, "ollama_model")
-        .map_err(|e| e.to_string())?
+    let model_setting = with_db(&app, |c| db::get_setting(c, "ollama_model"))?
         .unwrap_or_else(|| "llama3.2".to_string());
-    let prompt_template = db::get_setting(&db_path, "ollama_prompt")
-        .map_err(|e| e.to_string())?
+    let prompt_template = with_db(&app, |c| db::get_setting(c, "ollama_prompt"))?
         .unwrap_or_else(|| DEFAULT_PROMPT_TEMPLATE.to_string());
     
     // Get chunking settings
-    let chunk_enabled = db::get_setting(&db_path, "chunk_enabled")
-        .map_err(|e| e.to_string())?
+    let chunk_enabled = with_db(&app, |c| db::get_setting(c, "chunk_enabled"))?
         .map(|v| v == "true")
         .unwrap_or(true);
-    let chunk_size = db::get_setting(&db_path, "chunk_size")
-        .map_err(|e| e.to_string())?
+    let chunk_size = with_db(&app, |c| db::get_setting(c, "chunk_size"))?
         .and_then(|v| v.parse().ok())
         .unwrap_or(DEFAULT_CHUNK_SIZE);
-    let chunk_overlap = db::get_setting(&db_path, "chunk_overlap")
-        .map_err(|e| e.to_string())?
+    let chunk_overlap = with_db(&app, |c| db::get_setting(c, "chunk_overlap"))?
         .and_then(|v| v.parse().ok())
         .unwrap_or(DEFAULT_CHUNK_OVERLAP);
-    let max_chunks = db::get_setting(&db_path, "max_chunks")
-        .map_err(|e| e.to_string())?
+    let max_chunks = with_db(&app, |c| db::get_setting(c, "max_chunks"))?
         .and_then(|v| v.parse().ok())
         .unwrap_or(DEFAULT_MAX_CHUNKS);
     
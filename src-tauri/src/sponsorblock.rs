@@ -0,0 +1,80 @@
+use serde_json::Value;
+use crate::types::{SponsorSegment, TranscriptSegment};
+use crate::youtube;
+
+/// Segment categories worth cutting from an archived transcript.
+const CATEGORIES: [&str; 5] = ["sponsor", "selfpromo", "interaction", "intro", "outro"];
+
+/// The crowdsourced skip segments SponsorBlock has for a video, in order.
+/// Empty when it has none.
+pub async fn fetch_segments(video_id: &str) -> Result<Vec<SponsorSegment>, String> {
+    let categories = serde_json::to_string(&CATEGORIES).map_err(|e| e.to_string())?;
+    let url = format!(
+        "https://sponsor.ajay.app/api/skipSegments?videoID={}&categories={}",
+        urlencoding::encode(video_id), urlencoding::encode(&categories)
+    );
+    let response = youtube::http_client().get(url).send().await
+        .map_err(|e| format!("SponsorBlock request failed: {}", e))?;
+    // SponsorBlock answers 404 for videos nobody has submitted segments for
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        return Err(format!("SponsorBlock error: {}", response.status()));
+    }
+    let data: Value = response.json().await.map_err(|e| format!("Failed to parse SponsorBlock response: {}", e))?;
+    Ok(parse_segments(&data))
+}
+
+fn parse_segments(data: &Value) -> Vec<SponsorSegment> {
+    let mut segments: Vec<SponsorSegment> = data.as_array().map(Vec::as_slice).unwrap_or(&[]).iter()
+        // Full-video labels and chapter titles aren't time ranges to skip
+        .filter(|s| s["actionType"].as_str().unwrap_or("skip") == "skip")
+        .filter_map(|s| {
+            let range = s["segment"].as_array()?;
+            Some(SponsorSegment {
+                category: s["category"].as_str()?.to_string(),
+                start_ms: (range.first()?.as_f64()? * 1000.0) as i64,
+                end_ms: (range.get(1)?.as_f64()? * 1000.0) as i64,
+            })
+        })
+        .filter(|s| s.end_ms > s.start_ms)
+        .collect();
+    segments.sort_by_key(|s| s.start_ms);
+    segments
+}
+
+/// Transcript lines with those spoken mostly inside a skip segment removed.
+pub fn strip_segments(lines: &[TranscriptSegment], skips: &[SponsorSegment]) -> Vec<TranscriptSegment> {
+    lines.iter()
+        .filter(|line| {
+            let middle = line.start_ms + line.duration_ms / 2;
+            !skips.iter().any(|s| (s.start_ms..s.end_ms).contains(&middle))
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sponsor_reads_are_cut_from_transcripts() {
+        let data = serde_json::json!([
+            { "segment": [12.5, 30.0], "category": "sponsor", "actionType": "skip" },
+            { "segment": [0.0, 4.0], "category": "intro", "actionType": "skip" },
+            { "segment": [0, 0], "category": "sponsor", "actionType": "full" }
+        ]);
+        let skips = parse_segments(&data);
+        assert_eq!(skips, [
+            SponsorSegment { category: "intro".into(), start_ms: 0, end_ms: 4000 },
+            SponsorSegment { category: "sponsor".into(), start_ms: 12_500, end_ms: 30_000 },
+        ]);
+
+        let line = |start_ms, text: &str| TranscriptSegment { start_ms, duration_ms: 2000, text: text.into() };
+        let lines = [line(1000, "welcome"), line(5000, "today"), line(13_000, "this video is sponsored"), line(29_500, "back to it")];
+        let kept: Vec<String> = strip_segments(&lines, &skips).into_iter().map(|l| l.text).collect();
+        assert_eq!(kept, ["today", "back to it"]);
+    }
+}
@@ -44,7 +44,7 @@ pub fn extract_handle_from_url(url: &str) -> Option<String> {
     None
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct Video {
     pub id: String,
     pub title: String,
@@ -62,6 +62,79 @@ pub struct Video {
     pub length_seconds: Option<i32>,
     #[serde(rename = "videoType")]
     pub video_type: Option<String>,
+    /// How the video entered the library: `manual`, `bulk` (from search
+    /// results), or `channel:<query>` / `playlist:<id>` when saved from one.
+    pub source: Option<String>,
+    /// When the video was marked watched; `None` while unwatched.
+    #[serde(rename = "watchedAt")]
+    pub watched_at: Option<String>,
+    #[serde(default)]
+    pub starred: bool,
+    /// Words in the saved transcript; `None` until counted.
+    #[serde(rename = "wordCount", default)]
+    pub word_count: Option<i64>,
+    /// Estimated minutes to read the transcript.
+    #[serde(rename = "readingMinutes", default)]
+    pub reading_minutes: Option<i64>,
+    /// Likes and comments as of when the video was fetched or saved.
+    #[serde(rename = "likeCount", default)]
+    pub like_count: Option<i64>,
+    #[serde(rename = "commentCount", default)]
+    pub comment_count: Option<i64>,
+    /// The uploader's own title when `title` is a DeArrow replacement.
+    #[serde(rename = "originalTitle", default)]
+    pub original_title: Option<String>,
+    /// Length as YouTube shows it on a result's thumbnail ("12:34"), for
+    /// videos listed in search and playlist results.
+    #[serde(default)]
+    pub duration: Option<String>,
+    /// Streaming now, or a stream or premiere yet to start. Neither has a
+    /// transcript to save until it ends.
+    #[serde(rename = "isLive", default)]
+    pub is_live: bool,
+    #[serde(rename = "isUpcoming", default)]
+    pub is_upcoming: bool,
+    /// When an upcoming stream or premiere starts, in Unix seconds.
+    #[serde(rename = "scheduledStart", default)]
+    pub scheduled_start: Option<i64>,
+    /// Whether the saved transcript came from YouTube's automatic captions;
+    /// `None` for videos saved before this was recorded.
+    #[serde(rename = "captionsAutoGenerated", default)]
+    pub captions_auto_generated: Option<bool>,
+    /// Visibility when the video was fetched or saved, and whether it was
+    /// streamed live. `None` for videos saved before these were recorded.
+    #[serde(rename = "isPrivate", default)]
+    pub is_private: Option<bool>,
+    #[serde(rename = "isUnlisted", default)]
+    pub is_unlisted: Option<bool>,
+    #[serde(rename = "isLiveContent", default)]
+    pub is_live_content: Option<bool>,
+    /// `available`, `private` or `removed` as of the last `verify_library`;
+    /// `None` until a video has been checked.
+    #[serde(default)]
+    pub availability: Option<String>,
+    /// IDs of the collections the video is in, for library listings.
+    #[serde(default)]
+    pub collections: Vec<i64>,
+}
+
+/// A past, current or upcoming stream from a channel's Live tab.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveStream {
+    #[serde(flatten)]
+    pub video: Video,
+    /// `live`, `upcoming` or `ended`.
+    pub live_status: String,
+    /// When an upcoming stream or premiere starts, in Unix seconds.
+    pub scheduled_start: Option<i64>,
+    pub is_premiere: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LiveStreamResponse {
+    pub streams: Vec<LiveStream>,
+    pub continuation: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -72,10 +145,388 @@ pub struct ChannelInfo {
     pub channel_name: String,
 }
 
+/// A channel as its page describes it.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelDetails {
+    pub channel_id: String,
+    pub name: String,
+    pub handle: Option<String>,
+    pub description: String,
+    pub avatar: Option<String>,
+    pub banner: Option<String>,
+    /// Rounded the way YouTube shows it, e.g. 1.2M.
+    pub subscriber_count: Option<i64>,
+    pub video_count: Option<i64>,
+}
+
+/// What a playlist is, before its videos are fetched.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistDetails {
+    pub playlist_id: String,
+    pub title: String,
+    pub owner: Option<String>,
+    pub description: String,
+    pub video_count: Option<i64>,
+}
+
+/// One timed caption line of a transcript.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegment {
+    pub start_ms: i64,
+    pub duration_ms: i64,
+    pub text: String,
+}
+
+/// A tab of YouTube's trending page.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendingCategory {
+    /// Trending across every topic.
+    #[default]
+    Now,
+    Music,
+    Gaming,
+    Movies,
+}
+
+/// Sizes of the thumbnails YouTube serves for every video, largest first.
+/// `maxres` (1280x720) and `sd` (640x480) are missing for some videos.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailQuality {
+    Maxres,
+    Sd,
+    /// 480x360; exists for every video.
+    #[default]
+    Hq,
+    Mq,
+}
+
+/// How recently a search result was uploaded.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadDate {
+    LastHour,
+    Today,
+    ThisWeek,
+    ThisMonth,
+    ThisYear,
+}
+
+/// YouTube's length buckets for search results.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchDuration {
+    /// Under 4 minutes.
+    Short,
+    /// 4 to 20 minutes.
+    Medium,
+    /// Over 20 minutes.
+    Long,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSort {
+    Relevance,
+    UploadDate,
+    ViewCount,
+    Rating,
+}
+
+/// Narrows a YouTube search. Every field is optional; none means YouTube's
+/// own relevance ranking over everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilters {
+    pub upload_date: Option<UploadDate>,
+    pub duration: Option<SearchDuration>,
+    pub sort: Option<SearchSort>,
+}
+
+/// Shape `fetch_transcript` returns a transcript in.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptFormat {
+    /// Caption lines joined with newlines.
+    #[default]
+    Plain,
+    /// Each line with its start and duration.
+    Segments,
+    /// A SubRip subtitle file.
+    Srt,
+}
+
+/// A caption track a video offers, as listed by its player.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptionTrack {
+    pub language_code: String,
+    /// As YouTube names it, e.g. `English (auto-generated)`.
+    pub name: String,
+    /// Speech recognition rather than uploaded captions.
+    pub auto_generated: bool,
+    /// Whether YouTube can translate it into other languages.
+    pub translatable: bool,
+}
+
+/// A transcript saved for a video, identified by its caption language.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TranscriptTrack {
+    pub language: String,
+    /// The transcript used for search and summaries, saved with the video.
+    pub main: bool,
+}
+
+/// Subtitle file format for `export_captions`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptionFormat {
+    Srt,
+    Vtt,
+}
+
+/// A transcript as text (plain or SRT) or as timed segments.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TranscriptOutput {
+    Text(String),
+    Segments(Vec<TranscriptSegment>),
+}
+
+/// A chapter marker within a video.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    pub title: String,
+    pub start_ms: i64,
+}
+
+/// How much one stretch of a video is replayed, from YouTube's "most replayed"
+/// graph. `intensity` runs from 0 to 1, where 1 is the most replayed stretch.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatmapPoint {
+    pub start_ms: i64,
+    pub duration_ms: i64,
+    pub intensity: f64,
+}
+
+/// Preview frames for scrubbing, tiled `columns` by `rows` onto each image in
+/// `sheets`. Frame `i` is on sheet `i / (columns * rows)` and shows the video
+/// at `i * interval_ms`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Storyboard {
+    /// Size of one frame in pixels.
+    pub width: i64,
+    pub height: i64,
+    pub count: i64,
+    pub columns: i64,
+    pub rows: i64,
+    pub interval_ms: i64,
+    pub sheets: Vec<String>,
+}
+
+/// One line of lyrics synced to the song.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LyricLine {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+}
+
+/// A song's lyrics from YouTube Music. `lines` is empty when they aren't synced.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Lyrics {
+    pub text: String,
+    pub lines: Vec<LyricLine>,
+    /// The credit YouTube Music shows, e.g. "Source: LyricFind".
+    pub source: Option<String>,
+}
+
+/// What YouTube Music knows about a video that is a song.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Song {
+    pub video_id: String,
+    pub title: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub year: Option<i32>,
+    pub length_seconds: Option<i32>,
+    pub lyrics: Option<Lyrics>,
+}
+
+/// A saved video's audio or video, downloaded into the media folder.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaFile {
+    pub video_id: String,
+    /// `audio` or `video`.
+    pub kind: String,
+    pub path: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub downloaded_at: String,
+}
+
+/// A video stream offered for download, as the quality picker lists it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoFormat {
+    pub itag: i64,
+    pub mime_type: String,
+    /// YouTube's label, e.g. "1080p60".
+    pub quality_label: Option<String>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub fps: Option<i64>,
+    pub bitrate: i64,
+    pub content_length: Option<u64>,
+    /// False for adaptive streams, whose sound is downloaded separately.
+    pub has_audio: bool,
+}
+
+/// A stretch of a video SponsorBlock users marked as skippable.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorSegment {
+    /// `sponsor`, `selfpromo`, `interaction`, `intro` or `outro`.
+    pub category: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+/// A top-level comment on a video.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: String,
+    pub author: String,
+    pub text: String,
+    pub like_count: i64,
+    pub reply_count: i64,
+    /// As YouTube words it, e.g. `3 days ago`.
+    pub published: String,
+}
+
+/// One answer in a community post's poll.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PollChoice {
+    pub text: String,
+    /// Only shown once the poll has closed or been voted in.
+    pub vote_percentage: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Poll {
+    pub choices: Vec<PollChoice>,
+    /// As YouTube words it, e.g. `1.2K votes`.
+    pub total_votes: Option<String>,
+}
+
+/// A post from a channel's Community tab.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommunityPost {
+    pub id: String,
+    pub channel_id: String,
+    pub author: String,
+    pub text: String,
+    /// As YouTube words it, e.g. `3 days ago`.
+    pub published: String,
+    pub like_count: i64,
+    /// Full-size image URLs, for image and multi-image posts.
+    pub images: Vec<String>,
+    pub poll: Option<Poll>,
+    /// The video a post shares, if any.
+    pub video_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommunityPage {
+    pub posts: Vec<CommunityPost>,
+    pub continuation: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentPage {
+    pub comments: Vec<Comment>,
+    pub continuation: Option<String>,
+}
+
+/// A creator with saved videos in the library.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Channel {
+    pub channel_id: String,
+    pub name: String,
+    pub handle: Option<String>,
+    pub avatar: Option<String>,
+    pub video_count: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VideoResponse {
     pub videos: Vec<Video>,
     pub continuation: Option<String>,
+    /// Where the videos came from: `innertube`, `rss`, `data_api` or `library`.
+    pub source: String,
+}
+
+/// A channel found by a search.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelResult {
+    pub channel_id: String,
+    pub title: String,
+    pub handle: Option<String>,
+    pub thumbnail: String,
+    /// As YouTube words it, e.g. "1.2M subscribers".
+    pub subscribers: Option<String>,
+    pub description: Option<String>,
+}
+
+/// A playlist found by a search.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistResult {
+    pub playlist_id: String,
+    pub title: String,
+    pub author: Option<String>,
+    pub thumbnail: String,
+    pub video_count: Option<i64>,
+}
+
+/// One entry in a search, tagged with its `kind`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SearchResult {
+    Video(Video),
+    Channel(ChannelResult),
+    Playlist(PlaylistResult),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaylistPage {
+    pub playlists: Vec<PlaylistResult>,
+    pub continuation: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchPage {
+    pub results: Vec<SearchResult>,
+    pub continuation: Option<String>,
+    /// Where the results came from: `innertube` or `data_api`.
+    pub source: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -87,10 +538,260 @@ pub struct DisplaySettings {
     pub video_list_mode: String,
 }
 
+/// Interface language (`hl`, e.g. `en` or `pt-BR`) and content region (`gl`,
+/// e.g. `US`) that YouTube localizes titles, dates and captions for.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Locale {
+    pub hl: String,
+    pub gl: String,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self { hl: "en".to_string(), gl: "US".to_string() }
+    }
+}
+
+/// The version an InnerTube client claims, and where it came from: `manual`,
+/// `fetched` from YouTube, or `built_in`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClientVersion {
+    pub client: String,
+    pub version: String,
+    pub source: String,
+    /// Whether the version is looked up from YouTube at startup. The others
+    /// (the Android, iOS and TV apps) can only be set by hand.
+    pub refreshed: bool,
+}
+
+/// Whether YouTube cookies were imported, and whether requests use them.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CookieStatus {
+    pub imported: bool,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSettings {
+    /// Hours between automatic backups; 0 turns them off.
+    pub interval_hours: u32,
+    /// How many backups to keep before the oldest are deleted.
+    pub keep: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub path: String,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+/// Which saved videos `bulk_delete_videos` should trash. Criteria combine with
+/// AND; at least one must be set.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BulkDeleteFilter {
+    /// Matches the author name or channel handle, case-insensitively.
+    pub author: Option<String>,
+    pub tag: Option<String>,
+    /// `YYYY-MM-DD`; only videos saved before this date.
+    pub added_before: Option<String>,
+}
+
+/// A named library with its own database file.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub name: String,
+    pub active: bool,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailCacheStats {
+    pub count: i64,
+    pub size_bytes: i64,
+}
+
+/// What `undo_last_operation` brought back.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoReport {
+    /// `delete` or `bulk_delete`.
+    pub operation: String,
+    pub video_ids: Vec<String>,
+}
+
+/// Sort key for the saved-video list.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VideoSort {
+    #[default]
+    DateAdded,
+    PublishedAt,
+    Title,
+    ViewCount,
+    Length,
+    WordCount,
+}
+
+/// Filters and ordering for the saved-video list. Every field is optional.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VideoQuery {
+    pub video_type: Option<String>,
+    pub source: Option<String>,
+    pub channel_id: Option<String>,
+    pub collection_id: Option<i64>,
+    /// Matches the author name or channel handle, case-insensitively.
+    pub author: Option<String>,
+    pub tag: Option<String>,
+    /// YouTube's category for the video, such as `Education`.
+    pub category: Option<String>,
+    /// One of the uploader's keywords, matched whole and case-insensitively.
+    pub keyword: Option<String>,
+    pub has_note: Option<bool>,
+    /// Only videos `verify_library` found private or removed, or only the rest.
+    pub unavailable: Option<bool>,
+    pub watched: Option<bool>,
+    pub starred: Option<bool>,
+    /// Inclusive `YYYY-MM-DD` bounds on the date a video was saved.
+    pub added_from: Option<String>,
+    pub added_to: Option<String>,
+    /// Inclusive `YYYY-MM-DD` bounds on the upload date.
+    pub published_from: Option<String>,
+    pub published_to: Option<String>,
+    pub sort: VideoSort,
+    /// Defaults to descending, except for titles which sort A to Z.
+    pub descending: Option<bool>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DbDetails {
     pub path: String,
     pub size_bytes: u64,
     pub video_count: i64,
     pub history_count: i64,
+    /// Bytes taken by stored transcripts, compressed where they are.
+    pub transcript_bytes: i64,
+    pub segment_count: i64,
+    /// On-disk size of every table and index, largest first.
+    pub tables: Vec<TableSize>,
+    pub top_channels: Vec<ChannelSize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableSize {
+    pub name: String,
+    pub size_bytes: i64,
+}
+
+/// Text stored for one creator's videos: transcripts, segments, summaries,
+/// descriptions and notes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelSize {
+    pub name: String,
+    pub video_count: i64,
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BulkSaveFailure {
+    pub id: String,
+    pub code: String,
+    pub message: String,
+}
+
+/// Outcome of a bulk save: newly saved, already in the library, and failures.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct BulkSaveReport {
+    pub saved: Vec<Video>,
+    pub skipped: Vec<Video>,
+    pub failed: Vec<BulkSaveFailure>,
+}
+
+/// Outcome of `verify_library`: how many videos were checked, those now
+/// private or removed, and how many couldn't be checked.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct LibraryCheckReport {
+    pub checked: usize,
+    pub unavailable: Vec<Video>,
+    pub failed: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranscriptMatch {
+    pub video: Video,
+    pub snippet: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrashedVideo {
+    pub video: Video,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VideoDetails {
+    pub video: Video,
+    /// The uploader's description, including any links and chapter list.
+    pub description: Option<String>,
+    /// Language code of the saved transcript's captions, if known.
+    #[serde(rename = "transcriptLanguage")]
+    pub transcript_language: Option<String>,
+    /// The uploader's keywords (tags) and YouTube's category for the video.
+    pub keywords: Vec<String>,
+    pub category: Option<String>,
+    /// A Wayback Machine capture of the watch page, once `find_archived_copy`
+    /// has found one.
+    #[serde(rename = "archiveUrl")]
+    pub archive_url: Option<String>,
+}
+
+/// Outcome of a library import, counted per video.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ImportReport {
+    pub added: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    /// Saved here but in the trash. They stay there; restore them to use them.
+    pub trashed: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    /// False while an encrypted database is waiting for its passphrase.
+    pub unlocked: bool,
+}
+
+/// Result of compressing stored transcripts.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressionReport {
+    pub videos: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub video_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    pub created_at: String,
+    pub video_count: i64,
 }
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::AppHandle;
-use crate::{db, get_db_path};
+use crate::{db, with_db};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VeniceMessage {
@@ -16,14 +16,11 @@ pub struct VeniceRequest {
 }
 
 pub async fn summarize_transcript(app: AppHandle, transcript: String) -> Result<String, String> {
-    let db_path = get_db_path(&app);
     
-    let api_key = db::get_setting(&db_path, "venice_api_key")
-        .map_err(|e| e.to_string())?
+    let api_key = with_db(&app, |c| db::get_setting(c, "venice_api_key"))?
         .ok_or("Venice API key not found. Please set it in Settings.")?;
         
-    let prompt_template = db::get_setting(&db_path, "venice_prompt")
-        .map_err(|e| e.to_string())?
+    let prompt_template = with_db(&app, |c| db::get_setting(c, "venice_prompt"))?
         .unwrap_or_else(|| "Create a synopsis of this video transcript with pretty format.".to_string());
 
     let prompt = if prompt_template.contains("{}") {
@@ -0,0 +1,50 @@
+use serde_json::Value;
+use crate::youtube;
+
+/// The URL of the Internet Archive's latest capture of a video's watch page,
+/// or `None` if it never captured one.
+pub async fn latest_snapshot(video_id: &str) -> Result<Option<String>, String> {
+    let page = format!("youtube.com/watch?v={}", video_id);
+    let url = format!(
+        "https://web.archive.org/cdx/search/cdx?url={}&output=json&fl=timestamp,original&filter=statuscode:200&limit=-1",
+        urlencoding::encode(&page)
+    );
+    let response = youtube::http_client().get(url).send().await
+        .map_err(|e| format!("Wayback Machine request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Wayback Machine error: {}", response.status()));
+    }
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    // No captures at all comes back as an empty body rather than JSON
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    let data: Value = serde_json::from_str(&text).map_err(|e| format!("Failed to parse Wayback Machine response: {}", e))?;
+    Ok(parse_snapshot(&data))
+}
+
+/// The snapshot URL from a CDX answer: a header row, then one row of
+/// `[timestamp, original]` per capture, oldest first.
+fn parse_snapshot(data: &Value) -> Option<String> {
+    let row = data.as_array()?.iter().skip(1).last()?;
+    let timestamp = row.get(0)?.as_str()?;
+    let original = row.get(1)?.as_str()?;
+    Some(format!("https://web.archive.org/web/{}/{}", timestamp, original))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latest_capture_becomes_a_snapshot_url() {
+        let data = serde_json::json!([
+            ["timestamp", "original"],
+            ["20190301120000", "https://www.youtube.com/watch?v=abc"],
+            ["20230704093015", "https://www.youtube.com/watch?v=abc"]
+        ]);
+        assert_eq!(parse_snapshot(&data).as_deref(), Some("https://web.archive.org/web/20230704093015/https://www.youtube.com/watch?v=abc"));
+        assert_eq!(parse_snapshot(&serde_json::json!([["timestamp", "original"]])), None);
+        assert_eq!(parse_snapshot(&serde_json::json!([])), None);
+    }
+}
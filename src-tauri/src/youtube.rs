@@ -1,29 +1,468 @@
 use serde_json::Value;
 use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT, CONTENT_TYPE};
 use html_escape;
+use crate::types::{CaptionTrack, ChannelDetails, ChannelResult, Chapter, Comment, CommunityPost, HeatmapPoint, Poll, PollChoice, Locale, PlaylistDetails, PlaylistResult, SearchDuration, SearchFilters, SearchSort, Storyboard, ThumbnailQuality, TranscriptSegment, TrendingCategory, UploadDate};
+
+pub mod cache;
+pub mod models;
+pub mod music;
+pub mod throttle;
+pub mod url;
+
+pub use cache::ResponseCache;
+pub use url::ParsedTarget;
 
 /// Decode HTML entities in a string (e.g., &amp; -> &, &#39; -> ')
 fn decode_html(text: &str) -> String {
     html_escape::decode_html_entities(text).to_string()
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClientType {
     Web,
     Android,
+    Ios,
+    /// The embedded player used by smart TVs, which is served some videos
+    /// (age-gated ones especially) that other clients are refused.
+    TvEmbedded,
+    /// YouTube Music's web app, which knows songs' albums and lyrics.
+    Music,
+}
+
+impl ClientType {
+    /// Clients to ask for captions, in turn; some videos only offer them to one.
+    pub const CAPTION_FALLBACKS: [ClientType; 4] = [ClientType::Android, ClientType::Ios, ClientType::TvEmbedded, ClientType::Web];
+    pub const ALL: [ClientType; 5] = [ClientType::Web, ClientType::Android, ClientType::Ios, ClientType::TvEmbedded, ClientType::Music];
+
+    /// The name settings and the UI know this client by.
+    pub fn name(self) -> &'static str {
+        match self {
+            ClientType::Web => "web",
+            ClientType::Android => "android",
+            ClientType::Ios => "ios",
+            ClientType::TvEmbedded => "tv_embedded",
+            ClientType::Music => "music",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    /// The version claimed when no other is known. These go stale, and
+    /// YouTube starts refusing versions it considers too old.
+    pub fn built_in_version(self) -> &'static str {
+        match self {
+            ClientType::Web => "2.20230301.09.00",
+            ClientType::Android => "21.02.35",
+            ClientType::Ios => "20.10.4",
+            ClientType::TvEmbedded => "2.0",
+            ClientType::Music => "1.20240403.01.00",
+        }
+    }
+
+    /// Whether the version can be looked up from YouTube: the web and Music
+    /// clients' pages carry it, while the apps' versions are only published
+    /// in app stores and are set by hand when they go stale.
+    pub fn fetches_version(self) -> bool {
+        matches!(self, ClientType::Web | ClientType::Music)
+    }
+
+    /// Where this client's InnerTube requests go.
+    fn origin(self) -> &'static str {
+        match self {
+            ClientType::Music => "https://music.youtube.com",
+            _ => YOUTUBE_ORIGIN,
+        }
+    }
+}
+
+/// Client versions claimed in place of the built-in ones, whether fetched
+/// from YouTube or set by hand.
+static CLIENT_VERSIONS: std::sync::RwLock<Vec<(ClientType, String)>> = std::sync::RwLock::new(Vec::new());
+
+/// Claim `version` for `client_type` from now on, or the built-in version
+/// again when it is `None`.
+pub fn set_client_version(client_type: ClientType, version: Option<&str>) {
+    let mut versions = CLIENT_VERSIONS.write().unwrap();
+    versions.retain(|(c, _)| *c != client_type);
+    if let Some(version) = version.map(str::trim).filter(|v| !v.is_empty()) {
+        versions.push((client_type, version.to_string()));
+    }
+}
+
+/// The version `client_type` currently claims.
+pub fn client_version(client_type: ClientType) -> String {
+    CLIENT_VERSIONS.read().unwrap()
+        .iter()
+        .find(|(c, _)| *c == client_type)
+        .map(|(_, v)| v.clone())
+        .unwrap_or_else(|| client_type.built_in_version().to_string())
+}
+
+/// The version YouTube's own pages send for `client_type` right now, read
+/// from the configuration embedded in its home page. `None` for the clients
+/// that can't be looked up this way (see `ClientType::fetches_version`).
+pub async fn fetch_client_version(client_type: ClientType) -> Result<Option<String>, String> {
+    if !client_type.fetches_version() {
+        return Ok(None);
+    }
+    throttle::wait().await;
+    let text = http_client().get(client_type.origin())
+        .header(USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(parse_client_version(&text))
+}
+
+fn parse_client_version(html: &str) -> Option<String> {
+    let re = regex::Regex::new(r#""INNERTUBE_(?:CONTEXT_)?CLIENT_VERSION":"(\d+\.\d{8}\.[\d.]+)""#).unwrap();
+    re.captures(html).map(|caps| caps[1].to_string())
+}
+
+/// Tries per InnerTube request unless the client is told otherwise.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// A failed request, and whether trying it again might help.
+enum RequestError {
+    Retryable(String),
+    Fatal(String),
+}
+
+/// How long to wait before retry number `attempt`: 500ms doubling each time,
+/// capped at 8s, plus up to a quarter more so clients don't retry in step.
+fn backoff_delay(attempt: u32) -> std::time::Duration {
+    let base = 500u64.saturating_mul(1 << attempt.saturating_sub(1).min(4));
+    std::time::Duration::from_millis(base) + throttle::jitter(std::time::Duration::from_millis(base / 4))
+}
+
+const YOUTUBE_ORIGIN: &str = "https://www.youtube.com";
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// The `Authorization` header InnerTube expects from a signed-in browser,
+/// derived from the `SAPISID` cookie and the `origin` requests go to. `None`
+/// when the cookies lack one.
+fn sapisid_hash(cookies: &str, timestamp: u64, origin: &str) -> Option<String> {
+    use sha1::{Digest, Sha1};
+    let sapisid = sapisid(cookies)?;
+    let hash = Sha1::digest(format!("{} {} {}", timestamp, sapisid, origin));
+    let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+    Some(format!("SAPISIDHASH {}_{}", timestamp, hex))
+}
+
+fn sapisid(cookies: &str) -> Option<&str> {
+    cookies.split(';')
+        .filter_map(|c| c.trim().split_once('='))
+        .find(|(name, _)| *name == "SAPISID" || *name == "__Secure-3PAPISID")
+        .map(|(_, value)| value)
+}
+
+/// A short digest naming the account `cookies` sign in as, so cached responses
+/// for one account are never served to another. Falls back to the whole
+/// header when there is no `SAPISID`.
+fn account_digest(cookies: &str) -> String {
+    use sha1::{Digest, Sha1};
+    let hash = Sha1::digest(sapisid(cookies).unwrap_or(cookies));
+    hash.iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Turn an exported `cookies.txt` (Netscape format) or a pasted `Cookie`
+/// header into a `Cookie` header holding just the YouTube and Google cookies.
+/// Expired cookies are dropped. Returns the header and how many cookies it has,
+/// or `None` if there were none.
+pub fn parse_cookies(text: &str) -> Option<(String, usize)> {
+    let now = unix_now();
+    let mut pairs: Vec<(String, String)> = Vec::new();
+    let mut push = |name: &str, value: &str| {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        pairs.retain(|(n, _)| n != name);
+        pairs.push((name.to_string(), value.trim().to_string()));
+    };
+
+    if text.contains('\t') {
+        for line in text.lines() {
+            // curl marks HttpOnly cookies with a prefix on an otherwise commented line
+            let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+            if line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 7 {
+                continue;
+            }
+            let domain = fields[0].trim_start_matches('.');
+            if !(domain.ends_with("youtube.com") || domain.ends_with("google.com")) {
+                continue;
+            }
+            let expires = fields[4].trim().parse::<u64>().unwrap_or(0);
+            if expires != 0 && expires < now {
+                continue;
+            }
+            push(fields[5], fields[6]);
+        }
+    } else {
+        let header = text.trim();
+        let header = header.strip_prefix("Cookie:").unwrap_or(header);
+        for cookie in header.split(';') {
+            if let Some((name, value)) = cookie.split_once('=') {
+                push(name, value);
+            }
+        }
+    }
+
+    if pairs.is_empty() {
+        return None;
+    }
+    let header = pairs.iter().map(|(n, v)| format!("{}={}", n, v)).collect::<Vec<_>>().join("; ");
+    Some((header, pairs.len()))
+}
+
+/// Proxy that requests to YouTube go through, from the `proxy_url` setting.
+static PROXY: std::sync::RwLock<Option<String>> = std::sync::RwLock::new(None);
+
+/// Route requests to YouTube through `url` (`http://`, `https://` or
+/// `socks5://`, optionally with credentials), or connect directly when `None`.
+pub fn set_proxy(url: Option<&str>) -> Result<(), String> {
+    let url = url.map(str::trim).filter(|u| !u.is_empty());
+    if let Some(u) = url {
+        reqwest::Proxy::all(u).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    }
+    *PROXY.write().unwrap() = url.map(String::from);
+    Ok(())
+}
+
+/// The client `http_client` hands out, with the proxy it was built for.
+/// Clones share its connection pool, so connections and TLS sessions to
+/// YouTube are reused from one request to the next.
+static HTTP_CLIENT: std::sync::RwLock<Option<(Option<String>, reqwest::Client)>> = std::sync::RwLock::new(None);
+
+/// An HTTP client for YouTube and its APIs, going through the proxy if one is
+/// set. Every caller shares one client until the proxy changes.
+pub fn http_client() -> reqwest::Client {
+    let proxy = PROXY.read().unwrap().clone();
+    if let Some((built_for, client)) = HTTP_CLIENT.read().unwrap().as_ref() {
+        if *built_for == proxy {
+            return client.clone();
+        }
+    }
+
+    let mut builder = reqwest::Client::builder();
+    if let Some(url) = proxy.as_deref() {
+        builder = builder.proxy(reqwest::Proxy::all(url).expect("proxy URL was validated"));
+    }
+    let client = builder.build().unwrap_or_else(|e| {
+        log::warn!("Failed to build HTTP client: {}", e);
+        reqwest::Client::new()
+    });
+    *HTTP_CLIENT.write().unwrap() = Some((proxy, client.clone()));
+    client
+}
+
+/// The visitor ID YouTube issued this session, shared by every client so their
+/// requests read as coming from one browser.
+static VISITOR_DATA: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+/// Set once a visitor ID has been asked for, so a failed lookup isn't retried
+/// before every request.
+static VISITOR_DATA_REQUESTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+fn remember_visitor_data(visitor_data: &str) {
+    let mut cached = VISITOR_DATA.lock().unwrap();
+    if cached.is_none() {
+        *cached = Some(visitor_data.to_string());
+    }
 }
 
 pub struct YouTubeClient {
     client: reqwest::Client,
     client_type: ClientType,
+    locale: Locale,
+    /// Sent as `visitorData`; fetched on first use when not known yet.
+    visitor_data: Option<String>,
+    /// Proof-of-origin token sent with player requests.
+    po_token: Option<String>,
+    /// Tries per request before a retryable failure is given up on.
+    max_attempts: u32,
+    cache: ResponseCache,
+    /// A `Cookie` header for a signed-in session, from `parse_cookies`.
+    cookies: Option<String>,
 }
 
 impl YouTubeClient {
-    pub fn new(client_type: ClientType) -> Self {
+    /// A client that asks for titles, dates and captions localized for `locale`.
+    pub fn new(client_type: ClientType, locale: Locale) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: http_client(),
             client_type,
+            locale,
+            visitor_data: VISITOR_DATA.lock().unwrap().clone(),
+            po_token: None,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            cache: ResponseCache::disabled(),
+            cookies: None,
+        }
+    }
+
+    /// Make requests as the account `cookies` (a `Cookie` header) belong to,
+    /// so private playlists and members-only videos it can see are returned.
+    pub fn with_cookies(mut self, cookies: Option<String>) -> Self {
+        self.cookies = cookies.filter(|c| !c.trim().is_empty());
+        self
+    }
+
+    /// Serve repeated search, browse and next requests from `cache`. Player
+    /// responses are never cached, as the stream and caption URLs in them expire.
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Try each request up to `max_attempts` times (at least once) when it
+    /// fails in a way worth retrying.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Use `po_token` for player requests; blank tokens are ignored.
+    pub fn with_po_token(mut self, po_token: Option<String>) -> Self {
+        self.po_token = po_token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        self
+    }
+
+    /// This client's visitor ID, or the session's, asking YouTube for one the
+    /// first time it is needed. `None` if it couldn't be had; requests still go
+    /// out without it.
+    async fn visitor_data(&self) -> Option<String> {
+        if let Some(v) = &self.visitor_data {
+            return Some(v.clone());
+        }
+        if let Some(v) = VISITOR_DATA.lock().unwrap().clone() {
+            return Some(v);
+        }
+        if VISITOR_DATA_REQUESTED.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            return None;
+        }
+        match fetch_visitor_data(&self.client).await {
+            Ok(Some(v)) => {
+                remember_visitor_data(&v);
+                Some(v)
+            }
+            Ok(None) => {
+                log::warn!("No visitor ID in YouTube's service worker data");
+                None
+            }
+            Err(e) => {
+                log::warn!("Failed to fetch a visitor ID: {}", e);
+                None
+            }
+        }
+    }
+
+    /// POST `body` to an InnerTube endpoint with the visitor ID filled in, or
+    /// answer from the cache when the same request was made recently.
+    async fn post(&self, endpoint: &str, mut body: Value) -> Result<Value, String> {
+        // Signed-in responses may hold private playlists and members-only videos:
+        // key them by account and keep them off the disk
+        let (cache_endpoint, cache) = match &self.cookies {
+            Some(cookies) => (format!("{}#signed-in:{}", endpoint, account_digest(cookies)), self.cache.memory_only()),
+            None => (endpoint.to_string(), self.cache.clone()),
+        };
+        let cache_key = (endpoint != "player").then(|| ResponseCache::key(&cache_endpoint, &body));
+        if let Some(cached) = cache_key.and_then(|key| cache.get(key)) {
+            return Ok(cached);
+        }
+
+        let mut headers = self.get_headers();
+        if let Some(visitor_data) = self.visitor_data().await {
+            if let Ok(value) = HeaderValue::from_str(&visitor_data) {
+                headers.insert("X-Goog-Visitor-Id", value);
+            }
+            body["context"]["client"]["visitorData"] = serde_json::json!(visitor_data);
+        }
+        if let Some(cookies) = &self.cookies {
+            if let Ok(value) = HeaderValue::from_str(cookies) {
+                headers.insert(reqwest::header::COOKIE, value);
+            }
+            let origin = self.client_type.origin();
+            if let Some(auth) = sapisid_hash(cookies, unix_now(), origin) {
+                if let Ok(value) = HeaderValue::from_str(&auth) {
+                    headers.insert(reqwest::header::AUTHORIZATION, value);
+                }
+                headers.insert("X-Origin", HeaderValue::from_static(origin));
+                headers.insert("X-Goog-AuthUser", HeaderValue::from_static("0"));
+            }
+        }
+
+        let url = format!("{}/youtubei/v1/{}", self.client_type.origin(), endpoint);
+        let mut attempt = 1;
+        let data = loop {
+            match self.send(&url, &headers, &body).await {
+                Ok(data) => break data,
+                Err(RequestError::Retryable(e)) if attempt < self.max_attempts => {
+                    let delay = backoff_delay(attempt);
+                    log::warn!("{} request failed ({}); retrying in {:?}", endpoint, e, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(RequestError::Retryable(e)) => return Err(format!("{} (gave up after {} attempts)", e, attempt)),
+                Err(RequestError::Fatal(e)) => return Err(e),
+            }
+        };
+        // Any response will do if the lookup failed
+        if let Some(v) = data["responseContext"]["visitorData"].as_str() {
+            remember_visitor_data(v);
         }
+        if let Some(key) = cache_key.filter(|_| data.get("error").is_none()) {
+            cache.put(key, &data);
+        }
+        Ok(data)
+    }
+
+    /// One try at a request, sorting failures into those worth repeating
+    /// (timeouts, failed connections, 5xx and 429 responses) and the rest.
+    /// A request that couldn't be built fails the same way every time.
+    async fn send(&self, url: &str, headers: &HeaderMap, body: &Value) -> Result<Value, RequestError> {
+        throttle::wait().await;
+        let res = self.client.post(url)
+            .headers(headers.clone())
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_timeout() || e.is_connect() {
+                    RequestError::Retryable(e.to_string())
+                } else {
+                    RequestError::Fatal(e.to_string())
+                }
+            })?;
+
+        let status = res.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            throttle::cool_down("YouTube returned 429 Too Many Requests");
+        }
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(RequestError::Retryable(format!("YouTube returned {}", status)));
+        }
+        if !status.is_success() {
+            return Err(RequestError::Fatal(format!("YouTube returned {}", status)));
+        }
+        // A body cut off mid-transfer is a network failure; one that isn't JSON is not
+        let bytes = res.bytes().await.map_err(|e| RequestError::Retryable(e.to_string()))?;
+        let data: Value = serde_json::from_slice(&bytes).map_err(|e| RequestError::Fatal(e.to_string()))?;
+        if let Some(reason) = data["playabilityStatus"]["reason"].as_str().filter(|r| throttle::is_bot_check(r)) {
+            throttle::cool_down(reason);
+        }
+        Ok(data)
     }
 
     fn get_context(&self) -> Value {
@@ -33,9 +472,9 @@ impl YouTubeClient {
                     "context": {
                         "client": {
                             "clientName": "WEB",
-                            "clientVersion": "2.20230301.09.00",
-                            "hl": "en",
-                            "gl": "US",
+                            "clientVersion": client_version(ClientType::Web),
+                            "hl": self.locale.hl,
+                            "gl": self.locale.gl,
                             "utcOffsetMinutes": 0,
                         }
                     }
@@ -46,41 +485,104 @@ impl YouTubeClient {
                     "context": {
                         "client": {
                             "clientName": "ANDROID",
-                            "clientVersion": "21.02.35",
-                            "hl": "en",
-                            "gl": "US",
+                            "clientVersion": client_version(ClientType::Android),
+                            "hl": self.locale.hl,
+                            "gl": self.locale.gl,
                             "utcOffsetMinutes": 0,
                             "androidSdkVersion": 34,
                         }
                     }
                 })
             }
+            ClientType::Ios => {
+                serde_json::json!({
+                    "context": {
+                        "client": {
+                            "clientName": "IOS",
+                            "clientVersion": client_version(ClientType::Ios),
+                            "deviceMake": "Apple",
+                            "deviceModel": "iPhone16,2",
+                            "osName": "iPhone",
+                            "osVersion": "18.3.2.22D82",
+                            "hl": self.locale.hl,
+                            "gl": self.locale.gl,
+                            "utcOffsetMinutes": 0,
+                        }
+                    }
+                })
+            }
+            ClientType::TvEmbedded => {
+                serde_json::json!({
+                    "context": {
+                        "client": {
+                            "clientName": "TVHTML5_SIMPLY_EMBEDDED_PLAYER",
+                            "clientVersion": client_version(ClientType::TvEmbedded),
+                            "hl": self.locale.hl,
+                            "gl": self.locale.gl,
+                            "utcOffsetMinutes": 0,
+                        },
+                        "thirdParty": {
+                            "embedUrl": "https://www.youtube.com/",
+                        }
+                    }
+                })
+            }
+            ClientType::Music => {
+                serde_json::json!({
+                    "context": {
+                        "client": {
+                            "clientName": "WEB_REMIX",
+                            "clientVersion": client_version(ClientType::Music),
+                            "hl": self.locale.hl,
+                            "gl": self.locale.gl,
+                            "utcOffsetMinutes": 0,
+                        }
+                    }
+                })
+            }
         }
     }
 
     fn get_headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        // The apps name their version in the user agent, which has to agree with the context
         let ua = match self.client_type {
-            ClientType::Web => "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36",
-            ClientType::Android => "com.google.android.youtube/21.02.35 (Linux; U; Android 14; en_US) gzip",
+            ClientType::Web | ClientType::Music => "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36".to_string(),
+            ClientType::Android => format!("com.google.android.youtube/{} (Linux; U; Android 14; en_US) gzip", client_version(ClientType::Android)),
+            ClientType::Ios => format!("com.google.ios.youtube/{} (iPhone16,2; U; CPU iOS 18_3_2 like Mac OS X;)", client_version(ClientType::Ios)),
+            ClientType::TvEmbedded => "Mozilla/5.0 (PlayStation; PlayStation 4/12.00) AppleWebKit/605.1.15 (KHTML, like Gecko)".to_string(),
         };
-        headers.insert(USER_AGENT, HeaderValue::from_str(ua).unwrap());
+        if let Ok(value) = HeaderValue::from_str(&ua) {
+            headers.insert(USER_AGENT, value);
+        }
         headers
     }
 
-    pub async fn search(&self, query: &str) -> Result<Value, String> {
+    /// Search for `query`, or fetch the next page of an earlier search when a
+    /// continuation token is given (the token carries the query with it, and
+    /// any `filters`, which only apply to the first page).
+    pub async fn search(&self, query: &str, continuation: Option<String>, filters: &SearchFilters) -> Result<Value, String> {
         let mut body = self.get_context();
-        body["query"] = serde_json::json!(query);
+        match continuation {
+            Some(c) => body["continuation"] = serde_json::json!(c),
+            None => {
+                body["query"] = serde_json::json!(query);
+                if let Some(params) = search_params(filters) {
+                    body["params"] = serde_json::json!(params);
+                }
+            }
+        }
 
-        let res = self.client.post("https://www.youtube.com/youtubei/v1/search")
-            .headers(self.get_headers())
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        self.post("search", body).await
+    }
 
-        res.json::<Value>().await.map_err(|e| e.to_string())
+    /// Where a youtube.com URL leads, such as the browse endpoint of a
+    /// channel handle, without loading the page.
+    pub async fn resolve_url(&self, url: &str) -> Result<Value, String> {
+        let mut body = self.get_context();
+        body["url"] = serde_json::json!(url);
+        self.post("navigation/resolve_url", body).await
     }
 
     pub async fn browse(&self, browse_id: Option<String>, continuation: Option<String>) -> Result<Value, String> {
@@ -92,93 +594,516 @@ impl YouTubeClient {
             body["continuation"] = serde_json::json!(c);
         }
 
-        let res = self.client.post("https://www.youtube.com/youtubei/v1/browse")
-            .headers(self.get_headers())
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        self.post("browse", body).await
+    }
+
+    /// The watch page's data for a video: chapters, engagement panels and the
+    /// way into its comments. With a continuation token, the page of comments
+    /// it points to instead.
+    pub async fn next(&self, video_id: Option<&str>, continuation: Option<String>) -> Result<Value, String> {
+        let mut body = self.get_context();
+        if let Some(id) = video_id {
+            body["videoId"] = serde_json::json!(id);
+        }
+        if let Some(c) = continuation {
+            body["continuation"] = serde_json::json!(c);
+        }
+
+        self.post("next", body).await
+    }
+
+    /// The first page of a channel tab, opened directly on the channel rather
+    /// than through its uploads playlist.
+    pub async fn browse_channel_tab(&self, channel_id: &str, tab: ChannelTab) -> Result<Value, String> {
+        let mut body = self.get_context();
+        body["browseId"] = serde_json::json!(channel_id);
+        body["params"] = serde_json::json!(tab.params());
+
+        self.post("browse", body).await
+    }
+
+    /// The first page of videos tagged `#tag`. The page's browse params are
+    /// found by resolving the hashtag's URL.
+    pub async fn browse_hashtag(&self, tag: &str) -> Result<Value, String> {
+        let tag = tag.trim().trim_start_matches('#');
+        let resolved = self.resolve_url(&format!("https://www.youtube.com/hashtag/{}", urlencoding::encode(tag))).await?;
+        let (browse_id, params) = parse_resolved_browse(&resolved).ok_or("Hashtag not found")?;
+        let mut body = self.get_context();
+        body["browseId"] = serde_json::json!(browse_id);
+        body["params"] = serde_json::json!(params);
 
-        res.json::<Value>().await.map_err(|e| e.to_string())
+        self.post("browse", body).await
+    }
+
+    /// The trending page, on the tab for `category`.
+    pub async fn browse_trending(&self, category: TrendingCategory) -> Result<Value, String> {
+        let mut body = self.get_context();
+        body["browseId"] = serde_json::json!("FEtrending");
+        let params = match category {
+            TrendingCategory::Now => None,
+            TrendingCategory::Music => Some("4gINGgt5dG1hX2NoYXJ0cw=="),
+            TrendingCategory::Gaming => Some("4gIcGhpnYW1pbmdfY29ycHVzX21vc3RfcG9wdWxhcg=="),
+            TrendingCategory::Movies => Some("4gIKGgh0cmFpbGVycw=="),
+        };
+        if let Some(params) = params {
+            body["params"] = serde_json::json!(params);
+        }
+
+        self.post("browse", body).await
     }
 
     pub async fn player(&self, video_id: &str) -> Result<Value, String> {
         let mut body = self.get_context();
         body["videoId"] = serde_json::json!(video_id);
+        if let Some(token) = &self.po_token {
+            body["serviceIntegrityDimensions"] = serde_json::json!({ "poToken": token });
+        }
 
-        let res = self.client.post("https://www.youtube.com/youtubei/v1/player")
-            .headers(self.get_headers())
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        res.json::<Value>().await.map_err(|e| e.to_string())
+        self.post("player", body).await
     }
 }
 
-#[allow(dead_code)]
-pub fn extract_video_id(url_or_id: &str) -> String {
-    if url_or_id.contains("v=") {
-        let parts: Vec<&str> = url_or_id.split("v=").collect();
-        if parts.len() > 1 {
-            return parts[1].split('&').next().unwrap_or("").to_string();
+/// Ask YouTube for a visitor ID. The service worker's bootstrap data is the
+/// smallest response that carries one.
+async fn fetch_visitor_data(client: &reqwest::Client) -> Result<Option<String>, String> {
+    throttle::wait().await;
+    let text = client.get("https://www.youtube.com/sw.js_data")
+        .header(USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(parse_visitor_data(&text))
+}
+
+/// The visitor ID in `sw.js_data`, which is JSON behind an anti-hijacking prefix.
+fn parse_visitor_data(text: &str) -> Option<String> {
+    let json = text.trim_start().strip_prefix(")]}'").unwrap_or(text);
+    let data: Value = serde_json::from_str(json).ok()?;
+    data[0][2][0][0][13].as_str().filter(|v| !v.is_empty()).map(String::from)
+}
+
+/// Whether a player response was withheld because the video is age-restricted.
+/// The web and Android clients refuse these without a signed-in adult; the TV
+/// embedded client usually doesn't.
+pub fn is_age_restricted(player: &Value) -> bool {
+    let status = &player["playabilityStatus"];
+    match status["status"].as_str().unwrap_or("") {
+        "AGE_CHECK_REQUIRED" | "AGE_VERIFICATION_REQUIRED" => true,
+        "LOGIN_REQUIRED" | "UNPLAYABLE" | "CONTENT_CHECK_REQUIRED" => {
+            let reason = status["reason"].as_str().unwrap_or("").to_lowercase();
+            !status["desktopLegacyAgeGateReason"].is_null()
+                || reason.contains("age")
+                || reason.contains("inappropriate")
         }
+        _ => false,
+    }
+}
+
+/// Why YouTube won't play a video, read from a player response's
+/// `playabilityStatus`. Commands return its `Display` text as their error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unplayable {
+    Private,
+    /// Deleted, taken down, or its channel was terminated.
+    Removed(String),
+    RegionLocked,
+    /// Live now or not yet streamed, so there's nothing to transcribe.
+    LiveOnly,
+    AgeRestricted(String),
+    MembersOnly,
+    /// Refused for any other reason, such as a sign-in or bot check.
+    Other(String),
+}
+
+impl Unplayable {
+    /// Why `player` can't be played, or `None` if it can.
+    pub fn from_player(player: &Value) -> Option<Self> {
+        let status = &player["playabilityStatus"];
+        let reason = status["reason"].as_str()
+            .or(status["messages"][0].as_str())
+            .unwrap_or("")
+            .to_string();
+        let lower = reason.to_lowercase();
+        let unplayable = match status["status"].as_str().unwrap_or("OK") {
+            "OK" => return None,
+            "LIVE_STREAM_OFFLINE" => Self::LiveOnly,
+            _ if is_age_restricted(player) => Self::AgeRestricted(reason),
+            _ if lower.contains("private") => Self::Private,
+            _ if lower.contains("country") || lower.contains("region") => Self::RegionLocked,
+            _ if lower.contains("members") => Self::MembersOnly,
+            "ERROR" => Self::Removed(reason),
+            _ if lower.contains("removed") || lower.contains("terminated") || lower.contains("no longer available") => Self::Removed(reason),
+            _ => Self::Other(reason),
+        };
+        Some(unplayable)
     }
-    url_or_id.to_string()
 }
 
-pub fn extract_playlist_id(url_or_id: &str) -> String {
-    if url_or_id.contains("list=") {
-        let parts: Vec<&str> = url_or_id.split("list=").collect();
-        if parts.len() > 1 {
-            return parts[1].split('&').next().unwrap_or("").to_string();
+/// Whether a saved video is still on YouTube, for `verify_library`:
+/// `available`, `private` or `removed`. Videos that exist but are locked to
+/// a region, age or membership count as available. `None` when YouTube
+/// refused for some other reason, such as a bot check, so nothing can be said.
+pub fn availability(player: &Value) -> Option<&'static str> {
+    Unplayable::from_player(player).map_or(Some("available"), |u| u.availability())
+}
+
+impl Unplayable {
+    /// What `availability` makes of YouTube refusing to play a video this way.
+    pub fn availability(&self) -> Option<&'static str> {
+        match self {
+            Self::RegionLocked | Self::LiveOnly | Self::AgeRestricted(_) | Self::MembersOnly => Some("available"),
+            Self::Private => Some("private"),
+            Self::Removed(_) => Some("removed"),
+            Self::Other(_) => None,
         }
     }
-    url_or_id.to_string()
 }
 
-pub async fn extract_channel_id(url_or_handle: &str) -> Result<Option<String>, String> {
-    if url_or_handle.starts_with("UC") && url_or_handle.len() == 24 {
-        return Ok(Some(url_or_handle.to_string()));
+impl std::fmt::Display for Unplayable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let or_unknown = |reason: &str| if reason.is_empty() { "no reason given".to_string() } else { reason.to_string() };
+        match self {
+            Self::Private => write!(f, "Video is private"),
+            Self::Removed(reason) => write!(f, "Video is unavailable: {}", or_unknown(reason)),
+            Self::RegionLocked => write!(f, "Video is not available in your region"),
+            Self::LiveOnly => write!(f, "Video is a live stream or premiere that hasn't finished"),
+            Self::AgeRestricted(reason) => write!(f, "Video is age-restricted: {}", or_unknown(reason)),
+            Self::MembersOnly => write!(f, "Video is for channel members only"),
+            Self::Other(reason) => write!(f, "Video can't be played: {}", or_unknown(reason)),
+        }
     }
+}
 
-    if url_or_handle.contains("youtube.com/channel/") {
-        let parts: Vec<&str> = url_or_handle.split("youtube.com/channel/").collect();
-        if parts.len() > 1 {
-            return Ok(Some(parts[1].split('/').next().unwrap_or("").split('?').next().unwrap_or("").to_string()));
+/// Whether a video is streaming now or yet to start, read from a player
+/// response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LiveState {
+    pub is_live: bool,
+    pub is_upcoming: bool,
+    /// When an upcoming stream or premiere starts, in Unix seconds.
+    pub scheduled_start: Option<i64>,
+}
+
+impl LiveState {
+    pub fn from_player(player: &Value) -> Self {
+        let details = models::PlayerResponse::parse(player).video_details;
+        let is_upcoming = details.is_upcoming;
+        let slate = &player["playabilityStatus"]["liveStreamability"]["liveStreamabilityRenderer"]["offlineSlate"]["liveStreamOfflineSlateRenderer"];
+        Self {
+            is_live: details.is_live,
+            is_upcoming,
+            scheduled_start: slate["scheduledStartTime"].as_str()
+                .and_then(|t| t.parse().ok())
+                .filter(|_| is_upcoming),
         }
     }
+}
+
+/// A video's visibility and whether it was a live stream, read from a player
+/// response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VideoFlags {
+    pub is_private: bool,
+    pub is_unlisted: bool,
+    pub is_live_content: bool,
+}
 
-    let mut handle = url_or_handle.to_string();
-    if url_or_handle.contains("youtube.com/@") {
-        let parts: Vec<&str> = url_or_handle.split("youtube.com/@").collect();
-        if parts.len() > 1 {
-            handle = parts[1].split('/').next().unwrap_or("").split('?').next().unwrap_or("").to_string();
+impl VideoFlags {
+    pub fn from_player(player: &Value) -> Self {
+        let player = models::PlayerResponse::parse(player);
+        Self {
+            is_private: player.video_details.is_private,
+            is_unlisted: player.microformat().is_some_and(|m| m.is_unlisted),
+            is_live_content: player.video_details.is_live_content,
         }
-    } else if url_or_handle.starts_with('@') {
-        handle = url_or_handle[1..].to_string();
-    } else if url_or_handle.contains("youtube.com/c/") {
-        let parts: Vec<&str> = url_or_handle.split("youtube.com/c/").collect();
-        if parts.len() > 1 {
-            handle = parts[1].split('/').next().unwrap_or("").split('?').next().unwrap_or("").to_string();
+    }
+}
+
+/// A tab on a channel page that lists videos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelTab {
+    Videos,
+    Shorts,
+    Live,
+    Playlists,
+    Community,
+}
+
+impl ChannelTab {
+    /// The `params` the web client sends to open this tab.
+    fn params(self) -> &'static str {
+        match self {
+            ChannelTab::Videos => "EgZ2aWRlb3PyBgQKAjoA",
+            ChannelTab::Shorts => "EgZzaG9ydHPyBgUKA5oBAA==",
+            ChannelTab::Live => "EgdzdHJlYW1z8gYECgJ6AA==",
+            ChannelTab::Playlists => "EglwbGF5bGlzdHPyBgQKAkIA",
+            ChannelTab::Community => "Egljb21tdW5pdHnyBgQKAkoA",
         }
     }
+}
+
+/// The items listed on the selected tab of a channel page, or on a
+/// continuation of it. Hashtag pages share the layout. A page with neither is
+/// logged, since it usually means YouTube has moved things again.
+pub fn channel_tab_items(data: &Value) -> Vec<Value> {
+    let page = models::BrowseResponse::parse(data);
+    page.tab_items().unwrap_or_else(|| {
+        log::warn!("No tab items in browse response ({})", page.describe());
+        Vec::new()
+    })
+}
 
-    let client = reqwest::Client::new();
-    let url = format!("https://www.youtube.com/@{}", handle);
+/// The videos on the first page of a playlist, or `None` if it isn't one.
+pub fn playlist_video_items(data: &Value) -> Option<Vec<Value>> {
+    models::BrowseResponse::parse(data).playlist_videos()
+}
+
+/// The playlists on a page of a channel's Playlists tab, and the token for
+/// the next page. The tab lists them in a grid inside a section list rather
+/// than in a rich grid like the other tabs.
+pub fn channel_playlists(data: &Value) -> (Vec<PlaylistResult>, Option<String>) {
+    let mut grids = Vec::new();
+    objects_under(&data["contents"], "gridRenderer", &mut grids);
+    let items = grids.first()
+        .and_then(|grid| grid["items"].as_array())
+        .cloned()
+        .unwrap_or_else(|| channel_tab_items(data));
+    let author = channel_title(data);
+    let playlists = items.iter()
+        .filter_map(|item| extract_playlist_result(item).or_else(|| extract_playlist_result(&item["richItemRenderer"]["content"])))
+        .map(|mut p| {
+            if author.is_some() {
+                p.author = author.clone();
+            }
+            p
+        })
+        .collect();
+    (playlists, continuation_token(&items))
+}
+
+/// How long ago YouTube's relative time means, in seconds: `3 days ago`,
+/// `1 year ago (edited)`. Months and years are taken as 30 and 365 days.
+pub fn parse_time_ago(text: &str) -> Option<u64> {
+    let re = regex::Regex::new(r"(\d+)\s+(second|minute|hour|day|week|month|year)s?\s+ago").unwrap();
+    let caps = re.captures(text)?;
+    let unit = match &caps[2] {
+        "second" => 1,
+        "minute" => 60,
+        "hour" => 3600,
+        "day" => 86400,
+        "week" => 7 * 86400,
+        "month" => 30 * 86400,
+        _ => 365 * 86400,
+    };
+    Some(caps[1].parse::<u64>().ok()? * unit)
+}
+
+/// The posts on a page of a channel's Community tab, and the token for the
+/// next page. Posts shared from another channel are left out.
+pub fn community_posts(data: &Value) -> (Vec<CommunityPost>, Option<String>) {
+    let mut threads = Vec::new();
+    objects_under(data, "backstagePostThreadRenderer", &mut threads);
+    let posts = threads.into_iter()
+        .filter_map(models::BackstagePostThread::post)
+        .filter_map(extract_community_post)
+        .collect();
+    let mut more = Vec::new();
+    objects_under(data, "continuationItemRenderer", &mut more);
+    let continuation = more.last()
+        .and_then(|c| models::ContinuationItemRenderer::token(c));
+    (posts, continuation)
+}
+
+fn extract_community_post(post: models::BackstagePost) -> Option<CommunityPost> {
+    let attachment = &post.backstage_attachment;
+    let poll = attachment.poll_renderer.as_ref().map(|poll| Poll {
+        choices: poll.choices.iter()
+            .filter_map(|c| Some(PollChoice {
+                text: c.text.display()?,
+                vote_percentage: c.vote_percentage.display(),
+            }))
+            .collect(),
+        total_votes: poll.total_votes.display(),
+    });
+
+    Some(CommunityPost {
+        id: post.post_id?,
+        channel_id: post.author_endpoint.browse_endpoint.map(|e| e.browse_id).unwrap_or_default(),
+        author: post.author_text.display().unwrap_or_default(),
+        // Runs are kept whole: the text's own line breaks are in them
+        text: post.content_text.runs.iter().map(|r| r.text.as_str()).collect(),
+        published: post.published_time_text.display().unwrap_or_default(),
+        like_count: post.vote_count.display().and_then(|c| parse_count(&c)).unwrap_or(0),
+        images: attachment.image_urls().into_iter().map(absolute_url).collect(),
+        poll,
+        video_id: attachment.video_renderer.as_ref().and_then(|v| v.video_id.clone()),
+    })
+}
+
+/// The channel's name, given on the first page of any of its tabs.
+pub fn channel_title(data: &Value) -> Option<String> {
+    models::BrowseDetails::parse(data).metadata.channel_metadata_renderer?.title.as_deref().map(decode_html)
+}
+
+/// A channel's name, handle, artwork and counts from the first page of its
+/// browse response. Handles both the `pageHeaderRenderer` header and the
+/// older `c4TabbedHeaderRenderer`.
+pub fn parse_channel_details(channel_id: &str, data: &Value) -> ChannelDetails {
+    let mut page = models::BrowseDetails::parse(data);
+    let meta = page.metadata.channel_metadata_renderer.take().unwrap_or_default();
+    let header = page.page_header();
+    let c4 = page.header.c4_tabbed_header_renderer.as_ref();
+
+    let mut texts: Vec<String> = header.into_iter().flat_map(|h| h.metadata.texts()).map(String::from).collect();
+    if let Some(c4) = c4 {
+        texts.extend([&c4.channel_handle_text, &c4.subscriber_count_text, &c4.videos_count_text].map(models::Text::raw));
+    }
+    let count_of = |noun: &str| {
+        let re = regex::Regex::new(&format!(r"^([\d.,]+\s*[KMB]?) {}s?$", noun)).unwrap();
+        texts.iter().find_map(|t| re.captures(t.trim())).and_then(|caps| parse_count(&caps[1]))
+    };
+
+    let handle = meta.vanity_channel_url.as_deref()
+        .and_then(|url| url.rsplit('/').next())
+        .filter(|h| h.starts_with('@'))
+        .map(String::from)
+        .or_else(|| texts.iter().find(|t| t.starts_with('@')).cloned());
+    let banner = header.and_then(|h| h.banner())
+        .or_else(|| c4.and_then(|c| c.banner.last_url()))
+        .map(String::from);
+
+    ChannelDetails {
+        channel_id: meta.external_id.unwrap_or_else(|| channel_id.to_string()),
+        name: meta.title.as_deref().map(decode_html).unwrap_or_default(),
+        handle,
+        description: meta.description,
+        avatar: meta.avatar.last_url().map(String::from),
+        banner,
+        subscriber_count: count_of("subscriber"),
+        video_count: count_of("video"),
+    }
+}
+
+/// A playlist's title, owner, description and size from the first page of its
+/// browse response, read from the sidebar or, on newer pages, the header.
+pub fn parse_playlist_details(playlist_id: &str, data: &Value) -> PlaylistDetails {
+    let page = models::BrowseDetails::parse(data);
+    let meta = page.metadata.playlist_metadata_renderer.as_ref();
+    let primary = page.playlist_primary();
+    let header = page.page_header();
+
+    let header_texts: Vec<&str> = header.into_iter().flat_map(|h| h.metadata.texts()).collect();
+    let mut texts: Vec<String> = primary.into_iter().flat_map(|p| &p.stats).map(models::Text::raw).collect();
+    texts.extend(header_texts.iter().map(|t| t.to_string()));
+
+    let count_re = regex::Regex::new(r"^([\d,]+) videos?$").unwrap();
+    let video_count = texts.iter()
+        .find_map(|t| count_re.captures(t.trim()))
+        .and_then(|caps| parse_count(&caps[1]));
+    let owner = page.playlist_owner()
+        .or_else(|| header_texts.iter().find_map(|t| t.strip_prefix("by ")).map(String::from));
+
+    let title = meta.and_then(|m| m.title.clone())
+        .or_else(|| primary.map(|p| p.title.raw()).filter(|t| !t.is_empty()))
+        .unwrap_or_default();
+    let description = meta.and_then(|m| m.description.as_deref())
+        .or_else(|| header.and_then(|h| h.description()))
+        .unwrap_or("");
+
+    PlaylistDetails {
+        playlist_id: playlist_id.to_string(),
+        title: decode_html(&title),
+        owner: owner.map(|o| decode_html(&o)),
+        description: description.to_string(),
+        video_count,
+    }
+}
+
+/// A channel as a user names it: by its ID, or by a handle to resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelRef {
+    Id(String),
+    /// Without the leading `@`.
+    Handle(String),
+    /// A `/c/` or `/user/` page URL (see `ParsedTarget::LegacyChannel`).
+    LegacyUrl(String),
+}
+
+/// What a channel URL, `@handle` or channel ID names.
+pub fn channel_ref(url_or_handle: &str) -> ChannelRef {
+    match url::parse_youtube_url(url_or_handle) {
+        ParsedTarget::Channel(c) if c.starts_with("UC") => ChannelRef::Id(c),
+        ParsedTarget::Channel(h) => ChannelRef::Handle(h.trim_start_matches('@').to_string()),
+        ParsedTarget::LegacyChannel(url) => ChannelRef::LegacyUrl(url),
+        _ => ChannelRef::Handle(url_or_handle.trim().trim_start_matches('@').to_string()),
+    }
+}
+
+/// The channel ID behind a handle or legacy channel URL, asking YouTube to
+/// resolve it and falling back to scraping its channel page.
+pub async fn resolve_channel_ref(channel: &ChannelRef) -> Result<Option<String>, String> {
+    let url = match channel {
+        ChannelRef::Id(id) => return Ok(Some(id.clone())),
+        ChannelRef::Handle(handle) => format!("https://www.youtube.com/@{}", handle),
+        ChannelRef::LegacyUrl(url) => url.clone(),
+    };
+    let client = YouTubeClient::new(ClientType::Web, Locale::default());
+    match client.resolve_url(&url).await {
+        Ok(data) => match parse_resolved_channel_id(&data) {
+            Some(id) => return Ok(Some(id)),
+            None => log::warn!("resolve_url found no channel for {}; scraping its page", url),
+        },
+        Err(e) => log::warn!("resolve_url failed for {}: {}; scraping its page", url, e),
+    }
+    scrape_channel_id(&url).await
+}
+
+/// The channel ID a `navigation/resolve_url` response points to.
+fn parse_resolved_channel_id(data: &Value) -> Option<String> {
+    let endpoint = &data["endpoint"];
+    endpoint["browseEndpoint"]["browseId"].as_str()
+        .or(endpoint["commandMetadata"]["resolveUrlCommandMetadata"]["channelId"].as_str())
+        .filter(|id| id.starts_with("UC"))
+        .map(String::from)
+}
+
+/// The browse ID and params a `navigation/resolve_url` response points to,
+/// for pages such as hashtags that need both.
+fn parse_resolved_browse(data: &Value) -> Option<(String, String)> {
+    let endpoint = &data["endpoint"]["browseEndpoint"];
+    Some((endpoint["browseId"].as_str()?.to_string(), endpoint["params"].as_str()?.to_string()))
+}
+
+/// The body of a page from YouTube. A 429, or a redirect to Google's "unusual
+/// traffic" page, pauses further requests and fails this one.
+async fn page_text(response: reqwest::Response) -> Result<String, String> {
+    if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS || response.url().path().starts_with("/sorry") {
+        let reason = "YouTube is limiting requests from this connection";
+        throttle::cool_down(reason);
+        return Err(reason.to_string());
+    }
+    response.text().await.map_err(|e| e.to_string())
+}
+
+/// Find a channel's ID in the markup of its page at `url`.
+async fn scrape_channel_id(url: &str) -> Result<Option<String>, String> {
+    let client = http_client();
     let mut headers = HeaderMap::new();
     headers.insert(USER_AGENT, HeaderValue::from_static("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36"));
     headers.insert(reqwest::header::ACCEPT, HeaderValue::from_static("text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7"));
     headers.insert(reqwest::header::ACCEPT_LANGUAGE, HeaderValue::from_static("en-US,en;q=0.9"));
 
+    throttle::wait().await;
     let res = client.get(url)
         .headers(headers)
         .send()
         .await
         .map_err(|e| e.to_string())?;
 
-    let text = res.text().await.map_err(|e| e.to_string())?;
+    let text = page_text(res).await?;
     
     // Try multiple regex patterns for channel ID, prioritizing canonical and meta tags
     let patterns = [
@@ -198,6 +1123,52 @@ pub async fn extract_channel_id(url_or_handle: &str) -> Result<Option<String>, S
     Ok(None)
 }
 
+/// The `params` a search sends for `filters`: a base64 protobuf with the sort
+/// order in field 1 and a message of filters in field 2, holding the upload
+/// date in its field 1 and the duration in its field 3. `None` when nothing
+/// is narrowed.
+pub fn search_params(filters: &SearchFilters) -> Option<String> {
+    use base64::Engine;
+
+    // Every value here fits in one varint byte
+    let field = |number: u8, value: u8| [number << 3, value];
+    let mut narrowed = Vec::new();
+    if let Some(date) = filters.upload_date {
+        let value = match date {
+            UploadDate::LastHour => 1,
+            UploadDate::Today => 2,
+            UploadDate::ThisWeek => 3,
+            UploadDate::ThisMonth => 4,
+            UploadDate::ThisYear => 5,
+        };
+        narrowed.extend(field(1, value));
+    }
+    if let Some(duration) = filters.duration {
+        let value = match duration {
+            SearchDuration::Short => 1,
+            SearchDuration::Long => 2,
+            SearchDuration::Medium => 3,
+        };
+        narrowed.extend(field(3, value));
+    }
+
+    let mut params = Vec::new();
+    let sort = match filters.sort {
+        None | Some(SearchSort::Relevance) => None,
+        Some(SearchSort::Rating) => Some(1),
+        Some(SearchSort::UploadDate) => Some(2),
+        Some(SearchSort::ViewCount) => Some(3),
+    };
+    if let Some(sort) = sort {
+        params.extend(field(1, sort));
+    }
+    if !narrowed.is_empty() {
+        params.extend([2 << 3 | 2, narrowed.len() as u8]);
+        params.extend(narrowed);
+    }
+    (!params.is_empty()).then(|| base64::engine::general_purpose::STANDARD.encode(params))
+}
+
 pub fn channel_id_to_uploads_playlist(channel_id: &str) -> String {
     if channel_id.starts_with("UC") {
         return format!("UU{}", &channel_id[2..]);
@@ -219,109 +1190,394 @@ fn extract_handle_from_text(text: &str) -> Option<String> {
     None
 }
 
-pub async fn extract_handle_from_channel_id(channel_id: &str) -> Result<Option<String>, String> {
-    if !channel_id.starts_with("UC") || channel_id.len() != 24 {
-        return Ok(None);
-    }
-    
-    let client = reqwest::Client::new();
-    let url = format!("https://www.youtube.com/channel/{}", channel_id);
+pub async fn extract_handle_from_channel_id(channel_id: &str) -> Result<Option<String>, String> {
+    if !channel_id.starts_with("UC") || channel_id.len() != 24 {
+        return Ok(None);
+    }
+    
+    let client = http_client();
+    let url = format!("https://www.youtube.com/channel/{}", channel_id);
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_static("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36"));
+    headers.insert(reqwest::header::ACCEPT, HeaderValue::from_static("text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7"));
+    headers.insert(reqwest::header::ACCEPT_LANGUAGE, HeaderValue::from_static("en-US,en;q=0.9"));
+
+    throttle::wait().await;
+    let res = client.get(url)
+        .headers(headers)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let text = page_text(res).await?;
+    
+    // Try to find @handle in the page
+    // Pattern 1: canonical URL with @handle
+    let canonical_pattern = r#"link rel="canonical" href="https://www.youtube.com/(@[^""]+)"#;
+    let re = regex::Regex::new(canonical_pattern).unwrap();
+    if let Some(caps) = re.captures(&text) {
+        return Ok(Some(caps.get(1).unwrap().as_str().to_string()));
+    }
+    
+    // Pattern 2: og:url with @handle
+    let og_pattern = r#"meta property="og:url" content="https://www.youtube.com/(@[^""]+)"#;
+    let re = regex::Regex::new(og_pattern).unwrap();
+    if let Some(caps) = re.captures(&text) {
+        return Ok(Some(caps.get(1).unwrap().as_str().to_string()));
+    }
+    
+    // Pattern 3: external_id in script
+    let script_pattern = r#""externalId":"(UC[^"]+)"#;
+    let re = regex::Regex::new(script_pattern).unwrap();
+    if re.is_match(&text) {
+        // This is the channel ID, not handle - return None since we couldn't find handle
+        return Ok(None);
+    }
+    
+    Ok(None)
+}
+
+/// Fetch a channel's avatar URL from the `og:image` tag of its page.
+pub async fn fetch_channel_avatar(channel_id: &str) -> Result<Option<String>, String> {
+    if !channel_id.starts_with("UC") || channel_id.len() != 24 {
+        return Ok(None);
+    }
+
+    let client = http_client();
+    let url = format!("https://www.youtube.com/channel/{}", channel_id);
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_static("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36"));
+    headers.insert(reqwest::header::ACCEPT_LANGUAGE, HeaderValue::from_static("en-US,en;q=0.9"));
+
+    throttle::wait().await;
+    let response = client.get(url)
+        .headers(headers)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let text = page_text(response).await?;
+
+    let re = regex::Regex::new(r#"meta property="og:image" content="([^"]+)""#).unwrap();
+    Ok(re.captures(&text).map(|caps| caps[1].to_string()))
+}
+
+/// The latest uploads from a channel's RSS feed. This works when InnerTube
+/// doesn't, but only ever lists the newest 15 videos.
+pub async fn fetch_channel_feed(channel_id: &str) -> Result<Vec<Value>, String> {
+    let url = format!("https://www.youtube.com/feeds/videos.xml?channel_id={}", channel_id);
+    throttle::wait().await;
+    let response = http_client().get(url).send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    let text = page_text(response).await?;
+    if !status.is_success() {
+        return Err(format!("Channel feed returned {}", status));
+    }
+    parse_channel_feed(&text)
+}
+
+/// Parse a channel's Atom feed into the same shape as the InnerTube extractors.
+fn parse_channel_feed(xml: &str) -> Result<Vec<Value>, String> {
+    use quick_xml::events::Event;
+
+    let mut videos = Vec::new();
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut entry: Option<serde_json::Map<String, Value>> = None;
+    let mut field: Option<&'static str> = None;
+    let mut in_author = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => match e.name().as_ref() {
+                b"entry" => entry = Some(serde_json::Map::new()),
+                b"author" => in_author = true,
+                b"yt:videoId" => field = Some("id"),
+                b"title" if entry.is_some() => field = Some("title"),
+                b"published" if entry.is_some() => field = Some("publishedAt"),
+                b"name" if in_author && entry.is_some() => field = Some("author"),
+                _ => {}
+            },
+            Ok(Event::Empty(ref e)) => {
+                if let Some(entry) = entry.as_mut() {
+                    let attr = |name: &[u8]| {
+                        e.try_get_attribute(name).ok().flatten()
+                            .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+                    };
+                    match e.name().as_ref() {
+                        b"media:thumbnail" => {
+                            entry.insert("thumbnail".into(), serde_json::json!(attr(b"url").unwrap_or_default()));
+                        }
+                        b"media:statistics" => {
+                            entry.insert("viewCount".into(), serde_json::json!(attr(b"views").unwrap_or_default()));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Ok(Event::Text(e)) => {
+                if let (Some(entry), Some(name)) = (entry.as_mut(), field) {
+                    let text = e.unescape().map_err(|e| e.to_string())?;
+                    entry.insert(name.into(), serde_json::json!(text.trim()));
+                }
+            }
+            Ok(Event::End(ref e)) => {
+                field = None;
+                match e.name().as_ref() {
+                    b"author" => in_author = false,
+                    b"entry" => {
+                        if let Some(mut entry) = entry.take().filter(|e| e.contains_key("id")) {
+                            for key in ["title", "thumbnail", "publishedAt", "viewCount"] {
+                                entry.entry(key).or_insert_with(|| serde_json::json!(""));
+                            }
+                            videos.push(Value::Object(entry));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(e.to_string()),
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(videos)
+}
+
+/// The URL of a video's thumbnail at `quality`.
+pub fn thumbnail_url(video_id: &str, quality: ThumbnailQuality) -> String {
+    let name = match quality {
+        ThumbnailQuality::Maxres => "maxresdefault",
+        ThumbnailQuality::Sd => "sddefault",
+        ThumbnailQuality::Hq => "hqdefault",
+        ThumbnailQuality::Mq => "mqdefault",
+    };
+    format!("https://i.ytimg.com/vi/{}/{}.jpg", video_id, name)
+}
+
+/// `quality` and every smaller size, in the order to try them.
+pub fn thumbnail_fallbacks(quality: ThumbnailQuality) -> &'static [ThumbnailQuality] {
+    const ALL: [ThumbnailQuality; 4] = [ThumbnailQuality::Maxres, ThumbnailQuality::Sd, ThumbnailQuality::Hq, ThumbnailQuality::Mq];
+    let start = ALL.iter().position(|q| *q == quality).unwrap_or(0);
+    &ALL[start..]
+}
+
+/// Download a video's thumbnail at `quality`, or the next size down when
+/// YouTube has none that large. Returns the image's MIME type and bytes, or
+/// `None` if YouTube has no thumbnail for it.
+pub async fn fetch_thumbnail(video_id: &str, quality: ThumbnailQuality) -> Result<Option<(String, Vec<u8>)>, String> {
+    let client = http_client();
+    for &size in thumbnail_fallbacks(quality) {
+        let url = thumbnail_url(video_id, size);
+        throttle::wait().await;
+        let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            continue;
+        }
+        let mime = response.headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| v.starts_with("image/"))
+            .unwrap_or("image/jpeg")
+            .to_string();
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        if !bytes.is_empty() {
+            return Ok(Some((mime, bytes.to_vec())));
+        }
+    }
+    Ok(None)
+}
+
+/// Ask each of `ClientType::CAPTION_FALLBACKS` for a video's transcript until
+/// one has it. `Ok(None)` means none did; an error means every client failed
+/// outright, and carries the last failure. Captions in `language` are preferred
+/// as `pick_caption_track` describes; with `translate`, YouTube translates them
+/// into `language` when the video has none in it. `make_client` builds each
+/// client, so the caller's settings apply to all of them.
+pub async fn fetch_transcript_from_any_client(
+    video_id: &str,
+    language: &str,
+    translate: bool,
+    make_client: impl Fn(ClientType) -> YouTubeClient,
+) -> Result<Option<Transcript>, String> {
+    let mut last_error = None;
+    let mut answered = false;
+    for (i, client_type) in ClientType::CAPTION_FALLBACKS.into_iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+        let client = make_client(client_type);
+        let result = match client.player(video_id).await {
+            Ok(player) if translate => fetch_translated_transcript(&player, language).await,
+            Ok(player) => fetch_timed_transcript(&player, language).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(Some(t)) if !t.text.trim().is_empty() => return Ok(Some(t)),
+            Ok(_) => answered = true,
+            Err(e) => {
+                log::warn!("{:?} client found no transcript for {}: {}", client_type, video_id, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    match last_error {
+        Some(e) if !answered => Err(e),
+        _ => Ok(None),
+    }
+}
+
+/// A transcript as flat text plus the timed segments it was built from.
+/// `segments` is empty when the caption source carried no timing.
+pub struct Transcript {
+    pub text: String,
+    pub segments: Vec<TranscriptSegment>,
+    /// Language code of the caption track it came from.
+    pub language: Option<String>,
+    /// Whether that track is YouTube's automatic captions (`kind: "asr"`).
+    pub auto_generated: bool,
+}
+
+/// The caption track to use: one in `language` (an `hl` code such as `pt-BR`),
+/// else one in its base language, else English, else the first listed.
+fn pick_caption_track<'a>(tracks: &'a [Value], language: &str) -> Option<&'a Value> {
+    let primary = language.split(['-', '_']).next().unwrap_or(language);
+    let in_language = |code: &str| tracks.iter().find(|t| t["languageCode"].as_str().unwrap_or("").starts_with(code));
+    tracks.iter()
+        .find(|t| t["languageCode"].as_str().is_some_and(|c| c.eq_ignore_ascii_case(language)))
+        .or_else(|| in_language(primary))
+        .or_else(|| in_language("en"))
+        .or_else(|| tracks.first())
+}
+
+/// The transcript from a player response, in the caption track
+/// `pick_caption_track` chooses for `language`.
+pub async fn fetch_timed_transcript(player_json: &Value, language: &str) -> Result<Option<Transcript>, String> {
+    let Some(track) = pick_caption_track(caption_tracks(player_json), language) else {
+        return Ok(None);
+    };
+    let base_url = track["baseUrl"].as_str().ok_or("No base URL for transcript")?;
+    let transcript = fetch_caption_track(base_url).await?;
+    Ok(transcript.map(|t| Transcript {
+        language: track["languageCode"].as_str().map(String::from),
+        auto_generated: is_auto_generated(track),
+        ..t
+    }))
+}
+
+/// The transcript from a player response in `target`, machine-translated by
+/// YouTube from another track when the video has no captions in it.
+pub async fn fetch_translated_transcript(player_json: &Value, target: &str) -> Result<Option<Transcript>, String> {
+    let translatable: Vec<Value> = caption_tracks(player_json).iter()
+        .filter(|t| t["isTranslatable"].as_bool() != Some(false))
+        .cloned()
+        .collect();
+    let Some(track) = pick_caption_track(&translatable, target) else {
+        return Ok(None);
+    };
+    let base_url = track["baseUrl"].as_str().ok_or("No base URL for transcript")?;
+    let code = track["languageCode"].as_str().unwrap_or("");
+    let url = if same_language(code, target) {
+        base_url.to_string()
+    } else {
+        format!("{}&tlang={}", base_url, urlencoding::encode(target))
+    };
+    let transcript = fetch_caption_track(&url).await?;
+    Ok(transcript.map(|t| Transcript { language: Some(target.to_string()), auto_generated: is_auto_generated(track), ..t }))
+}
+
+/// The caption tracks listed in a player response.
+fn caption_tracks(player_json: &Value) -> &[Value] {
+    let captions = &player_json["captions"];
+    captions["playerCaptionsTracklistRenderer"]["captionTracks"].as_array()
+        // Fallback if the above path is missing
+        .or_else(|| captions["captionTracks"].as_array())
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+/// Whether a caption track is YouTube's speech recognition rather than
+/// captions someone wrote.
+fn is_auto_generated(track: &Value) -> bool {
+    track["kind"].as_str() == Some("asr")
+}
+
+/// The caption tracks a player response lists, described for choosing one.
+pub fn parse_caption_tracks(player_json: &Value) -> Vec<CaptionTrack> {
+    caption_tracks(player_json).iter()
+        .filter_map(|t| {
+            let language_code = t["languageCode"].as_str()?.to_string();
+            let name = t["name"]["simpleText"].as_str()
+                .or_else(|| t["name"]["runs"][0]["text"].as_str())
+                .unwrap_or(&language_code)
+                .to_string();
+            Some(CaptionTrack {
+                language_code,
+                name,
+                auto_generated: is_auto_generated(t),
+                translatable: t["isTranslatable"].as_bool().unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+/// Whether two language codes name the same language, ignoring region.
+pub fn same_language(a: &str, b: &str) -> bool {
+    let primary = |code: &str| code.split(['-', '_']).next().unwrap_or(code).to_ascii_lowercase();
+    primary(a) == primary(b)
+}
+
+/// Download one caption track and parse it, whether YouTube serves it as json3
+/// or as XML.
+async fn fetch_caption_track(base_url: &str) -> Result<Option<Transcript>, String> {
     let mut headers = HeaderMap::new();
     headers.insert(USER_AGENT, HeaderValue::from_static("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36"));
-    headers.insert(reqwest::header::ACCEPT, HeaderValue::from_static("text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7"));
-    headers.insert(reqwest::header::ACCEPT_LANGUAGE, HeaderValue::from_static("en-US,en;q=0.9"));
-
-    let res = client.get(url)
+    
+    let client = http_client();
+    throttle::wait().await;
+    let res = client.get(base_url)
         .headers(headers)
         .send()
         .await
         .map_err(|e| e.to_string())?;
+    let text = page_text(res).await?;
 
-    let text = res.text().await.map_err(|e| e.to_string())?;
-    
-    // Try to find @handle in the page
-    // Pattern 1: canonical URL with @handle
-    let canonical_pattern = r#"link rel="canonical" href="https://www.youtube.com/(@[^""]+)"#;
-    let re = regex::Regex::new(canonical_pattern).unwrap();
-    if let Some(caps) = re.captures(&text) {
-        return Ok(Some(caps.get(1).unwrap().as_str().to_string()));
-    }
-    
-    // Pattern 2: og:url with @handle
-    let og_pattern = r#"meta property="og:url" content="https://www.youtube.com/(@[^""]+)"#;
-    let re = regex::Regex::new(og_pattern).unwrap();
-    if let Some(caps) = re.captures(&text) {
-        return Ok(Some(caps.get(1).unwrap().as_str().to_string()));
-    }
-    
-    // Pattern 3: external_id in script
-    let script_pattern = r#""externalId":"(UC[^"]+)"#;
-    let re = regex::Regex::new(script_pattern).unwrap();
-    if re.is_match(&text) {
-        // This is the channel ID, not handle - return None since we couldn't find handle
-        return Ok(None);
-    }
-    
-    Ok(None)
-}
-
-pub async fn fetch_transcript(player_json: &Value) -> Result<Option<String>, String> {
-    let captions = &player_json["captions"];
-    let mut caption_tracks = captions["playerCaptionsTracklistRenderer"]["captionTracks"].as_array();
-    
-    // Fallback if the above path is missing
-    if caption_tracks.is_none() {
-        caption_tracks = captions["captionTracks"].as_array();
-    }
-    
-    if let Some(tracks) = caption_tracks {
-        let track = tracks.iter()
-            .find(|t| t["languageCode"].as_str().unwrap_or("").starts_with("en"))
-            .or_else(|| tracks.first());
-
-        if let Some(track) = track {
-            let base_url = track["baseUrl"].as_str().ok_or("No base URL for transcript")?;
-            
-            let mut headers = HeaderMap::new();
-            headers.insert(USER_AGENT, HeaderValue::from_static("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36"));
-            
-            let client = reqwest::Client::new();
-            let res = client.get(base_url)
-                .headers(headers)
-                .send()
-                .await
-                .map_err(|e| e.to_string())?;
-            let text = res.text().await.map_err(|e| e.to_string())?;
-
-            if text.trim().starts_with('{') {
-                let data: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
-                let mut lines: Vec<String> = Vec::new();
-                
-                if let Some(events) = data["events"].as_array() {
-                    for event in events {
-                        if let Some(segs) = event["segs"].as_array() {
-                            let line: String = segs.iter()
-                                .map(|s| s["utf8"].as_str().unwrap_or(""))
-                                .collect::<Vec<_>>()
-                                .join("");
-                            if !line.trim().is_empty() {
-                                lines.push(line);
-                            }
-                        }
+    if text.trim().starts_with('{') {
+        let data: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+        let mut segments: Vec<TranscriptSegment> = Vec::new();
+        
+        if let Some(events) = data["events"].as_array() {
+            for event in events {
+                if let Some(segs) = event["segs"].as_array() {
+                    let line: String = segs.iter()
+                        .map(|s| s["utf8"].as_str().unwrap_or(""))
+                        .collect::<Vec<_>>()
+                        .join("");
+                    if !line.trim().is_empty() {
+                        segments.push(TranscriptSegment {
+                            start_ms: event["tStartMs"].as_i64().unwrap_or(0),
+                            duration_ms: event["dDurationMs"].as_i64().unwrap_or(0),
+                            text: line,
+                        });
                     }
                 }
-                
-                if lines.is_empty() {
-                    collect_transcript_lines(&data, &mut lines);
-                }
-                
-                return Ok(Some(lines.join("\n")));
-            } else {
-                return parse_xml_transcript(&text);
             }
         }
+        
+        if segments.is_empty() {
+            let mut lines: Vec<String> = Vec::new();
+            collect_transcript_lines(&data, &mut lines);
+            return Ok(Some(Transcript { text: lines.join("\n"), segments, language: None, auto_generated: false }));
+        }
+        
+        Ok(Some(Transcript { text: join_segments(&segments), segments, language: None, auto_generated: false }))
+    } else {
+        parse_xml_transcript(&text)
     }
-    Ok(None)
+}
+
+pub fn join_segments(segments: &[TranscriptSegment]) -> String {
+    segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join("\n")
 }
 
 fn collect_transcript_lines(val: &Value, lines: &mut Vec<String>) {
@@ -346,11 +1602,23 @@ fn collect_transcript_lines(val: &Value, lines: &mut Vec<String>) {
     }
 }
 
-fn parse_xml_transcript(xml: &str) -> Result<Option<String>, String> {
-    let mut lines = Vec::new();
+/// Read a numeric attribute from a caption element, scaled to milliseconds.
+fn timing_attr(e: &quick_xml::events::BytesStart, name: &[u8], ms_per_unit: f64) -> i64 {
+    e.try_get_attribute(name).ok().flatten()
+        .and_then(|a| std::str::from_utf8(&a.value).ok().and_then(|v| v.parse::<f64>().ok()))
+        .map(|v| (v * ms_per_unit).round() as i64)
+        .unwrap_or(0)
+}
+
+/// Parse the timedtext XML formats: srv3 (`<p t=".." d="..">` in milliseconds,
+/// optionally split into `<s>` words) and the legacy `<text start=".." dur="..">`
+/// in seconds.
+fn parse_xml_transcript(xml: &str) -> Result<Option<Transcript>, String> {
+    let mut segments = Vec::new();
     let mut reader = quick_xml::Reader::from_str(xml);
     let mut buf = Vec::new();
     let mut current_line = Vec::new();
+    let mut timing = (0, 0);
     let mut in_p = false;
     let mut in_s = false;
     let mut in_text = false;
@@ -362,11 +1630,13 @@ fn parse_xml_transcript(xml: &str) -> Result<Option<String>, String> {
                     b"p" => {
                         in_p = true;
                         current_line.clear();
+                        timing = (timing_attr(e, b"t", 1.0), timing_attr(e, b"d", 1.0));
                     }
                     b"s" => in_s = true,
                     b"text" => {
                         in_text = true;
                         current_line.clear();
+                        timing = (timing_attr(e, b"start", 1000.0), timing_attr(e, b"dur", 1000.0));
                     }
                     _ => {}
                 }
@@ -382,14 +1652,16 @@ fn parse_xml_transcript(xml: &str) -> Result<Option<String>, String> {
                     b"p" => {
                         in_p = false;
                         if !current_line.is_empty() {
-                            lines.push(current_line.iter().map(|s| s.trim()).collect::<Vec<_>>().join(" "));
+                            let text = current_line.iter().map(|s| s.trim()).collect::<Vec<_>>().join(" ");
+                            segments.push(TranscriptSegment { start_ms: timing.0, duration_ms: timing.1, text });
                         }
                     }
                     b"s" => in_s = false,
                     b"text" => {
                         in_text = false;
                         if !current_line.is_empty() {
-                            lines.push(current_line.join(" "));
+                            let text = current_line.join(" ");
+                            segments.push(TranscriptSegment { start_ms: timing.0, duration_ms: timing.1, text });
                         }
                     }
                     _ => {}
@@ -402,13 +1674,294 @@ fn parse_xml_transcript(xml: &str) -> Result<Option<String>, String> {
         buf.clear();
     }
 
-    if lines.is_empty() {
+    if segments.is_empty() {
         Ok(None)
     } else {
-        Ok(Some(lines.join("\n")))
+        Ok(Some(Transcript { text: join_segments(&segments), segments, language: None, auto_generated: false }))
     }
 }
 
+/// The token for the next page from a list of results, which InnerTube ends
+/// with a `continuationItemRenderer` when there is more to load.
+pub fn continuation_token(items: &[Value]) -> Option<String> {
+    items.iter().rev().find_map(models::ContinuationItem::token)
+}
+
+/// Like and comment counts as shown on a video's watch page.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Engagement {
+    pub like_count: Option<i64>,
+    pub comment_count: Option<i64>,
+}
+
+/// Parse a count as YouTube displays it: `1,234`, `1.2K` or `3M`.
+fn parse_count(text: &str) -> Option<i64> {
+    let text = text.trim().replace(',', "");
+    let number_end = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+    let number = text[..number_end].parse::<f64>().ok()?;
+    let multiplier = match text[number_end..].trim_start().chars().next() {
+        Some('K' | 'k') => 1e3,
+        Some('M' | 'm') => 1e6,
+        Some('B' | 'b') => 1e9,
+        _ => 1.0,
+    };
+    Some((number * multiplier).round() as i64)
+}
+
+/// Every string under `key` anywhere within `val`.
+/// Every object found under `key` anywhere inside `val`, in document order.
+fn objects_under<'a>(val: &'a Value, key: &str, found: &mut Vec<&'a Value>) {
+    if let Some(obj) = val.as_object() {
+        for (k, v) in obj {
+            if k == key && v.is_object() {
+                found.push(v);
+            } else {
+                objects_under(v, key, found);
+            }
+        }
+    } else if let Some(arr) = val.as_array() {
+        for v in arr {
+            objects_under(v, key, found);
+        }
+    }
+}
+
+/// The videos on the trending page's selected tab. They sit in shelves
+/// whose layout keeps changing, so every `videoRenderer` is collected, once.
+pub fn trending_videos(data: &Value) -> Vec<Value> {
+    let mut renderers = Vec::new();
+    objects_under(&data["contents"], "videoRenderer", &mut renderers);
+    let mut seen = std::collections::HashSet::new();
+    renderers.into_iter()
+        .filter_map(extract_video_basic_info)
+        .filter(|v| seen.insert(v["id"].as_str().unwrap_or("").to_string()))
+        .collect()
+}
+
+fn strings_under<'a>(val: &'a Value, key: &str, found: &mut Vec<&'a str>) {
+    if let Some(obj) = val.as_object() {
+        for (k, v) in obj {
+            match v.as_str() {
+                Some(text) if k == key => found.push(text),
+                _ => strings_under(v, key, found),
+            }
+        }
+    } else if let Some(arr) = val.as_array() {
+        for v in arr {
+            strings_under(v, key, found);
+        }
+    }
+}
+
+/// Like and comment counts from a `next` response. The like button's
+/// accessibility text carries the exact count, where its label is rounded.
+pub fn parse_engagement(next_json: &Value) -> Engagement {
+    let next = models::NextResponse::parse(next_json);
+    let primary = next.primary_contents().iter().find_map(|item| item.get("videoPrimaryInfoRenderer"));
+
+    let mut labels = Vec::new();
+    if let Some(primary) = primary {
+        strings_under(&primary["videoActions"], "accessibilityText", &mut labels);
+        strings_under(&primary["videoActions"], "label", &mut labels);
+    }
+    let likes_re = regex::Regex::new(r"(?i)along with ([\d,]+) other|^([\d,.]+[KMB]?) likes?$").unwrap();
+    let like_count = labels.iter()
+        .find_map(|l| likes_re.captures(l))
+        .and_then(|caps| caps.get(1).or_else(|| caps.get(2)).and_then(|m| parse_count(m.as_str())));
+
+    let comment_count = next.panel_info("engagement-panel-comments-section")
+        .and_then(|info| info.runs.first())
+        .and_then(|run| parse_count(&run.text));
+
+    Engagement { like_count, comment_count }
+}
+
+/// The token that loads the first page of comments from a video's watch page.
+pub fn comments_continuation(next_json: &Value) -> Option<String> {
+    let items = models::NextResponse::parse(next_json).section("comment-item-section")?;
+    continuation_token(&items)
+}
+
+/// The comments on a page from `comments_continuation` or a later one, and the
+/// token for the next. Comment threads either carry a `commentRenderer` or,
+/// in the newer layout, point to a `commentEntityPayload` by ID.
+pub fn parse_comments(page: &Value) -> (Vec<Comment>, Option<String>) {
+    let items = models::BrowseResponse::parse(page).continuation_items();
+    let entities = models::EntityUpdates::parse(page);
+
+    let comments = items.iter()
+        .filter_map(models::CommentItem::thread)
+        .filter_map(|thread| {
+            if let Some(c) = thread.comment.comment_renderer {
+                return Some(Comment {
+                    id: c.comment_id?,
+                    author: c.author_text.raw(),
+                    text: c.content_text.raw(),
+                    like_count: parse_count(&c.vote_count.raw()).unwrap_or(0),
+                    reply_count: c.reply_count,
+                    published: c.published_time_text.raw(),
+                });
+            }
+            let id = thread.comment_view_model.comment_view_model?.comment_id?;
+            let entity = entities.comment(&id)?;
+            Some(Comment {
+                author: entity.author.display_name.clone(),
+                text: entity.properties.content.content.clone().unwrap_or_default(),
+                like_count: parse_count(&entity.toolbar.like_count_notliked).unwrap_or(0),
+                reply_count: parse_count(&entity.toolbar.reply_count).unwrap_or(0),
+                published: entity.properties.published_time.clone(),
+                id,
+            })
+        })
+        .collect();
+
+    (comments, continuation_token(&items))
+}
+
+/// Chapter markers from a `next` response, preferring the creator's own over
+/// the ones YouTube generates.
+pub fn parse_chapters(next_json: &Value) -> Vec<Chapter> {
+    let overlays = models::WatchOverlays::parse(next_json);
+    let markers = overlays.markers_map();
+    let map = ["DESCRIPTION_CHAPTERS", "AUTO_CHAPTERS"].iter()
+        .find_map(|key| markers.iter().find(|m| m.key == *key))
+        .or_else(|| markers.first());
+
+    map.into_iter()
+        .flat_map(|m| &m.value.chapters)
+        .filter_map(|c| {
+            let chapter = c.chapter_renderer.as_ref()?;
+            Some(Chapter {
+                title: decode_html(chapter.title.simple_text.as_deref()?),
+                start_ms: chapter.time_range_start_millis?,
+            })
+        })
+        .collect()
+}
+
+/// The largest storyboard in a player response. Its spec is a URL template
+/// followed by one `|`-separated level per frame size, each
+/// `width#height#count#columns#rows#interval#name#sigh`. `$L` in the template
+/// is the level, `$N` the name, in which `$M` is the sheet number. The
+/// smallest level gives no interval, so it is worked out from the length.
+pub fn parse_storyboard(player: &Value) -> Option<Storyboard> {
+    let spec = player["storyboards"]["playerStoryboardSpecRenderer"]["spec"].as_str()?;
+    let mut parts = spec.split('|');
+    let template = parts.next()?;
+    let (level, fields) = parts.enumerate()
+        .filter_map(|(level, part)| {
+            let fields: Vec<&str> = part.split('#').collect();
+            (fields.len() >= 8).then_some((level, fields))
+        })
+        .last()?;
+    let number = |i: usize| fields[i].parse::<i64>().ok().filter(|n| *n > 0);
+    let (width, height, count, columns, rows) = (number(0)?, number(1)?, number(2)?, number(3)?, number(4)?);
+    let interval_ms = number(5).or_else(|| {
+        let length: i64 = models::PlayerResponse::parse(player).video_details.length_seconds.parse().ok()?;
+        Some(length * 1000 / count)
+    })?;
+
+    let per_sheet = columns * rows;
+    let sheets = (0..(count + per_sheet - 1) / per_sheet)
+        .map(|sheet| {
+            let name = fields[6].replace("$M", &sheet.to_string());
+            let url = template.replace("$L", &level.to_string()).replace("$N", &name);
+            let joiner = if url.contains('?') { '&' } else { '?' };
+            format!("{}{}sigh={}", url, joiner, fields[7])
+        })
+        .collect();
+    Some(Storyboard { width, height, count, columns, rows, interval_ms, sheets })
+}
+
+/// The "most replayed" graph from a `next` response, scaled so the most
+/// replayed stretch has an intensity of 1. Newer responses carry it as a
+/// heatmap markers entity, older ones in the player bar's markers map.
+pub fn parse_heatmap(next_json: &Value) -> Vec<HeatmapPoint> {
+    let entities = models::EntityUpdates::parse(next_json);
+    let mut points: Vec<HeatmapPoint> = match entities.markers("MARKER_TYPE_HEATMAP") {
+        Some(list) => list.markers.iter()
+            .filter_map(|m| Some(HeatmapPoint {
+                start_ms: m.start_millis?,
+                duration_ms: m.duration_millis?,
+                intensity: m.intensity_score_normalized?,
+            }))
+            .collect(),
+        None => models::WatchOverlays::parse(next_json).markers_map().iter()
+            .find(|m| m.key == "HEATSEEKER")
+            .and_then(|m| m.value.heatmap.heatmap_renderer.as_ref())
+            .into_iter()
+            .flat_map(|h| &h.heat_markers)
+            .filter_map(|m| {
+                let marker = m.heat_marker_renderer.as_ref()?;
+                Some(HeatmapPoint {
+                    start_ms: marker.time_range_start_millis?,
+                    duration_ms: marker.marker_duration_millis?,
+                    intensity: marker.heat_marker_intensity_score_normalized?,
+                })
+            })
+            .collect(),
+    };
+
+    let peak = points.iter().map(|p| p.intensity).fold(0.0, f64::max);
+    if peak > 0.0 {
+        for point in &mut points {
+            point.intensity /= peak;
+        }
+    }
+    points.sort_by_key(|p| p.start_ms);
+    points
+}
+
+/// Chapters written as timestamps in a description. Like YouTube, this needs
+/// at least three in ascending order starting at 0:00, or finds none.
+pub fn chapters_from_description(description: &str) -> Vec<Chapter> {
+    let re = regex::Regex::new(r"(?:^|\s|\()(?:(\d{1,2}):)?(\d{1,2}):(\d{2})(?:$|\s|\))").unwrap();
+    let mut chapters: Vec<Chapter> = Vec::new();
+    for line in description.lines() {
+        let Some(caps) = re.captures(line) else { continue };
+        let part = |i: usize| caps.get(i).map_or(0, |m| m.as_str().parse::<i64>().unwrap_or(0));
+        let start_ms = (part(1) * 3600 + part(2) * 60 + part(3)) * 1000;
+        let whole = caps.get(0).unwrap();
+        let title = format!("{} {}", &line[..whole.start()], &line[whole.end()..]);
+        let title = title.trim_matches(|c: char| c.is_whitespace() || "-–—:|•·()[]".contains(c));
+        if title.is_empty() {
+            continue;
+        }
+        if chapters.last().is_some_and(|last| last.start_ms >= start_ms) {
+            return Vec::new();
+        }
+        chapters.push(Chapter { title: title.to_string(), start_ms });
+    }
+    if chapters.len() < 3 || chapters[0].start_ms != 0 {
+        return Vec::new();
+    }
+    chapters
+}
+
+/// Seconds in a "1:02:03" or "12:34" duration.
+pub fn parse_duration(text: &str) -> Option<i32> {
+    let mut seconds = 0i32;
+    let mut parts = 0;
+    for part in text.trim().split(':') {
+        let value: i32 = part.parse().ok()?;
+        seconds = seconds.checked_mul(60)?.checked_add(value)?;
+        parts += 1;
+    }
+    (2..=3).contains(&parts).then_some(seconds)
+}
+
+/// The length a result shows on its thumbnail, from `lengthText` or else the
+/// time-status overlay. Streams that are live or upcoming have none.
+fn duration_text(renderer: &Value) -> Option<String> {
+    let overlays = renderer["thumbnailOverlays"].as_array().into_iter().flatten()
+        .map(|o| &o["thumbnailOverlayTimeStatusRenderer"]["text"]);
+    std::iter::once(&renderer["lengthText"]).chain(overlays)
+        .filter_map(|text| text["simpleText"].as_str().or(text["runs"][0]["text"].as_str()))
+        .map(str::trim)
+        .find(|t| parse_duration(t).is_some())
+        .map(String::from)
+}
+
 pub fn extract_video_basic_info(renderer: &Value) -> Option<Value> {
     let video_id = renderer["videoId"].as_str()?;
     let title = decode_html(renderer["title"]["runs"][0]["text"].as_str().unwrap_or("Unknown"));
@@ -431,6 +1984,7 @@ pub fn extract_video_basic_info(renderer: &Value) -> Option<Value> {
     
     // Try to extract handle from ownerText (e.g., "Channel Name (@handle)")
     let handle = extract_handle_from_text(&owner_text);
+    let duration = duration_text(renderer);
 
     Some(serde_json::json!({
         "id": video_id,
@@ -439,7 +1993,9 @@ pub fn extract_video_basic_info(renderer: &Value) -> Option<Value> {
         "publishedAt": published_text,
         "viewCount": view_count_text,
         "author": owner_text,
-        "handle": handle
+        "handle": handle,
+        "lengthSeconds": duration.as_deref().and_then(parse_duration),
+        "duration": duration
     }))
 }
 
@@ -468,6 +2024,11 @@ pub fn extract_playlist_video_info(renderer: &Value) -> Option<Value> {
         }
     }
 
+    let duration = duration_text(renderer);
+    let length_seconds = renderer["lengthSeconds"].as_str()
+        .and_then(|s| s.parse::<i32>().ok())
+        .or_else(|| duration.as_deref().and_then(parse_duration));
+
     Some(serde_json::json!({
         "id": video_id,
         "title": title,
@@ -475,6 +2036,903 @@ pub fn extract_playlist_video_info(renderer: &Value) -> Option<Value> {
         "publishedAt": published_at,
         "viewCount": view_count,
         "author": owner_text,
-        "handle": handle
+        "handle": handle,
+        "lengthSeconds": length_seconds,
+        "duration": duration
     }))
 }
+
+/// The text of a `simpleText` or `runs` field.
+fn text_of(value: &Value) -> Option<String> {
+    let text: String = match value["simpleText"].as_str() {
+        Some(text) => text.to_string(),
+        None => value["runs"].as_array()?.iter().filter_map(|r| r["text"].as_str()).collect(),
+    };
+    Some(decode_html(text.trim())).filter(|t| !t.is_empty())
+}
+
+/// Thumbnail URLs in search results may leave out the scheme.
+fn absolute_url(url: &str) -> String {
+    match url.strip_prefix("//") {
+        Some(rest) => format!("https://{}", rest),
+        None => url.to_string(),
+    }
+}
+
+/// A channel from a `channelRenderer` in search results. Newer results put
+/// the handle where the subscriber count used to be and the subscriber count
+/// where the video count was, so both are told apart by their text.
+pub fn extract_channel_result(renderer: &Value) -> Option<ChannelResult> {
+    let channel_id = renderer["channelId"].as_str()?;
+    let texts: Vec<String> = [&renderer["subscriberCountText"], &renderer["videoCountText"]]
+        .into_iter()
+        .filter_map(text_of)
+        .collect();
+    let handle = renderer["navigationEndpoint"]["browseEndpoint"]["canonicalBaseUrl"].as_str()
+        .and_then(|url| url.strip_prefix('/'))
+        .filter(|h| h.starts_with('@'))
+        .map(String::from)
+        .or_else(|| texts.iter().find(|t| t.starts_with('@')).cloned());
+    Some(ChannelResult {
+        channel_id: channel_id.to_string(),
+        title: text_of(&renderer["title"]).unwrap_or_else(|| "Unknown".to_string()),
+        handle,
+        thumbnail: renderer["thumbnail"]["thumbnails"].as_array()
+            .and_then(|t| t.last())
+            .and_then(|t| t["url"].as_str())
+            .map(absolute_url)
+            .unwrap_or_default(),
+        subscribers: texts.into_iter().find(|t| t.contains("subscriber")),
+        description: text_of(&renderer["descriptionSnippet"]),
+    })
+}
+
+/// A playlist from a `playlistRenderer` or `gridPlaylistRenderer` or, in
+/// newer pages, a `lockupViewModel` whose content is a playlist.
+pub fn extract_playlist_result(item: &Value) -> Option<PlaylistResult> {
+    if let Some(renderer) = item.get("gridPlaylistRenderer") {
+        return Some(PlaylistResult {
+            playlist_id: renderer["playlistId"].as_str()?.to_string(),
+            title: text_of(&renderer["title"]).unwrap_or_else(|| "Unknown".to_string()),
+            author: text_of(&renderer["shortBylineText"]),
+            thumbnail: renderer["thumbnail"]["thumbnails"].as_array()
+                .and_then(|t| t.last())
+                .and_then(|t| t["url"].as_str())
+                .map(absolute_url)
+                .unwrap_or_default(),
+            video_count: text_of(&renderer["videoCountShortText"])
+                .or_else(|| text_of(&renderer["videoCountText"]))
+                .and_then(|t| parse_count(t.split_whitespace().next()?)),
+        });
+    }
+    if let Some(renderer) = item.get("playlistRenderer") {
+        return Some(PlaylistResult {
+            playlist_id: renderer["playlistId"].as_str()?.to_string(),
+            title: text_of(&renderer["title"]).unwrap_or_else(|| "Unknown".to_string()),
+            author: text_of(&renderer["shortBylineText"]),
+            thumbnail: renderer["thumbnails"][0]["thumbnails"].as_array()
+                .or(renderer["thumbnail"]["thumbnails"].as_array())
+                .and_then(|t| t.last())
+                .and_then(|t| t["url"].as_str())
+                .map(absolute_url)
+                .unwrap_or_default(),
+            video_count: renderer["videoCount"].as_str().and_then(parse_count),
+        });
+    }
+
+    let lockup = models::Lockup::from_item(item)?;
+    if lockup.content_type != "LOCKUP_CONTENT_TYPE_PLAYLIST" {
+        return None;
+    }
+    let count_re = regex::Regex::new(r"^([\d,]+) (?:videos?|episodes?)$").unwrap();
+    let video_count = lockup.badges()
+        .find_map(|t| count_re.captures(t.trim()))
+        .and_then(|caps| parse_count(&caps[1]));
+    Some(PlaylistResult {
+        playlist_id: lockup.content_id.clone()?,
+        title: lockup.title().map(decode_html).unwrap_or_else(|| "Unknown".to_string()),
+        author: lockup.metadata_part(0, 0).map(decode_html),
+        thumbnail: lockup.thumbnail()
+            .and_then(|t| t.image.sources.first())
+            .map(|s| absolute_url(&s.url))
+            .unwrap_or_default(),
+        video_count,
+    })
+}
+
+/// A video from the watch page's sidebar: a `compactVideoRenderer`, or a
+/// `lockupViewModel` whose content is a video on newer pages.
+pub fn extract_compact_video_info(item: &Value) -> Option<Value> {
+    if let Some(renderer) = item.get("compactVideoRenderer") {
+        let author = text_of(&renderer["shortBylineText"]).or_else(|| text_of(&renderer["longBylineText"]));
+        let duration = duration_text(renderer);
+        return Some(serde_json::json!({
+            "id": renderer["videoId"].as_str()?,
+            "title": text_of(&renderer["title"]).unwrap_or_else(|| "Unknown".to_string()),
+            "thumbnail": renderer["thumbnail"]["thumbnails"].as_array()
+                .and_then(|t| t.last())
+                .and_then(|t| t["url"].as_str())
+                .unwrap_or(""),
+            "publishedAt": text_of(&renderer["publishedTimeText"]).unwrap_or_default(),
+            "viewCount": text_of(&renderer["viewCountText"]).unwrap_or_default(),
+            "author": author.as_deref().unwrap_or(""),
+            "handle": author.as_deref().and_then(extract_handle_from_text),
+            "lengthSeconds": duration.as_deref().and_then(parse_duration),
+            "duration": duration
+        }));
+    }
+
+    let lockup = models::Lockup::from_item(item)?;
+    if lockup.content_type != "LOCKUP_CONTENT_TYPE_VIDEO" {
+        return None;
+    }
+    let part = |row: usize, i: usize| lockup.metadata_part(row, i).map(decode_html);
+    let duration = lockup.badges().map(str::trim).find(|t| parse_duration(t).is_some());
+    Some(serde_json::json!({
+        "id": lockup.content_id.as_deref()?,
+        "title": lockup.title().map(decode_html).unwrap_or_else(|| "Unknown".to_string()),
+        "thumbnail": lockup.thumbnail()
+            .and_then(|t| t.image.sources.last())
+            .map_or("", |s| s.url.as_str()),
+        "publishedAt": part(1, 1).unwrap_or_default(),
+        "viewCount": part(1, 0).unwrap_or_default(),
+        "author": part(0, 0).unwrap_or_default(),
+        "lengthSeconds": duration.and_then(parse_duration),
+        "duration": duration
+    }))
+}
+
+/// The related videos in the watch page's sidebar, or in a continuation of
+/// it, and the token for the next batch.
+pub fn related_videos(data: &Value) -> (Vec<Value>, Option<String>) {
+    let items = models::NextResponse::parse(data).related().unwrap_or_default();
+    let items = items.as_slice();
+    // Signed-in pages wrap the list in a section under topic chips
+    let items = items.iter()
+        .find_map(|i| i["itemSectionRenderer"]["contents"].as_array())
+        .map(Vec::as_slice)
+        .unwrap_or(items);
+    let videos = items.iter().filter_map(extract_compact_video_info).collect();
+    (videos, continuation_token(items))
+}
+
+/// A video from a channel's Videos tab, where each entry is a
+/// `richItemRenderer` around a `videoRenderer` with no owner line; `author`
+/// fills that in.
+pub fn extract_rich_item_info(item: &Value, author: Option<&str>) -> Option<Value> {
+    let mut info = extract_video_basic_info(&item["richItemRenderer"]["content"]["videoRenderer"])?;
+    if let Some(author) = author.filter(|_| info["author"].as_str().unwrap_or("").is_empty()) {
+        info["author"] = serde_json::json!(author);
+    }
+    Some(info)
+}
+
+/// A stream from a channel's Live tab: a video plus `liveStatus` (`live`,
+/// `upcoming` or `ended`), `scheduledStart` in Unix seconds for upcoming
+/// ones, and `isPremiere` for premieres rather than streams.
+pub fn extract_stream_info(item: &Value, author: Option<&str>) -> Option<Value> {
+    let renderer = &item["richItemRenderer"]["content"]["videoRenderer"];
+    let mut info = extract_rich_item_info(item, author)?;
+
+    let upcoming = &renderer["upcomingEventData"];
+    let live_now = renderer["badges"].as_array().into_iter().flatten()
+        .any(|b| b["metadataBadgeRenderer"]["style"] == "BADGE_STYLE_TYPE_LIVE_NOW")
+        || renderer["thumbnailOverlays"].as_array().into_iter().flatten()
+            .any(|o| o["thumbnailOverlayTimeStatusRenderer"]["style"] == "LIVE");
+    let status = if upcoming.is_object() {
+        "upcoming"
+    } else if live_now {
+        "live"
+    } else {
+        "ended"
+    };
+
+    info["liveStatus"] = serde_json::json!(status);
+    info["scheduledStart"] = serde_json::json!(upcoming["startTime"].as_str().and_then(|t| t.parse::<i64>().ok()));
+    info["isPremiere"] = serde_json::json!(upcoming["upcomingEventText"]["runs"][0]["text"].as_str().is_some_and(|t| t.starts_with("Premieres")));
+    Some(info)
+}
+
+/// A Short from a channel's Shorts tab. Older pages list `reelItemRenderer`s,
+/// newer ones `shortsLockupViewModel`s; neither carries an upload date.
+pub fn extract_short_info(item: &Value, author: Option<&str>) -> Option<Value> {
+    let content = &item["richItemRenderer"]["content"];
+    let (video_id, title, thumbnail, view_count) = if let Some(reel) = content.get("reelItemRenderer") {
+        (
+            reel["videoId"].as_str()?,
+            reel["headline"]["simpleText"].as_str().unwrap_or("Unknown"),
+            reel["thumbnail"]["thumbnails"].as_array().and_then(|t| t.last()).and_then(|t| t["url"].as_str()),
+            reel["viewCountText"]["simpleText"].as_str(),
+        )
+    } else {
+        let lockup = &content["shortsLockupViewModel"];
+        (
+            lockup["onTap"]["innertubeCommand"]["reelWatchEndpoint"]["videoId"].as_str()?,
+            lockup["overlayMetadata"]["primaryText"]["content"].as_str().unwrap_or("Unknown"),
+            lockup["thumbnail"]["sources"][0]["url"].as_str(),
+            lockup["overlayMetadata"]["secondaryText"]["content"].as_str(),
+        )
+    };
+
+    Some(serde_json::json!({
+        "id": video_id,
+        "title": decode_html(title),
+        "thumbnail": thumbnail.unwrap_or(""),
+        "publishedAt": "",
+        "viewCount": view_count.unwrap_or(""),
+        "author": author.unwrap_or(""),
+        "videoType": "short"
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srv3_captions_keep_millisecond_timing() {
+        let xml = r#"<timedtext format="3"><body>
+            <p t="1200" d="2500"><s>Hello</s><s t="300"> there</s></p>
+            <p t="3700" d="1800"><s>General</s><s> Kenobi</s></p>
+        </body></timedtext>"#;
+        let transcript = parse_xml_transcript(xml).unwrap().unwrap();
+        assert_eq!(transcript.text, "Hello there\nGeneral Kenobi");
+        assert_eq!(transcript.segments[0], TranscriptSegment { start_ms: 1200, duration_ms: 2500, text: "Hello there".into() });
+        assert_eq!(transcript.segments[1].start_ms, 3700);
+    }
+
+    #[test]
+    fn legacy_captions_convert_seconds() {
+        let xml = r#"<transcript><text start="0.5" dur="1.25">It&amp;#39;s fine</text><text start="1.75" dur="2">Next</text></transcript>"#;
+        let transcript = parse_xml_transcript(xml).unwrap().unwrap();
+        assert_eq!(transcript.segments.len(), 2);
+        assert_eq!((transcript.segments[0].start_ms, transcript.segments[0].duration_ms), (500, 1250));
+        assert_eq!((transcript.segments[1].start_ms, transcript.segments[1].duration_ms), (1750, 2000));
+        assert_eq!(transcript.text.lines().nth(1), Some("Next"));
+    }
+
+    #[test]
+    fn channel_tab_reads_the_selected_tab() {
+        let data: Value = serde_json::from_str(r#"{
+            "metadata": {"channelMetadataRenderer": {"title": "Kurz &amp; Gesagt"}},
+            "contents": {"twoColumnBrowseResultsRenderer": {"tabs": [
+                {"tabRenderer": {"title": "Home"}},
+                {"tabRenderer": {"selected": true, "content": {"richGridRenderer": {"contents": [
+                    {"richItemRenderer": {"content": {"videoRenderer": {
+                        "videoId": "abc123def45",
+                        "title": {"runs": [{"text": "A video"}]},
+                        "viewCountText": {"simpleText": "1,234 views"},
+                        "publishedTimeText": {"simpleText": "2 days ago"}
+                    }}}},
+                    {"continuationItemRenderer": {"continuationEndpoint": {"continuationCommand": {"token": "more"}}}}
+                ]}}}}
+            ]}}
+        }"#).unwrap();
+        let items = channel_tab_items(&data);
+        let author = channel_title(&data);
+        let video = extract_rich_item_info(&items[0], author.as_deref()).unwrap();
+        assert_eq!(video["viewCount"], "1,234 views");
+        assert_eq!(video["publishedAt"], "2 days ago");
+        assert_eq!(video["author"], "Kurz & Gesagt");
+        assert_eq!(continuation_token(&items).as_deref(), Some("more"));
+    }
+
+    #[test]
+    fn shorts_parse_from_both_layouts() {
+        let items: Vec<Value> = serde_json::from_str(r#"[
+            {"richItemRenderer": {"content": {"reelItemRenderer": {
+                "videoId": "reel0000001",
+                "headline": {"simpleText": "Old layout"},
+                "viewCountText": {"simpleText": "12K views"}
+            }}}},
+            {"richItemRenderer": {"content": {"shortsLockupViewModel": {
+                "onTap": {"innertubeCommand": {"reelWatchEndpoint": {"videoId": "lockup00001"}}},
+                "overlayMetadata": {"primaryText": {"content": "New layout"}, "secondaryText": {"content": "3.4M views"}},
+                "thumbnail": {"sources": [{"url": "https://i.ytimg.com/vi/lockup00001/oar2.jpg"}]}
+            }}}}
+        ]"#).unwrap();
+        let old = extract_short_info(&items[0], Some("Channel")).unwrap();
+        assert_eq!((old["id"].as_str(), old["viewCount"].as_str()), (Some("reel0000001"), Some("12K views")));
+        let new = extract_short_info(&items[1], None).unwrap();
+        assert_eq!((new["title"].as_str(), new["videoType"].as_str()), (Some("New layout"), Some("short")));
+    }
+
+    #[test]
+    fn streams_report_live_and_upcoming() {
+        let items: Vec<Value> = serde_json::from_str(r#"[
+            {"richItemRenderer": {"content": {"videoRenderer": {
+                "videoId": "upcoming001",
+                "title": {"runs": [{"text": "Launch"}]},
+                "upcomingEventData": {"startTime": "1767225600", "upcomingEventText": {"runs": [{"text": "Premieres "}, {"text": "1/1/26, 12:00 AM"}]}}
+            }}}},
+            {"richItemRenderer": {"content": {"videoRenderer": {
+                "videoId": "livenow0001",
+                "title": {"runs": [{"text": "Live now"}]},
+                "badges": [{"metadataBadgeRenderer": {"style": "BADGE_STYLE_TYPE_LIVE_NOW", "label": "LIVE"}}]
+            }}}},
+            {"richItemRenderer": {"content": {"videoRenderer": {
+                "videoId": "finished001",
+                "title": {"runs": [{"text": "Yesterday"}]},
+                "publishedTimeText": {"simpleText": "Streamed 1 day ago"}
+            }}}}
+        ]"#).unwrap();
+        let upcoming = extract_stream_info(&items[0], None).unwrap();
+        assert_eq!(upcoming["liveStatus"], "upcoming");
+        assert_eq!(upcoming["scheduledStart"], 1767225600);
+        assert_eq!(upcoming["isPremiere"], true);
+        let stream: crate::types::LiveStream = serde_json::from_value(upcoming).unwrap();
+        assert_eq!((stream.video.id.as_str(), stream.scheduled_start), ("upcoming001", Some(1767225600)));
+        assert_eq!(extract_stream_info(&items[1], None).unwrap()["liveStatus"], "live");
+        let ended = extract_stream_info(&items[2], None).unwrap();
+        assert_eq!((ended["liveStatus"].as_str(), ended["scheduledStart"].is_null()), (Some("ended"), true));
+    }
+
+    #[test]
+    fn chapters_prefer_the_creators_markers() {
+        let chapter = |title: &str, ms: i64| serde_json::json!({"chapterRenderer": {"title": {"simpleText": title}, "timeRangeStartMillis": ms}});
+        let next = serde_json::json!({"playerOverlays": {"playerOverlayRenderer": {"decoratedPlayerBarRenderer": {"decoratedPlayerBarRenderer": {
+            "playerBar": {"multiMarkersPlayerBarRenderer": {"markersMap": [
+                {"key": "AUTO_CHAPTERS", "value": {"chapters": [chapter("Auto", 0)]}},
+                {"key": "DESCRIPTION_CHAPTERS", "value": {"chapters": [chapter("Intro", 0), chapter("Q &amp; A", 95000)]}}
+            ]}}
+        }}}}});
+        let chapters = parse_chapters(&next);
+        assert_eq!(chapters[1], Chapter { title: "Q & A".into(), start_ms: 95000 });
+        assert!(parse_chapters(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn description_chapters_follow_youtubes_rules() {
+        let description = "Thanks for watching!\n\n0:00 Intro\n1:30 - Setup\nThe build (12:05)\n1:02:03 Outro\n\nSources below.";
+        let chapters = chapters_from_description(description);
+        let starts: Vec<i64> = chapters.iter().map(|c| c.start_ms).collect();
+        assert_eq!(starts, [0, 90_000, 725_000, 3_723_000]);
+        assert_eq!((chapters[1].title.as_str(), chapters[2].title.as_str()), ("Setup", "The build"));
+
+        assert!(chapters_from_description("0:00 Intro\n1:00 Middle").is_empty());
+        assert!(chapters_from_description("0:10 Intro\n1:00 Middle\n2:00 End").is_empty());
+        assert!(chapters_from_description("0:00 Intro\n3:00 Middle\n2:00 End").is_empty());
+    }
+
+    #[test]
+    fn engagement_reads_likes_and_comments() {
+        let next: Value = serde_json::from_str(r#"{
+            "contents": {"twoColumnWatchNextResults": {"results": {"results": {"contents": [
+                {"videoPrimaryInfoRenderer": {"videoActions": {"menuRenderer": {"topLevelButtons": [
+                    {"segmentedLikeDislikeButtonViewModel": {"likeButtonViewModel": {"likeButtonViewModel": {"toggleButtonViewModel": {"toggleButtonViewModel": {
+                        "defaultButtonViewModel": {"buttonViewModel": {"title": "12K", "accessibilityText": "like this video along with 12,345 other people"}}
+                    }}}}}}
+                ]}}}}
+            ]}}}},
+            "engagementPanels": [
+                {"engagementPanelSectionListRenderer": {"panelIdentifier": "engagement-panel-structured-description"}},
+                {"engagementPanelSectionListRenderer": {"panelIdentifier": "engagement-panel-comments-section",
+                    "header": {"engagementPanelTitleHeaderRenderer": {"contextualInfo": {"runs": [{"text": "1.2K"}]}}}}}
+            ]
+        }"#).unwrap();
+        assert_eq!(parse_engagement(&next), Engagement { like_count: Some(12345), comment_count: Some(1200) });
+        assert_eq!(parse_engagement(&serde_json::json!({})), Engagement::default());
+        assert_eq!(parse_count("3M"), Some(3_000_000));
+    }
+
+    #[test]
+    fn comments_parse_from_both_layouts() {
+        let page: Value = serde_json::from_str(r#"{
+            "onResponseReceivedEndpoints": [
+                {"reloadContinuationItemsCommand": {"continuationItems": [{"commentsHeaderRenderer": {}}]}},
+                {"reloadContinuationItemsCommand": {"continuationItems": [
+                    {"commentThreadRenderer": {"comment": {"commentRenderer": {
+                        "commentId": "old1",
+                        "authorText": {"simpleText": "@first"},
+                        "contentText": {"runs": [{"text": "Great "}, {"text": "video"}]},
+                        "voteCount": {"simpleText": "1.5K"},
+                        "replyCount": 4,
+                        "publishedTimeText": {"runs": [{"text": "1 year ago"}]}
+                    }}}},
+                    {"commentThreadRenderer": {"commentViewModel": {"commentViewModel": {"commentId": "new1"}}}},
+                    {"continuationItemRenderer": {"continuationEndpoint": {"continuationCommand": {"token": "page2"}}}}
+                ]}}
+            ],
+            "frameworkUpdates": {"entityBatchUpdate": {"mutations": [
+                {"payload": {"commentEntityPayload": {
+                    "properties": {"commentId": "new1", "content": {"content": "Second"}, "publishedTime": "2 days ago"},
+                    "author": {"displayName": "@second"},
+                    "toolbar": {"likeCountNotliked": "12", "replyCount": "3"}
+                }}}
+            ]}}
+        }"#).unwrap();
+        let (comments, next) = parse_comments(&page);
+        assert_eq!(next.as_deref(), Some("page2"));
+        assert_eq!(comments.len(), 2);
+        assert_eq!((comments[0].text.as_str(), comments[0].like_count, comments[0].reply_count), ("Great video", 1500, 4));
+        assert_eq!((comments[1].author.as_str(), comments[1].like_count, comments[1].reply_count), ("@second", 12, 3));
+    }
+
+    #[test]
+    fn channel_details_from_the_page_header() {
+        let data: Value = serde_json::from_str(r#"{
+            "metadata": {"channelMetadataRenderer": {
+                "title": "Tom Scott", "externalId": "UCBa659QWEk1AI4Tg--mrJ2A", "description": "Videos.",
+                "vanityChannelUrl": "http://www.youtube.com/@TomScottGo",
+                "avatar": {"thumbnails": [{"url": "https://yt3.example/avatar=s900"}]}
+            }},
+            "header": {"pageHeaderRenderer": {"content": {"pageHeaderViewModel": {
+                "banner": {"imageBannerViewModel": {"image": {"sources": [{"url": "https://yt3.example/banner=w1060"}, {"url": "https://yt3.example/banner=w2560"}]}}},
+                "metadata": {"contentMetadataViewModel": {"metadataRows": [
+                    {"metadataParts": [{"text": {"content": "@TomScottGo"}}]},
+                    {"metadataParts": [{"text": {"content": "6.5M subscribers"}}, {"text": {"content": "1,234 videos"}}]}
+                ]}}
+            }}}}
+        }"#).unwrap();
+        let details = parse_channel_details("@TomScottGo", &data);
+        assert_eq!(details.channel_id, "UCBa659QWEk1AI4Tg--mrJ2A");
+        assert_eq!(details.handle.as_deref(), Some("@TomScottGo"));
+        assert_eq!(details.banner.as_deref(), Some("https://yt3.example/banner=w2560"));
+        assert_eq!((details.subscriber_count, details.video_count), (Some(6_500_000), Some(1234)));
+    }
+
+    #[test]
+    fn playlist_details_from_sidebar_and_header() {
+        let sidebar: Value = serde_json::from_str(r#"{
+            "metadata": {"playlistMetadataRenderer": {"title": "Lectures", "description": "All of them."}},
+            "sidebar": {"playlistSidebarRenderer": {"items": [
+                {"playlistSidebarPrimaryInfoRenderer": {"stats": [{"runs": [{"text": "1,024"}, {"text": " videos"}]}, {"simpleText": "5,000 views"}]}},
+                {"playlistSidebarSecondaryInfoRenderer": {"videoOwner": {"videoOwnerRenderer": {"title": {"runs": [{"text": "MIT OpenCourseWare"}]}}}}}
+            ]}}
+        }"#).unwrap();
+        let details = parse_playlist_details("PL1", &sidebar);
+        assert_eq!((details.title.as_str(), details.owner.as_deref(), details.video_count), ("Lectures", Some("MIT OpenCourseWare"), Some(1024)));
+
+        let header: Value = serde_json::from_str(r#"{
+            "metadata": {"playlistMetadataRenderer": {"title": "Mixes"}},
+            "header": {"pageHeaderRenderer": {"content": {"pageHeaderViewModel": {"metadata": {"contentMetadataViewModel": {"metadataRows": [
+                {"metadataParts": [{"text": {"content": "by Someone"}}]},
+                {"metadataParts": [{"text": {"content": "Playlist"}}, {"text": {"content": "12 videos"}}]}
+            ]}}}}}}
+        }"#).unwrap();
+        let details = parse_playlist_details("PL2", &header);
+        assert_eq!((details.owner.as_deref(), details.video_count), (Some("Someone"), Some(12)));
+    }
+
+    #[test]
+    fn channel_feed_lists_entries() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <feed xmlns:yt="http://www.youtube.com/xml/schemas/2015" xmlns:media="http://search.yahoo.com/mrss/" xmlns="http://www.w3.org/2005/Atom">
+                <title>Channel</title>
+                <author><name>Channel</name></author>
+                <entry>
+                    <yt:videoId>abc123def45</yt:videoId>
+                    <title>Fish &amp; Chips</title>
+                    <author><name>Channel</name></author>
+                    <published>2024-05-01T12:00:00+00:00</published>
+                    <media:group>
+                        <media:thumbnail url="https://i2.ytimg.com/vi/abc123def45/hqdefault.jpg" width="480" height="360"/>
+                        <media:community><media:statistics views="4321"/></media:community>
+                    </media:group>
+                </entry>
+            </feed>"#;
+        let videos = parse_channel_feed(xml).unwrap();
+        assert_eq!(videos.len(), 1);
+        assert_eq!(videos[0]["title"], "Fish & Chips");
+        assert_eq!(videos[0]["author"], "Channel");
+        assert_eq!(videos[0]["viewCount"], "4321");
+        assert_eq!(videos[0]["publishedAt"], "2024-05-01T12:00:00+00:00");
+        let video: crate::types::Video = serde_json::from_value(videos[0].clone()).unwrap();
+        assert_eq!(video.thumbnail, "https://i2.ytimg.com/vi/abc123def45/hqdefault.jpg");
+    }
+
+    #[test]
+    fn continuation_token_comes_from_the_last_item() {
+        let items: Vec<Value> = serde_json::from_str(r#"[
+            {"itemSectionRenderer": {"contents": []}},
+            {"continuationItemRenderer": {"continuationEndpoint": {"continuationCommand": {"token": "next-page"}}}}
+        ]"#).unwrap();
+        assert_eq!(continuation_token(&items).as_deref(), Some("next-page"));
+        assert_eq!(continuation_token(&items[..1]), None);
+    }
+
+    #[test]
+    fn visitor_data_is_read_from_service_worker_data() {
+        let text = r#")]}'
+[[null,null,[[[null,null,null,null,null,null,null,null,null,null,null,null,null,"CgtWaXNpdG9ySWQ%3D"]]]]]"#;
+        assert_eq!(parse_visitor_data(text).as_deref(), Some("CgtWaXNpdG9ySWQ%3D"));
+        assert_eq!(parse_visitor_data(")]}'\n[]"), None);
+        assert_eq!(parse_visitor_data("<html>"), None);
+    }
+
+    #[test]
+    fn caption_track_follows_the_interface_language() {
+        let tracks: Vec<Value> = serde_json::from_str(r#"[
+            {"languageCode": "de"}, {"languageCode": "en"}, {"languageCode": "pt"}, {"languageCode": "pt-BR"}
+        ]"#).unwrap();
+        let code = |language| pick_caption_track(&tracks, language).unwrap()["languageCode"].as_str().unwrap();
+        assert_eq!(code("pt-BR"), "pt-BR");
+        assert_eq!(code("pt-PT"), "pt");
+        assert_eq!(code("ja"), "en");
+        assert_eq!(pick_caption_track(&tracks[..1], "ja").unwrap()["languageCode"], "de");
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_a_cap_with_jitter() {
+        for (attempt, base) in [(1, 500), (2, 1000), (3, 2000), (5, 8000), (9, 8000)] {
+            let ms = backoff_delay(attempt).as_millis() as u64;
+            assert!(ms >= base && ms <= base + base / 4, "attempt {} waited {}ms", attempt, ms);
+        }
+    }
+
+    #[test]
+    fn cookies_txt_keeps_current_youtube_cookies() {
+        let text = "# Netscape HTTP Cookie File\n\
+            .youtube.com\tTRUE\t/\tTRUE\t0\tSAPISID\tabc/def\n\
+            #HttpOnly_.youtube.com\tTRUE\t/\tTRUE\t0\tLOGIN_INFO\txyz\n\
+            .youtube.com\tTRUE\t/\tTRUE\t1\tOLD\tgone\n\
+            .example.com\tTRUE\t/\tFALSE\t0\tOTHER\tnope\n";
+        assert_eq!(parse_cookies(text), Some(("SAPISID=abc/def; LOGIN_INFO=xyz".to_string(), 2)));
+        assert_eq!(parse_cookies("Cookie: HSID=a; SAPISID=b"), Some(("HSID=a; SAPISID=b".to_string(), 2)));
+        assert_eq!(parse_cookies("  "), None);
+
+        // Accounts are told apart by SAPISID alone
+        assert_eq!(account_digest("SAPISID=abc; HSID=1"), account_digest("HSID=2; SAPISID=abc"));
+        assert_ne!(account_digest("SAPISID=abc"), account_digest("SAPISID=xyz"));
+        assert_eq!(account_digest("SAPISID=abc").len(), 16);
+    }
+
+    #[test]
+    fn sapisid_hash_signs_the_timestamp_and_origin() {
+        // sha1("1700000000 abc https://www.youtube.com")
+        assert_eq!(
+            sapisid_hash("HSID=x; SAPISID=abc", 1700000000, YOUTUBE_ORIGIN).as_deref(),
+            Some("SAPISIDHASH 1700000000_27b236f59d4ec583d7530f2c7055d2f9c6aecf92"),
+        );
+        assert_eq!(sapisid_hash("HSID=x", 1700000000, YOUTUBE_ORIGIN), None);
+    }
+
+    #[test]
+    fn age_gates_are_told_apart_from_other_refusals() {
+        let player = |status: &str, reason: &str| serde_json::json!({ "playabilityStatus": { "status": status, "reason": reason } });
+        assert!(is_age_restricted(&player("LOGIN_REQUIRED", "Sign in to confirm your age")));
+        assert!(is_age_restricted(&player("AGE_VERIFICATION_REQUIRED", "")));
+        assert!(is_age_restricted(&player("UNPLAYABLE", "This video may be inappropriate for some users.")));
+        assert!(!is_age_restricted(&player("LOGIN_REQUIRED", "This video is private")));
+        assert!(!is_age_restricted(&player("OK", "")));
+    }
+
+    #[test]
+    fn refusals_are_classified_by_status_and_reason() {
+        let player = |status: &str, reason: &str| serde_json::json!({ "playabilityStatus": { "status": status, "reason": reason } });
+        assert_eq!(Unplayable::from_player(&player("OK", "")), None);
+        assert_eq!(Unplayable::from_player(&player("LOGIN_REQUIRED", "This video is private")), Some(Unplayable::Private));
+        assert_eq!(Unplayable::from_player(&player("ERROR", "Video unavailable")), Some(Unplayable::Removed("Video unavailable".into())));
+        assert_eq!(
+            Unplayable::from_player(&player("UNPLAYABLE", "The uploader has not made this video available in your country")),
+            Some(Unplayable::RegionLocked),
+        );
+        assert_eq!(Unplayable::from_player(&player("LIVE_STREAM_OFFLINE", "Premieres in 2 hours")), Some(Unplayable::LiveOnly));
+        assert_eq!(Unplayable::from_player(&player("LOGIN_REQUIRED", "Sign in to confirm your age")), Some(Unplayable::AgeRestricted("Sign in to confirm your age".into())));
+        assert_eq!(Unplayable::from_player(&player("UNPLAYABLE", "Join this channel to get access to members-only content")), Some(Unplayable::MembersOnly));
+
+        assert_eq!(availability(&player("OK", "")), Some("available"));
+        assert_eq!(availability(&player("UNPLAYABLE", "Video unavailable in your country")), Some("available"));
+        assert_eq!(availability(&player("LOGIN_REQUIRED", "This video is private")), Some("private"));
+        assert_eq!(availability(&player("ERROR", "This video has been removed by the uploader")), Some("removed"));
+        assert_eq!(availability(&player("LOGIN_REQUIRED", "Sign in to confirm you're not a bot")), None);
+    }
+
+    #[test]
+    fn caption_tracks_are_found_on_either_path() {
+        let player = serde_json::json!({ "captions": { "captionTracks": [{ "languageCode": "es-419" }] } });
+        assert_eq!(caption_tracks(&player).len(), 1);
+        assert!(caption_tracks(&Value::Null).is_empty());
+        assert!(same_language("es-419", "ES"));
+        assert!(!same_language("es", "en"));
+    }
+
+    #[test]
+    fn caption_tracks_report_name_and_kind() {
+        let player = serde_json::json!({ "captions": { "playerCaptionsTracklistRenderer": { "captionTracks": [
+            { "languageCode": "en", "name": { "runs": [{ "text": "English (auto-generated)" }] }, "kind": "asr", "isTranslatable": true },
+            { "languageCode": "de", "name": { "simpleText": "German" } },
+            { "name": { "simpleText": "No code" } }
+        ] } } });
+        assert_eq!(parse_caption_tracks(&player), [
+            CaptionTrack { language_code: "en".into(), name: "English (auto-generated)".into(), auto_generated: true, translatable: true },
+            CaptionTrack { language_code: "de".into(), name: "German".into(), auto_generated: false, translatable: false },
+        ]);
+    }
+
+    #[test]
+    fn result_durations_come_from_length_text_or_overlay() {
+        assert_eq!(parse_duration("1:02:03"), Some(3723));
+        assert_eq!(parse_duration("12:34"), Some(754));
+        assert_eq!(parse_duration("LIVE"), None);
+        assert_eq!(parse_duration("45"), None);
+
+        let search = serde_json::json!({ "videoId": "abc", "lengthText": { "simpleText": "4:05" } });
+        let info = extract_video_basic_info(&search).unwrap();
+        assert_eq!((info["duration"].as_str(), info["lengthSeconds"].as_i64()), (Some("4:05"), Some(245)));
+
+        let playlist = serde_json::json!({
+            "videoId": "def",
+            "thumbnailOverlays": [
+                { "thumbnailOverlayResumePlaybackRenderer": {} },
+                { "thumbnailOverlayTimeStatusRenderer": { "text": { "runs": [{ "text": "1:00:00" }] } } }
+            ]
+        });
+        let info = extract_playlist_video_info(&playlist).unwrap();
+        assert_eq!((info["duration"].as_str(), info["lengthSeconds"].as_i64()), (Some("1:00:00"), Some(3600)));
+
+        let live = serde_json::json!({ "videoId": "ghi", "thumbnailOverlays": [{ "thumbnailOverlayTimeStatusRenderer": { "text": { "simpleText": "LIVE" } } }] });
+        assert!(extract_video_basic_info(&live).unwrap()["duration"].is_null());
+    }
+
+    #[test]
+    fn resolved_urls_give_the_channel_id() {
+        let data = serde_json::json!({ "endpoint": { "browseEndpoint": { "browseId": "UCBJycsmduvYEL83R_U4JriQ", "canonicalBaseUrl": "/@mkbhd" } } });
+        assert_eq!(parse_resolved_channel_id(&data).as_deref(), Some("UCBJycsmduvYEL83R_U4JriQ"));
+        let watch = serde_json::json!({ "endpoint": { "watchEndpoint": { "videoId": "dQw4w9WgXcQ" } } });
+        assert_eq!(parse_resolved_channel_id(&watch), None);
+        assert_eq!(parse_resolved_browse(&data), None);
+
+        let hashtag = serde_json::json!({ "endpoint": { "browseEndpoint": { "browseId": "FEhashtag", "params": "ggMGCgRyZXRybw==" } } });
+        assert_eq!(parse_resolved_browse(&hashtag), Some(("FEhashtag".to_string(), "ggMGCgRyZXRybw==".to_string())));
+        assert_eq!(parse_resolved_channel_id(&hashtag), None);
+    }
+
+    #[test]
+    fn search_filters_encode_like_youtubes_own() {
+        assert_eq!(search_params(&SearchFilters::default()), None);
+        let sorted = SearchFilters { sort: Some(SearchSort::UploadDate), ..Default::default() };
+        assert_eq!(search_params(&sorted).as_deref(), Some("CAI="));
+        let this_week = SearchFilters { upload_date: Some(UploadDate::ThisWeek), ..Default::default() };
+        assert_eq!(search_params(&this_week).as_deref(), Some("EgIIAw=="));
+        let all = SearchFilters { upload_date: Some(UploadDate::Today), duration: Some(SearchDuration::Short), sort: Some(SearchSort::ViewCount) };
+        assert_eq!(search_params(&all).as_deref(), Some("CAMSBAgCGAE="));
+    }
+
+    #[test]
+    fn channels_and_playlists_are_read_from_search_results() {
+        let channel = serde_json::json!({
+            "channelId": "UCBJycsmduvYEL83R_U4JriQ",
+            "title": { "simpleText": "Marques Brownlee" },
+            "navigationEndpoint": { "browseEndpoint": { "canonicalBaseUrl": "/@mkbhd" } },
+            "thumbnail": { "thumbnails": [{ "url": "//yt3.ggpht.com/a=s88" }, { "url": "//yt3.ggpht.com/a=s176" }] },
+            "subscriberCountText": { "simpleText": "@mkbhd" },
+            "videoCountText": { "simpleText": "19.6M subscribers" }
+        });
+        let channel = extract_channel_result(&channel).unwrap();
+        assert_eq!(channel.handle.as_deref(), Some("@mkbhd"));
+        assert_eq!(channel.subscribers.as_deref(), Some("19.6M subscribers"));
+        assert_eq!(channel.thumbnail, "https://yt3.ggpht.com/a=s176");
+
+        let playlist = serde_json::json!({ "playlistRenderer": {
+            "playlistId": "PL123",
+            "title": { "simpleText": "Studio tours" },
+            "videoCount": "12",
+            "shortBylineText": { "runs": [{ "text": "Some Channel" }] }
+        }});
+        let playlist = extract_playlist_result(&playlist).unwrap();
+        assert_eq!((playlist.playlist_id.as_str(), playlist.video_count), ("PL123", Some(12)));
+        assert_eq!(playlist.author.as_deref(), Some("Some Channel"));
+
+        let lockup = serde_json::json!({ "lockupViewModel": {
+            "contentId": "PL456",
+            "contentType": "LOCKUP_CONTENT_TYPE_PLAYLIST",
+            "contentImage": { "collectionThumbnailViewModel": { "primaryThumbnail": { "thumbnailViewModel": {
+                "image": { "sources": [{ "url": "https://i.ytimg.com/vi/x/hqdefault.jpg" }] },
+                "overlays": [{ "thumbnailOverlayBadgeViewModel": { "thumbnailBadges": [{ "thumbnailBadgeViewModel": { "text": "1,204 videos" } }] } }]
+            }}}},
+            "metadata": { "lockupMetadataViewModel": { "title": { "content": "Every episode" } } }
+        }});
+        let playlist = extract_playlist_result(&lockup).unwrap();
+        assert_eq!((playlist.title.as_str(), playlist.video_count), ("Every episode", Some(1204)));
+        let video_lockup = serde_json::json!({ "lockupViewModel": { "contentId": "abc", "contentType": "LOCKUP_CONTENT_TYPE_VIDEO" } });
+        assert_eq!(extract_playlist_result(&video_lockup), None);
+    }
+
+    #[test]
+    fn trending_videos_are_collected_from_every_shelf_once() {
+        let video = |id: &str| serde_json::json!({ "videoRenderer": { "videoId": id, "title": { "runs": [{ "text": id }] } } });
+        let data = serde_json::json!({ "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [{ "tabRenderer": { "content": { "sectionListRenderer": { "contents": [
+            { "itemSectionRenderer": { "contents": [{ "shelfRenderer": { "content": { "expandedShelfContentsRenderer": { "items": [video("a"), video("b")] } } } }] } },
+            { "itemSectionRenderer": { "contents": [{ "shelfRenderer": { "content": { "horizontalListRenderer": { "items": [video("c"), video("a")] } } } }] } }
+        ] } } } }] } } });
+        let ids: Vec<String> = trending_videos(&data).iter().map(|v| v["id"].as_str().unwrap().to_string()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn related_videos_come_from_either_sidebar_layout() {
+        let data = serde_json::json!({ "contents": { "twoColumnWatchNextResults": { "secondaryResults": { "secondaryResults": { "results": [
+            { "compactVideoRenderer": {
+                "videoId": "a",
+                "title": { "simpleText": "Old layout" },
+                "shortBylineText": { "runs": [{ "text": "Channel A" }] },
+                "lengthText": { "simpleText": "3:10" }
+            } },
+            { "lockupViewModel": {
+                "contentId": "b",
+                "contentType": "LOCKUP_CONTENT_TYPE_VIDEO",
+                "contentImage": { "thumbnailViewModel": { "overlays": [{ "thumbnailOverlayBadgeViewModel": { "thumbnailBadges": [{ "thumbnailBadgeViewModel": { "text": "12:00" } }] } }] } },
+                "metadata": { "lockupMetadataViewModel": {
+                    "title": { "content": "New layout" },
+                    "metadata": { "contentMetadataViewModel": { "metadataRows": [
+                        { "metadataParts": [{ "text": { "content": "Channel B" } }] },
+                        { "metadataParts": [{ "text": { "content": "1.2K views" } }, { "text": { "content": "2 days ago" } }] }
+                    ] } }
+                } }
+            } },
+            { "lockupViewModel": { "contentId": "PL1", "contentType": "LOCKUP_CONTENT_TYPE_PLAYLIST" } },
+            { "continuationItemRenderer": { "continuationEndpoint": { "continuationCommand": { "token": "more" } } } }
+        ] } } } } });
+        let (videos, continuation) = related_videos(&data);
+        assert_eq!(continuation.as_deref(), Some("more"));
+        assert_eq!(videos.len(), 2);
+        assert_eq!((videos[0]["author"].as_str(), videos[0]["lengthSeconds"].as_i64()), (Some("Channel A"), Some(190)));
+        assert_eq!((videos[1]["title"].as_str(), videos[1]["viewCount"].as_str()), (Some("New layout"), Some("1.2K views")));
+        assert_eq!(videos[1]["duration"].as_str(), Some("12:00"));
+    }
+
+    #[test]
+    fn heatmaps_are_read_from_either_layout_and_scaled() {
+        let entity = serde_json::json!({ "frameworkUpdates": { "entityBatchUpdate": { "mutations": [
+            { "payload": { "macroMarkersListEntity": { "markersList": { "markerType": "MARKER_TYPE_TIMESTAMPS", "markers": [] } } } },
+            { "payload": { "macroMarkersListEntity": { "markersList": { "markerType": "MARKER_TYPE_HEATMAP", "markers": [
+                { "startMillis": "5000", "durationMillis": "5000", "intensityScoreNormalized": 0.25 },
+                { "startMillis": "0", "durationMillis": "5000", "intensityScoreNormalized": 0.5 }
+            ] } } } }
+        ] } } });
+        assert_eq!(parse_heatmap(&entity), vec![
+            HeatmapPoint { start_ms: 0, duration_ms: 5000, intensity: 1.0 },
+            HeatmapPoint { start_ms: 5000, duration_ms: 5000, intensity: 0.5 },
+        ]);
+
+        let legacy = serde_json::json!({ "playerOverlays": { "playerOverlayRenderer": { "decoratedPlayerBarRenderer": { "decoratedPlayerBarRenderer": { "playerBar": {
+            "multiMarkersPlayerBarRenderer": { "markersMap": [{ "key": "HEATSEEKER", "value": { "heatmap": { "heatmapRenderer": { "heatMarkers": [
+                { "heatMarkerRenderer": { "timeRangeStartMillis": 0, "markerDurationMillis": 2000, "heatMarkerIntensityScoreNormalized": 1.0 } }
+            ] } } } }] }
+        } } } } } });
+        assert_eq!(parse_heatmap(&legacy).len(), 1);
+        assert!(parse_heatmap(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn thumbnails_fall_back_to_smaller_sizes() {
+        assert_eq!(thumbnail_url("abc123def45", ThumbnailQuality::Maxres), "https://i.ytimg.com/vi/abc123def45/maxresdefault.jpg");
+        assert_eq!(thumbnail_url("abc123def45", ThumbnailQuality::default()), "https://i.ytimg.com/vi/abc123def45/hqdefault.jpg");
+        assert_eq!(thumbnail_fallbacks(ThumbnailQuality::Sd), [ThumbnailQuality::Sd, ThumbnailQuality::Hq, ThumbnailQuality::Mq]);
+        assert_eq!(thumbnail_fallbacks(ThumbnailQuality::Mq), [ThumbnailQuality::Mq]);
+    }
+
+    #[test]
+    fn storyboard_uses_the_largest_level() {
+        let player = serde_json::json!({
+            "videoDetails": { "lengthSeconds": "300" },
+            "storyboards": { "playerStoryboardSpecRenderer": {
+                "spec": "https://i.ytimg.com/sb/abc123def45/storyboard3_L$L/$N.jpg?sqp=x|48#27#100#10#10#0#default#rs$A|160#90#120#5#5#2500#M$M#rs$B"
+            } }
+        });
+        let board = parse_storyboard(&player).unwrap();
+        assert_eq!((board.width, board.height, board.count, board.columns, board.rows, board.interval_ms), (160, 90, 120, 5, 5, 2500));
+        assert_eq!(board.sheets.len(), 5);
+        assert_eq!(board.sheets[4], "https://i.ytimg.com/sb/abc123def45/storyboard3_L1/M4.jpg?sqp=x&sigh=rs$B");
+
+        let small_only = serde_json::json!({
+            "videoDetails": { "lengthSeconds": "300" },
+            "storyboards": { "playerStoryboardSpecRenderer": {
+                "spec": "https://i.ytimg.com/sb/abc123def45/storyboard3_L$L/$N.jpg?sqp=x|48#27#100#10#10#0#default#rs$A"
+            } }
+        });
+        let board = parse_storyboard(&small_only).unwrap();
+        assert_eq!((board.interval_ms, board.sheets.len()), (3000, 1));
+        assert_eq!(board.sheets[0], "https://i.ytimg.com/sb/abc123def45/storyboard3_L0/default.jpg?sqp=x&sigh=rs$A");
+        assert_eq!(parse_storyboard(&Value::Null), None);
+    }
+
+    #[test]
+    fn channel_playlists_come_from_the_grid() {
+        let data = serde_json::json!({
+            "metadata": { "channelMetadataRenderer": { "title": "Crash Course" } },
+            "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [{ "tabRenderer": { "selected": true, "content": { "sectionListRenderer": { "contents": [
+                { "itemSectionRenderer": { "contents": [{ "gridRenderer": { "items": [
+                    { "gridPlaylistRenderer": {
+                        "playlistId": "PLbiology",
+                        "title": { "runs": [{ "text": "Biology" }] },
+                        "thumbnail": { "thumbnails": [{ "url": "https://i.ytimg.com/vi/x/hqdefault.jpg" }] },
+                        "videoCountShortText": { "simpleText": "1,234" }
+                    } },
+                    { "continuationItemRenderer": { "continuationEndpoint": { "continuationCommand": { "token": "more" } } } }
+                ] } }] } }
+            ] } } } }] } }
+        });
+        let (playlists, continuation) = channel_playlists(&data);
+        assert_eq!(playlists, [PlaylistResult {
+            playlist_id: "PLbiology".to_string(),
+            title: "Biology".to_string(),
+            author: Some("Crash Course".to_string()),
+            thumbnail: "https://i.ytimg.com/vi/x/hqdefault.jpg".to_string(),
+            video_count: Some(1234),
+        }]);
+        assert_eq!(continuation.as_deref(), Some("more"));
+        assert_eq!(channel_playlists(&Value::Null), (Vec::new(), None));
+    }
+
+    #[test]
+    fn client_version_is_read_from_the_page_config() {
+        let html = r#"<script>ytcfg.set({"INNERTUBE_API_KEY":"x","INNERTUBE_CLIENT_NAME":"WEB","INNERTUBE_CLIENT_VERSION":"2.20261014.01.00","LINK_API_KEY":"y"});</script>"#;
+        assert_eq!(parse_client_version(html).as_deref(), Some("2.20261014.01.00"));
+        assert_eq!(parse_client_version(r#""INNERTUBE_CONTEXT_CLIENT_VERSION":"2.20250101.00.00""#).as_deref(), Some("2.20250101.00.00"));
+        assert_eq!(parse_client_version(r#""INNERTUBE_CLIENT_NAME":"WEB_REMIX","INNERTUBE_CLIENT_VERSION":"1.20261012.01.00""#).as_deref(), Some("1.20261012.01.00"));
+        assert_eq!(parse_client_version(r#""INNERTUBE_CLIENT_VERSION":"1.0""#), None);
+        assert_eq!(ClientType::from_name("tv_embedded"), Some(ClientType::TvEmbedded));
+        assert_eq!(ClientType::from_name("WEB"), None);
+    }
+
+    #[test]
+    fn community_posts_keep_text_images_and_polls() {
+        let post = |id: &str, attachment: Value| serde_json::json!({ "backstagePostThreadRenderer": { "post": { "backstagePostRenderer": {
+            "postId": id,
+            "authorText": { "runs": [{ "text": "Tom Scott" }] },
+            "authorEndpoint": { "browseEndpoint": { "browseId": "UCBa659QWEk1AI4Tg--mrJ2A" } },
+            "contentText": { "runs": [{ "text": "New video out.\n" }, { "text": "Which next?" }] },
+            "publishedTimeText": { "runs": [{ "text": "2 days ago" }] },
+            "voteCount": { "simpleText": "1.2K" },
+            "backstageAttachment": attachment
+        } } } });
+        let data = serde_json::json!({ "contents": { "itemSectionRenderer": { "contents": [
+            post("Ugpoll", serde_json::json!({ "pollRenderer": {
+                "choices": [{ "text": { "runs": [{ "text": "Trains" }] }, "votePercentage": { "simpleText": "64%" } }, { "text": { "runs": [{ "text": "Bridges" }] } }],
+                "totalVotes": { "simpleText": "8K votes" }
+            } })),
+            post("Ugimages", serde_json::json!({ "postMultiImageRenderer": { "images": [
+                { "backstageImageRenderer": { "image": { "thumbnails": [{ "url": "https://yt3.ggpht.com/a=s288" }, { "url": "https://yt3.ggpht.com/a=s1080" }] } } },
+                { "backstageImageRenderer": { "image": { "thumbnails": [{ "url": "//yt3.ggpht.com/b=s1080" }] } } }
+            ] } })),
+            { "continuationItemRenderer": { "continuationEndpoint": { "continuationCommand": { "token": "more-posts" } } } }
+        ] } } });
+
+        let (posts, continuation) = community_posts(&data);
+        assert_eq!(continuation.as_deref(), Some("more-posts"));
+        assert_eq!(posts.len(), 2);
+        assert_eq!((posts[0].text.as_str(), posts[0].like_count, posts[0].channel_id.as_str()), ("New video out.\nWhich next?", 1200, "UCBa659QWEk1AI4Tg--mrJ2A"));
+        let poll = posts[0].poll.as_ref().unwrap();
+        assert_eq!(poll.choices[0], PollChoice { text: "Trains".to_string(), vote_percentage: Some("64%".to_string()) });
+        assert_eq!((poll.choices[1].vote_percentage.as_deref(), poll.total_votes.as_deref()), (None, Some("8K votes")));
+        assert_eq!(posts[1].images, ["https://yt3.ggpht.com/a=s1080", "https://yt3.ggpht.com/b=s1080"]);
+        assert_eq!(posts[1].poll, None);
+
+        assert_eq!(parse_time_ago("2 days ago"), Some(2 * 86400));
+        assert_eq!(parse_time_ago("1 year ago (edited)"), Some(365 * 86400));
+        assert_eq!(parse_time_ago("Streamed 45 minutes ago"), Some(45 * 60));
+        assert_eq!(parse_time_ago("yesterday"), None);
+    }
+
+    #[test]
+    fn live_state_reads_premiere_time() {
+        let premiere = serde_json::json!({
+            "videoDetails": { "isUpcoming": true, "isLiveContent": true },
+            "playabilityStatus": { "status": "LIVE_STREAM_OFFLINE", "liveStreamability": { "liveStreamabilityRenderer": {
+                "offlineSlate": { "liveStreamOfflineSlateRenderer": { "scheduledStartTime": "1767225600" } }
+            } } }
+        });
+        assert_eq!(LiveState::from_player(&premiere), LiveState { is_live: false, is_upcoming: true, scheduled_start: Some(1767225600) });
+
+        let live = serde_json::json!({ "videoDetails": { "isLive": true, "isLiveContent": true } });
+        assert_eq!(LiveState::from_player(&live), LiveState { is_live: true, is_upcoming: false, scheduled_start: None });
+        assert_eq!(LiveState::from_player(&serde_json::json!({ "videoDetails": {} })), LiveState::default());
+
+        let unlisted = serde_json::json!({
+            "videoDetails": { "isPrivate": false, "isLiveContent": true },
+            "microformat": { "playerMicroformatRenderer": { "isUnlisted": true } }
+        });
+        assert_eq!(VideoFlags::from_player(&unlisted), VideoFlags { is_private: false, is_unlisted: true, is_live_content: true });
+    }
+
+    #[test]
+    fn channel_refs_tell_ids_from_handles() {
+        assert_eq!(channel_ref("UCabcdefghijklmnopqrstuv"), ChannelRef::Id("UCabcdefghijklmnopqrstuv".into()));
+        assert_eq!(channel_ref("https://www.youtube.com/channel/UCabcdefghijklmnopqrstuv"), ChannelRef::Id("UCabcdefghijklmnopqrstuv".into()));
+        assert_eq!(channel_ref("https://www.youtube.com/@SomeCreator/videos"), ChannelRef::Handle("SomeCreator".into()));
+        assert_eq!(channel_ref(" @SomeCreator "), ChannelRef::Handle("SomeCreator".into()));
+        assert_eq!(channel_ref("https://www.youtube.com/c/Some Creator"), ChannelRef::LegacyUrl("https://www.youtube.com/c/Some%20Creator".into()));
+    }
+}
@@ -0,0 +1,165 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Entries kept in memory before the oldest are dropped; the disk copy stays.
+const MEMORY_ENTRIES: usize = 256;
+
+/// Responses held in memory, shared by every client and keyed by the
+/// profile they were fetched for as well as the request.
+static MEMORY: Mutex<Option<HashMap<(String, u64), Stored>>> = Mutex::new(None);
+
+/// A response and when it was fetched.
+type Stored = (SystemTime, Value);
+
+/// Where and for how long InnerTube responses are kept. Fresh responses are
+/// served from memory, then from one JSON file per request under `dir`.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    /// The profile the responses are for, so one profile's memory copies
+    /// never answer another's.
+    profile: String,
+    dir: Option<PathBuf>,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    /// A cache of `profile`'s responses that keeps them for `ttl`, on disk
+    /// under `dir` as well as in memory when a directory is given. A zero
+    /// `ttl` caches nothing.
+    pub fn new(profile: &str, dir: Option<PathBuf>, ttl: Duration) -> Self {
+        Self { profile: profile.to_string(), dir, ttl }
+    }
+
+    pub fn disabled() -> Self {
+        Self::new("", None, Duration::ZERO)
+    }
+
+    /// The same cache without the disk copy, for responses that shouldn't be
+    /// written out in plain text.
+    pub fn memory_only(&self) -> Self {
+        Self::new(&self.profile, None, self.ttl)
+    }
+
+    /// The key for a request: its endpoint and body, which carries the client,
+    /// language and region as well as what was asked for. Keys are stable
+    /// between runs of the same build, so a new version starts afresh.
+    pub fn key(endpoint: &str, body: &Value) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        endpoint.hash(&mut hasher);
+        body.to_string().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn file(&self, key: u64) -> Option<PathBuf> {
+        self.dir.as_ref().map(|d| d.join(format!("{:016x}.json", key)))
+    }
+
+    fn is_fresh(&self, stored_at: SystemTime) -> bool {
+        stored_at.elapsed().map(|age| age < self.ttl).unwrap_or(false)
+    }
+
+    /// The response stored under `key`, if it's younger than the TTL.
+    pub fn get(&self, key: u64) -> Option<Value> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+        if let Some((stored_at, value)) = MEMORY.lock().unwrap().as_ref().and_then(|m| m.get(&(self.profile.clone(), key))) {
+            if self.is_fresh(*stored_at) {
+                return Some(value.clone());
+            }
+        }
+
+        let file = self.file(key)?;
+        let stored_at = std::fs::metadata(&file).and_then(|m| m.modified()).ok()?;
+        if !self.is_fresh(stored_at) {
+            let _ = std::fs::remove_file(&file);
+            return None;
+        }
+        let value: Value = serde_json::from_slice(&std::fs::read(&file).ok()?).ok()?;
+        self.remember(key, stored_at, value.clone());
+        Some(value)
+    }
+
+    /// Store `value` under `key`. Failing to write the disk copy is only logged.
+    pub fn put(&self, key: u64, value: &Value) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        self.remember(key, SystemTime::now(), value.clone());
+        if let Some(file) = self.file(key) {
+            let written = file.parent().map(std::fs::create_dir_all).unwrap_or(Ok(()))
+                .and_then(|_| std::fs::write(&file, value.to_string()));
+            if let Err(e) = written {
+                log::warn!("Failed to cache response in {}: {}", file.display(), e);
+            }
+        }
+    }
+
+    fn remember(&self, key: u64, stored_at: SystemTime, value: Value) {
+        let key = (self.profile.clone(), key);
+        let mut guard = MEMORY.lock().unwrap();
+        let memory = guard.get_or_insert_with(HashMap::new);
+        if memory.len() >= MEMORY_ENTRIES && !memory.contains_key(&key) {
+            if let Some(oldest) = memory.iter().min_by_key(|(_, (at, _))| *at).map(|(k, _)| k.clone()) {
+                memory.remove(&oldest);
+            }
+        }
+        memory.insert(key, (stored_at, value));
+    }
+}
+
+/// Forget every cached response, in memory and under `dir`. Returns how many
+/// files were removed.
+pub fn clear(dir: Option<&std::path::Path>) -> std::io::Result<usize> {
+    *MEMORY.lock().unwrap() = None;
+    let Some(dir) = dir.filter(|d| d.is_dir()) else { return Ok(0) };
+    let mut removed = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "json") {
+            std::fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn responses_come_back_from_disk_until_they_expire() {
+        let dir = std::env::temp_dir().join(format!("kinesis-cache-test-{}", std::process::id()));
+        let cache = ResponseCache::new("default", Some(dir.clone()), Duration::from_secs(60));
+        let body = serde_json::json!({ "browseId": "UC123" });
+        let key = ResponseCache::key("browse", &body);
+        assert_ne!(key, ResponseCache::key("next", &body));
+
+        cache.put(key, &serde_json::json!({ "page": 1 }));
+        *MEMORY.lock().unwrap() = None;
+        assert_eq!(cache.get(key), Some(serde_json::json!({ "page": 1 })));
+
+        let expired = ResponseCache::new("default", Some(dir.clone()), Duration::from_nanos(1));
+        *MEMORY.lock().unwrap() = None;
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(expired.get(key), None);
+        assert_eq!(ResponseCache::disabled().get(key), None);
+
+        let other = ResponseCache::key("browse", &serde_json::json!({ "browseId": "UC456" }));
+        cache.memory_only().put(other, &serde_json::json!({ "private": true }));
+        assert_eq!(cache.get(other), Some(serde_json::json!({ "private": true })));
+        assert!(!dir.join(format!("{:016x}.json", other)).exists());
+        // Another profile's cache doesn't see this one's memory copies
+        assert_eq!(ResponseCache::new("work", None, Duration::from_secs(60)).get(other), None);
+
+        cache.put(key, &serde_json::json!({ "page": 2 }));
+        assert_eq!(clear(Some(&dir)).unwrap(), 1);
+        assert_eq!(cache.get(key), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
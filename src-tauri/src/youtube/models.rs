@@ -0,0 +1,1534 @@
+//! Typed views of the InnerTube responses the app reads: browse, search and
+//! next pages, the video details in a player response, and the renderers the
+//! parent module's parsers read. List containers hold their items as
+//! `Value`s, since each extractor picks the renderers it knows; those it
+//! reads are typed further down.
+//!
+//! Every field defaults, so a moved key leaves a container empty rather than
+//! failing the page, and the list accessors return `None` when the container
+//! is missing altogether, as opposed to empty.
+
+use serde::de::{DeserializeOwned, Deserializer};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Read a response as `T`, logging why it didn't fit rather than failing: a
+/// field of an unexpected type leaves the whole of it at its defaults.
+fn parse<T: DeserializeOwned + Default>(data: &Value, what: &str) -> T {
+    T::deserialize(data).unwrap_or_else(|e| {
+        log::warn!("Unexpected layout in InnerTube {} response: {}", what, e);
+        T::default()
+    })
+}
+
+/// Numbers InnerTube sends as strings (`"lengthSeconds": "300"`), taking a
+/// plain number too in case that changes.
+fn number_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(s) => s,
+        Value::Number(n) => n.to_string(),
+        _ => String::new(),
+    })
+}
+
+/// A browse response, or a continuation page from browse or next.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BrowseResponse {
+    pub contents: BrowseContents,
+    /// Continuation pages of a browse list append their items here...
+    pub on_response_received_actions: Vec<ResponseAction>,
+    /// ...and those of next, such as comments, here.
+    pub on_response_received_endpoints: Vec<ResponseAction>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BrowseContents {
+    pub two_column_browse_results_renderer: Option<TwoColumnBrowseResults>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TwoColumnBrowseResults {
+    pub tabs: Vec<Tab>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Tab {
+    /// Channels with a search box end the tab bar with an
+    /// `expandableTabRenderer`, which has the same fields.
+    #[serde(alias = "expandableTabRenderer")]
+    pub tab_renderer: Option<TabRenderer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TabRenderer {
+    pub selected: bool,
+    pub content: TabContent,
+}
+
+/// What a tab shows: a rich grid on channel and hashtag pages, a section list
+/// on playlist pages.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TabContent {
+    pub rich_grid_renderer: Option<ItemList>,
+    pub section_list_renderer: Option<SectionList>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ItemList {
+    pub contents: Vec<Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SectionList {
+    pub contents: Vec<Section>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Section {
+    pub item_section_renderer: Option<ItemSection>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ItemSection {
+    pub contents: Vec<SectionItem>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SectionItem {
+    pub playlist_video_list_renderer: Option<ItemList>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ResponseAction {
+    /// Later pages append to the list; sorting comments replaces it.
+    #[serde(rename = "appendContinuationItemsAction", alias = "reloadContinuationItemsCommand")]
+    pub continuation: Option<ContinuationItems>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ContinuationItems {
+    pub continuation_items: Vec<Value>,
+}
+
+/// The item that ends a list with more to load.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ContinuationItem {
+    pub continuation_item_renderer: Option<ContinuationItemRenderer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ContinuationItemRenderer {
+    pub continuation_endpoint: ContinuationEndpoint,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ContinuationEndpoint {
+    pub continuation_command: Option<ContinuationCommand>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ContinuationCommand {
+    pub token: String,
+}
+
+impl BrowseResponse {
+    pub fn parse(data: &Value) -> Self {
+        parse(data, "browse")
+    }
+
+    fn tabs(&self) -> &[Tab] {
+        self.contents.two_column_browse_results_renderer.as_ref().map_or(&[], |r| r.tabs.as_slice())
+    }
+
+    fn selected_tab(&self) -> Option<&TabRenderer> {
+        self.tabs().iter().filter_map(|t| t.tab_renderer.as_ref()).find(|t| t.selected)
+    }
+
+    fn continuations(&self) -> impl Iterator<Item = &ContinuationItems> {
+        self.on_response_received_actions.iter()
+            .chain(&self.on_response_received_endpoints)
+            .filter_map(|a| a.continuation.as_ref())
+    }
+
+    /// The items on the selected tab's rich grid, or those a continuation
+    /// page adds. `None` when the response has neither, as opposed to an
+    /// empty list.
+    pub fn tab_items(&self) -> Option<Vec<Value>> {
+        if let Some(grid) = self.selected_tab().and_then(|t| t.content.rich_grid_renderer.as_ref()) {
+            return Some(grid.contents.clone());
+        }
+        self.continuations().next()?;
+        Some(self.continuation_items())
+    }
+
+    /// Every item the continuation commands on the page add, in order.
+    pub fn continuation_items(&self) -> Vec<Value> {
+        self.continuations().flat_map(|c| c.continuation_items.iter().cloned()).collect()
+    }
+
+    /// The videos on the first page of a playlist, which lists them in a
+    /// section list on its only tab.
+    pub fn playlist_videos(&self) -> Option<Vec<Value>> {
+        let sections = self.tabs().first()?.tab_renderer.as_ref()?.content.section_list_renderer.as_ref()?;
+        let list = sections.contents.first()?.item_section_renderer.as_ref()?
+            .contents.first()?.playlist_video_list_renderer.as_ref()?;
+        Some(list.contents.clone())
+    }
+
+    /// Which containers the response does have, for a log line when the
+    /// expected one is missing.
+    pub fn describe(&self) -> String {
+        match self.selected_tab() {
+            Some(tab) => format!(
+                "selected tab has rich grid: {}, section list: {}",
+                tab.content.rich_grid_renderer.is_some(),
+                tab.content.section_list_renderer.is_some()
+            ),
+            None => format!("{} tabs, none selected; {} continuation commands", self.tabs().len(), self.continuations().count()),
+        }
+    }
+}
+
+/// A search response, or a continuation of one.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SearchResponse {
+    pub contents: SearchContents,
+    pub on_response_received_commands: Vec<ResponseAction>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SearchContents {
+    pub two_column_search_results_renderer: Option<SearchResults>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SearchResults {
+    pub primary_contents: PrimaryContents,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PrimaryContents {
+    pub section_list_renderer: Option<ItemList>,
+}
+
+/// One section of search results. The last one on a page is usually the
+/// `continuationItemRenderer` for the next.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SearchSection {
+    pub item_section_renderer: Option<ItemList>,
+}
+
+impl SearchResponse {
+    pub fn parse(data: &Value) -> Self {
+        parse(data, "search")
+    }
+
+    /// The sections on the first page, or those a continuation adds. `None`
+    /// when the response has neither.
+    pub fn sections(&self) -> Option<Vec<Value>> {
+        let first_page = self.contents.two_column_search_results_renderer.as_ref()
+            .and_then(|r| r.primary_contents.section_list_renderer.as_ref());
+        if let Some(list) = first_page {
+            return Some(list.contents.clone());
+        }
+        let mut continuations = self.on_response_received_commands.iter().filter_map(|c| c.continuation.as_ref()).peekable();
+        continuations.peek()?;
+        Some(continuations.flat_map(|c| c.continuation_items.iter().cloned()).collect())
+    }
+}
+
+impl SearchSection {
+    /// The results in a section, or nothing for other kinds of section.
+    pub fn items(section: &Value) -> Vec<Value> {
+        Self::deserialize(section).ok()
+            .and_then(|s| s.item_section_renderer)
+            .map(|list| list.contents)
+            .unwrap_or_default()
+    }
+}
+
+/// A next response for a watch page, or a continuation of its related videos.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NextResponse {
+    pub contents: NextContents,
+    pub on_response_received_endpoints: Vec<ResponseAction>,
+    pub engagement_panels: Vec<EngagementPanel>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NextContents {
+    pub two_column_watch_next_results: Option<WatchNextResults>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WatchNextResults {
+    /// The video's own info, description and comments section.
+    pub results: PrimaryResults,
+    /// The related videos in the sidebar.
+    pub secondary_results: SecondaryResults,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PrimaryResults {
+    pub results: Option<ItemList>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SecondaryResults {
+    pub secondary_results: Option<ResultList>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ResultList {
+    pub results: Vec<Value>,
+}
+
+/// A section of the watch page identified by name, such as the comments.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WatchSection {
+    pub item_section_renderer: Option<IdentifiedSection>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct IdentifiedSection {
+    pub section_identifier: String,
+    pub contents: Vec<Value>,
+}
+
+/// A panel that slides out of the watch page: comments, description,
+/// transcript.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EngagementPanel {
+    pub engagement_panel_section_list_renderer: Option<PanelRenderer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PanelRenderer {
+    pub panel_identifier: String,
+    pub header: PanelHeader,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PanelHeader {
+    pub engagement_panel_title_header_renderer: Option<PanelTitle>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PanelTitle {
+    /// Text beside the title, such as the comment count.
+    pub contextual_info: Text,
+}
+
+impl NextResponse {
+    pub fn parse(data: &Value) -> Self {
+        parse(data, "next")
+    }
+
+    fn watch_next(&self) -> Option<&WatchNextResults> {
+        self.contents.two_column_watch_next_results.as_ref()
+    }
+
+    /// The items under the video: its primary and secondary info, then the
+    /// comments section.
+    pub fn primary_contents(&self) -> &[Value] {
+        self.watch_next().and_then(|w| w.results.results.as_ref()).map_or(&[], |r| r.contents.as_slice())
+    }
+
+    /// The contents of the section called `identifier`.
+    pub fn section(&self, identifier: &str) -> Option<Vec<Value>> {
+        self.primary_contents().iter()
+            .filter_map(|item| WatchSection::deserialize(item).ok()?.item_section_renderer)
+            .find(|s| s.section_identifier == identifier)
+            .map(|s| s.contents)
+    }
+
+    /// The related videos in the sidebar, or those a continuation adds.
+    /// `None` when the response has neither.
+    pub fn related(&self) -> Option<Vec<Value>> {
+        if let Some(list) = self.watch_next().and_then(|w| w.secondary_results.secondary_results.as_ref()) {
+            return Some(list.results.clone());
+        }
+        let mut continuations = self.on_response_received_endpoints.iter().filter_map(|e| e.continuation.as_ref()).peekable();
+        continuations.peek()?;
+        Some(continuations.flat_map(|c| c.continuation_items.iter().cloned()).collect())
+    }
+
+    /// The header text of the panel called `identifier`.
+    pub fn panel_info(&self, identifier: &str) -> Option<&Text> {
+        self.engagement_panels.iter()
+            .filter_map(|p| p.engagement_panel_section_list_renderer.as_ref())
+            .find(|p| p.panel_identifier == identifier)
+            .and_then(|p| p.header.engagement_panel_title_header_renderer.as_ref())
+            .map(|t| &t.contextual_info)
+    }
+}
+
+/// The parts of a player response that describe the video rather than how to
+/// play it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlayerResponse {
+    pub video_details: VideoDetails,
+    pub microformat: Microformat,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct VideoDetails {
+    pub video_id: String,
+    pub title: String,
+    pub author: Author,
+    pub channel_id: String,
+    #[serde(deserialize_with = "number_string")]
+    pub length_seconds: String,
+    #[serde(deserialize_with = "number_string")]
+    pub view_count: String,
+    pub short_description: String,
+    pub keywords: Vec<String>,
+    pub thumbnail: Thumbnails,
+    pub is_live: bool,
+    pub is_upcoming: bool,
+    pub is_private: bool,
+    pub is_live_content: bool,
+}
+
+/// The uploader: a name from most clients, a list of channels from some.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Author {
+    Name(String),
+    Channels(Vec<AuthorChannel>),
+}
+
+impl Default for Author {
+    fn default() -> Self {
+        Author::Name(String::new())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AuthorChannel {
+    pub name: String,
+    pub url: Option<String>,
+    #[serde(alias = "channel_id")]
+    pub channel_id: Option<String>,
+}
+
+impl Author {
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Author::Name(name) => Some(name.as_str()),
+            Author::Channels(channels) => channels.first().map(|c| c.name.as_str()),
+        }
+        .filter(|n| !n.is_empty())
+    }
+
+    /// The first channel's page, when the author comes as a list.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Author::Channels(channels) => channels.first()?.url.as_deref(),
+            Author::Name(_) => None,
+        }
+    }
+
+    /// The first channel's ID, when the author comes as a list.
+    pub fn channel_id(&self) -> Option<&str> {
+        match self {
+            Author::Channels(channels) => channels.first()?.channel_id.as_deref(),
+            Author::Name(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Thumbnails {
+    pub thumbnails: Vec<Thumbnail>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Thumbnail {
+    pub url: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Microformat {
+    pub player_microformat_renderer: Option<PlayerMicroformat>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlayerMicroformat {
+    pub publish_date: String,
+    pub category: Option<String>,
+    pub owner_profile_url: Option<String>,
+    pub is_unlisted: bool,
+}
+
+impl PlayerResponse {
+    pub fn parse(data: &Value) -> Self {
+        parse(data, "player")
+    }
+
+    pub fn microformat(&self) -> Option<&PlayerMicroformat> {
+        self.microformat.player_microformat_renderer.as_ref()
+    }
+}
+
+/// Millisecond offsets, which InnerTube sends as numbers in some layouts and
+/// as strings in others.
+fn millis<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_i64(),
+        _ => None,
+    })
+}
+
+/// A text field: either `simpleText` or formatted `runs`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Text {
+    pub simple_text: Option<String>,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Run {
+    pub text: String,
+}
+
+impl Text {
+    /// The text as sent, runs joined.
+    pub fn raw(&self) -> String {
+        match &self.simple_text {
+            Some(text) => text.clone(),
+            None => self.runs.iter().map(|r| r.text.as_str()).collect(),
+        }
+    }
+
+    /// The text trimmed and unescaped, or `None` when there is none.
+    pub fn display(&self) -> Option<String> {
+        Some(super::decode_html(self.raw().trim())).filter(|t| !t.is_empty())
+    }
+}
+
+/// Text in the newer view models, which carry it as `content`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Content {
+    pub content: Option<String>,
+}
+
+/// An image in the newer view models, in increasing sizes.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Image {
+    pub sources: Vec<Thumbnail>,
+}
+
+// ─── Channel and playlist pages ──────────────────────────────────────────────
+
+/// The parts of a channel or playlist browse response that describe it rather
+/// than list its items. Read apart from `BrowseResponse` so an odd header
+/// can't cost the page its items.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BrowseDetails {
+    pub metadata: BrowseMetadata,
+    pub header: BrowseHeader,
+    pub sidebar: Sidebar,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BrowseMetadata {
+    pub channel_metadata_renderer: Option<ChannelMetadata>,
+    pub playlist_metadata_renderer: Option<PlaylistMetadata>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChannelMetadata {
+    pub title: Option<String>,
+    pub external_id: Option<String>,
+    pub description: String,
+    pub vanity_channel_url: Option<String>,
+    pub avatar: Thumbnails,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlaylistMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+/// The page header: `pageHeaderRenderer` on current pages, the older
+/// `c4TabbedHeaderRenderer` on some channels.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BrowseHeader {
+    pub page_header_renderer: Option<PageHeader>,
+    pub c4_tabbed_header_renderer: Option<C4TabbedHeader>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PageHeader {
+    pub content: PageHeaderContent,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PageHeaderContent {
+    pub page_header_view_model: Option<PageHeaderViewModel>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PageHeaderViewModel {
+    pub banner: Banner,
+    pub metadata: ContentMetadata,
+    pub description: HeaderDescription,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Banner {
+    pub image_banner_view_model: Option<ImageViewModel>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ImageViewModel {
+    pub image: Image,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HeaderDescription {
+    pub description_preview_view_model: Option<DescriptionPreview>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DescriptionPreview {
+    pub description: Content,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct C4TabbedHeader {
+    pub banner: Thumbnails,
+    pub channel_handle_text: Text,
+    pub subscriber_count_text: Text,
+    pub videos_count_text: Text,
+}
+
+/// Rows of short texts, such as a header's handle and counts or a lockup's
+/// channel, views and age.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ContentMetadata {
+    pub content_metadata_view_model: Option<MetadataRows>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MetadataRows {
+    pub metadata_rows: Vec<MetadataRow>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MetadataRow {
+    pub metadata_parts: Vec<MetadataPart>,
+}
+
+/// A piece of a metadata row. Playlist owners sometimes come as the text
+/// beside an avatar stack instead.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MetadataPart {
+    pub text: Content,
+    pub avatar_stack: AvatarStack,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AvatarStack {
+    pub avatar_stack_view_model: Option<AvatarStackViewModel>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AvatarStackViewModel {
+    pub text: Content,
+}
+
+impl MetadataPart {
+    fn content(&self) -> Option<&str> {
+        self.text.content.as_deref()
+            .or_else(|| self.avatar_stack.avatar_stack_view_model.as_ref()?.text.content.as_deref())
+    }
+}
+
+impl ContentMetadata {
+    fn rows(&self) -> &[MetadataRow] {
+        self.content_metadata_view_model.as_ref().map_or(&[], |m| m.metadata_rows.as_slice())
+    }
+
+    /// Every text in every row, in order.
+    pub fn texts(&self) -> impl Iterator<Item = &str> {
+        self.rows().iter().flat_map(|r| &r.metadata_parts).filter_map(MetadataPart::content)
+    }
+
+    /// The `part`th text of the `row`th row.
+    pub fn part(&self, row: usize, part: usize) -> Option<&str> {
+        self.rows().get(row)?.metadata_parts.get(part)?.content()
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Sidebar {
+    pub playlist_sidebar_renderer: Option<SidebarItems>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SidebarItems {
+    pub items: Vec<SidebarItem>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SidebarItem {
+    pub playlist_sidebar_primary_info_renderer: Option<PlaylistPrimaryInfo>,
+    pub playlist_sidebar_secondary_info_renderer: Option<PlaylistSecondaryInfo>,
+}
+
+/// The playlist's title and stats such as "1,024 videos".
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlaylistPrimaryInfo {
+    pub title: Text,
+    pub stats: Vec<Text>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlaylistSecondaryInfo {
+    pub video_owner: VideoOwner,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct VideoOwner {
+    pub video_owner_renderer: Option<VideoOwnerRenderer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct VideoOwnerRenderer {
+    pub title: Text,
+}
+
+impl BrowseDetails {
+    pub fn parse(data: &Value) -> Self {
+        parse(data, "browse header")
+    }
+
+    pub fn page_header(&self) -> Option<&PageHeaderViewModel> {
+        self.header.page_header_renderer.as_ref()?.content.page_header_view_model.as_ref()
+    }
+
+    fn sidebar_items(&self) -> &[SidebarItem] {
+        self.sidebar.playlist_sidebar_renderer.as_ref().map_or(&[], |s| s.items.as_slice())
+    }
+
+    pub fn playlist_primary(&self) -> Option<&PlaylistPrimaryInfo> {
+        self.sidebar_items().iter().find_map(|i| i.playlist_sidebar_primary_info_renderer.as_ref())
+    }
+
+    /// The playlist owner's name from the sidebar.
+    pub fn playlist_owner(&self) -> Option<String> {
+        let owner = self.sidebar_items().iter().find_map(|i| i.playlist_sidebar_secondary_info_renderer.as_ref())?;
+        Some(owner.video_owner.video_owner_renderer.as_ref()?.title.raw()).filter(|o| !o.is_empty())
+    }
+}
+
+impl PageHeaderViewModel {
+    pub fn banner(&self) -> Option<&str> {
+        self.banner.image_banner_view_model.as_ref()?.image.sources.last().map(|s| s.url.as_str())
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.description_preview_view_model.as_ref()?.description.content.as_deref()
+    }
+}
+
+impl Thumbnails {
+    /// The largest one's URL.
+    pub fn last_url(&self) -> Option<&str> {
+        self.thumbnails.last().map(|t| t.url.as_str()).filter(|u| !u.is_empty())
+    }
+}
+
+// ─── Watch page extras ───────────────────────────────────────────────────────
+
+/// The entities a response sends alongside its renderers: comments in the
+/// newer layout and the "most replayed" markers.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EntityUpdates {
+    pub framework_updates: FrameworkUpdates,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FrameworkUpdates {
+    pub entity_batch_update: EntityBatchUpdate,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EntityBatchUpdate {
+    pub mutations: Vec<Mutation>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Mutation {
+    pub payload: Payload,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Payload {
+    pub comment_entity_payload: Option<CommentEntity>,
+    pub macro_markers_list_entity: Option<MarkersListEntity>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommentEntity {
+    pub properties: CommentProperties,
+    pub author: CommentAuthor,
+    pub toolbar: CommentToolbar,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommentProperties {
+    pub comment_id: String,
+    pub content: Content,
+    pub published_time: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommentAuthor {
+    pub display_name: String,
+}
+
+/// Counts as displayed, such as "1.2K".
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommentToolbar {
+    pub like_count_notliked: String,
+    pub reply_count: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarkersListEntity {
+    pub markers_list: MarkersList,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarkersList {
+    pub marker_type: String,
+    pub markers: Vec<Marker>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Marker {
+    #[serde(deserialize_with = "millis")]
+    pub start_millis: Option<i64>,
+    #[serde(deserialize_with = "millis")]
+    pub duration_millis: Option<i64>,
+    pub intensity_score_normalized: Option<f64>,
+}
+
+impl EntityUpdates {
+    pub fn parse(data: &Value) -> Self {
+        parse(data, "entity update")
+    }
+
+    fn payloads(&self) -> impl Iterator<Item = &Payload> {
+        self.framework_updates.entity_batch_update.mutations.iter().map(|m| &m.payload)
+    }
+
+    pub fn comment(&self, id: &str) -> Option<&CommentEntity> {
+        self.payloads().filter_map(|p| p.comment_entity_payload.as_ref()).find(|c| c.properties.comment_id == id)
+    }
+
+    /// The first markers list of `marker_type`, such as `MARKER_TYPE_HEATMAP`.
+    pub fn markers(&self, marker_type: &str) -> Option<&MarkersList> {
+        self.payloads()
+            .filter_map(|p| p.macro_markers_list_entity.as_ref())
+            .map(|e| &e.markers_list)
+            .find(|l| l.marker_type == marker_type)
+    }
+}
+
+/// The player bar's markers on a watch page: chapters, and the "most
+/// replayed" graph on older pages. Read apart from `NextResponse` for the
+/// same reason as `BrowseDetails`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WatchOverlays {
+    pub player_overlays: PlayerOverlays,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlayerOverlays {
+    pub player_overlay_renderer: Option<PlayerOverlayRenderer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlayerOverlayRenderer {
+    pub decorated_player_bar_renderer: Option<DecoratedPlayerBar>,
+}
+
+/// The renderer is wrapped in another of the same name.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DecoratedPlayerBar {
+    pub decorated_player_bar_renderer: Option<DecoratedPlayerBarRenderer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DecoratedPlayerBarRenderer {
+    pub player_bar: PlayerBar,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlayerBar {
+    pub multi_markers_player_bar_renderer: Option<MarkersMap>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarkersMap {
+    pub markers_map: Vec<MarkersMapEntry>,
+}
+
+/// A set of markers named by `key`: `DESCRIPTION_CHAPTERS`, `AUTO_CHAPTERS`
+/// or `HEATSEEKER`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarkersMapEntry {
+    pub key: String,
+    pub value: MarkersValue,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MarkersValue {
+    pub chapters: Vec<ChapterItem>,
+    pub heatmap: Heatmap,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChapterItem {
+    pub chapter_renderer: Option<ChapterRenderer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChapterRenderer {
+    pub title: Text,
+    pub time_range_start_millis: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Heatmap {
+    pub heatmap_renderer: Option<HeatmapRenderer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HeatmapRenderer {
+    pub heat_markers: Vec<HeatMarkerItem>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HeatMarkerItem {
+    pub heat_marker_renderer: Option<HeatMarker>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HeatMarker {
+    #[serde(deserialize_with = "millis")]
+    pub time_range_start_millis: Option<i64>,
+    #[serde(deserialize_with = "millis")]
+    pub marker_duration_millis: Option<i64>,
+    pub heat_marker_intensity_score_normalized: Option<f64>,
+}
+
+impl WatchOverlays {
+    pub fn parse(data: &Value) -> Self {
+        parse(data, "player overlay")
+    }
+
+    pub fn markers_map(&self) -> &[MarkersMapEntry] {
+        self.player_overlays.player_overlay_renderer.as_ref()
+            .and_then(|r| r.decorated_player_bar_renderer.as_ref())
+            .and_then(|r| r.decorated_player_bar_renderer.as_ref())
+            .and_then(|r| r.player_bar.multi_markers_player_bar_renderer.as_ref())
+            .map_or(&[], |m| m.markers_map.as_slice())
+    }
+}
+
+// ─── Items ───────────────────────────────────────────────────────────────────
+
+/// An item in a page of comments.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommentItem {
+    pub comment_thread_renderer: Option<CommentThread>,
+}
+
+/// A top-level comment: a `commentRenderer`, or in the newer layout a view
+/// model naming the `CommentEntity` that holds it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommentThread {
+    pub comment: CommentHolder,
+    pub comment_view_model: CommentViewModelHolder,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommentHolder {
+    pub comment_renderer: Option<CommentRenderer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommentRenderer {
+    pub comment_id: Option<String>,
+    pub author_text: Text,
+    pub content_text: Text,
+    pub vote_count: Text,
+    pub reply_count: i64,
+    pub published_time_text: Text,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommentViewModelHolder {
+    pub comment_view_model: Option<CommentViewModel>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommentViewModel {
+    pub comment_id: Option<String>,
+}
+
+impl CommentItem {
+    /// The thread in an item, if it is one.
+    pub fn thread(item: &Value) -> Option<CommentThread> {
+        parse::<Self>(item, "comment").comment_thread_renderer
+    }
+}
+
+/// A Community tab post thread; the post it starts is the one kept.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BackstagePostThread {
+    pub post: BackstagePostHolder,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BackstagePostHolder {
+    /// Shared posts come as a `sharedPostRenderer` instead and are left out.
+    pub backstage_post_renderer: Option<BackstagePost>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BackstagePost {
+    pub post_id: Option<String>,
+    pub author_text: Text,
+    pub author_endpoint: Endpoint,
+    pub content_text: Text,
+    pub published_time_text: Text,
+    pub vote_count: Text,
+    pub backstage_attachment: Attachment,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Endpoint {
+    pub browse_endpoint: Option<BrowseEndpoint>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BrowseEndpoint {
+    pub browse_id: String,
+}
+
+/// What a post carries besides its text: one image or several, a poll or a
+/// video.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Attachment {
+    pub backstage_image_renderer: Option<BackstageImage>,
+    pub post_multi_image_renderer: Option<MultiImage>,
+    pub poll_renderer: Option<PollRenderer>,
+    pub video_renderer: Option<AttachedVideo>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BackstageImage {
+    pub image: Thumbnails,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MultiImage {
+    pub images: Vec<MultiImageItem>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MultiImageItem {
+    pub backstage_image_renderer: Option<BackstageImage>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PollRenderer {
+    pub choices: Vec<PollChoiceRenderer>,
+    pub total_votes: Text,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PollChoiceRenderer {
+    pub text: Text,
+    pub vote_percentage: Text,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AttachedVideo {
+    pub video_id: Option<String>,
+}
+
+impl BackstagePostThread {
+    /// The post a thread starts, unless it is a shared one.
+    pub fn post(thread: &Value) -> Option<BackstagePost> {
+        parse::<Self>(thread, "community post").post.backstage_post_renderer
+    }
+}
+
+impl Attachment {
+    /// The largest version of each attached image, in order.
+    pub fn image_urls(&self) -> Vec<&str> {
+        match &self.post_multi_image_renderer {
+            Some(multi) => multi.images.iter()
+                .filter_map(|i| i.backstage_image_renderer.as_ref()?.image.last_url())
+                .collect(),
+            None => self.backstage_image_renderer.iter().filter_map(|i| i.image.last_url()).collect(),
+        }
+    }
+}
+
+/// A video or playlist in the newer lockup layout, used in search results,
+/// channel pages and the watch page's sidebar.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LockupItem {
+    pub lockup_view_model: Option<Lockup>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Lockup {
+    pub content_id: Option<String>,
+    /// `LOCKUP_CONTENT_TYPE_VIDEO`, `LOCKUP_CONTENT_TYPE_PLAYLIST`, ...
+    pub content_type: String,
+    pub content_image: ContentImage,
+    pub metadata: LockupMetadata,
+}
+
+/// A video's thumbnail, or for a playlist a stack of them.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ContentImage {
+    pub thumbnail_view_model: Option<ThumbnailViewModel>,
+    pub collection_thumbnail_view_model: Option<CollectionThumbnail>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CollectionThumbnail {
+    pub primary_thumbnail: PrimaryThumbnail,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PrimaryThumbnail {
+    pub thumbnail_view_model: Option<ThumbnailViewModel>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ThumbnailViewModel {
+    pub image: Image,
+    pub overlays: Vec<ThumbnailOverlay>,
+}
+
+/// Badges over a thumbnail, such as a duration or a video count; newer pages
+/// put them in a bottom overlay.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ThumbnailOverlay {
+    #[serde(alias = "thumbnailBottomOverlayViewModel")]
+    pub thumbnail_overlay_badge_view_model: Option<BadgeOverlay>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BadgeOverlay {
+    #[serde(alias = "badges")]
+    pub thumbnail_badges: Vec<Badge>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Badge {
+    pub thumbnail_badge_view_model: Option<BadgeText>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BadgeText {
+    pub text: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LockupMetadata {
+    pub lockup_metadata_view_model: Option<LockupMetadataViewModel>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LockupMetadataViewModel {
+    pub title: Content,
+    pub metadata: ContentMetadata,
+}
+
+impl Lockup {
+    /// The lockup in an item, if it is one.
+    pub fn from_item(item: &Value) -> Option<Self> {
+        parse::<LockupItem>(item, "lockup").lockup_view_model
+    }
+
+    pub fn thumbnail(&self) -> Option<&ThumbnailViewModel> {
+        let image = &self.content_image;
+        image.thumbnail_view_model.as_ref()
+            .or_else(|| image.collection_thumbnail_view_model.as_ref()?.primary_thumbnail.thumbnail_view_model.as_ref())
+    }
+
+    /// The badge texts over the thumbnail.
+    pub fn badges(&self) -> impl Iterator<Item = &str> {
+        self.thumbnail().into_iter()
+            .flat_map(|t| &t.overlays)
+            .filter_map(|o| o.thumbnail_overlay_badge_view_model.as_ref())
+            .flat_map(|o| &o.thumbnail_badges)
+            .filter_map(|b| b.thumbnail_badge_view_model.as_ref())
+            .map(|b| b.text.as_str())
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.metadata.lockup_metadata_view_model.as_ref()?.title.content.as_deref()
+    }
+
+    /// The `part`th text of the `row`th metadata row.
+    pub fn metadata_part(&self, row: usize, part: usize) -> Option<&str> {
+        self.metadata.lockup_metadata_view_model.as_ref()?.metadata.part(row, part)
+    }
+}
+
+impl ContinuationItem {
+    /// The token from an item, if it is a `continuationItemRenderer`.
+    pub fn token(item: &Value) -> Option<String> {
+        let item = Self::deserialize(item).ok()?;
+        item.continuation_item_renderer?.continuation_endpoint.continuation_command.map(|c| c.token)
+    }
+}
+
+impl ContinuationItemRenderer {
+    /// The token from a `continuationItemRenderer` found on its own.
+    pub fn token(renderer: &Value) -> Option<String> {
+        Self::deserialize(renderer).ok()?.continuation_endpoint.continuation_command.map(|c| c.token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_the_selected_tab_and_continuations() {
+        let page = BrowseResponse::parse(&json!({
+            "contents": {"twoColumnBrowseResultsRenderer": {"tabs": [
+                {"tabRenderer": {"title": "Home", "content": {"sectionListRenderer": {"contents": []}}}},
+                {"tabRenderer": {"selected": true, "content": {"richGridRenderer": {"contents": [
+                    {"richItemRenderer": {"content": {"videoRenderer": {"videoId": "a"}}}}
+                ]}}}},
+                {"expandableTabRenderer": {"title": "Search"}}
+            ]}}
+        }));
+        let items = page.tab_items().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["richItemRenderer"]["content"]["videoRenderer"]["videoId"], "a");
+
+        let next = BrowseResponse::parse(&json!({
+            "onResponseReceivedActions": [{"appendContinuationItemsAction": {"continuationItems": [
+                {"richItemRenderer": {}},
+                {"continuationItemRenderer": {"continuationEndpoint": {"continuationCommand": {"token": "more"}}}}
+            ]}}]
+        }));
+        let items = next.tab_items().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(ContinuationItem::token(&items[1]).as_deref(), Some("more"));
+        assert_eq!(ContinuationItem::token(&items[0]), None);
+
+        let comments = BrowseResponse::parse(&json!({
+            "onResponseReceivedEndpoints": [
+                {"reloadContinuationItemsCommand": {"continuationItems": [{"commentsHeaderRenderer": {}}]}},
+                {"reloadContinuationItemsCommand": {"continuationItems": [{"commentThreadRenderer": {}}]}}
+            ]
+        }));
+        assert_eq!(comments.continuation_items().len(), 2);
+    }
+
+    #[test]
+    fn reads_playlist_videos_from_the_section_list() {
+        let page = BrowseResponse::parse(&json!({
+            "contents": {"twoColumnBrowseResultsRenderer": {"tabs": [
+                {"tabRenderer": {"content": {"sectionListRenderer": {"contents": [
+                    {"itemSectionRenderer": {"contents": [{"playlistVideoListRenderer": {"contents": [
+                        {"playlistVideoRenderer": {"videoId": "a"}},
+                        {"playlistVideoRenderer": {"videoId": "b"}}
+                    ]}}]}}
+                ]}}}}
+            ]}}
+        }));
+        assert_eq!(page.playlist_videos().unwrap().len(), 2);
+        // Not a channel page, so there is no grid to fall back to
+        assert!(page.tab_items().is_none());
+    }
+
+    #[test]
+    fn tells_a_changed_layout_from_an_empty_list() {
+        let empty = BrowseResponse::parse(&json!({
+            "contents": {"twoColumnBrowseResultsRenderer": {"tabs": [
+                {"tabRenderer": {"selected": true, "content": {"richGridRenderer": {"contents": []}}}}
+            ]}}
+        }));
+        assert_eq!(empty.tab_items(), Some(Vec::new()));
+
+        let moved = BrowseResponse::parse(&json!({
+            "contents": {"twoColumnBrowseResultsRenderer": {"tabs": [
+                {"tabRenderer": {"selected": true, "content": {"gridRenderer": {"items": []}}}}
+            ]}}
+        }));
+        assert_eq!(moved.tab_items(), None);
+        assert_eq!(moved.describe(), "selected tab has rich grid: false, section list: false");
+
+        // A field of the wrong type leaves nothing rather than failing
+        let garbled = BrowseResponse::parse(&json!({"contents": {"twoColumnBrowseResultsRenderer": {"tabs": "none"}}}));
+        assert_eq!(garbled.tab_items(), None);
+        assert_eq!(garbled.describe(), "0 tabs, none selected; 0 continuation commands");
+    }
+
+    #[test]
+    fn reads_search_sections_from_either_page() {
+        let first = SearchResponse::parse(&json!({
+            "contents": {"twoColumnSearchResultsRenderer": {"primaryContents": {"sectionListRenderer": {"contents": [
+                {"itemSectionRenderer": {"contents": [{"videoRenderer": {"videoId": "a"}}, {"channelRenderer": {}}]}},
+                {"continuationItemRenderer": {"continuationEndpoint": {"continuationCommand": {"token": "more"}}}}
+            ]}}}}
+        }));
+        let sections = first.sections().unwrap();
+        assert_eq!(SearchSection::items(&sections[0]).len(), 2);
+        assert!(SearchSection::items(&sections[1]).is_empty());
+
+        let next = SearchResponse::parse(&json!({
+            "onResponseReceivedCommands": [{"appendContinuationItemsAction": {"continuationItems": [
+                {"itemSectionRenderer": {"contents": [{"videoRenderer": {"videoId": "b"}}]}}
+            ]}}]
+        }));
+        assert_eq!(next.sections().unwrap().len(), 1);
+        assert!(SearchResponse::parse(&json!({"contents": {}})).sections().is_none());
+    }
+
+    #[test]
+    fn reads_the_watch_page_sections_and_related_videos() {
+        let page = NextResponse::parse(&json!({
+            "contents": {"twoColumnWatchNextResults": {
+                "results": {"results": {"contents": [
+                    {"videoPrimaryInfoRenderer": {}},
+                    {"itemSectionRenderer": {"sectionIdentifier": "comment-item-section", "contents": [{"continuationItemRenderer": {}}]}}
+                ]}},
+                "secondaryResults": {"secondaryResults": {"results": [{"compactVideoRenderer": {}}, {"compactVideoRenderer": {}}]}}
+            }},
+            "engagementPanels": [
+                {"engagementPanelSectionListRenderer": {"panelIdentifier": "engagement-panel-structured-description"}},
+                {"engagementPanelSectionListRenderer": {"panelIdentifier": "engagement-panel-comments-section",
+                    "header": {"engagementPanelTitleHeaderRenderer": {"contextualInfo": {"runs": [{"text": "1,234"}]}}}}}
+            ]
+        }));
+        assert_eq!(page.primary_contents().len(), 2);
+        assert_eq!(page.section("comment-item-section").unwrap().len(), 1);
+        assert!(page.section("other").is_none());
+        assert_eq!(page.related().unwrap().len(), 2);
+        assert_eq!(page.panel_info("engagement-panel-comments-section").unwrap().raw(), "1,234");
+
+        let more = NextResponse::parse(&json!({
+            "onResponseReceivedEndpoints": [{"appendContinuationItemsAction": {"continuationItems": [{"compactVideoRenderer": {}}]}}]
+        }));
+        assert_eq!(more.related().unwrap().len(), 1);
+        assert!(more.primary_contents().is_empty());
+    }
+
+    #[test]
+    fn reads_video_details_with_either_author_shape() {
+        let player = PlayerResponse::parse(&json!({
+            "videoDetails": {"videoId": "a", "title": "T", "author": "Someone", "lengthSeconds": "300", "viewCount": 42, "isLive": true},
+            "microformat": {"playerMicroformatRenderer": {"publishDate": "2024-01-02", "isUnlisted": true}}
+        }));
+        let details = &player.video_details;
+        assert_eq!(details.author.name(), Some("Someone"));
+        assert_eq!(details.author.url(), None);
+        assert_eq!(details.length_seconds, "300");
+        assert_eq!(details.view_count, "42");
+        assert!(details.is_live && !details.is_upcoming);
+        assert!(player.microformat().unwrap().is_unlisted);
+
+        let channels = PlayerResponse::parse(&json!({
+            "videoDetails": {"author": [{"name": "Someone", "url": "https://www.youtube.com/@someone", "channel_id": "UC1"}]}
+        }));
+        let author = &channels.video_details.author;
+        assert_eq!(author.name(), Some("Someone"));
+        assert_eq!(author.url(), Some("https://www.youtube.com/@someone"));
+        assert_eq!(author.channel_id(), Some("UC1"));
+        assert!(channels.microformat().is_none());
+    }
+
+    #[test]
+    fn reads_lockups_with_either_badge_overlay() {
+        let lockup = Lockup::from_item(&json!({"lockupViewModel": {
+            "contentId": "a",
+            "contentType": "LOCKUP_CONTENT_TYPE_VIDEO",
+            "contentImage": {"thumbnailViewModel": {
+                "image": {"sources": [{"url": "small"}, {"url": "large"}]},
+                "overlays": [{"thumbnailBottomOverlayViewModel": {"badges": [{"thumbnailBadgeViewModel": {"text": "4:20"}}]}}]
+            }},
+            "metadata": {"lockupMetadataViewModel": {
+                "title": {"content": "Title"},
+                "metadata": {"contentMetadataViewModel": {"metadataRows": [
+                    {"metadataParts": [{"text": {"content": "Channel"}}]},
+                    {"metadataParts": [{"text": {"content": "1K views"}}, {"text": {"content": "1 day ago"}}]}
+                ]}}
+            }}
+        }})).unwrap();
+        assert_eq!(lockup.badges().collect::<Vec<_>>(), ["4:20"]);
+        assert_eq!(lockup.thumbnail().unwrap().image.sources.last().unwrap().url, "large");
+        assert_eq!((lockup.title(), lockup.metadata_part(1, 1), lockup.metadata_part(2, 0)), (Some("Title"), Some("1 day ago"), None));
+        assert!(Lockup::from_item(&json!({"compactVideoRenderer": {}})).is_none());
+    }
+
+    #[test]
+    fn reads_entities_and_player_bar_markers() {
+        let updates = EntityUpdates::parse(&json!({"frameworkUpdates": {"entityBatchUpdate": {"mutations": [
+            {"payload": {"commentEntityPayload": {"properties": {"commentId": "c1", "content": {"content": "Hi"}}}}},
+            {"payload": {"macroMarkersListEntity": {"markersList": {"markerType": "MARKER_TYPE_HEATMAP", "markers": [
+                {"startMillis": "1000", "durationMillis": 500, "intensityScoreNormalized": 0.5}
+            ]}}}}
+        ]}}}));
+        assert_eq!(updates.comment("c1").unwrap().properties.content.content.as_deref(), Some("Hi"));
+        assert!(updates.comment("c2").is_none());
+        let marker = &updates.markers("MARKER_TYPE_HEATMAP").unwrap().markers[0];
+        assert_eq!((marker.start_millis, marker.duration_millis), (Some(1000), Some(500)));
+
+        let overlays = WatchOverlays::parse(&json!({"playerOverlays": {"playerOverlayRenderer": {"decoratedPlayerBarRenderer": {"decoratedPlayerBarRenderer": {
+            "playerBar": {"multiMarkersPlayerBarRenderer": {"markersMap": [{"key": "AUTO_CHAPTERS"}]}}
+        }}}}}));
+        assert_eq!(overlays.markers_map()[0].key, "AUTO_CHAPTERS");
+        assert!(WatchOverlays::parse(&json!({"playerOverlays": "none"})).markers_map().is_empty());
+        assert_eq!(Text::deserialize(&json!({"runs": [{"text": " Q &amp; "}, {"text": "A "}]})).unwrap().display().as_deref(), Some("Q & A"));
+    }
+}
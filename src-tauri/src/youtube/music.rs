@@ -0,0 +1,133 @@
+use serde_json::Value;
+use crate::types::{LyricLine, Lyrics, Song};
+use super::{decode_html, objects_under, text_of};
+use super::models::PlayerResponse;
+
+/// The browse ID of a song's lyrics page, from a `next` response of the
+/// Music client. Songs without lyrics have no such tab.
+pub fn lyrics_browse_id(next_json: &Value) -> Option<String> {
+    let mut endpoints = Vec::new();
+    objects_under(&next_json["contents"], "browseEndpoint", &mut endpoints);
+    endpoints.into_iter()
+        .filter_map(|e| e["browseId"].as_str())
+        .find(|id| id.starts_with("MPLYt"))
+        .map(String::from)
+}
+
+/// A song's lyrics from its lyrics page: synced lines when the page has
+/// them, otherwise the plain text shelf.
+pub fn parse_lyrics(browse_json: &Value) -> Option<Lyrics> {
+    let mut models = Vec::new();
+    objects_under(browse_json, "timedLyricsModel", &mut models);
+    let lines: Vec<LyricLine> = models.first()
+        .and_then(|m| m["lyricsData"]["timedLyricsData"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|line| {
+            let ms = |key: &str| line["cueRange"][key].as_str().and_then(|n| n.parse().ok()).or(line["cueRange"][key].as_i64());
+            Some(LyricLine {
+                start_ms: ms("startTimeMilliseconds")?,
+                end_ms: ms("endTimeMilliseconds")?,
+                text: line["lyricLine"].as_str()?.to_string(),
+            })
+        })
+        .collect();
+    if !lines.is_empty() {
+        let source = models.first().and_then(|m| m["lyricsData"]["sourceMessage"].as_str()).map(String::from);
+        let text = lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n");
+        return Some(Lyrics { text, lines, source });
+    }
+
+    let mut shelves = Vec::new();
+    objects_under(browse_json, "musicDescriptionShelfRenderer", &mut shelves);
+    let shelf = shelves.first()?;
+    let text = text_of(&shelf["description"]).filter(|t| !t.trim().is_empty())?;
+    Some(Lyrics { text, lines: Vec::new(), source: text_of(&shelf["footer"]) })
+}
+
+/// A song's title, artist, album and year. The title and length come from
+/// the Music client's player response; the album and year from the byline
+/// of the song's entry in the `next` response's queue, "Artist • Album • 2019".
+pub fn parse_song(video_id: &str, player: &Value, next_json: &Value) -> Song {
+    let details = PlayerResponse::parse(player).video_details;
+    let mut entries = Vec::new();
+    objects_under(&next_json["contents"], "playlistPanelVideoRenderer", &mut entries);
+    let byline = entries.iter()
+        .find(|e| e["videoId"] == video_id)
+        .and_then(|e| e["longBylineText"]["runs"].as_array());
+
+    let album = byline.and_then(|runs| runs.iter().find(|r| {
+        r["navigationEndpoint"]["browseEndpoint"]["browseEndpointContextSupportedConfigs"]["browseEndpointContextMusicConfig"]["pageType"]
+            == "MUSIC_PAGE_TYPE_ALBUM"
+    }));
+    let year = byline.and_then(|runs| runs.iter().filter_map(|r| r["text"].as_str()).find_map(|t| {
+        let t = t.trim();
+        (t.len() == 4).then(|| t.parse().ok()).flatten()
+    }));
+    let artist = byline.and_then(|runs| runs.first()).and_then(|r| r["text"].as_str()).map(decode_html)
+        .or_else(|| details.author.name().map(|a| decode_html(a.trim_end_matches(" - Topic"))));
+
+    Song {
+        video_id: video_id.to_string(),
+        title: Some(details.title.as_str()).filter(|t| !t.is_empty()).map(decode_html).unwrap_or_else(|| "Unknown".to_string()),
+        artist,
+        album: album.and_then(|r| r["text"].as_str()).map(decode_html),
+        year,
+        length_seconds: details.length_seconds.parse().ok(),
+        lyrics: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn song_details_come_from_the_queue_byline() {
+        let player = serde_json::json!({ "videoDetails": { "title": "Windowlicker", "author": "Aphex Twin - Topic", "lengthSeconds": "367" } });
+        let next = serde_json::json!({ "contents": { "singleColumnMusicWatchNextResultsRenderer": { "tabbedRenderer": { "watchNextTabbedResultsRenderer": { "tabs": [
+            { "tabRenderer": { "content": { "musicQueueRenderer": { "content": { "playlistPanelRenderer": { "contents": [
+                { "playlistPanelVideoRenderer": { "videoId": "UBS4Gi1y_nc", "longBylineText": { "runs": [
+                    { "text": "Aphex Twin" },
+                    { "text": " • " },
+                    { "text": "Windowlicker", "navigationEndpoint": { "browseEndpoint": { "browseEndpointContextSupportedConfigs": { "browseEndpointContextMusicConfig": { "pageType": "MUSIC_PAGE_TYPE_ALBUM" } } } } },
+                    { "text": " • " },
+                    { "text": "1999" }
+                ] } } }
+            ] } } } } } },
+            { "tabRenderer": { "endpoint": { "browseEndpoint": { "browseId": "MPLYt_lyrics1" } } } }
+        ] } } } } });
+
+        let song = parse_song("UBS4Gi1y_nc", &player, &next);
+        assert_eq!(song.title, "Windowlicker");
+        assert_eq!((song.artist.as_deref(), song.album.as_deref(), song.year, song.length_seconds), (Some("Aphex Twin"), Some("Windowlicker"), Some(1999), Some(367)));
+        assert_eq!(lyrics_browse_id(&next).as_deref(), Some("MPLYt_lyrics1"));
+
+        // Without a queue entry the channel name stands in for the artist
+        let alone = parse_song("UBS4Gi1y_nc", &player, &Value::Null);
+        assert_eq!((alone.artist.as_deref(), alone.album), (Some("Aphex Twin"), None));
+        assert_eq!(lyrics_browse_id(&Value::Null), None);
+    }
+
+    #[test]
+    fn lyrics_are_synced_when_the_page_has_timings() {
+        let plain = serde_json::json!({ "contents": { "sectionListRenderer": { "contents": [{ "musicDescriptionShelfRenderer": {
+            "description": { "runs": [{ "text": "First line\nSecond line" }] },
+            "footer": { "runs": [{ "text": "Source: LyricFind" }] }
+        } }] } } });
+        let lyrics = parse_lyrics(&plain).unwrap();
+        assert_eq!((lyrics.text.as_str(), lyrics.source.as_deref(), lyrics.lines.len()), ("First line\nSecond line", Some("Source: LyricFind"), 0));
+
+        let synced = serde_json::json!({ "contents": { "elementRenderer": { "newElement": { "type": { "componentType": { "model": { "timedLyricsModel": { "lyricsData": {
+            "timedLyricsData": [
+                { "lyricLine": "First line", "cueRange": { "startTimeMilliseconds": "1200", "endTimeMilliseconds": "3400" } },
+                { "lyricLine": "Second line", "cueRange": { "startTimeMilliseconds": "3400", "endTimeMilliseconds": "6000" } }
+            ],
+            "sourceMessage": "Source: Musixmatch"
+        } } } } } } } } });
+        let lyrics = parse_lyrics(&synced).unwrap();
+        assert_eq!(lyrics.text, "First line\nSecond line");
+        assert_eq!(lyrics.lines[1], LyricLine { start_ms: 3400, end_ms: 6000, text: "Second line".to_string() });
+        assert_eq!(parse_lyrics(&Value::Null), None);
+    }
+}
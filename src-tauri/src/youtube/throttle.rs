@@ -0,0 +1,176 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Requests per second YouTube sees from the app over time. Bulk saves and
+/// library checks fetch several things per video, so without a cap they
+/// arrive fast enough to get the IP temporarily blocked.
+const REQUESTS_PER_SECOND: f64 = 4.0;
+/// Requests that can go at once after a quiet spell, so browsing stays quick.
+const BURST: f64 = 8.0;
+/// Most extra delay added to a held-back request, so queued requests don't
+/// leave in a perfectly even beat.
+const MAX_JITTER_MS: u64 = 250;
+/// How long every request pauses once YouTube pushes back. Pushed back again
+/// soon after resuming, the pause doubles, up to `MAX_COOL_DOWN`.
+const COOL_DOWN: Duration = Duration::from_secs(60);
+const MAX_COOL_DOWN: Duration = Duration::from_secs(15 * 60);
+
+/// A token bucket. Each request takes a token; tokens come back at `rate` a
+/// second up to `burst`. A request finding none reserves the next one, so the
+/// balance goes below zero and later requests queue behind it.
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    /// The balance and when it was last brought up to date.
+    bucket: Mutex<Option<(f64, Instant)>>,
+}
+
+impl RateLimiter {
+    pub const fn new(rate: f64, burst: f64) -> Self {
+        Self { rate, burst, bucket: Mutex::new(None) }
+    }
+
+    /// Take a token at `now` and return how long the request must wait for it.
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        let (tokens, last) = bucket.unwrap_or((self.burst, now));
+        let refilled = now.saturating_duration_since(last).as_secs_f64() * self.rate;
+        let tokens = (tokens + refilled).min(self.burst) - 1.0;
+        *bucket = Some((tokens, now));
+        if tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-tokens / self.rate)
+        }
+    }
+
+    /// Wait until a request may go. Held-back requests get a little jitter.
+    pub async fn acquire(&self) {
+        let delay = self.reserve(Instant::now());
+        if !delay.is_zero() {
+            tokio::time::sleep(delay + jitter(Duration::from_millis(MAX_JITTER_MS))).await;
+        }
+    }
+}
+
+/// A delay of up to `max`, so waiting requests and retries don't go out in
+/// step. Taken from the clock's nanoseconds, which is random enough for that.
+pub fn jitter(max: Duration) -> Duration {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    Duration::from_millis(nanos % (max.as_millis() as u64 + 1))
+}
+
+/// The limiter every request to YouTube goes through: InnerTube calls, caption
+/// tracks, feeds, thumbnails and scraped pages alike.
+static LIMITER: RateLimiter = RateLimiter::new(REQUESTS_PER_SECOND, BURST);
+
+/// When the current or last cool-down ends, and how long it was.
+static COOL_DOWN_STATE: Mutex<Option<(Instant, Duration)>> = Mutex::new(None);
+
+type Listener = Box<dyn Fn(Duration, &str) + Send + Sync>;
+
+/// Told the length of each cool-down and why it started, for the UI.
+static LISTENER: OnceLock<Listener> = OnceLock::new();
+
+/// Call `listener` whenever requests start cooling down. Only the first
+/// listener set is kept.
+pub fn on_cool_down(listener: impl Fn(Duration, &str) + Send + Sync + 'static) {
+    let _ = LISTENER.set(Box::new(listener));
+}
+
+/// The cool-down to start at `now` after the one in `last`, or `None` while
+/// that one is still running, as it is when several requests in flight are
+/// refused together.
+fn next_cool_down(last: Option<(Instant, Duration)>, now: Instant) -> Option<(Instant, Duration)> {
+    let length = match last {
+        Some((until, _)) if now < until => return None,
+        Some((until, length)) if now < until + length => (length * 2).min(MAX_COOL_DOWN),
+        _ => COOL_DOWN,
+    };
+    Some((now + length, length))
+}
+
+/// Pause every request to YouTube because it refused one for `reason`.
+/// Requests already waiting in `wait` go ahead once the pause is over.
+pub fn cool_down(reason: &str) {
+    let started = {
+        let mut state = COOL_DOWN_STATE.lock().unwrap();
+        let next = next_cool_down(*state, Instant::now());
+        if next.is_some() {
+            *state = next;
+        }
+        next
+    };
+    if let Some((_, length)) = started {
+        log::warn!("{}; pausing requests to YouTube for {:?}", reason, length);
+        if let Some(listener) = LISTENER.get() {
+            listener(length, reason);
+        }
+    }
+}
+
+/// Whether a refusal reason is YouTube asking to prove the requests come
+/// from a person, which it does once they come too fast.
+pub fn is_bot_check(reason: &str) -> bool {
+    let lower = reason.to_lowercase();
+    lower.contains("not a bot") || lower.contains("not a robot")
+}
+
+/// Wait out any cool-down, then for the shared limiter, before a request to
+/// YouTube.
+pub async fn wait() {
+    loop {
+        let until = COOL_DOWN_STATE.lock().unwrap().map(|(until, _)| until);
+        match until.map(|until| until.saturating_duration_since(Instant::now())) {
+            Some(remaining) if !remaining.is_zero() => tokio::time::sleep(remaining).await,
+            _ => break,
+        }
+    }
+    LIMITER.acquire().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bursts_then_spaces_requests_out() {
+        let limiter = RateLimiter::new(2.0, 3.0);
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(limiter.reserve(start), Duration::ZERO);
+        }
+        // The bucket is empty: the next requests queue half a second apart
+        assert_eq!(limiter.reserve(start), Duration::from_millis(500));
+        assert_eq!(limiter.reserve(start), Duration::from_millis(1000));
+
+        // Quiet time refills it, but never past the burst size
+        let later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(limiter.reserve(later), Duration::ZERO);
+        }
+        assert!(limiter.reserve(later) > Duration::ZERO);
+    }
+
+    #[test]
+    fn repeated_push_back_lengthens_the_cool_down() {
+        let now = Instant::now();
+        let first = next_cool_down(None, now).unwrap();
+        assert_eq!(first, (now + COOL_DOWN, COOL_DOWN));
+        // Refusals while paused don't start another
+        assert_eq!(next_cool_down(Some(first), now + Duration::from_secs(1)), None);
+
+        let soon_after = first.0 + Duration::from_secs(5);
+        assert_eq!(next_cool_down(Some(first), soon_after).unwrap().1, COOL_DOWN * 2);
+        let long_after = first.0 + Duration::from_secs(3600);
+        assert_eq!(next_cool_down(Some(first), long_after).unwrap().1, COOL_DOWN);
+        assert_eq!(next_cool_down(Some((now, MAX_COOL_DOWN)), now + Duration::from_secs(1)).unwrap().1, MAX_COOL_DOWN);
+
+        assert!(is_bot_check("Sign in to confirm you’re not a bot"));
+        assert!(!is_bot_check("Sign in to confirm your age"));
+    }
+}
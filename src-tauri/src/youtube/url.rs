@@ -0,0 +1,273 @@
+use reqwest::Url;
+
+/// What a pasted URL or bare identifier points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsedTarget {
+    Video(String),
+    Playlist(String),
+    /// Either a `UC…` channel ID or an `@handle`.
+    Channel(String),
+    /// A `/c/<name>` or `/user/<name>` page, as a www.youtube.com URL. The
+    /// name is a custom URL or an old username rather than a handle, so only
+    /// YouTube can say which channel it is.
+    LegacyChannel(String),
+    Unknown,
+}
+
+/// Parse any YouTube URL shape (or bare ID / handle) into its target.
+pub fn parse_youtube_url(input: &str) -> ParsedTarget {
+    let input = clean(input);
+    if input.is_empty() {
+        return ParsedTarget::Unknown;
+    }
+
+    if let Some(target) = parse_bare_id(input) {
+        return target;
+    }
+
+    let url = match to_url(input) {
+        Some(u) => u,
+        None => return ParsedTarget::Unknown,
+    };
+
+    let host = url.host_str().unwrap_or("").to_lowercase();
+    let segments: Vec<String> = url.path_segments()
+        .map(|s| s.filter(|p| !p.is_empty()).map(|p| urlencoding::decode(p).map(|d| d.into_owned()).unwrap_or_else(|_| p.to_string())).collect())
+        .unwrap_or_default();
+    let query = |key: &str| url.query_pairs().find(|(k, _)| k == key).map(|(_, v)| v.into_owned());
+
+    if host == "youtu.be" || host == "www.youtu.be" {
+        return match segments.first() {
+            Some(id) if is_video_id(id) => ParsedTarget::Video(id.clone()),
+            _ => ParsedTarget::Unknown,
+        };
+    }
+
+    if !(host == "youtube.com" || host.ends_with(".youtube.com") || host == "youtube-nocookie.com" || host.ends_with(".youtube-nocookie.com")) {
+        return ParsedTarget::Unknown;
+    }
+
+    if let Some(v) = query("v") {
+        if is_video_id(&v) {
+            return ParsedTarget::Video(v);
+        }
+    }
+
+    let first = segments.first().map(|s| s.as_str()).unwrap_or("");
+    let second = segments.get(1).map(|s| s.as_str());
+
+    match (first, second) {
+        // Share links wrap the real path, URL-encoded, in `u`
+        ("attribution_link", _) => {
+            return match query("u").filter(|u| u.starts_with('/')) {
+                Some(path) => parse_youtube_url(&format!("https://www.youtube.com{}", path)),
+                None => ParsedTarget::Unknown,
+            };
+        }
+        // A playlist player, not a video, however much the name looks like one
+        ("embed", Some("videoseries")) => {}
+        ("shorts" | "embed" | "live" | "v" | "e" | "watch", Some(id)) if is_video_id(id) => {
+            return ParsedTarget::Video(id.to_string());
+        }
+        ("feeds", _) => {
+            if let Some(id) = query("channel_id").filter(|id| is_channel_id(id)) {
+                return ParsedTarget::Channel(id);
+            }
+            if let Some(list) = query("playlist_id").filter(|list| is_playlist_id(list)) {
+                return ParsedTarget::Playlist(list);
+            }
+        }
+        ("channel", Some(id)) if is_channel_id(id) => return ParsedTarget::Channel(id.to_string()),
+        ("c" | "user", Some(name)) => {
+            return ParsedTarget::LegacyChannel(format!("https://www.youtube.com/{}/{}", first, urlencoding::encode(name)));
+        }
+        (handle, _) if handle.len() > 1 && handle.starts_with('@') => {
+            return ParsedTarget::Channel(handle.to_string());
+        }
+        _ => {}
+    }
+
+    match query("list") {
+        Some(list) if is_playlist_id(&list) => ParsedTarget::Playlist(list),
+        _ => ParsedTarget::Unknown,
+    }
+}
+
+/// The video a pasted URL or bare ID points at.
+pub fn extract_video_id(input: &str) -> Option<String> {
+    match parse_youtube_url(input) {
+        ParsedTarget::Video(id) => Some(id),
+        _ => None,
+    }
+}
+
+/// The playlist a pasted URL or bare ID points at, including the one a watch
+/// URL plays the video from.
+pub fn extract_playlist_id(input: &str) -> Option<String> {
+    match parse_youtube_url(input) {
+        ParsedTarget::Playlist(list) => Some(list),
+        ParsedTarget::Video(_) => to_url(clean(input))?
+            .query_pairs()
+            .find(|(k, _)| k == "list")
+            .map(|(_, v)| v.into_owned())
+            .filter(|list| is_playlist_id(list)),
+        _ => None,
+    }
+}
+
+/// Links pasted from mail or Markdown often keep their angle brackets or quotes.
+fn clean(input: &str) -> &str {
+    input.trim().trim_matches(|c| matches!(c, '<' | '>' | '"' | '\''))
+}
+
+fn to_url(input: &str) -> Option<Url> {
+    let with_scheme = if input.contains("://") { input.to_string() } else { format!("https://{}", input) };
+    Url::parse(&with_scheme).ok()
+}
+
+/// Recognize identifiers pasted without any URL around them.
+fn parse_bare_id(input: &str) -> Option<ParsedTarget> {
+    if input.starts_with('@') && input.len() > 1 && !input.contains('/') {
+        return Some(ParsedTarget::Channel(input.to_string()));
+    }
+    if is_channel_id(input) {
+        return Some(ParsedTarget::Channel(input.to_string()));
+    }
+    if is_playlist_id(input) {
+        return Some(ParsedTarget::Playlist(input.to_string()));
+    }
+    if is_video_id(input) && !is_word(input) {
+        return Some(ParsedTarget::Video(input.to_string()));
+    }
+    None
+}
+
+/// An 11-letter word such as `programming` is more likely a handle or a search
+/// than a video ID. Real IDs almost always mix in digits, `-`, `_` or case
+/// changes, so a bare one shaped like a word isn't taken as a video.
+fn is_word(s: &str) -> bool {
+    let rest = s.get(1..).unwrap_or("");
+    s.starts_with(|c: char| c.is_ascii_alphabetic())
+        && (rest.chars().all(|c| c.is_ascii_lowercase()) || s.chars().all(|c| c.is_ascii_uppercase()))
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+fn is_video_id(s: &str) -> bool {
+    s.len() == 11 && s.chars().all(is_id_char)
+}
+
+fn is_channel_id(s: &str) -> bool {
+    s.len() == 24 && s.starts_with("UC") && s.chars().all(is_id_char)
+}
+
+fn is_playlist_id(s: &str) -> bool {
+    const PREFIXES: [&str; 7] = ["PL", "UU", "OL", "FL", "LL", "RD", "VL"];
+    s.len() > 12 && PREFIXES.iter().any(|p| s.starts_with(p)) && s.chars().all(is_id_char)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ParsedTarget::*;
+
+    const VID: &str = "dQw4w9WgXcQ";
+    const CHAN: &str = "UCuAXFkgsw1L7xaCfnd5JJOw";
+    const LIST: &str = "PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI";
+
+    #[test]
+    fn parses_every_url_shape() {
+        let cases: Vec<(String, ParsedTarget)> = vec![
+            // Videos
+            (format!("https://www.youtube.com/watch?v={}", VID), Video(VID.into())),
+            (format!("https://youtube.com/watch?v={}&t=42s", VID), Video(VID.into())),
+            (format!("https://m.youtube.com/watch?feature=share&v={}", VID), Video(VID.into())),
+            (format!("https://music.youtube.com/watch?v={}", VID), Video(VID.into())),
+            (format!("youtube.com/watch?v={}", VID), Video(VID.into())),
+            (format!("http://www.youtube.com/watch?v={}&list={}", VID, LIST), Video(VID.into())),
+            (format!("https://youtu.be/{}", VID), Video(VID.into())),
+            (format!("https://youtu.be/{}?si=abc&t=10", VID), Video(VID.into())),
+            (format!("youtu.be/{}", VID), Video(VID.into())),
+            (format!("https://www.youtube.com/shorts/{}", VID), Video(VID.into())),
+            (format!("https://youtube.com/shorts/{}?feature=share", VID), Video(VID.into())),
+            (format!("https://www.youtube.com/embed/{}", VID), Video(VID.into())),
+            (format!("https://www.youtube-nocookie.com/embed/{}?rel=0", VID), Video(VID.into())),
+            (format!("https://www.youtube.com/live/{}", VID), Video(VID.into())),
+            (format!("https://www.youtube.com/v/{}", VID), Video(VID.into())),
+            (format!("https://www.youtube.com/v/{}?version=3&hl=en_US", VID), Video(VID.into())),
+            (format!("https://www.youtube.com/e/{}", VID), Video(VID.into())),
+            (format!("https://m.youtube.com/watch/{}", VID), Video(VID.into())),
+            (format!("https://www.youtube.com/live/{}?si=abc", VID), Video(VID.into())),
+            (format!("https://WWW.YouTube.com/watch?v={}", VID), Video(VID.into())),
+            (format!("https://www.youtube.com/watch?app=desktop&v={}&feature=youtu.be", VID), Video(VID.into())),
+            (format!("https://www.youtube.com/watch?v={}#t=1m30s", VID), Video(VID.into())),
+            (format!("https://youtube.com/watch?v={}&list=RD{}&index=2", VID, VID), Video(VID.into())),
+            (format!("https://www.youtube.com/attribution_link?a=xyz&u=%2Fwatch%3Fv%3D{}%26feature%3Dshare", VID), Video(VID.into())),
+            (format!("<https://youtu.be/{}>", VID), Video(VID.into())),
+            (format!("\"https://www.youtube.com/shorts/{}\"", VID), Video(VID.into())),
+            (VID.to_string(), Video(VID.into())),
+            ("https://youtu.be/programming".to_string(), Video("programming".into())),
+            // Playlists
+            (format!("https://www.youtube.com/playlist?list={}", LIST), Playlist(LIST.into())),
+            (format!("https://m.youtube.com/playlist?list={}&feature=share", LIST), Playlist(LIST.into())),
+            (format!("https://www.youtube.com/watch?list={}", LIST), Playlist(LIST.into())),
+            (format!("https://www.youtube.com/embed/videoseries?list={}", LIST), Playlist(LIST.into())),
+            (format!("https://music.youtube.com/playlist?list=OLAK5uy_{}", &LIST[2..]), Playlist(format!("OLAK5uy_{}", &LIST[2..]))),
+            (format!("https://www.youtube.com/feeds/videos.xml?playlist_id={}", LIST), Playlist(LIST.into())),
+            (LIST.to_string(), Playlist(LIST.into())),
+            ("UUuAXFkgsw1L7xaCfnd5JJOw".to_string(), Playlist("UUuAXFkgsw1L7xaCfnd5JJOw".into())),
+            // Channels
+            (format!("https://www.youtube.com/channel/{}", CHAN), Channel(CHAN.into())),
+            (format!("https://www.youtube.com/channel/{}/videos", CHAN), Channel(CHAN.into())),
+            (format!("youtube.com/channel/{}?view_as=subscriber", CHAN), Channel(CHAN.into())),
+            (CHAN.to_string(), Channel(CHAN.into())),
+            (format!("https://www.youtube.com/feeds/videos.xml?channel_id={}", CHAN), Channel(CHAN.into())),
+            ("https://www.youtube.com/@mkbhd".to_string(), Channel("@mkbhd".into())),
+            ("https://www.youtube.com/@mkbhd/videos".to_string(), Channel("@mkbhd".into())),
+            ("https://m.youtube.com/@mkbhd?si=x".to_string(), Channel("@mkbhd".into())),
+            ("youtube.com/@some.handle-1".to_string(), Channel("@some.handle-1".into())),
+            ("@mkbhd".to_string(), Channel("@mkbhd".into())),
+            ("https://www.youtube.com/c/LinusTechTips".to_string(), LegacyChannel("https://www.youtube.com/c/LinusTechTips".into())),
+            ("https://m.youtube.com/user/marquesbrownlee/videos".to_string(), LegacyChannel("https://www.youtube.com/user/marquesbrownlee".into())),
+            // Unknown
+            ("".to_string(), Unknown),
+            ("   ".to_string(), Unknown),
+            ("mkbhd".to_string(), Unknown),
+            ("programming".to_string(), Unknown),
+            ("Programming".to_string(), Unknown),
+            ("PROGRAMMING".to_string(), Unknown),
+            ("https://vimeo.com/12345".to_string(), Unknown),
+            ("https://www.youtube.com/".to_string(), Unknown),
+            ("https://www.youtube.com/feed/subscriptions".to_string(), Unknown),
+            ("https://www.youtube.com/watch?v=tooshort".to_string(), Unknown),
+            ("https://youtu.be/".to_string(), Unknown),
+            ("https://www.youtube.com/embed/videoseries".to_string(), Unknown),
+            ("https://www.youtube.com/attribution_link?u=https://evil.example/".to_string(), Unknown),
+            ("https://notyoutube.com/watch?v=dQw4w9WgXcQ".to_string(), Unknown),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse_youtube_url(&input), expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn extracts_only_the_matching_kind() {
+        assert_eq!(extract_video_id(&format!("https://youtu.be/{}?t=5", VID)).as_deref(), Some(VID));
+        assert_eq!(extract_video_id(&format!("https://www.youtube.com/playlist?list={}", LIST)), None);
+        assert_eq!(extract_playlist_id(&format!("https://www.youtube.com/playlist?list={}", LIST)).as_deref(), Some(LIST));
+        assert_eq!(extract_playlist_id(CHAN), None);
+        // A video played from a playlist still imports the playlist
+        assert_eq!(extract_playlist_id(&format!("https://www.youtube.com/watch?v={}&list={}&index=3", VID, LIST)).as_deref(), Some(LIST));
+        assert_eq!(extract_playlist_id(&format!("https://youtu.be/{}?list={}", VID, LIST)).as_deref(), Some(LIST));
+        assert_eq!(extract_playlist_id(&format!("https://www.youtube.com/watch?v={}&list=bogus", VID)), None);
+        assert_eq!(extract_playlist_id(VID), None);
+    }
+
+    #[test]
+    fn trims_surrounding_whitespace() {
+        assert_eq!(parse_youtube_url(&format!("  https://youtu.be/{}\n", VID)), Video(VID.into()));
+    }
+}
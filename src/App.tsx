@@ -1,7 +1,7 @@
 import { useEffect, useState, useCallback } from "react";
 import {
     getTranscript, getDisplaySettings, setDisplaySettings,
-    getApiKey, getSetting, setDbPath,
+    getApiKey, getSetting, setDbPath, getEncryptionStatus, markOpened, takeDataDirWarning,
     type Video
 } from "./api";
 import { SearchBar, type Facet } from "./components/SearchBar";
@@ -11,6 +11,7 @@ import { BRAND } from "./branding";
 import { Notification, type NotificationType } from "./components/Notification";
 import { ConfirmDialog } from "./components/ConfirmDialog";
 import { SettingsModal } from "./components/SettingsModal";
+import { PassphrasePrompt } from "./components/PassphrasePrompt";
 import { Settings, ChevronUp, LayoutGrid, List } from "lucide-react";
 import { useSearch } from "./hooks/useSearch";
 import { useLibrary } from "./hooks/useLibrary";
@@ -26,6 +27,7 @@ function App() {
     const [hasApiKey, setHasApiKey] = useState(false);
     const [videoListMode, setVideoListMode] = useState<'grid' | 'compact'>('grid');
     const [pluginSummarizeEnabled, setPluginSummarizeEnabled] = useState(false);
+    const [dbLocked, setDbLocked] = useState(false);
 
     // ── Sidebar / transcript state ───────────────────────────────────────────
     const [selectedVideo, setSelectedVideo] = useState<Video | null>(null);
@@ -97,6 +99,13 @@ function App() {
                     : savedPath;
                 try { await setDbPath(folderPath); } catch { /* ignore */ }
             }
+            const dataDirWarning = await takeDataDirWarning().catch(() => null);
+            if (dataDirWarning) setNotification({ message: dataDirWarning, type: 'error' });
+            const encryption = await getEncryptionStatus().catch(() => null);
+            if (encryption?.enabled && !encryption.unlocked) {
+                setDbLocked(true);
+                return;
+            }
             getApiKey().then(k => setHasApiKey(!!k));
             getSetting('plugin_summarize_enabled').then(v => setPluginSummarizeEnabled(v === 'true'));
         };
@@ -136,6 +145,7 @@ function App() {
     const handleSelectVideo = useCallback(async (video: Video) => {
         setSelectedVideo(video);
         setSidebarOpen(true);
+        markOpened(video.id).catch(() => {});
         setTranscript("");
         setLoadingTranscript(true);
         try {
@@ -236,7 +246,7 @@ function App() {
                             <VideoList
                                 videos={displayedVideos}
                                 onSelect={handleSelectVideo}
-                                onSaveAll={displayedVideos.length > 0 ? library.handleSaveAll : undefined}
+                                onSaveAll={displayedVideos.length > 0 ? () => library.handleSaveAll(search.saveSource) : undefined}
                                 saveProgress={library.saveProgress}
                                 compact={videoListMode === 'compact'}
                                 onLoadMore={search.isSearch && search.continuationToken ? search.handleLoadMore : undefined}
@@ -320,6 +330,10 @@ function App() {
                 onPluginsChange={() => getSetting('plugin_summarize_enabled').then(v => setPluginSummarizeEnabled(v === 'true'))}
             />
 
+            {dbLocked && (
+                <PassphrasePrompt onUnlocked={() => window.location.reload()} />
+            )}
+
             {notification && (
                 <Notification
                     message={notification.message}
@@ -14,11 +14,41 @@ export interface Video {
     dateAdded?: string;
     handle?: string;
     status?: string;
+    source?: string;
+    watchedAt?: string | null;
+    starred?: boolean;
+    wordCount?: number | null;
+    readingMinutes?: number | null;
+    likeCount?: number | null;
+    commentCount?: number | null;
+    originalTitle?: string | null;
+    duration?: string | null;
+    isLive?: boolean;
+    isUpcoming?: boolean;
+    scheduledStart?: number | null;
+    captionsAutoGenerated?: boolean | null;
+    isPrivate?: boolean | null;
+    isUnlisted?: boolean | null;
+    isLiveContent?: boolean | null;
+    availability?: "available" | "private" | "removed" | null;
+    collections?: number[];
 }
 
 export interface SearchResponse {
     videos: Video[];
     continuation: string | null;
+    source: 'innertube' | 'rss' | 'data_api' | 'library';
+}
+
+export interface TableSize {
+    name: string;
+    size_bytes: number;
+}
+
+export interface ChannelSize {
+    name: string;
+    video_count: number;
+    size_bytes: number;
 }
 
 export interface DbDetails {
@@ -26,6 +56,10 @@ export interface DbDetails {
     size_bytes: number;
     video_count: number;
     history_count: number;
+    transcript_bytes: number;
+    segment_count: number;
+    tables: TableSize[];
+    top_channels: ChannelSize[];
 }
 
 export interface DisplaySettings {
@@ -50,8 +84,205 @@ export async function getVideos(id: string, isPlaylist: boolean, continuation?:
     return await invoke("fetch_videos", { id, isPlaylist, continuation });
 }
 
-export async function getTranscript(id: string): Promise<string> {
-    return await invoke("fetch_transcript", { videoId: id });
+export async function fetchAllPlaylistVideos(id: string): Promise<SearchResponse> {
+    return await invoke("fetch_all_playlist_videos", { id });
+}
+
+export interface PlaylistDetails {
+    playlistId: string;
+    title: string;
+    owner: string | null;
+    description: string;
+    videoCount: number | null;
+}
+
+export async function fetchPlaylistInfo(id: string): Promise<PlaylistDetails> {
+    return await invoke("fetch_playlist_info", { id });
+}
+
+export async function fetchChannelShorts(channel: string, continuation?: string | null): Promise<SearchResponse> {
+    return await invoke("fetch_channel_shorts", { query: channel, continuation });
+}
+
+export interface LiveStream extends Video {
+    liveStatus: 'live' | 'upcoming' | 'ended';
+    scheduledStart: number | null;
+    isPremiere: boolean;
+}
+
+export interface LiveStreamResponse {
+    streams: LiveStream[];
+    continuation: string | null;
+}
+
+export async function fetchChannelStreams(channel: string, continuation?: string | null): Promise<LiveStreamResponse> {
+    return await invoke("fetch_channel_streams", { query: channel, continuation });
+}
+
+export interface PlaylistPage {
+    playlists: PlaylistResult[];
+    continuation: string | null;
+}
+
+export async function fetchChannelPlaylists(channel: string, continuation?: string | null): Promise<PlaylistPage> {
+    return await invoke("fetch_channel_playlists", { query: channel, continuation });
+}
+
+export interface PollChoice {
+    text: string;
+    votePercentage: string | null;
+}
+
+export interface CommunityPost {
+    id: string;
+    channelId: string;
+    author: string;
+    text: string;
+    published: string;
+    likeCount: number;
+    images: string[];
+    poll: { choices: PollChoice[]; totalVotes: string | null } | null;
+    videoId: string | null;
+}
+
+export interface CommunityPage {
+    posts: CommunityPost[];
+    continuation: string | null;
+}
+
+export async function fetchCommunityPosts(channel: string, continuation?: string | null, archive?: boolean): Promise<CommunityPage> {
+    return await invoke("fetch_community_posts", { query: channel, continuation, archive: archive ?? false });
+}
+
+export async function listArchivedPosts(channelId: string): Promise<CommunityPost[]> {
+    return await invoke("list_archived_posts", { channelId });
+}
+
+export async function getTranscript(id: string, language?: string | null): Promise<string> {
+    return await invoke("fetch_transcript", { videoId: id, language: language ?? null });
+}
+
+export async function getTranscriptSrt(id: string, language?: string | null): Promise<string> {
+    return await invoke("fetch_transcript", { videoId: id, language: language ?? null, format: "srt" });
+}
+
+export async function getTimedTranscript(id: string, language?: string | null): Promise<TranscriptSegment[]> {
+    return await invoke("fetch_transcript", { videoId: id, language: language ?? null, format: "segments" });
+}
+
+export async function getTranslatedTranscript(id: string, lang: string): Promise<string> {
+    return await invoke("fetch_transcript_translated", { videoId: id, lang });
+}
+
+export interface SponsorSegment {
+    category: string;
+    startMs: number;
+    endMs: number;
+}
+
+export async function fetchSponsorSegments(videoId: string): Promise<SponsorSegment[]> {
+    return await invoke("fetch_sponsor_segments", { videoId });
+}
+
+export interface CaptionTrack {
+    languageCode: string;
+    name: string;
+    autoGenerated: boolean;
+    translatable: boolean;
+}
+
+export async function listCaptionTracks(videoId: string): Promise<CaptionTrack[]> {
+    return await invoke("list_caption_tracks", { videoId });
+}
+
+export interface TranscriptTrack {
+    language: string;
+    main: boolean;
+}
+
+export async function saveTranscriptTrack(videoId: string, language: string): Promise<TranscriptTrack> {
+    return await invoke("save_transcript_track", { videoId, language });
+}
+
+export async function listTranscriptTracks(videoId: string): Promise<TranscriptTrack[]> {
+    return await invoke("list_transcript_tracks", { videoId });
+}
+
+export async function getTranscriptTrack(videoId: string, language: string): Promise<string> {
+    return await invoke("get_transcript_track", { videoId, language });
+}
+
+export async function deleteTranscriptTrack(videoId: string, language: string): Promise<void> {
+    await invoke("delete_transcript_track", { videoId, language });
+}
+
+export interface TranscriptSegment {
+    startMs: number;
+    durationMs: number;
+    text: string;
+}
+
+export async function getTranscriptSegments(videoId: string): Promise<TranscriptSegment[]> {
+    return await invoke("get_transcript_segments", { videoId });
+}
+
+export interface Chapter {
+    title: string;
+    startMs: number;
+}
+
+export async function fetchChapters(videoId: string): Promise<Chapter[]> {
+    return await invoke("fetch_chapters", { videoId });
+}
+
+export interface HeatmapPoint {
+    startMs: number;
+    durationMs: number;
+    intensity: number;
+}
+
+export async function fetchHeatmap(videoId: string): Promise<HeatmapPoint[]> {
+    return await invoke("fetch_heatmap", { videoId });
+}
+
+export interface Storyboard {
+    width: number;
+    height: number;
+    count: number;
+    columns: number;
+    rows: number;
+    intervalMs: number;
+    sheets: string[];
+}
+
+export async function fetchStoryboard(videoId: string): Promise<Storyboard | null> {
+    return await invoke("fetch_storyboard", { videoId });
+}
+
+export interface LyricLine {
+    startMs: number;
+    endMs: number;
+    text: string;
+}
+
+export interface Lyrics {
+    text: string;
+    lines: LyricLine[];
+    source: string | null;
+}
+
+export interface Song {
+    videoId: string;
+    title: string;
+    artist: string | null;
+    album: string | null;
+    year: number | null;
+    lengthSeconds: number | null;
+    lyrics: Lyrics | null;
+}
+
+export async function fetchSong(videoId: string): Promise<Song> {
+    return await invoke("fetch_song", { videoId });
 }
 
 export async function summarizeTranscript(transcript: string): Promise<string> {
@@ -62,24 +293,241 @@ export async function getVideoInfo(id: string): Promise<Video> {
     return await invoke("fetch_video_info", { videoId: id });
 }
 
-export async function saveVideo(id: string, summary?: string | null): Promise<Video> {
-    return await invoke("save_video", { videoId: id, summary });
+export async function saveVideo(id: string, summary?: string | null, source?: string, archiveComments?: number): Promise<Video> {
+    return await invoke("save_video", { videoId: id, summary, source, archiveComments });
+}
+
+export interface Comment {
+    id: string;
+    author: string;
+    text: string;
+    likeCount: number;
+    replyCount: number;
+    published: string;
+}
+
+export interface CommentPage {
+    comments: Comment[];
+    continuation: string | null;
+}
+
+export async function fetchComments(videoId: string, continuation?: string | null): Promise<CommentPage> {
+    return await invoke("fetch_comments", { videoId, continuation });
+}
+
+export async function getSavedComments(videoId: string): Promise<Comment[]> {
+    return await invoke("get_saved_comments", { videoId });
+}
+
+export interface SearchFilters {
+    uploadDate?: 'last_hour' | 'today' | 'this_week' | 'this_month' | 'this_year' | null;
+    duration?: 'short' | 'medium' | 'long' | null;
+    sort?: 'relevance' | 'upload_date' | 'view_count' | 'rating' | null;
+}
+
+export async function searchVideos(query: string, continuation?: string | null, filters?: SearchFilters | null): Promise<SearchResponse> {
+    return await invoke("search_videos", { query, continuation, filters: filters ?? null });
+}
+
+export type TrendingCategory = 'now' | 'music' | 'gaming' | 'movies';
+
+export async function fetchTrending(category?: TrendingCategory | null): Promise<SearchResponse> {
+    return await invoke("fetch_trending", { category: category ?? null });
+}
+
+export async function fetchHashtag(tag: string, continuation?: string | null): Promise<SearchResponse> {
+    return await invoke("fetch_hashtag", { tag, continuation: continuation ?? null });
+}
+
+export async function fetchRelated(videoId: string, continuation?: string | null): Promise<SearchResponse> {
+    return await invoke("fetch_related", { videoId, continuation: continuation ?? null });
+}
+
+export interface ChannelResult {
+    channelId: string;
+    title: string;
+    handle: string | null;
+    thumbnail: string;
+    subscribers: string | null;
+    description: string | null;
+}
+
+export interface PlaylistResult {
+    playlistId: string;
+    title: string;
+    author: string | null;
+    thumbnail: string;
+    videoCount: number | null;
 }
 
-export async function searchVideos(query: string, continuation?: string | null): Promise<SearchResponse> {
-    return await invoke("search_videos", { query, continuation });
+export type SearchResult =
+    | ({ kind: "video" } & Video)
+    | ({ kind: "channel" } & ChannelResult)
+    | ({ kind: "playlist" } & PlaylistResult);
+
+export interface SearchPage {
+    results: SearchResult[];
+    continuation: string | null;
+    source: string;
 }
 
-export async function getSavedVideos(videoType?: string): Promise<SearchResponse> {
-    return await invoke("fetch_saved_videos", { videoType });
+export async function searchYouTube(query: string, continuation?: string | null, filters?: SearchFilters | null): Promise<SearchPage> {
+    return await invoke("search_youtube", { query, continuation, filters: filters ?? null });
+}
+
+export type VideoSort = 'date_added' | 'published_at' | 'title' | 'view_count' | 'length' | 'word_count';
+
+export interface VideoQuery {
+    videoType?: string;
+    source?: string;
+    channelId?: string;
+    collectionId?: number;
+    author?: string;
+    tag?: string;
+    category?: string;
+    keyword?: string;
+    hasNote?: boolean;
+    unavailable?: boolean;
+    watched?: boolean;
+    starred?: boolean;
+    addedFrom?: string;
+    addedTo?: string;
+    publishedFrom?: string;
+    publishedTo?: string;
+    sort?: VideoSort;
+    descending?: boolean;
+}
+
+export async function getSavedVideos(query?: VideoQuery): Promise<SearchResponse> {
+    return await invoke("fetch_saved_videos", { query });
+}
+
+export interface VideoDetails {
+    video: Video;
+    description: string | null;
+    transcriptLanguage: string | null;
+    keywords: string[];
+    category: string | null;
+    archiveUrl: string | null;
+}
+
+export async function getVideoDetails(videoId: string): Promise<VideoDetails> {
+    return await invoke("get_video_details", { videoId });
+}
+
+export async function searchLibrary(query: string): Promise<SearchResponse> {
+    return await invoke("search_library", { query });
+}
+
+export interface TranscriptMatch {
+    video: Video;
+    snippet: string;
+}
+
+export async function searchTranscripts(query: string, limit?: number): Promise<TranscriptMatch[]> {
+    return await invoke("search_transcripts", { query, limit });
+}
+
+export async function setNote(videoId: string, note: string): Promise<void> {
+    await invoke("set_note", { videoId, note });
+}
+
+export async function getNote(videoId: string): Promise<string | null> {
+    return await invoke("get_note", { videoId });
+}
+
+export async function markOpened(videoId: string): Promise<boolean> {
+    return await invoke("mark_opened", { videoId });
+}
+
+export async function recentVideos(limit?: number): Promise<Video[]> {
+    return await invoke("recent_videos", { limit });
+}
+
+export async function markWatched(videoId: string): Promise<void> {
+    await invoke("mark_watched", { videoId });
+}
+
+export async function markUnwatched(videoId: string): Promise<void> {
+    await invoke("mark_unwatched", { videoId });
+}
+
+export async function toggleStarred(videoId: string): Promise<boolean> {
+    return await invoke("toggle_starred", { videoId });
 }
 
 export async function deleteVideo(id: string): Promise<void> {
     await invoke("delete_video", { videoId: id });
 }
 
-export async function bulkSaveVideos(ids: string[]): Promise<any[]> {
-    return await invoke("bulk_save_videos", { videoIds: ids });
+export interface BulkDeleteFilter {
+    author?: string;
+    tag?: string;
+    addedBefore?: string;
+}
+
+export async function bulkDeleteVideos(target: { ids: string[] } | { filter: BulkDeleteFilter }): Promise<number> {
+    return await invoke("bulk_delete_videos", target);
+}
+
+export interface UndoReport {
+    operation: "delete" | "bulk_delete";
+    videoIds: string[];
+}
+
+export async function undoLastOperation(): Promise<UndoReport> {
+    return await invoke("undo_last_operation");
+}
+
+export interface TrashedVideo {
+    video: Video;
+    deletedAt: string;
+}
+
+export async function listTrash(): Promise<TrashedVideo[]> {
+    return await invoke("list_trash");
+}
+
+export async function restoreVideo(videoId: string): Promise<void> {
+    await invoke("restore_video", { videoId });
+}
+
+export async function purgeTrash(olderThanDays?: number): Promise<number> {
+    return await invoke("purge_trash", { olderThanDays });
+}
+
+export interface BulkSaveFailure {
+    id: string;
+    code: string;
+    message: string;
+}
+
+export interface BulkSaveReport {
+    saved: Video[];
+    skipped: Video[];
+    failed: BulkSaveFailure[];
+}
+
+export async function bulkSaveVideos(ids: string[], source?: string): Promise<BulkSaveReport> {
+    return await invoke("bulk_save_videos", { videoIds: ids, source });
+}
+
+export async function retryFailed(ids: string[], source?: string): Promise<BulkSaveReport> {
+    return await invoke("retry_failed", { ids, source });
+}
+
+export interface LibraryCheckReport {
+    checked: number;
+    unavailable: Video[];
+    failed: number;
+}
+
+export async function verifyLibrary(limit?: number): Promise<LibraryCheckReport> {
+    return await invoke("verify_library", { limit: limit ?? null });
+}
+
+export async function findArchivedCopy(videoId: string): Promise<string | null> {
+    return await invoke("find_archived_copy", { videoId });
 }
 
 export async function fetchChannelVideosV3(handle: string, continuationToken?: string | null): Promise<SearchResponse> {
@@ -98,6 +546,67 @@ export async function removeApiKey(): Promise<void> {
     await invoke("remove_api_key");
 }
 
+export async function getProxy(): Promise<string | null> {
+    return await invoke("get_proxy");
+}
+
+export async function setProxy(proxyUrl: string | null): Promise<void> {
+    await invoke("set_proxy", { proxyUrl });
+}
+
+export interface CookieStatus {
+    imported: boolean;
+    enabled: boolean;
+}
+
+export async function importCookies(cookies?: string | null): Promise<number | null> {
+    return await invoke("import_cookies", { cookies: cookies ?? null });
+}
+
+export async function getCookieStatus(): Promise<CookieStatus> {
+    return await invoke("get_cookie_status");
+}
+
+export async function setCookiesEnabled(enabled: boolean): Promise<void> {
+    await invoke("set_cookies_enabled", { enabled });
+}
+
+export async function removeCookies(): Promise<void> {
+    await invoke("remove_cookies");
+}
+
+export interface Locale {
+    hl: string;
+    gl: string;
+}
+
+export async function getLocale(): Promise<Locale> {
+    return await invoke("get_locale");
+}
+
+export async function setLocale(locale: Locale): Promise<void> {
+    await invoke("set_locale", { locale });
+}
+
+export interface ClientVersion {
+    client: "web" | "android" | "ios" | "tv_embedded" | "music";
+    version: string;
+    source: "manual" | "fetched" | "built_in";
+    refreshed: boolean;
+}
+
+export async function getClientVersions(): Promise<ClientVersion[]> {
+    return await invoke("get_client_versions");
+}
+
+export async function setClientVersion(client: ClientVersion["client"], version: string | null): Promise<void> {
+    await invoke("set_client_version", { client, version });
+}
+
+export async function takeDataDirWarning(): Promise<string | null> {
+    return await invoke("take_data_dir_warning");
+}
+
 export async function openDbLocation(): Promise<void> {
     await invoke("open_db_location");
 }
@@ -142,10 +651,29 @@ export async function resolveChannel(query: string): Promise<ChannelInfo> {
     return await invoke("resolve_channel", { query });
 }
 
+export interface ChannelDetails {
+    channelId: string;
+    name: string;
+    handle: string | null;
+    description: string;
+    avatar: string | null;
+    banner: string | null;
+    subscriberCount: number | null;
+    videoCount: number | null;
+}
+
+export async function fetchChannelInfo(query: string): Promise<ChannelDetails> {
+    return await invoke("fetch_channel_info", { query });
+}
+
 export async function fetchViewCount(videoId: string): Promise<string> {
     return await invoke("fetch_view_count", { videoId });
 }
 
+export async function fetchViewCounts(videoIds: string[]): Promise<Record<string, string>> {
+    return await invoke("fetch_view_counts", { videoIds });
+}
+
 export async function getSetting(key: string): Promise<string | null> {
     return await invoke("get_setting", { key });
 }
@@ -230,6 +758,20 @@ export async function setVenicePrompt(prompt: string): Promise<void> {
     await invoke("set_venice_prompt", { prompt });
 }
 
+export interface CompressionReport {
+    videos: number;
+    bytesBefore: number;
+    bytesAfter: number;
+}
+
+export async function compressTranscripts(): Promise<CompressionReport> {
+    return await invoke("compress_transcripts");
+}
+
+export async function backfillWordCounts(): Promise<number> {
+    return await invoke("backfill_word_counts");
+}
+
 export async function selectFolder(): Promise<string | null> {
     return await invoke("select_folder");
 }
@@ -238,6 +780,10 @@ export async function setDbPath(path: string): Promise<string> {
     return await invoke("set_db_path_override", { folderPath: path });
 }
 
+export async function setDbLocation(folderPath: string | null): Promise<string> {
+    return await invoke("set_db_location", { folderPath });
+}
+
 export interface AppInfo {
     name: string;
     version: string;
@@ -246,3 +792,283 @@ export interface AppInfo {
 export async function getAppInfo(): Promise<AppInfo> {
     return await invoke("get_app_info");
 }
+
+export interface Channel {
+    channelId: string;
+    name: string;
+    handle: string | null;
+    avatar: string | null;
+    videoCount: number;
+}
+
+export async function listChannels(): Promise<Channel[]> {
+    return await invoke("list_channels");
+}
+
+export interface Tag {
+    id: number;
+    name: string;
+    videoCount: number;
+}
+
+export async function addTag(videoId: string, tag: string): Promise<void> {
+    await invoke("add_tag", { videoId, tag });
+}
+
+export async function removeTag(videoId: string, tag: string): Promise<void> {
+    await invoke("remove_tag", { videoId, tag });
+}
+
+export async function listTags(): Promise<Tag[]> {
+    return await invoke("list_tags");
+}
+
+export async function getVideoTags(videoId: string): Promise<string[]> {
+    return await invoke("get_video_tags", { videoId });
+}
+
+export async function listVideosByTag(tag: string): Promise<SearchResponse> {
+    return await invoke("list_videos_by_tag", { tag });
+}
+
+export interface Collection {
+    id: number;
+    name: string;
+    createdAt: string;
+    videoCount: number;
+}
+
+export async function createCollection(name: string): Promise<number> {
+    return await invoke("create_collection", { name });
+}
+
+export async function renameCollection(id: number, name: string): Promise<void> {
+    await invoke("rename_collection", { id, name });
+}
+
+export async function deleteCollection(id: number): Promise<void> {
+    await invoke("delete_collection", { id });
+}
+
+export async function listCollections(): Promise<Collection[]> {
+    return await invoke("list_collections");
+}
+
+export async function addToCollection(collectionId: number, videoId: string): Promise<void> {
+    await invoke("add_to_collection", { collectionId, videoId });
+}
+
+export async function removeFromCollection(collectionId: number, videoId: string): Promise<void> {
+    await invoke("remove_from_collection", { collectionId, videoId });
+}
+
+export async function getVideoCollections(videoId: string): Promise<number[]> {
+    return await invoke("get_video_collections", { videoId });
+}
+
+export async function setVideoMeta(videoId: string, key: string, value: string): Promise<void> {
+    await invoke("set_video_meta", { videoId, key, value });
+}
+
+export async function getVideoMeta(videoId: string): Promise<Record<string, string>> {
+    return await invoke("get_video_meta", { videoId });
+}
+
+export async function deleteVideoMeta(videoId: string, key: string): Promise<void> {
+    await invoke("delete_video_meta", { videoId, key });
+}
+
+export async function exportLibrary(): Promise<string | null> {
+    return await invoke("export_library");
+}
+
+export async function exportCaptions(videoId: string, format: "srt" | "vtt"): Promise<string | null> {
+    return await invoke("export_captions", { videoId, format });
+}
+
+export async function exportTranscriptsMarkdown(): Promise<number | null> {
+    return await invoke("export_transcripts_markdown");
+}
+
+export async function exportMetadataCsv(): Promise<string | null> {
+    return await invoke("export_metadata_csv");
+}
+
+export interface ImportReport {
+    added: number;
+    updated: number;
+    skipped: number;
+    trashed: number;
+}
+
+export async function importLibrary(): Promise<ImportReport | null> {
+    return await invoke("import_library");
+}
+
+export async function exportSettings(): Promise<string | null> {
+    return await invoke("export_settings");
+}
+
+export async function importSettings(): Promise<number | null> {
+    return await invoke("import_settings");
+}
+
+export interface ActivityEntry {
+    id: number;
+    kind: "save" | "delete" | "restore" | "purge" | "import" | "export";
+    videoId: string | null;
+    detail: string | null;
+    createdAt: string;
+}
+
+export interface ActivityPage {
+    entries: ActivityEntry[];
+    total: number;
+}
+
+export async function getActivityLog(kind?: string, limit?: number, offset?: number): Promise<ActivityPage> {
+    return await invoke("get_activity_log", { kind, limit, offset });
+}
+
+export async function clearActivityLog(): Promise<number> {
+    return await invoke("clear_activity_log");
+}
+
+export async function getThumbnail(videoId: string): Promise<string | null> {
+    return await invoke("get_thumbnail", { videoId });
+}
+
+export type ThumbnailQuality = "maxres" | "sd" | "hq" | "mq";
+
+export async function downloadThumbnail(videoId: string, quality?: ThumbnailQuality): Promise<string | null> {
+    return await invoke("download_thumbnail", { videoId, quality: quality ?? null });
+}
+
+export interface ThumbnailCacheStats {
+    count: number;
+    sizeBytes: number;
+}
+
+export async function getThumbnailCacheStats(): Promise<ThumbnailCacheStats> {
+    return await invoke("get_thumbnail_cache_stats");
+}
+
+export async function purgeThumbnailCache(trashedOnly?: boolean): Promise<number> {
+    return await invoke("purge_thumbnail_cache", { trashedOnly });
+}
+
+export async function clearResponseCache(): Promise<number> {
+    return await invoke("clear_response_cache");
+}
+
+export interface Profile {
+    name: string;
+    active: boolean;
+    path: string;
+    sizeBytes: number;
+}
+
+export async function listProfiles(): Promise<Profile[]> {
+    return await invoke("list_profiles");
+}
+
+export async function createProfile(name: string): Promise<Profile> {
+    return await invoke("create_profile", { name });
+}
+
+export async function switchProfile(name: string): Promise<void> {
+    await invoke("switch_profile", { name });
+}
+
+export async function deleteProfile(name: string): Promise<void> {
+    await invoke("delete_profile", { name });
+}
+
+export interface BackupSettings {
+    intervalHours: number;
+    keep: number;
+}
+
+export interface BackupInfo {
+    path: string;
+    createdAt: string;
+    sizeBytes: number;
+}
+
+export async function getBackupSettings(): Promise<BackupSettings> {
+    return await invoke("get_backup_settings");
+}
+
+export async function setBackupSettings(settings: BackupSettings): Promise<void> {
+    await invoke("set_backup_settings", { settings });
+}
+
+export async function listBackups(): Promise<BackupInfo[]> {
+    return await invoke("list_backups");
+}
+
+export async function createBackup(): Promise<string> {
+    return await invoke("create_backup");
+}
+
+export async function restoreBackup(path: string, passphrase?: string): Promise<void> {
+    await invoke("restore_backup", { path, passphrase });
+}
+
+export interface EncryptionStatus {
+    enabled: boolean;
+    unlocked: boolean;
+}
+
+export async function getEncryptionStatus(): Promise<EncryptionStatus> {
+    return await invoke("get_encryption_status");
+}
+
+export async function unlockDatabase(passphrase: string): Promise<void> {
+    await invoke("unlock_database", { passphrase });
+}
+
+export async function enableEncryption(passphrase: string): Promise<void> {
+    await invoke("enable_encryption", { passphrase });
+}
+
+export async function disableEncryption(passphrase: string): Promise<void> {
+    await invoke("disable_encryption", { passphrase });
+}
+
+export interface MediaFile {
+    videoId: string;
+    kind: "audio" | "video";
+    path: string;
+    mimeType: string;
+    size: number;
+    downloadedAt: string;
+}
+
+export async function downloadAudio(videoId: string): Promise<MediaFile> {
+    return await invoke("download_audio", { videoId });
+}
+
+export async function listMediaFiles(videoId: string): Promise<MediaFile[]> {
+    return await invoke("list_media_files", { videoId });
+}
+
+export interface VideoFormat {
+    itag: number;
+    mimeType: string;
+    qualityLabel: string | null;
+    width: number | null;
+    height: number | null;
+    fps: number | null;
+    bitrate: number;
+    contentLength: number | null;
+    hasAudio: boolean;
+}
+
+export async function listVideoFormats(videoId: string): Promise<VideoFormat[]> {
+    return await invoke("list_video_formats", { videoId });
+}
+
+export async function downloadVideo(videoId: string, itag?: number | null, maxHeight?: number | null): Promise<MediaFile> {
+    return await invoke("download_video", { videoId, itag: itag ?? null, maxHeight: maxHeight ?? null });
+}
@@ -0,0 +1,61 @@
+import { useState } from "react";
+import { Lock } from "lucide-react";
+import { unlockDatabase } from "../api";
+
+interface PassphrasePromptProps {
+    onUnlocked: () => void;
+}
+
+export function PassphrasePrompt({ onUnlocked }: PassphrasePromptProps) {
+    const [passphrase, setPassphrase] = useState("");
+    const [error, setError] = useState("");
+    const [loading, setLoading] = useState(false);
+
+    const handleSubmit = async (e: React.FormEvent) => {
+        e.preventDefault();
+        if (!passphrase) return;
+        setLoading(true);
+        setError("");
+        try {
+            await unlockDatabase(passphrase);
+            onUnlocked();
+        } catch (err: any) {
+            setError(typeof err === "string" ? err : err.message || String(err));
+        } finally {
+            setLoading(false);
+        }
+    };
+
+    return (
+        <div className="fixed inset-0 z-[300] flex items-center justify-center bg-black/80 animate-in fade-in duration-200">
+            <form
+                onSubmit={handleSubmit}
+                className="bg-[#0f0f0f] border border-[#303030] rounded-lg p-6 w-full max-w-sm mx-4 shadow-2xl animate-in zoom-in-95 duration-200"
+            >
+                <div className="flex items-center gap-2 mb-2">
+                    <Lock className="w-5 h-5 text-red-500" />
+                    <h3 className="text-lg font-bold text-white">Database Locked</h3>
+                </div>
+                <p className="text-[#aaaaaa] text-sm mb-4 leading-relaxed">Enter your passphrase to open your encrypted library.</p>
+
+                <input
+                    type="password"
+                    autoFocus
+                    value={passphrase}
+                    onChange={(e) => setPassphrase(e.target.value)}
+                    placeholder="Passphrase"
+                    className="w-full bg-[#121212] border border-[#303030] rounded-lg px-3 py-2 text-sm text-white focus:outline-none focus:border-[#555555]"
+                />
+                {error && <p className="text-[#ff4e4e] text-xs mt-2">{error}</p>}
+
+                <button
+                    type="submit"
+                    disabled={loading || !passphrase}
+                    className="w-full mt-4 px-4 py-2 rounded-lg bg-red-600 hover:bg-red-700 cursor-pointer text-white text-sm font-semibold transition-colors disabled:opacity-50"
+                >
+                    {loading ? "Unlocking..." : "Unlock"}
+                </button>
+            </form>
+        </div>
+    );
+}
@@ -4,7 +4,7 @@ import { BRAND } from '../branding';
 import {
     getApiKey, getDbDetails, getDisplaySettings, setDisplaySettings,
     getSearchHistory, clearHistoryBeforeDate, deleteHistoryEntry, clearAllHistory,
-    getSetting, setSetting, openDbLocation, selectFolder, setDbPath,
+    getSetting, setSetting, openDbLocation, selectFolder, setDbLocation,
     type DbDetails, type DisplaySettings, type HistoryEntry
 } from "../api";
 import { ApiKeyTab } from "./settings/ApiKeyTab";
@@ -88,7 +88,7 @@ export function SettingsModal({
         try {
             const folder = await selectFolder();
             if (folder) {
-                const newPath = await setDbPath(folder);
+                const newPath = await setDbLocation(folder);
                 localStorage.setItem(BRAND.storageKey, newPath);
                 setDbDetails(await getDbDetails());
             }
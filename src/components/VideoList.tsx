@@ -1,5 +1,5 @@
 import { Save, Trash2, Bookmark, ArrowDown, ArrowUp, Calendar, Users, Sparkles } from 'lucide-react';
-import { type Video } from '../api';
+import { type Video, getThumbnail } from '../api';
 import { useState, useMemo, useRef, useEffect } from 'react';
 import { format } from 'date-fns';
 
@@ -194,6 +194,12 @@ export function VideoList({ videos, onSelect, onSaveAll, onDelete, saveProgress,
                                 alt={video.title}
                                 className="w-full h-full object-cover group-hover:scale-105 transition-transform duration-500"
                                 loading="lazy"
+                                onError={e => {
+                                    // Offline or taken down: fall back to the locally cached copy
+                                    const img = e.currentTarget;
+                                    if (img.src.startsWith('data:') || video.status !== 'saved') return;
+                                    getThumbnail(video.id).then(url => { if (url) img.src = url; }).catch(() => {});
+                                }}
                             />
                         </div>
 
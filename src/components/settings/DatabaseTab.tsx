@@ -35,6 +35,30 @@ export function DatabaseTab({ dbDetails, onOpen, onChangeLocation, loading }: Pr
                         <span className="text-xl font-bold text-white">{formatSize(dbDetails.size_bytes)}</span>
                     </div>
                 </div>
+                <div className="grid grid-cols-2 gap-4 mb-6">
+                    <div className="bg-[#121212] border border-[#303030] p-4 rounded-xl">
+                        <span className="text-[10px] uppercase font-bold text-[#aaaaaa] tracking-widest block mb-3">Largest Tables</span>
+                        {dbDetails.tables.slice(0, 5).map(t => (
+                            <div key={t.name} className="flex justify-between text-xs py-0.5">
+                                <span className="text-[#aaaaaa] truncate mr-2">{t.name}</span>
+                                <span className="text-white font-mono">{formatSize(t.size_bytes)}</span>
+                            </div>
+                        ))}
+                        <div className="flex justify-between text-xs pt-2 mt-2 border-t border-[#303030]">
+                            <span className="text-[#aaaaaa]">Transcripts ({dbDetails.segment_count} segments)</span>
+                            <span className="text-white font-mono">{formatSize(dbDetails.transcript_bytes)}</span>
+                        </div>
+                    </div>
+                    <div className="bg-[#121212] border border-[#303030] p-4 rounded-xl">
+                        <span className="text-[10px] uppercase font-bold text-[#aaaaaa] tracking-widest block mb-3">Top Channels by Size</span>
+                        {dbDetails.top_channels.slice(0, 6).map(c => (
+                            <div key={c.name} className="flex justify-between text-xs py-0.5">
+                                <span className="text-[#aaaaaa] truncate mr-2">{c.name} ({c.video_count})</span>
+                                <span className="text-white font-mono">{formatSize(c.size_bytes)}</span>
+                            </div>
+                        ))}
+                    </div>
+                </div>
                 <div className="space-y-3">
                     <div>
                         <span className="text-[10px] uppercase font-bold text-[#aaaaaa] tracking-widest block mb-2">Location</span>
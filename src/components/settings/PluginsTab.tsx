@@ -6,7 +6,7 @@ import {
     setSetting,
     checkOllama, checkModelPulled, pullModel, deleteModel, installOllama,
     getOllamaPrompt, setOllamaPrompt as saveOllamaPrompt,
-    getVeniceApiKey, getVenicePrompt, setVenicePrompt as saveVenicePromptCmd,
+    getVeniceApiKey, setVeniceApiKey, removeVeniceApiKey, getVenicePrompt, setVenicePrompt as saveVenicePromptCmd,
 } from "../../api";
 
 // ─── Shared sub-components ───────────────────────────────────────────────────
@@ -221,7 +221,7 @@ function VeniceSubTab({ summarizeProvider, onSetDefault }: VeniceProps) {
         const original = keyInput;
         setKeyInput('');
         try {
-            await setSetting("venice_api_key", key);
+            await setVeniceApiKey(key);
         } catch {
             setHasKey(false);
             setKeyInput(original);
@@ -235,7 +235,7 @@ function VeniceSubTab({ summarizeProvider, onSetDefault }: VeniceProps) {
         setLoading(true);
         setHasKey(false);
         try {
-            await setSetting("venice_api_key", "");
+            await removeVeniceApiKey();
         } catch {
             setHasKey(true);
             alert("Failed to remove Venice API Key.");
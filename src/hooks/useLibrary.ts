@@ -43,7 +43,7 @@ export function useLibrary(
     const handleSaveVideo = useCallback(async (video: Video, summary?: string | null) => {
         if (!video) return;
         try {
-            const result = await saveVideo(video.id, summary);
+            const result = await saveVideo(video.id, summary, "manual");
             if (result.status === 'exists') {
                 setNotification({ message: `"${video.title.substring(0, 30)}..." already exists in DB.`, type: "info" });
             } else {
@@ -74,7 +74,7 @@ export function useLibrary(
         try {
             await deleteVideo(confirmDelete.video.id);
             setLibraryVideos(prev => prev.filter(v => v.id !== confirmDelete.video.id));
-            setNotification({ message: `Deleted "${confirmDelete.video.title}"`, type: "success" });
+            setNotification({ message: `Moved "${confirmDelete.video.title}" to trash`, type: "success" });
             if (confirmDelete.fromSidebar) onSidebarClose();
         } catch (e: any) {
             setNotification({ message: `Failed to delete: ${e.message}`, type: "error" });
@@ -84,19 +84,19 @@ export function useLibrary(
         }
     }, [confirmDelete, refreshSummarizedCount, setNotification]);
 
-    const handleSaveAll = useCallback(async () => {
+    const handleSaveAll = useCallback(async (source: string) => {
         if (filteredSearchVideos.length === 0 || saveProgress) return;
         const chunkSize = 10;
-        let allResults: any[] = [];
+        let saved = 0, existed = 0, errored = 0;
         try {
             for (let i = 0; i < filteredSearchVideos.length; i += chunkSize) {
                 const chunk = filteredSearchVideos.slice(i, i + chunkSize);
                 setSaveProgress(`Saving ${Math.min(i + chunk.length, filteredSearchVideos.length)}/${filteredSearchVideos.length}...`);
-                const results = await bulkSaveVideos(chunk.map(v => v.id));
-                allResults.push(...results);
+                const report = await bulkSaveVideos(chunk.map(v => v.id), source);
+                saved += report.saved.length;
+                existed += report.skipped.length;
+                errored += report.failed.length;
             }
-            let saved = 0, existed = 0, errored = 0;
-            allResults.forEach(r => { if (r.error) errored++; else if (r.status === 'exists') existed++; else saved++; });
             setNotification({
                 message: `Bulk save complete. Saved: ${saved}, Existed: ${existed}, Failed: ${errored}`,
                 type: errored > 0 ? "info" : "success"
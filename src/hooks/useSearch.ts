@@ -10,6 +10,7 @@ interface SearchState {
     isPlaylist: boolean;
     isV3Channel?: boolean;
     isSearch?: boolean;
+    isVideo?: boolean;
 }
 
 export function useSearch(hasApiKey: boolean) {
@@ -80,18 +81,14 @@ export function useSearch(hasApiKey: boolean) {
                 if (!videoId) throw new Error("Invalid Video ID");
                 const videoInfo = await getVideoInfo(videoId);
                 setVideos([videoInfo]);
-                setCurrentSearch({ id: videoId, isPlaylist: false });
+                setCurrentSearch({ id: videoId, isPlaylist: false, isVideo: true });
                 return videoInfo; // so App can open sidebar
             } else if (mode === 'channel') {
-                if (!hasApiKey) {
-                    setError("You must import an API Key to search for channels.");
-                } else {
-                    const res = await fetchChannelVideosV3(targetId);
-                    setVideos(dedup(res.videos));
-                    setContinuationToken(res.continuation);
-                    setCurrentSearch({ id: targetId, isPlaylist: false, isV3Channel: true });
-                    if (res.videos.length === 0) setError("No videos found for this channel.");
-                }
+                const res = hasApiKey ? await fetchChannelVideosV3(targetId) : await getVideos(targetId, false);
+                setVideos(dedup(res.videos));
+                setContinuationToken(res.continuation);
+                setCurrentSearch({ id: targetId, isPlaylist: false, isV3Channel: hasApiKey });
+                if (res.videos.length === 0) setError("No videos found for this channel.");
             } else {
                 const res = await searchVideos(targetId);
                 setVideos(dedup(res.videos));
@@ -190,6 +187,13 @@ export function useSearch(hasApiKey: boolean) {
     // Computed: is this a regular search (not handle/playlist facet)?
     const isSearch = currentSearch?.isSearch === true;
 
+    // Computed: the source recorded for videos saved from these results
+    const saveSource = useMemo(() => {
+        if (!currentSearch || currentSearch.isSearch) return "bulk";
+        if (currentSearch.isVideo) return "manual";
+        return currentSearch.isPlaylist ? `playlist:${currentSearch.id.trim()}` : `channel:${currentSearch.id.trim()}`;
+    }, [currentSearch]);
+
     return {
         videos,
         loading,
@@ -206,5 +210,6 @@ export function useSearch(hasApiKey: boolean) {
         handleLoadAll,
         filteredVideos,
         isSearch,
+        saveSource,
     };
 }